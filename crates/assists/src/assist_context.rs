@@ -7,7 +7,7 @@ use hir::Semantics;
 use ide_db::base_db::{FileId, FileRange};
 use ide_db::{
     label::Label,
-    source_change::{SourceChange, SourceFileEdit},
+    source_change::{FileSystemEdit, SourceChange, SourceFileEdit},
     RootDatabase,
 };
 use syntax::{
@@ -219,15 +219,23 @@ impl AssistBuilder {
     }
 
     pub(crate) fn edit_file(&mut self, file_id: FileId) {
+        self.commit();
         self.file_id = file_id;
     }
 
     fn commit(&mut self) {
         let edit = mem::take(&mut self.edit).finish();
-        if !edit.is_empty() {
-            let new_edit = SourceFileEdit { file_id: self.file_id, edit };
-            assert!(!self.change.source_file_edits.iter().any(|it| it.file_id == new_edit.file_id));
-            self.change.source_file_edits.push(new_edit);
+        if edit.is_empty() {
+            return;
+        }
+        let file_id = self.file_id;
+        match self.change.source_file_edits.iter_mut().find(|it| it.file_id == file_id) {
+            Some(file_edit) => {
+                if file_edit.edit.union(edit).is_err() {
+                    panic!("overlapping edits for the same file");
+                }
+            }
+            None => self.change.source_file_edits.push(SourceFileEdit { file_id, edit }),
         }
     }
 
@@ -272,6 +280,19 @@ impl AssistBuilder {
         algo::diff(&node, &new).into_text_edit(&mut self.edit);
     }
 
+    /// Creates the file at `dst`, relative to the directory of `anchor`.
+    pub(crate) fn create_file(&mut self, anchor: FileId, dst: String, contents: impl Into<String>) {
+        let initial_contents = contents.into();
+        self.change.file_system_edits.push(FileSystemEdit::CreateFile {
+            anchor,
+            dst,
+            initial_contents,
+        });
+    }
+    pub(crate) fn delete_file(&mut self, file: FileId) {
+        self.change.file_system_edits.push(FileSystemEdit::DeleteFile { file });
+    }
+
     // FIXME: kill this API
     /// Get access to the raw `TextEditBuilder`.
     pub(crate) fn text_edit_builder(&mut self) -> &mut TextEditBuilder {
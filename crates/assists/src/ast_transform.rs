@@ -4,7 +4,7 @@ use rustc_hash::FxHashMap;
 use syntax::{
     algo::SyntaxRewriter,
     ast::{self, AstNode},
-    SyntaxNode,
+    SyntaxKind, SyntaxNode, SyntaxToken,
 };
 
 use crate::utils::mod_path_to_ast;
@@ -15,7 +15,10 @@ pub fn apply<'a, N: AstNode>(transformer: &dyn AstTransform<'a>, node: N) -> N {
             let replacement = transformer.get_substitution(&n, transformer)?;
             Some(replacement.into())
         }
-        _ => None,
+        syntax::SyntaxElement::Token(t) => {
+            let replacement = transformer.get_token_substitution(&t)?;
+            Some(replacement.into())
+        }
     })
     .rewrite_ast(&node)
 }
@@ -55,6 +58,12 @@ pub trait AstTransform<'a> {
         recur: &dyn AstTransform<'a>,
     ) -> Option<SyntaxNode>;
 
+    /// Like `get_substitution`, but for tokens that aren't part of a replaced
+    /// node, e.g. lifetimes.
+    fn get_token_substitution(&self, _token: &SyntaxToken) -> Option<SyntaxToken> {
+        None
+    }
+
     fn or<T: AstTransform<'a> + 'a>(self, other: T) -> Box<dyn AstTransform<'a> + 'a>
     where
         Self: Sized + 'a,
@@ -73,11 +82,16 @@ impl<'a> AstTransform<'a> for Or<'a> {
     ) -> Option<SyntaxNode> {
         self.0.get_substitution(node, recur).or_else(|| self.1.get_substitution(node, recur))
     }
+
+    fn get_token_substitution(&self, token: &SyntaxToken) -> Option<SyntaxToken> {
+        self.0.get_token_substitution(token).or_else(|| self.1.get_token_substitution(token))
+    }
 }
 
 pub struct SubstituteTypeParams<'a> {
     source_scope: &'a SemanticsScope<'a>,
     substs: FxHashMap<hir::TypeParam, ast::Type>,
+    lifetime_substs: FxHashMap<String, SyntaxToken>,
 }
 
 impl<'a> SubstituteTypeParams<'a> {
@@ -87,8 +101,16 @@ impl<'a> SubstituteTypeParams<'a> {
         trait_: hir::Trait,
         impl_def: ast::Impl,
     ) -> SubstituteTypeParams<'a> {
-        let substs = get_syntactic_substs(impl_def).unwrap_or_default();
+        let (substs, lifetime_substs) = get_syntactic_substs(impl_def).unwrap_or_default();
         let generic_def: hir::GenericDef = trait_.into();
+        // Lifetimes can't be shadowed, so a lifetime in a trait item with the
+        // name of a trait lifetime parameter always refers to that parameter.
+        let lifetime_substs_by_name: FxHashMap<_, _> = generic_def
+            .lifetime_params(source_scope.db)
+            .into_iter()
+            .map(|it| it.name(source_scope.db).to_string())
+            .zip(lifetime_substs)
+            .collect();
         let substs_by_param: FxHashMap<_, _> = generic_def
             .params(source_scope.db)
             .into_iter()
@@ -116,11 +138,15 @@ impl<'a> SubstituteTypeParams<'a> {
                 }
             })
             .collect();
-        return SubstituteTypeParams { source_scope, substs: substs_by_param };
+        return SubstituteTypeParams {
+            source_scope,
+            substs: substs_by_param,
+            lifetime_substs: lifetime_substs_by_name,
+        };
 
         // FIXME: It would probably be nicer if we could get this via HIR (i.e. get the
         // trait ref, and then go from the types in the substs back to the syntax).
-        fn get_syntactic_substs(impl_def: ast::Impl) -> Option<Vec<ast::Type>> {
+        fn get_syntactic_substs(impl_def: ast::Impl) -> Option<(Vec<ast::Type>, Vec<SyntaxToken>)> {
             let target_trait = impl_def.trait_()?;
             let path_type = match target_trait {
                 ast::Type::PathType(path) => path,
@@ -129,16 +155,18 @@ impl<'a> SubstituteTypeParams<'a> {
             let generic_arg_list = path_type.path()?.segment()?.generic_arg_list()?;
 
             let mut result = Vec::new();
+            let mut lifetimes = Vec::new();
             for generic_arg in generic_arg_list.generic_args() {
                 match generic_arg {
                     ast::GenericArg::TypeArg(type_arg) => result.push(type_arg.ty()?),
-                    ast::GenericArg::AssocTypeArg(_)
-                    | ast::GenericArg::LifetimeArg(_)
-                    | ast::GenericArg::ConstArg(_) => (),
+                    ast::GenericArg::LifetimeArg(lifetime_arg) => {
+                        lifetimes.push(lifetime_arg.lifetime_token()?)
+                    }
+                    ast::GenericArg::AssocTypeArg(_) | ast::GenericArg::ConstArg(_) => (),
                 }
             }
 
-            Some(result)
+            Some((result, lifetimes))
         }
     }
 }
@@ -160,6 +188,13 @@ impl<'a> AstTransform<'a> for SubstituteTypeParams<'a> {
             _ => None,
         }
     }
+
+    fn get_token_substitution(&self, token: &SyntaxToken) -> Option<SyntaxToken> {
+        if token.kind() != SyntaxKind::LIFETIME {
+            return None;
+        }
+        self.lifetime_substs.get(token.text().as_str()).cloned()
+    }
 }
 
 pub struct QualifyPaths<'a> {
@@ -528,6 +528,35 @@ impl foo::Foo<Param> for S {
         );
     }
 
+    #[test]
+    fn test_substitute_lifetime_param() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+trait Foo<'a, T> {
+    fn foo(&self, bar: &'a T) -> &'static str;
+    fn baz<'b>(&self, x: &'b T) -> &'b T where T: 'a;
+}
+struct S;
+impl<'x> Foo<'x, u32> for S { <|> }"#,
+            r#"
+trait Foo<'a, T> {
+    fn foo(&self, bar: &'a T) -> &'static str;
+    fn baz<'b>(&self, x: &'b T) -> &'b T where T: 'a;
+}
+struct S;
+impl<'x> Foo<'x, u32> for S {
+    fn foo(&self, bar: &'x u32) -> &'static str {
+        ${0:todo!()}
+    }
+
+    fn baz<'b>(&self, x: &'b u32) -> &'b u32 where u32: 'x {
+        todo!()
+    }
+}"#,
+        );
+    }
+
     #[test]
     fn test_qualify_path_associated_item() {
         check_assist(
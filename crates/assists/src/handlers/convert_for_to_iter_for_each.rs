@@ -0,0 +1,929 @@
+use hir::{AsAssocItem, AssocItemContainer};
+use syntax::{
+    ast::{self, edit::IndentLevel, ArgListOwner, AstNode, LoopBodyOwner},
+    SyntaxKind::*,
+    SyntaxNode, TextRange, WalkEvent,
+};
+use test_utils::mark;
+
+use crate::{utils::FamousDefs, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_for_to_iter_for_each
+//
+// Converts a `for` loop into a `for_each` call on the iterator.
+//
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     for<|> v in x {
+//         let y = v * 2;
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     x.into_iter().for_each(|v| {
+//         let y = v * 2;
+//     });
+// }
+// ```
+pub(crate) fn convert_for_to_iter_for_each(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let for_loop = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    let pat = for_loop.pat()?;
+    let iterable = for_loop.iterable()?;
+    let body = for_loop.loop_body()?;
+    if body.syntax().text_range().contains(ctx.offset()) {
+        return None;
+    }
+    let target = statement_of(for_loop.syntax())?;
+
+    let label = for_loop.label().and_then(|it| it.lifetime_token()).map(|it| it.text().to_string());
+    let exits = LoopExits::collect(&body, label.as_deref())?;
+    let style = if exits.has_try {
+        if !exits.breaks.is_empty() {
+            mark::hit!(convert_for_to_iter_for_each_break_and_try);
+            return None;
+        }
+        let (ret_ty, ok) = try_return_type(&for_loop)?;
+        ClosureStyle::Try { ret_ty, ok }
+    } else if !exits.breaks.is_empty() {
+        ClosureStyle::Break
+    } else {
+        ClosureStyle::Plain
+    };
+    let receiver = iterable_receiver(ctx, &iterable);
+
+    acc.add(
+        AssistId("convert_for_to_iter_for_each", AssistKind::RefactorRewrite),
+        "Convert a for loop into an Iterator::for_each",
+        for_loop.syntax().text_range(),
+        |builder| {
+            let indent = IndentLevel::from_node(for_loop.syntax());
+            let continue_ = match &style {
+                ClosureStyle::Plain => "return".to_string(),
+                ClosureStyle::Try { ok, .. } => format!("return {}(())", ok),
+                ClosureStyle::Break => "return Ok(())".to_string(),
+            };
+            let mut edits: Vec<(TextRange, String)> =
+                exits.continues.iter().map(|it| (*it, continue_.clone())).collect();
+            edits.extend(exits.breaks.iter().map(|it| (*it, "return Err(())".to_string())));
+            let text = match &style {
+                ClosureStyle::Plain => {
+                    let body = edit_block(&body, edits, None, indent);
+                    format!("{}.for_each(|{}| {});", receiver, pat, body)
+                }
+                ClosureStyle::Try { ret_ty, ok } => {
+                    let body = edit_block(&body, edits, Some(&format!("{}(())", ok)), indent);
+                    format!("{}.try_for_each(|{}| -> {} {})?;", receiver, pat, ret_ty, body)
+                }
+                ClosureStyle::Break => {
+                    let body = edit_block(&body, edits, Some("Ok(())"), indent);
+                    format!("let _ = {}.try_for_each(|{}| {});", receiver, pat, body)
+                }
+            };
+            builder.replace(target.text_range(), text);
+        },
+    )
+}
+
+// Assist: convert_iter_for_each_to_for
+//
+// Converts an `Iterator::for_each` call into a `for` loop.
+//
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     x.iter().for_each<|>(|v| {
+//         let y = v * 2;
+//     });
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     for v in &x {
+//         let y = v * 2;
+//     }
+// }
+// ```
+pub(crate) fn convert_iter_for_each_to_for(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = call.name_ref()?;
+    let is_try = match name_ref.text().as_str() {
+        "for_each" => false,
+        "try_for_each" => true,
+        _ => return None,
+    };
+    let mut args = call.arg_list()?.args();
+    let closure = match args.next()? {
+        ast::Expr::ClosureExpr(it) => it,
+        _ => return None,
+    };
+    if args.next().is_some() {
+        return None;
+    }
+    let mut params = closure.param_list()?.params();
+    let pat = params.next()?.pat()?;
+    if params.next().is_some() || !is_iterator_method(ctx, &call) {
+        return None;
+    }
+    let receiver = call.receiver()?;
+    let body = closure.body()?;
+
+    // `try_for_each` is only understood in the two shapes the other assist produces.
+    let (target, break_style) = if is_try {
+        let parent = call.syntax().parent()?;
+        if let Some(try_expr) = ast::TryExpr::cast(parent.clone()) {
+            (statement_of(try_expr.syntax())?, false)
+        } else {
+            let let_stmt = ast::LetStmt::cast(parent)?;
+            match let_stmt.pat()? {
+                ast::Pat::WildcardPat(_) if let_stmt.ty().is_none() => {}
+                _ => return None,
+            }
+            (let_stmt.syntax().clone(), true)
+        }
+    } else {
+        (statement_of(call.syntax())?, false)
+    };
+
+    let mut edits = Vec::new();
+    let mut loop_depth = 0;
+    walk_body(body.syntax(), &mut |event| {
+        let node = match event {
+            WalkEvent::Enter(node) => node,
+            WalkEvent::Leave(node) => {
+                if is_loop(&node) {
+                    loop_depth -= 1;
+                }
+                return Some(());
+            }
+        };
+        if is_loop(&node) {
+            loop_depth += 1;
+        } else if let Some(ret) = ast::ReturnExpr::cast(node) {
+            if loop_depth > 0 {
+                mark::hit!(convert_iter_for_each_to_for_return_in_loop);
+                return None;
+            }
+            let replacement = match (is_try, ret.expr()) {
+                (false, None) => "continue",
+                (true, Some(expr)) if is_unit_variant(&expr, &["Ok", "Some"]) => "continue",
+                (true, Some(expr)) if break_style && is_unit_variant(&expr, &["Err"]) => "break",
+                _ => return None,
+            };
+            edits.push((ret.syntax().text_range(), replacement.to_string()));
+        }
+        Some(())
+    })?;
+
+    let block = match &body {
+        ast::Expr::BlockExpr(it) => Some(it.clone()),
+        _ => None,
+    };
+    if is_try {
+        let tail = block.as_ref()?.expr()?;
+        if !is_unit_variant(&tail, &["Ok", "Some"]) {
+            return None;
+        }
+        let start = match tail.syntax().prev_sibling_or_token() {
+            Some(ws) if ws.kind() == WHITESPACE => ws.text_range().start(),
+            _ => tail.syntax().text_range().start(),
+        };
+        edits.push((TextRange::new(start, tail.syntax().text_range().end()), String::new()));
+    }
+    let iterable = for_iterable(ctx, &receiver);
+
+    acc.add(
+        AssistId("convert_iter_for_each_to_for", AssistKind::RefactorRewrite),
+        "Convert an Iterator::for_each into a for loop",
+        call.syntax().text_range(),
+        |builder| {
+            let indent = IndentLevel::from_node(&target);
+            let body = match block {
+                Some(block) => edit_block(&block, edits, None, indent),
+                None => format!("{{\n{}{};\n{}}}", indent + 1, body, indent),
+            };
+            let text = format!("for {} in {} {}", pat, iterable, body);
+            builder.replace(target.text_range(), text);
+        },
+    )
+}
+
+enum ClosureStyle {
+    Plain,
+    /// `?` in the body, the closure returns `ret_ty`, built from the function's return type.
+    Try {
+        ret_ty: String,
+        ok: &'static str,
+    },
+    /// `break` in the body, the closure returns `Err(())` to stop the iteration.
+    Break,
+}
+
+/// The `continue`s and `break`s leaving the loop, and whether its body uses `?`.
+#[derive(Default)]
+struct LoopExits {
+    continues: Vec<TextRange>,
+    breaks: Vec<TextRange>,
+    has_try: bool,
+}
+
+impl LoopExits {
+    /// Returns `None` if the body does something a closure can't express.
+    fn collect(body: &ast::BlockExpr, label: Option<&str>) -> Option<LoopExits> {
+        let mut exits = LoopExits::default();
+        let mut inner_labels: Vec<Option<String>> = Vec::new();
+        walk_body(body.syntax(), &mut |event| {
+            let node = match event {
+                WalkEvent::Enter(node) => node,
+                WalkEvent::Leave(node) => {
+                    if is_loop(&node) {
+                        inner_labels.pop();
+                    }
+                    return Some(());
+                }
+            };
+            match node.kind() {
+                FOR_EXPR | WHILE_EXPR | LOOP_EXPR => {
+                    let label = node
+                        .children()
+                        .find_map(ast::Label::cast)
+                        .and_then(|it| it.lifetime_token())
+                        .map(|it| it.text().to_string());
+                    inner_labels.push(label);
+                }
+                RETURN_EXPR => {
+                    mark::hit!(convert_for_to_iter_for_each_return);
+                    return None;
+                }
+                AWAIT_EXPR => return None,
+                TRY_EXPR => exits.has_try = true,
+                BREAK_EXPR | CONTINUE_EXPR => {
+                    let lifetime = node
+                        .children_with_tokens()
+                        .filter_map(|it| it.into_token())
+                        .find(|it| it.kind() == LIFETIME)
+                        .map(|it| it.text().to_string());
+                    let targets_this_loop = match &lifetime {
+                        None => inner_labels.is_empty(),
+                        Some(lifetime) if inner_labels.contains(&Some(lifetime.clone())) => false,
+                        Some(lifetime) if Some(lifetime.as_str()) == label => true,
+                        Some(_) => {
+                            mark::hit!(convert_for_to_iter_for_each_outer_label);
+                            return None;
+                        }
+                    };
+                    if !targets_this_loop {
+                        return Some(());
+                    }
+                    if node.kind() == CONTINUE_EXPR {
+                        exits.continues.push(node.text_range());
+                    } else {
+                        exits.breaks.push(node.text_range());
+                    }
+                }
+                _ => {}
+            }
+            Some(())
+        })?;
+        Some(exits)
+    }
+}
+
+/// Walks the descendants of `node` that belong to the same function body, stopping when
+/// `f` returns `None`.
+fn walk_body(
+    node: &SyntaxNode,
+    f: &mut dyn FnMut(WalkEvent<SyntaxNode>) -> Option<()>,
+) -> Option<()> {
+    for child in node.children() {
+        if child.kind() == CLOSURE_EXPR || ast::Item::can_cast(child.kind()) {
+            continue;
+        }
+        f(WalkEvent::Enter(child.clone()))?;
+        walk_body(&child, f)?;
+        f(WalkEvent::Leave(child))?;
+    }
+    Some(())
+}
+
+fn is_loop(node: &SyntaxNode) -> bool {
+    matches!(node.kind(), FOR_EXPR | WHILE_EXPR | LOOP_EXPR)
+}
+
+/// The statement consisting of `node`, or `node` itself when it ends a block.
+fn statement_of(node: &SyntaxNode) -> Option<SyntaxNode> {
+    let parent = node.parent()?;
+    match parent.kind() {
+        EXPR_STMT => Some(parent),
+        BLOCK_EXPR => Some(node.clone()),
+        _ => None,
+    }
+}
+
+/// The type the closure returns for `?` to keep working, taken from the enclosing function.
+fn try_return_type(for_loop: &ast::ForExpr) -> Option<(String, &'static str)> {
+    let fn_ = for_loop.syntax().ancestors().find_map(|it| {
+        if it.kind() == CLOSURE_EXPR {
+            Some(None)
+        } else {
+            ast::Fn::cast(it).map(Some)
+        }
+    });
+    let path = match fn_.flatten().and_then(|it| it.ret_type()).and_then(|it| it.ty()) {
+        Some(ast::Type::PathType(it)) => it.path(),
+        _ => None,
+    };
+    let segment = path.as_ref().and_then(|it| it.segment());
+    let generic_args = segment.as_ref().and_then(|it| it.generic_arg_list());
+    let (path, segment, generic_args) = match (path, segment, generic_args) {
+        (Some(path), Some(segment), Some(generic_args)) => (path, segment, generic_args),
+        _ => {
+            mark::hit!(convert_for_to_iter_for_each_unknown_try_type);
+            return None;
+        }
+    };
+    let ok = match segment.name_ref()?.text().as_str() {
+        "Option" => "Some",
+        _ => "Ok",
+    };
+    let args_start =
+        generic_args.syntax().text_range().start() - path.syntax().text_range().start();
+    let path_text = path.syntax().text().slice(..args_start).to_string();
+    let mut args = vec!["()".to_string()];
+    args.extend(generic_args.generic_args().skip(1).map(|it| it.to_string()));
+    Some((format!("{}<{}>", path_text, args.join(", ")), ok))
+}
+
+/// The expression to call `for_each` on, borrowing like the loop did.
+fn iterable_receiver(ctx: &AssistContext, iterable: &ast::Expr) -> String {
+    if let ast::Expr::RefExpr(ref_expr) = iterable {
+        if let Some(inner) = ref_expr.expr() {
+            let method = if ref_expr.mut_token().is_some() { "iter_mut" } else { "iter" };
+            if has_method(ctx, &inner, method) {
+                return format!("{}.{}()", receiver_text(&inner), method);
+            }
+        }
+    } else if is_iterator(ctx, iterable) {
+        return receiver_text(iterable);
+    }
+    format!("{}.into_iter()", receiver_text(iterable))
+}
+
+/// The expression to loop over, undoing `iterable_receiver`.
+fn for_iterable(ctx: &AssistContext, receiver: &ast::Expr) -> String {
+    let call = match receiver {
+        ast::Expr::MethodCallExpr(it) => it,
+        ast::Expr::ParenExpr(it) => {
+            return it.expr().unwrap_or_else(|| receiver.clone()).to_string()
+        }
+        _ => return receiver.to_string(),
+    };
+    let (name_ref, inner) = match (call.name_ref(), call.receiver(), call.arg_list()) {
+        (Some(name_ref), Some(inner), Some(args)) if args.args().next().is_none() => {
+            (name_ref, inner)
+        }
+        _ => return receiver.to_string(),
+    };
+    // Borrowing a reference again would not be iterable, keep the call for those.
+    let is_reference = ctx.sema.type_of_expr(&inner).map_or(false, |it| it.remove_ref().is_some());
+    match name_ref.text().as_str() {
+        "iter" if !is_reference => format!("&{}", inner),
+        "iter_mut" if !is_reference => format!("&mut {}", inner),
+        "into_iter" => inner.to_string(),
+        _ => receiver.to_string(),
+    }
+}
+
+fn receiver_text(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::PathExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::Literal(_)
+        | ast::Expr::MacroCall(_)
+        | ast::Expr::ArrayExpr(_)
+        | ast::Expr::TupleExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::AwaitExpr(_) => expr.to_string(),
+        _ => format!("({})", expr),
+    }
+}
+
+fn has_method(ctx: &AssistContext, expr: &ast::Expr, name: &str) -> bool {
+    let db = ctx.db();
+    let scope = ctx.sema.scope(expr.syntax());
+    let (ty, krate) = match (ctx.sema.type_of_expr(expr), scope.module()) {
+        (Some(ty), Some(module)) => (ty, module.krate()),
+        _ => return false,
+    };
+    let traits_in_scope = scope.traits_in_scope();
+    ty.iterate_method_candidates(db, krate, &traits_in_scope, None, |_, func| {
+        if func.name(db).to_string() == name {
+            Some(())
+        } else {
+            None
+        }
+    })
+    .is_some()
+}
+
+fn is_iterator(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    let krate = ctx.sema.scope(expr.syntax()).module().map(|it| it.krate());
+    match (ctx.sema.type_of_expr(expr), FamousDefs(&ctx.sema, krate).core_iter_Iterator()) {
+        (Some(ty), Some(iterator)) => ty.impls_trait(ctx.db(), iterator, &[]),
+        _ => false,
+    }
+}
+
+/// Whether `call` is `Iterator::for_each`, calls that don't resolve are assumed to be.
+fn is_iterator_method(ctx: &AssistContext, call: &ast::MethodCallExpr) -> bool {
+    let func = match ctx.sema.resolve_method_call(call) {
+        Some(it) => it,
+        None => return true,
+    };
+    let db = ctx.db();
+    let krate = ctx.sema.scope(call.syntax()).module().map(|it| it.krate());
+    match func.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Trait(trait_)) => {
+            Some(trait_) == FamousDefs(&ctx.sema, krate).core_iter_Iterator()
+        }
+        _ => false,
+    }
+}
+
+/// Whether `expr` is `Variant(())` for one of `variants`.
+fn is_unit_variant(expr: &ast::Expr, variants: &[&str]) -> bool {
+    let call = match expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return false,
+    };
+    let callee = match call.expr() {
+        Some(ast::Expr::PathExpr(it)) => it.to_string(),
+        _ => return false,
+    };
+    let mut args = match call.arg_list() {
+        Some(it) => it.args(),
+        None => return false,
+    };
+    let is_unit =
+        matches!(args.next(), Some(ast::Expr::TupleExpr(it)) if it.fields().next().is_none());
+    variants.contains(&callee.as_str()) && is_unit && args.next().is_none()
+}
+
+/// Applies `edits` to the text of `block`, ending it with `tail` if given.
+fn edit_block(
+    block: &ast::BlockExpr,
+    mut edits: Vec<(TextRange, String)>,
+    tail: Option<&str>,
+    indent: IndentLevel,
+) -> String {
+    let offset = block.syntax().text_range().start();
+    let mut text = block.syntax().to_string();
+    edits.sort_by_key(|(range, _)| range.start());
+    for (range, replacement) in edits.iter().rev() {
+        let range = *range - offset;
+        text.replace_range(usize::from(range.start())..usize::from(range.end()), replacement);
+    }
+    let tail = match tail {
+        Some(it) => it,
+        None => return text,
+    };
+    let mut inner = text[1..text.len() - 1].trim().to_string();
+    let needs_semicolon = block.expr().map_or(false, |it| {
+        !matches!(
+            it,
+            ast::Expr::IfExpr(_)
+                | ast::Expr::MatchExpr(_)
+                | ast::Expr::BlockExpr(_)
+                | ast::Expr::ForExpr(_)
+                | ast::Expr::WhileExpr(_)
+                | ast::Expr::LoopExpr(_)
+        )
+    });
+    if needs_semicolon && !inner.ends_with(';') {
+        inner.push(';');
+    }
+    if inner.is_empty() {
+        format!("{{\n{}{}\n{}}}", indent + 1, tail, indent)
+    } else {
+        format!("{{\n{}{}\n{}{}\n{}}}", indent + 1, inner, indent + 1, tail, indent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::{
+        tests::{check_assist, check_assist_not_applicable},
+        utils::FamousDefs,
+    };
+
+    use super::*;
+
+    fn check_with_core(assist: crate::handlers::Handler, before: &str, after: &str) {
+        let before = format!("//- /main.rs crate:main deps:core{}{}", before, FamousDefs::FIXTURE);
+        check_assist(assist, &before, after);
+    }
+
+    #[test]
+    fn for_to_for_each_into_iter() {
+        check_assist(
+            convert_for_to_iter_for_each,
+            r#"
+fn main() {
+    let x = vec![1, 2, 3];
+    for<|> v in x {
+        if v > 1 {
+            continue;
+        }
+        println!("{}", v);
+    }
+}
+"#,
+            r#"
+fn main() {
+    let x = vec![1, 2, 3];
+    x.into_iter().for_each(|v| {
+        if v > 1 {
+            return;
+        }
+        println!("{}", v);
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_for_each_borrowed() {
+        check_assist(
+            convert_for_to_iter_for_each,
+            r#"
+struct Bag;
+impl Bag {
+    fn iter(&self) {}
+    fn iter_mut(&mut self) {}
+}
+fn main() {
+    let mut bag = Bag;
+    for<|> v in &bag {}
+    for v in &mut bag {}
+}
+"#,
+            r#"
+struct Bag;
+impl Bag {
+    fn iter(&self) {}
+    fn iter_mut(&mut self) {}
+}
+fn main() {
+    let mut bag = Bag;
+    bag.iter().for_each(|v| {});
+    for v in &mut bag {}
+}
+"#,
+        );
+        check_assist(
+            convert_for_to_iter_for_each,
+            r#"
+struct Bag;
+impl Bag {
+    fn iter_mut(&mut self) {}
+}
+fn main() {
+    let mut bag = Bag;
+    for<|> (i, v) in &mut bag {}
+}
+"#,
+            r#"
+struct Bag;
+impl Bag {
+    fn iter_mut(&mut self) {}
+}
+fn main() {
+    let mut bag = Bag;
+    bag.iter_mut().for_each(|(i, v)| {});
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_for_each_on_iterator() {
+        check_with_core(
+            convert_for_to_iter_for_each,
+            r#"
+struct Counter;
+impl core::iter::Iterator for Counter {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+fn main() {
+    for<|> v in Counter {
+        let w = v;
+    }
+}
+"#,
+            r#"
+struct Counter;
+impl core::iter::Iterator for Counter {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+fn main() {
+    Counter.for_each(|v| {
+        let w = v;
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_try_for_each() {
+        check_assist(
+            convert_for_to_iter_for_each,
+            r#"
+fn parse(items: Vec<String>) -> Result<(), Error> {
+    for<|> item in items {
+        if item.is_empty() {
+            continue;
+        }
+        check(&item)?;
+        log(item)
+    }
+    Ok(())
+}
+"#,
+            r#"
+fn parse(items: Vec<String>) -> Result<(), Error> {
+    items.into_iter().try_for_each(|item| -> Result<(), Error> {
+        if item.is_empty() {
+            return Ok(());
+        }
+        check(&item)?;
+        log(item);
+        Ok(())
+    })?;
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_try_for_each_with_break() {
+        check_assist(
+            convert_for_to_iter_for_each,
+            r#"
+fn main() {
+    'outer: for<|> v in 0..10 {
+        for w in 0..v {
+            if w == 3 {
+                break;
+            }
+            if w == 4 {
+                continue 'outer;
+            }
+        }
+        if v == 5 {
+            break 'outer;
+        }
+    }
+}
+"#,
+            r#"
+fn main() {
+    let _ = (0..10).into_iter().try_for_each(|v| {
+        for w in 0..v {
+            if w == 3 {
+                break;
+            }
+            if w == 4 {
+                return Ok(());
+            }
+        }
+        if v == 5 {
+            return Err(());
+        }
+        Ok(())
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_for_each_not_applicable_with_return() {
+        mark::check!(convert_for_to_iter_for_each_return);
+        check_assist_not_applicable(
+            convert_for_to_iter_for_each,
+            r#"
+fn f(x: Vec<i32>) -> i32 {
+    for<|> v in x {
+        return v;
+    }
+    0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_for_each_not_applicable_with_outer_label() {
+        mark::check!(convert_for_to_iter_for_each_outer_label);
+        check_assist_not_applicable(
+            convert_for_to_iter_for_each,
+            r#"
+fn f(x: Vec<Vec<i32>>) {
+    'outer: for row in x {
+        for<|> v in row {
+            continue 'outer;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_for_each_not_applicable_with_break_and_try() {
+        mark::check!(convert_for_to_iter_for_each_break_and_try);
+        check_assist_not_applicable(
+            convert_for_to_iter_for_each,
+            r#"
+fn f(x: Vec<i32>) -> Option<()> {
+    for<|> v in x {
+        g(v)?;
+        break;
+    }
+    None
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_to_for_each_not_applicable_with_unknown_try_type() {
+        mark::check!(convert_for_to_iter_for_each_unknown_try_type);
+        check_assist_not_applicable(
+            convert_for_to_iter_for_each,
+            r#"
+fn f(x: Vec<i32>) -> Fallible {
+    for<|> v in x {
+        g(v)?;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_each_to_for() {
+        check_assist(
+            convert_iter_for_each_to_for,
+            r#"
+fn main() {
+    let mut x = vec![1, 2, 3];
+    x.iter_mut().for_each<|>(|v| {
+        if *v == 2 {
+            return;
+        }
+        *v += 1;
+    });
+}
+"#,
+            r#"
+fn main() {
+    let mut x = vec![1, 2, 3];
+    for v in &mut x {
+        if *v == 2 {
+            continue;
+        }
+        *v += 1;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_each_to_for_expression_body() {
+        check_assist(
+            convert_iter_for_each_to_for,
+            r#"
+fn main() {
+    let x = vec![1, 2, 3];
+    x.into_iter().for_each<|>(|v| println!("{}", v))
+}
+"#,
+            r#"
+fn main() {
+    let x = vec![1, 2, 3];
+    for v in x {
+        println!("{}", v);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn try_for_each_to_for() {
+        check_assist(
+            convert_iter_for_each_to_for,
+            r#"
+fn parse(items: Vec<String>) -> Result<(), Error> {
+    items.into_iter().try_for_each<|>(|item| -> Result<(), Error> {
+        if item.is_empty() {
+            return Ok(());
+        }
+        check(&item)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+"#,
+            r#"
+fn parse(items: Vec<String>) -> Result<(), Error> {
+    for item in items {
+        if item.is_empty() {
+            continue;
+        }
+        check(&item)?;
+    }
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn try_for_each_with_break_to_for() {
+        check_assist(
+            convert_iter_for_each_to_for,
+            r#"
+fn main() {
+    let _ = (0..10).try_for_each<|>(|v| {
+        if v == 5 {
+            return Err(());
+        }
+        Ok(())
+    });
+}
+"#,
+            r#"
+fn main() {
+    for v in 0..10 {
+        if v == 5 {
+            break;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_each_to_for_not_applicable_with_return_in_loop() {
+        mark::check!(convert_iter_for_each_to_for_return_in_loop);
+        check_assist_not_applicable(
+            convert_iter_for_each_to_for,
+            r#"
+fn main() {
+    let x = vec![vec![1]];
+    x.into_iter().for_each<|>(|row| {
+        for v in row {
+            return;
+        }
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_each_to_for_not_applicable_to_other_methods() {
+        check_assist_not_applicable(
+            convert_iter_for_each_to_for,
+            r#"
+struct S;
+impl S {
+    fn for_each(&self, f: impl Fn(i32)) {}
+}
+fn main() {
+    S.for_each<|>(|v| {});
+}
+"#,
+        );
+    }
+}
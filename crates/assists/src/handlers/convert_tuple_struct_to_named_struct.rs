@@ -0,0 +1,854 @@
+use hir::{Adt, HasSource, ImplDef, ModuleDef, VariantDef};
+use ide_db::{base_db::FileId, defs::Definition, search::Reference};
+use rustc_hash::FxHashMap;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, ArgListOwner, AstNode, GenericParamsOwner, NameOwner},
+    match_ast, NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode, TextRange, T,
+};
+use test_utils::mark;
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_tuple_struct_to_named_struct
+//
+// Converts a tuple struct or enum variant to one with named fields, updating its usages.
+//
+// ```
+// struct Point<|>(f32, f32);
+//
+// fn main() {
+//     let p = Point(1.0, 2.0);
+//     let Point(x, _) = p;
+//     let y = p.1;
+// }
+// ```
+// ->
+// ```
+// struct Point { field1: f32, field2: f32 }
+//
+// fn main() {
+//     let p = Point { field1: 1.0, field2: 2.0 };
+//     let Point { field1: x, field2: _ } = p;
+//     let y = p.field2;
+// }
+// ```
+pub(crate) fn convert_tuple_struct_to_named_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = StructOrVariant::at_cursor(ctx)?;
+    let field_list = match strukt.kind() {
+        ast::StructKind::Tuple(it) => it,
+        _ => return None,
+    };
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let fields = field_list.fields().collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    let (def, variant_def) = strukt.to_def(ctx)?;
+    let names = (1..=fields.len()).map(|it| format!("field{}", it)).collect::<Vec<_>>();
+    let usages = constructor_usages(ctx, def)?;
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_tuple_struct_to_named_struct", AssistKind::RefactorRewrite),
+        "Convert to named struct",
+        target,
+        |builder| {
+            let mut edits = WorkspaceEdits::default();
+            rename_field_accesses(ctx, &mut edits, variant_def, &names);
+            for (file_id, node) in usages {
+                let edits = edits.file(file_id);
+                match_ast! {
+                    match node {
+                        ast::CallExpr(call) => {
+                            let args = match call.arg_list() {
+                                Some(it) => it,
+                                None => continue,
+                            };
+                            let items = args
+                                .args()
+                                .zip(&names)
+                                .map(|(arg, name)| format!("{}: {}", name, edits.text_of(arg.syntax())))
+                                .collect();
+                            let list = rewrite_list(args.syntax(), items, true);
+                            edits.replace(args.syntax().text_range(), format!(" {}", list));
+                        },
+                        ast::TupleStructPat(pat) => {
+                            let path = match pat.path() {
+                                Some(it) => it,
+                                None => continue,
+                            };
+                            let pats = pat.fields().collect::<Vec<_>>();
+                            let rest = pats.iter().position(|it| matches!(it, ast::Pat::RestPat(_)));
+                            let mut items = Vec::new();
+                            for (idx, field_pat) in pats.iter().enumerate() {
+                                let field_idx = match rest {
+                                    Some(rest) if idx == rest => continue,
+                                    Some(rest) if idx > rest => names.len() - (pats.len() - idx),
+                                    _ => idx,
+                                };
+                                let name = match names.get(field_idx) {
+                                    Some(it) => it,
+                                    None => continue,
+                                };
+                                items.push(format!("{}: {}", name, edits.text_of(field_pat.syntax())));
+                            }
+                            if rest.is_some() {
+                                items.push("..".to_string());
+                            }
+                            let text = format!("{} {{ {} }}", path, items.join(", "));
+                            edits.replace(pat.syntax().text_range(), text);
+                        },
+                        ast::PathExpr(path_expr) => {
+                            // The constructor used as a function.
+                            let params = names.join(", ");
+                            let text = format!("|{}| {} {{ {} }}", params, path_expr, params);
+                            edits.replace(path_expr.syntax().text_range(), text);
+                        },
+                        _ => (),
+                    }
+                }
+            }
+
+            let items = fields
+                .iter()
+                .zip(&names)
+                .map(|(field, name)| {
+                    let ty_start = field.ty().map_or(field.syntax().text_range().end(), |it| {
+                        it.syntax().text_range().start()
+                    });
+                    let prefix = text_between(field.syntax(), field.syntax().text_range().start(), ty_start);
+                    let ty = field.ty().map_or(String::new(), |it| it.to_string());
+                    format!("{}{}: {}", prefix, name, ty)
+                })
+                .collect();
+            let list = rewrite_list(field_list.syntax(), items, true);
+            let file_edits = edits.file(ctx.frange.file_id);
+            match &strukt {
+                StructOrVariant::Struct(strukt) => {
+                    let where_clause = strukt.where_clause().map(|it| format!("{} ", it));
+                    let range = TextRange::new(
+                        field_list.syntax().text_range().start(),
+                        strukt.syntax().text_range().end(),
+                    );
+                    let text = format!(" {}{}", where_clause.unwrap_or_default(), list);
+                    file_edits.replace(range, text);
+                }
+                StructOrVariant::Variant(_) => {
+                    file_edits.replace(field_list.syntax().text_range(), format!(" {}", list));
+                }
+            }
+            edits.apply(builder);
+        },
+    )
+}
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts a struct or enum variant with named fields to a tuple one, updating its usages.
+//
+// ```
+// struct Point<|> { x: f32, y: f32 }
+//
+// fn main() {
+//     let p = Point { y: 2.0, x: 1.0 };
+//     let Point { x, .. } = p;
+//     let y = p.y;
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// fn main() {
+//     let p = Point(1.0, 2.0);
+//     let Point(x, ..) = p;
+//     let y = p.1;
+// }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = StructOrVariant::at_cursor(ctx)?;
+    let field_list = match strukt.kind() {
+        ast::StructKind::Record(it) => it,
+        _ => return None,
+    };
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let fields = field_list.fields().collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    let names =
+        fields.iter().map(|it| it.name().map(|it| it.to_string())).collect::<Option<Vec<_>>>()?;
+    let (def, variant_def) = strukt.to_def(ctx)?;
+    let usages = constructor_usages(ctx, def)?;
+    let has_spread = usages.iter().any(|(_, node)| {
+        ast::RecordExpr::cast(node.clone())
+            .and_then(|it| it.record_expr_field_list())
+            .map_or(false, |it| it.spread().is_some())
+    });
+    if has_spread {
+        mark::hit!(convert_named_struct_to_tuple_struct_spread);
+        return None;
+    }
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_named_struct_to_tuple_struct", AssistKind::RefactorRewrite),
+        "Convert to tuple struct",
+        target,
+        |builder| {
+            let indices = (0..names.len()).map(|it| it.to_string()).collect::<Vec<_>>();
+            let mut edits = WorkspaceEdits::default();
+            rename_field_accesses(ctx, &mut edits, variant_def, &indices);
+            for (file_id, node) in usages {
+                let edits = edits.file(file_id);
+                match_ast! {
+                    match node {
+                        ast::RecordExpr(record) => {
+                            let list = match record.record_expr_field_list() {
+                                Some(it) => it,
+                                None => continue,
+                            };
+                            let record_fields = list.fields().collect::<Vec<_>>();
+                            let items = names
+                                .iter()
+                                .filter_map(|name| {
+                                    let field = record_fields.iter().find(|it| {
+                                        it.field_name().map_or(false, |it| it.text() == name.as_str())
+                                    })?;
+                                    match (field.name_ref(), field.expr()) {
+                                        (Some(_), Some(expr)) => Some(edits.text_of(expr.syntax())),
+                                        _ => Some(name.clone()),
+                                    }
+                                })
+                                .collect();
+                            let list = rewrite_list(list.syntax(), items, false);
+                            let start = match record.path() {
+                                Some(it) => it.syntax().text_range().end(),
+                                None => continue,
+                            };
+                            let range = TextRange::new(start, record.syntax().text_range().end());
+                            edits.replace(range, list);
+                        },
+                        ast::RecordPat(pat) => {
+                            let (path, list) = match (pat.path(), pat.record_pat_field_list()) {
+                                (Some(path), Some(list)) => (path, list),
+                                _ => continue,
+                            };
+                            let record_fields = list.fields().collect::<Vec<_>>();
+                            let mut items = names
+                                .iter()
+                                .map(|name| {
+                                    let field = record_fields.iter().find(|it| {
+                                        it.field_name().map_or(false, |it| it.to_string() == *name)
+                                    });
+                                    match field.and_then(|it| it.pat()) {
+                                        Some(pat) => Some(edits.text_of(pat.syntax())),
+                                        None => None,
+                                    }
+                                })
+                                .collect::<Vec<_>>();
+                            let has_rest = list.dotdot_token().is_some();
+                            if has_rest {
+                                while let Some(None) = items.last() {
+                                    items.pop();
+                                }
+                            }
+                            let mut items = items
+                                .into_iter()
+                                .map(|it| it.unwrap_or_else(|| "_".to_string()))
+                                .collect::<Vec<_>>();
+                            if has_rest && items.len() < names.len() {
+                                items.push("..".to_string());
+                            }
+                            let text = format!("{}({})", path, items.join(", "));
+                            edits.replace(pat.syntax().text_range(), text);
+                        },
+                        _ => (),
+                    }
+                }
+            }
+
+            let items = fields
+                .iter()
+                .map(|field| {
+                    let name_start = field.name().map_or(field.syntax().text_range().start(), |it| {
+                        it.syntax().text_range().start()
+                    });
+                    let prefix = text_between(field.syntax(), field.syntax().text_range().start(), name_start);
+                    let ty = field.ty().map_or(String::new(), |it| it.to_string());
+                    format!("{}{}", prefix, ty)
+                })
+                .collect();
+            let list = rewrite_list(field_list.syntax(), items, false);
+            let file_edits = edits.file(ctx.frange.file_id);
+            match &strukt {
+                StructOrVariant::Struct(strukt) => {
+                    let where_clause = strukt.where_clause();
+                    let first = match &where_clause {
+                        Some(it) => it.syntax().clone(),
+                        None => field_list.syntax().clone(),
+                    };
+                    let range = TextRange::new(
+                        leading_whitespace_start(&first),
+                        strukt.syntax().text_range().end(),
+                    );
+                    let where_clause = where_clause.map(|it| format!(" {}", it));
+                    let text = format!("{}{};", list, where_clause.unwrap_or_default());
+                    file_edits.replace(range, text);
+                }
+                StructOrVariant::Variant(_) => {
+                    let range = TextRange::new(
+                        leading_whitespace_start(field_list.syntax()),
+                        field_list.syntax().text_range().end(),
+                    );
+                    file_edits.replace(range, list);
+                }
+            }
+            edits.apply(builder);
+        },
+    )
+}
+
+enum StructOrVariant {
+    Struct(ast::Struct),
+    Variant(ast::Variant),
+}
+
+impl StructOrVariant {
+    fn at_cursor(ctx: &AssistContext) -> Option<StructOrVariant> {
+        match ctx.find_node_at_offset::<ast::Variant>() {
+            Some(it) => Some(StructOrVariant::Variant(it)),
+            None => ctx.find_node_at_offset::<ast::Struct>().map(StructOrVariant::Struct),
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            StructOrVariant::Struct(it) => it.syntax(),
+            StructOrVariant::Variant(it) => it.syntax(),
+        }
+    }
+
+    fn kind(&self) -> ast::StructKind {
+        match self {
+            StructOrVariant::Struct(it) => it.kind(),
+            StructOrVariant::Variant(it) => it.kind(),
+        }
+    }
+
+    fn to_def(&self, ctx: &AssistContext) -> Option<(ModuleDef, VariantDef)> {
+        match self {
+            StructOrVariant::Struct(it) => {
+                let def = ctx.sema.to_def(it)?;
+                Some((ModuleDef::Adt(Adt::Struct(def)), VariantDef::Struct(def)))
+            }
+            StructOrVariant::Variant(it) => {
+                let def = ctx.sema.to_def(it)?;
+                Some((ModuleDef::EnumVariant(def), VariantDef::EnumVariant(def)))
+            }
+        }
+    }
+}
+
+/// Text edits across the workspace, where an edit may take over the ones nested in it.
+#[derive(Default)]
+struct WorkspaceEdits {
+    files: FxHashMap<FileId, FileEdits>,
+}
+
+impl WorkspaceEdits {
+    fn file(&mut self, file_id: FileId) -> &mut FileEdits {
+        self.files.entry(file_id).or_default()
+    }
+
+    fn apply(self, builder: &mut AssistBuilder) {
+        for (file_id, edits) in self.files {
+            builder.edit_file(file_id);
+            for (range, text) in edits.edits {
+                builder.replace(range, text);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct FileEdits {
+    edits: Vec<(TextRange, String)>,
+}
+
+impl FileEdits {
+    fn replace(&mut self, range: TextRange, text: String) {
+        self.edits.push((range, text));
+    }
+
+    /// The text of `node` with the edits inside of it applied, those edits are dropped.
+    fn text_of(&mut self, node: &SyntaxNode) -> String {
+        let range = node.text_range();
+        let mut text = node.to_string();
+        let mut nested = Vec::new();
+        self.edits.retain(|(it, text)| {
+            if range.contains_range(*it) {
+                nested.push((*it, text.clone()));
+                false
+            } else {
+                true
+            }
+        });
+        nested.sort_by_key(|(it, _)| it.start());
+        for (it, replacement) in nested.into_iter().rev() {
+            let it = it - range.start();
+            text.replace_range(usize::from(it.start())..usize::from(it.end()), &replacement);
+        }
+        text
+    }
+}
+
+/// Constructor calls, patterns and record literals of `def`, innermost first.
+fn constructor_usages(ctx: &AssistContext, def: ModuleDef) -> Option<Vec<(FileId, SyntaxNode)>> {
+    let mut res = Vec::new();
+    for reference in Definition::ModuleDef(def).usages(&ctx.sema).all() {
+        let source_file = ctx.sema.parse(reference.file_range.file_id);
+        let name_ref =
+            find_node_at_range::<ast::NameRef>(source_file.syntax(), reference.file_range.range)?;
+        let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+        if let Some(node) = constructor_of(&path) {
+            res.push((reference.file_range.file_id, node));
+        }
+    }
+    // `Self` and `Self::Variant` paths inside of the impls are not always found by the search.
+    let db = ctx.db();
+    let (adt, variant_name) = match def {
+        ModuleDef::Adt(adt) => (adt, None),
+        ModuleDef::EnumVariant(variant) => {
+            (Adt::Enum(variant.parent_enum(db)), Some(variant.name(db).to_string()))
+        }
+        _ => return Some(res),
+    };
+    let impls = ImplDef::all_in_crate(db, adt.module(db).krate())
+        .into_iter()
+        .filter(|it| it.target_ty(db).as_adt() == Some(adt));
+    for impl_ in impls {
+        let source = impl_.source(db);
+        let file_id = source.file_id.original_file(db);
+        for path in source.value.syntax().descendants().filter_map(ast::Path::cast) {
+            let is_self =
+                |path: &ast::Path| path.qualifier().is_none() && path.syntax().text() == "Self";
+            let matches = match (&variant_name, path.qualifier()) {
+                (None, _) => is_self(&path),
+                (Some(name), Some(qualifier)) => {
+                    is_self(&qualifier)
+                        && path.segment().map_or(false, |it| it.syntax().text() == name.as_str())
+                }
+                (Some(_), None) => false,
+            };
+            if !matches {
+                continue;
+            }
+            if let Some(node) = constructor_of(&path) {
+                if !res.contains(&(file_id, node.clone())) {
+                    res.push((file_id, node));
+                }
+            }
+        }
+    }
+    res.sort_by_key(|(_, node)| node.text_range().len());
+    Some(res)
+}
+
+fn constructor_of(path: &ast::Path) -> Option<SyntaxNode> {
+    let parent = path.syntax().parent()?;
+    match parent.kind() {
+        SyntaxKind::PATH_EXPR => {
+            let call = parent.parent().and_then(ast::CallExpr::cast);
+            match call {
+                Some(call) if call.expr().map(|it| it.syntax().clone()) == Some(parent.clone()) => {
+                    Some(call.syntax().clone())
+                }
+                _ => Some(parent),
+            }
+        }
+        SyntaxKind::TUPLE_STRUCT_PAT | SyntaxKind::RECORD_EXPR | SyntaxKind::RECORD_PAT => {
+            Some(parent)
+        }
+        _ => None,
+    }
+}
+
+/// Renames the field in every `x.field` expression.
+fn rename_field_accesses(
+    ctx: &AssistContext,
+    edits: &mut WorkspaceEdits,
+    def: VariantDef,
+    new_names: &[String],
+) {
+    for (field, new_name) in def.fields(ctx.db()).into_iter().zip(new_names) {
+        for reference in Definition::Field(field).usages(&ctx.sema).all() {
+            if let Some(name_ref) = field_access(ctx, &reference) {
+                let range = name_ref.syntax().text_range();
+                edits.file(reference.file_range.file_id).replace(range, new_name.clone());
+            }
+        }
+    }
+}
+
+fn field_access(ctx: &AssistContext, reference: &Reference) -> Option<ast::NameRef> {
+    let source_file = ctx.sema.parse(reference.file_range.file_id);
+    let name_ref =
+        find_node_at_range::<ast::NameRef>(source_file.syntax(), reference.file_range.range)?;
+    ast::FieldExpr::cast(name_ref.syntax().parent()?)?;
+    Some(name_ref)
+}
+
+/// Swaps the delimiters of `list` and replaces its items, keeping the layout.
+fn rewrite_list(list: &SyntaxNode, items: Vec<String>, to_braces: bool) -> String {
+    let (open, close) = if to_braces { ("{", "}") } else { ("(", ")") };
+    let elements = list.children_with_tokens().collect::<Vec<_>>();
+    let is_whitespace = |element: Option<&SyntaxElement>| {
+        element.map(|it| it.kind()) == Some(SyntaxKind::WHITESPACE)
+    };
+    let is_delimiter = |element: Option<&SyntaxElement>| {
+        matches!(
+            element.map(|it| it.kind()),
+            Some(T!['(']) | Some(T![')']) | Some(T!['{']) | Some(T!['}'])
+        )
+    };
+    let mut items = items.into_iter();
+    let mut res = String::new();
+    for (idx, element) in elements.iter().enumerate() {
+        let prev = idx.checked_sub(1).and_then(|it| elements.get(it));
+        let next = elements.get(idx + 1);
+        match element {
+            NodeOrToken::Node(_) => {
+                if let Some(item) = items.next() {
+                    res.push_str(&item)
+                }
+            }
+            NodeOrToken::Token(token) => match token.kind() {
+                T!['('] | T!['{'] => {
+                    res.push_str(open);
+                    if to_braces && !is_whitespace(next) {
+                        res.push(' ');
+                    }
+                }
+                T![')'] | T!['}'] => {
+                    if to_braces && !is_whitespace(prev) {
+                        res.push(' ');
+                    }
+                    res.push_str(close);
+                }
+                SyntaxKind::WHITESPACE
+                    if !to_braces
+                        && !token.text().contains('\n')
+                        && (is_delimiter(prev) || is_delimiter(next)) => {}
+                _ => res.push_str(token.text()),
+            },
+        }
+    }
+    res
+}
+
+fn text_between(node: &SyntaxNode, start: syntax::TextSize, end: syntax::TextSize) -> String {
+    let offset = node.text_range().start();
+    node.text().slice(TextRange::new(start - offset, end - offset)).to_string()
+}
+
+fn leading_whitespace_start(node: &SyntaxNode) -> syntax::TextSize {
+    match node.prev_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+        _ => node.text_range().start(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn tuple_struct_to_named() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+pub struct Inner<|>(pub u32, #[allow(dead_code)] String);
+
+impl Inner {
+    fn new(a: u32) -> Self {
+        Self(a, String::new())
+    }
+    fn a(&self) -> u32 {
+        let Self(a, ..) = self;
+        self.0 + *a
+    }
+}
+
+fn f(inner: Inner) {
+    let x = Inner(inner.0, inner.1);
+    let v: Vec<Inner> = vec![1].into_iter().map(|a| (a, String::new())).map(|(a, s)| Inner(a, s)).collect();
+    match x {
+        Inner(1, _) => (),
+        Inner(.., s) => (),
+    }
+}
+"#,
+            r#"
+pub struct Inner { pub field1: u32, #[allow(dead_code)] field2: String }
+
+impl Inner {
+    fn new(a: u32) -> Self {
+        Self { field1: a, field2: String::new() }
+    }
+    fn a(&self) -> u32 {
+        let Self { field1: a, .. } = self;
+        self.field1 + *a
+    }
+}
+
+fn f(inner: Inner) {
+    let x = Inner { field1: inner.field1, field2: inner.field2 };
+    let v: Vec<Inner> = vec![1].into_iter().map(|a| (a, String::new())).map(|(a, s)| Inner { field1: a, field2: s }).collect();
+    match x {
+        Inner { field1: 1, field2: _ } => (),
+        Inner { field2: s, .. } => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_struct_to_named_keeps_layout_and_where_clause() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Wrapper<|><T>(
+    T,
+    Option<T>,
+)
+where
+    T: Clone;
+
+fn f() {
+    let w = Wrapper(
+        1,
+        Wrapper(2, None).0.into(),
+    );
+    let g = Wrapper;
+}
+"#,
+            r#"
+struct Wrapper<T> where
+    T: Clone {
+    field1: T,
+    field2: Option<T>,
+}
+
+fn f() {
+    let w = Wrapper {
+        field1: 1,
+        field2: Wrapper { field1: 2, field2: None }.field1.into(),
+    };
+    let g = |field1, field2| Wrapper { field1, field2 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_variant_to_named() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+enum Shape {
+    Circle<|>(f32),
+    Square(f32),
+}
+
+impl Shape {
+    fn area(&self) -> f32 {
+        match self {
+            Self::Circle(r) => r * r,
+            Shape::Square(s) => s * s,
+        }
+    }
+}
+"#,
+            r#"
+enum Shape {
+    Circle { field1: f32 },
+    Square(f32),
+}
+
+impl Shape {
+    fn area(&self) -> f32 {
+        match self {
+            Self::Circle { field1: r } => r * r,
+            Shape::Square(s) => s * s,
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_struct_to_named_in_other_files() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+//- /main.rs
+mod other;
+pub struct S<|>(pub u32);
+//- /other.rs
+use crate::S;
+fn f(s: S) -> S {
+    S(s.0)
+}
+"#,
+            r#"
+//- /main.rs
+mod other;
+pub struct S { pub field1: u32 }
+//- /other.rs
+use crate::S;
+fn f(s: S) -> S {
+    S { field1: s.field1 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_named_struct() {
+        check_assist_not_applicable(convert_tuple_struct_to_named_struct, "struct S<|> { a: u32 }");
+        check_assist_not_applicable(convert_tuple_struct_to_named_struct, "struct S<|>();");
+    }
+
+    #[test]
+    fn not_applicable_in_field_list() {
+        check_assist_not_applicable(convert_tuple_struct_to_named_struct, "struct S(<|>u32);");
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, "struct S { <|>a: u32 }");
+    }
+
+    #[test]
+    fn named_struct_to_tuple() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+pub struct Inner<|> {
+    pub a: u32,
+    b: String,
+}
+
+impl Inner {
+    fn new(a: u32) -> Self {
+        Self { b: String::new(), a }
+    }
+}
+
+fn f(inner: Inner) {
+    let x = Inner { a: inner.a, b: inner.b.clone() };
+    match x {
+        Inner { a: 1, .. } => (),
+        Inner { b, .. } => (),
+        Inner { ref a, b: _ } => (),
+    }
+}
+"#,
+            r#"
+pub struct Inner(
+    pub u32,
+    String,
+);
+
+impl Inner {
+    fn new(a: u32) -> Self {
+        Self(a, String::new())
+    }
+}
+
+fn f(inner: Inner) {
+    let x = Inner(inner.0, inner.1.clone());
+    match x {
+        Inner(1, ..) => (),
+        Inner(_, b) => (),
+        Inner(ref a, _) => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn named_variant_to_tuple() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+enum E {
+    V<|> { x: i32 },
+}
+
+fn f() -> E {
+    let x = 1;
+    let e = E::V { x };
+    match e {
+        E::V { x: y } => E::V { x: y },
+    }
+}
+"#,
+            r#"
+enum E {
+    V(i32),
+}
+
+fn f() -> E {
+    let x = 1;
+    let e = E::V(x);
+    match e {
+        E::V(y) => E::V(y),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn named_struct_to_tuple_with_where_clause() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct S<|><T> where T: Clone { value: T }
+"#,
+            r#"
+struct S<T>(T) where T: Clone;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_struct_update() {
+        mark::check!(convert_named_struct_to_tuple_struct_spread);
+        check_assist_not_applicable(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct S<|> { a: u32, b: u32 }
+fn f(s: S) -> S {
+    S { a: 1, ..s }
+}
+"#,
+        );
+    }
+}
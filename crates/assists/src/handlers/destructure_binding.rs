@@ -0,0 +1,526 @@
+use hir::{Access, Adt, HasVisibility, ModuleDef, ScopeDef, StructKind};
+use ide_db::{defs::Definition, search::Reference};
+use rustc_hash::FxHashSet;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, AstNode, NameOwner},
+};
+use test_utils::mark;
+
+use crate::{
+    assist_context::AssistBuilder, utils::mod_path_to_ast, AssistContext, AssistId, AssistKind,
+    Assists,
+};
+
+// Assist: destructure_tuple_binding
+//
+// Destructures a tuple binding into its fields, replacing the field accesses.
+//
+// ```
+// fn main() {
+//     let <|>t = (1, 2);
+//     let v = t.0 + t.1;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let (t_0, t_1) = (1, 2);
+//     let v = t_0 + t_1;
+// }
+// ```
+pub(crate) fn destructure_tuple_binding(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let binding = Binding::at_cursor(ctx)?;
+    let field_count = binding.ty.tuple_fields(ctx.db()).len();
+    if field_count == 0 || binding.ty.as_adt().is_some() {
+        return None;
+    }
+    let name = binding.pat.name()?.to_string();
+    let fields = (0..field_count)
+        .map(|idx| Field { access: idx.to_string(), binding: format!("{}_{}", name, idx) })
+        .collect::<Vec<_>>();
+    let accesses = binding.field_accesses(ctx, &fields)?;
+
+    let target = binding.pat.syntax().text_range();
+    acc.add(
+        AssistId("destructure_tuple_binding", AssistKind::RefactorRewrite),
+        "Destructure tuple binding",
+        target,
+        |builder| {
+            let pats = (0..fields.len()).map(|it| binding.field_pat(&fields, it, &accesses));
+            let pat = format!("({})", pats.collect::<Vec<_>>().join(", "));
+            builder.replace(binding.pat.syntax().text_range(), pat);
+            binding.replace_accesses(builder, &fields, &accesses);
+        },
+    )
+}
+
+// Assist: destructure_struct_binding
+//
+// Destructures a struct binding into its fields, replacing the field accesses.
+//
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn main() {
+//     let <|>p = Point { x: 1, y: 2 };
+//     let v = p.x + p.y;
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn main() {
+//     let Point { x, y } = Point { x: 1, y: 2 };
+//     let v = x + y;
+// }
+// ```
+pub(crate) fn destructure_struct_binding(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let binding = Binding::at_cursor(ctx)?;
+    let strukt = match binding.ty.as_adt()? {
+        Adt::Struct(it) => it,
+        _ => return None,
+    };
+    let db = ctx.db();
+    let module = ctx.sema.scope(binding.pat.syntax()).module()?;
+    let kind = strukt.kind(db);
+    let (visible, hidden): (Vec<_>, Vec<_>) =
+        strukt.fields(db).into_iter().partition(|it| it.is_visible_from(db, module));
+    if kind == StructKind::Unit || visible.is_empty() {
+        return None;
+    }
+    if kind == StructKind::Tuple && !hidden.is_empty() {
+        mark::hit!(destructure_struct_binding_private_tuple_field);
+        return None;
+    }
+    let name = binding.pat.name()?.to_string();
+    let mut fields = visible
+        .iter()
+        .map(|field| {
+            let access = field.name(db).to_string();
+            let binding = match kind {
+                StructKind::Tuple => format!("{}_{}", name, access),
+                _ => access.clone(),
+            };
+            Field { access, binding }
+        })
+        .collect::<Vec<_>>();
+    let accesses = binding.field_accesses(ctx, &fields)?;
+    // Don't shadow the other locals used next to the field accesses.
+    let locals = locals_in_scope(ctx, &accesses);
+    for field in fields.iter_mut() {
+        if field.binding != name && locals.contains(&field.binding) {
+            field.binding = format!("{}_{}", name, field.access);
+        }
+    }
+    let path = module
+        .find_use_path(db, ModuleDef::Adt(Adt::Struct(strukt)))
+        .map(|it| mod_path_to_ast(&it).to_string())
+        .unwrap_or_else(|| strukt.name(db).to_string());
+
+    let target = binding.pat.syntax().text_range();
+    acc.add(
+        AssistId("destructure_struct_binding", AssistKind::RefactorRewrite),
+        "Destructure struct binding",
+        target,
+        |builder| {
+            let pat = match kind {
+                StructKind::Tuple => {
+                    let pats =
+                        (0..fields.len()).map(|it| binding.field_pat(&fields, it, &accesses));
+                    format!("{}({})", path, pats.collect::<Vec<_>>().join(", "))
+                }
+                _ => {
+                    let mut pats = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, it)| {
+                            let pat = binding.field_pat(&fields, idx, &accesses);
+                            if it.binding == it.access {
+                                pat
+                            } else {
+                                format!("{}: {}", it.access, pat)
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    if !hidden.is_empty() {
+                        pats.push("..".to_string());
+                    }
+                    format!("{} {{ {} }}", path, pats.join(", "))
+                }
+            };
+            builder.replace(binding.pat.syntax().text_range(), pat);
+            binding.replace_accesses(builder, &fields, &accesses);
+        },
+    )
+}
+
+struct Binding {
+    pat: ast::IdentPat,
+    local: hir::Local,
+    /// The type of the destructured value, without the reference.
+    ty: hir::Type,
+    /// Whether the new bindings are references, `Some(true)` for mutable ones.
+    by_ref: Option<bool>,
+}
+
+struct Field {
+    /// The name of the field in `x.field`.
+    access: String,
+    binding: String,
+}
+
+struct FieldAccess {
+    field_expr: ast::FieldExpr,
+    field: usize,
+    is_write: bool,
+}
+
+impl Binding {
+    fn at_cursor(ctx: &AssistContext) -> Option<Binding> {
+        let pat = ctx.find_node_at_offset::<ast::IdentPat>()?;
+        if pat.at_token().is_some() || pat.pat().is_some() {
+            return None;
+        }
+        let local = ctx.sema.to_def(&pat)?;
+        let ty = ctx.sema.type_of_pat(&ast::Pat::IdentPat(pat.clone()))?;
+        let (ty, by_ref) = match (pat.ref_token(), ty.remove_ref()) {
+            (Some(_), inner) => (inner.unwrap_or(ty), Some(pat.mut_token().is_some())),
+            (None, Some(inner)) => {
+                let is_mut = ty.is_mutable_reference();
+                (inner, Some(is_mut))
+            }
+            (None, None) => (ty, None),
+        };
+        Some(Binding { pat, local, ty, by_ref })
+    }
+
+    /// Every usage of the binding, unless it is used as a whole somewhere.
+    fn field_accesses(&self, ctx: &AssistContext, fields: &[Field]) -> Option<Vec<FieldAccess>> {
+        let usages = Definition::Local(self.local).usages(&ctx.sema).all();
+        let res = usages
+            .iter()
+            .map(|reference| self.field_access(ctx, reference, fields))
+            .collect::<Option<Vec<_>>>();
+        if res.is_none() {
+            mark::hit!(destructure_binding_used_as_whole);
+        }
+        res
+    }
+
+    fn field_access(
+        &self,
+        ctx: &AssistContext,
+        reference: &Reference,
+        fields: &[Field],
+    ) -> Option<FieldAccess> {
+        let source_file = ctx.sema.parse(reference.file_range.file_id);
+        let name_ref =
+            find_node_at_range::<ast::NameRef>(source_file.syntax(), reference.file_range.range)?;
+        let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+        let field_expr = ast::FieldExpr::cast(path_expr.syntax().parent()?)?;
+        let field_name = field_expr.name_ref()?.to_string();
+        let field = fields.iter().position(|it| it.access == field_name)?;
+        let is_write = is_write(ctx, &field_expr);
+        Some(FieldAccess { field_expr, field, is_write })
+    }
+
+    fn field_pat(&self, fields: &[Field], field: usize, accesses: &[FieldAccess]) -> String {
+        let binding = &fields[field].binding;
+        match self.by_ref {
+            Some(_) if self.pat.ref_token().is_none() => binding.to_string(),
+            Some(true) => format!("ref mut {}", binding),
+            Some(false) => format!("ref {}", binding),
+            None => {
+                let is_written = accesses.iter().any(|it| it.is_write && it.field == field);
+                if self.pat.mut_token().is_some() && is_written {
+                    format!("mut {}", binding)
+                } else {
+                    binding.to_string()
+                }
+            }
+        }
+    }
+
+    fn replace_accesses(
+        &self,
+        builder: &mut AssistBuilder,
+        fields: &[Field],
+        accesses: &[FieldAccess],
+    ) {
+        for access in accesses {
+            let binding = &fields[access.field].binding;
+            let field_expr = access.field_expr.syntax();
+            let parent = field_expr.parent();
+            if self.by_ref.is_none() {
+                builder.replace(field_expr.text_range(), binding);
+                continue;
+            }
+            // The new binding is a reference, the field access was not.
+            let auto_derefs = parent.clone().map_or(false, |it| {
+                ast::FieldExpr::can_cast(it.kind())
+                    || ast::IndexExpr::cast(it.clone())
+                        .and_then(|it| it.base())
+                        .map_or(false, |it| it.syntax() == field_expr)
+                    || ast::MethodCallExpr::cast(it)
+                        .and_then(|it| it.receiver())
+                        .map_or(false, |it| it.syntax() == field_expr)
+            });
+            let ref_expr = parent.and_then(ast::RefExpr::cast).filter(|it| {
+                self.by_ref == Some(it.mut_token().is_some()) && it.raw_token().is_none()
+            });
+            if let Some(ref_expr) = ref_expr {
+                builder.replace(ref_expr.syntax().text_range(), binding);
+            } else if auto_derefs {
+                builder.replace(field_expr.text_range(), binding);
+            } else {
+                builder.replace(field_expr.text_range(), format!("*{}", binding));
+            }
+        }
+    }
+}
+
+fn locals_in_scope(ctx: &AssistContext, accesses: &[FieldAccess]) -> FxHashSet<String> {
+    let mut res = FxHashSet::default();
+    for access in accesses {
+        ctx.sema.scope(access.field_expr.syntax()).process_all_names(&mut |name, def| {
+            if let ScopeDef::Local(_) = def {
+                res.insert(name.to_string());
+            }
+        });
+    }
+    res
+}
+
+/// Whether the field accessed by `field_expr` is modified, which needs a `mut` binding.
+fn is_write(ctx: &AssistContext, field_expr: &ast::FieldExpr) -> bool {
+    // Walk up the place expression, `x.0.a[1]` modifies `x.0` as well.
+    let mut expr = field_expr.syntax().clone();
+    while let Some(parent) = expr.parent() {
+        let is_base = ast::FieldExpr::cast(parent.clone())
+            .and_then(|it| it.expr())
+            .or_else(|| ast::IndexExpr::cast(parent.clone()).and_then(|it| it.base()))
+            .map_or(false, |it| it.syntax() == &expr);
+        if !is_base {
+            break;
+        }
+        expr = parent;
+    }
+    let range = expr.text_range();
+    let parent = match expr.parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+        return bin_expr.op_kind().map_or(false, |it| it.is_assignment())
+            && bin_expr.lhs().map(|it| it.syntax().text_range()) == Some(range);
+    }
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        return ref_expr.mut_token().is_some();
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        if call.receiver().map(|it| it.syntax().text_range()) != Some(range) {
+            return false;
+        }
+        let self_param = ctx.sema.resolve_method_call(&call).and_then(|it| it.self_param(ctx.db()));
+        return self_param.map_or(false, |it| matches!(it.access(ctx.db()), Access::Exclusive));
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn destructure_tuple_with_mut() {
+        check_assist(
+            destructure_tuple_binding,
+            r#"
+struct S;
+impl S {
+    fn push(&mut self) {}
+    fn len(&self) -> usize { 0 }
+}
+
+fn f() {
+    let mut <|>t = (1, S, 2);
+    t.0 += 1;
+    t.1.push();
+    let v = t.2 + t.1.len();
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn push(&mut self) {}
+    fn len(&self) -> usize { 0 }
+}
+
+fn f() {
+    let (mut t_0, mut t_1, t_2) = (1, S, 2);
+    t_0 += 1;
+    t_1.push();
+    let v = t_2 + t_1.len();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_tuple_reference() {
+        check_assist(
+            destructure_tuple_binding,
+            r#"
+struct S;
+impl S {
+    fn len(&self) -> usize { 0 }
+}
+
+fn f(<|>p: &(usize, S)) {
+    let a = p.0 + 1;
+    let b = &p.1;
+    let c = p.1.len();
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn len(&self) -> usize { 0 }
+}
+
+fn f((p_0, p_1): &(usize, S)) {
+    let a = *p_0 + 1;
+    let b = p_1;
+    let c = p_1.len();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_ref_binding() {
+        check_assist(
+            destructure_tuple_binding,
+            r#"
+fn f() {
+    let ref <|>t = (1, 2);
+    let v = t.0;
+}
+"#,
+            r#"
+fn f() {
+    let (ref t_0, ref t_1) = (1, 2);
+    let v = *t_0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_used_as_whole() {
+        mark::check!(destructure_binding_used_as_whole);
+        check_assist_not_applicable(
+            destructure_tuple_binding,
+            r#"
+fn g(t: (i32, i32)) {}
+fn f() {
+    let <|>t = (1, 2);
+    let v = t.0;
+    g(t);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_struct_with_private_fields() {
+        check_assist(
+            destructure_struct_binding,
+            r#"
+mod m {
+    pub struct P { pub x: i32, pub y: i32, z: i32 }
+    impl P {
+        pub fn new() -> P { P { x: 0, y: 0, z: 0 } }
+    }
+}
+
+fn f() {
+    let y = 1;
+    let <|>p = m::P::new();
+    let v = p.x + p.y + y;
+}
+"#,
+            r#"
+mod m {
+    pub struct P { pub x: i32, pub y: i32, z: i32 }
+    impl P {
+        pub fn new() -> P { P { x: 0, y: 0, z: 0 } }
+    }
+}
+
+fn f() {
+    let y = 1;
+    let m::P { x, y: p_y, .. } = m::P::new();
+    let v = x + p_y + y;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_tuple_struct() {
+        check_assist(
+            destructure_struct_binding,
+            r#"
+struct T(i32, i32);
+
+fn f() {
+    let mut <|>t = T(1, 2);
+    t.1 = t.0;
+}
+"#,
+            r#"
+struct T(i32, i32);
+
+fn f() {
+    let T(t_0, mut t_1) = T(1, 2);
+    t_1 = t_0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_tuple_struct_with_private_fields() {
+        mark::check!(destructure_struct_binding_private_tuple_field);
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            r#"
+mod m {
+    pub struct T(pub i32, i32);
+    pub fn t() -> T { T(1, 2) }
+}
+
+fn f() {
+    let <|>t = m::t();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_other_kinds() {
+        check_assist_not_applicable(
+            destructure_tuple_binding,
+            "struct S { a: i32 } fn f(<|>s: S) {}",
+        );
+        check_assist_not_applicable(destructure_struct_binding, "fn f() { let <|>t = (1, 2); }");
+        check_assist_not_applicable(destructure_tuple_binding, "fn f() { let <|>t = 1; }");
+    }
+}
@@ -0,0 +1,1866 @@
+use std::ops::Range;
+
+use either::Either;
+use hir::{Access, HirDisplay, Local, Module, PathResolution, Semantics};
+use ide_db::RootDatabase;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, BinOp},
+    match_ast, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, T,
+};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: extract_function
+//
+// Extracts the selected statements or expression into a new function. Locals used by the
+// selection become parameters, locals defined by it and used afterwards are returned.
+//
+// ```
+// fn main() {
+//     let n = 1;
+//     <|>let m = n + 2;
+//     let k = m * n;<|>
+//     let g = 3;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let n = 1;
+//     fun_name(n);
+//     let g = 3;
+// }
+//
+// fn $0fun_name(n: i32) {
+//     let m = n + 2;
+//     let k = m * n;
+// }
+// ```
+pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if ctx.frange.range.is_empty() {
+        return None;
+    }
+    let node = match ctx.covering_element() {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) if it.kind() == SyntaxKind::COMMENT => return None,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let body = FunctionBody::from_range(&node, ctx.frange.range)?;
+
+    let outer_fn = body.node().ancestors().find_map(ast::Fn::cast)?;
+    let fn_body = outer_fn.body()?;
+    let module = ctx.sema.scope(body.node()).module()?;
+    let usages = collect_usages(&ctx.sema, &fn_body);
+
+    let params = body.params(ctx, &usages);
+    let self_param = match params.iter().position(|it| it.local.is_self(ctx.db())) {
+        Some(idx) => Some(params[idx].clone()),
+        None => None,
+    };
+    let params = params.into_iter().filter(|it| !it.local.is_self(ctx.db())).collect::<Vec<_>>();
+
+    let container = Container::new(&outer_fn)?;
+    if self_param.is_some() && !matches!(container, Container::Impl) {
+        mark::hit!(extract_function_self_outside_of_impl);
+        return None;
+    }
+
+    let ret = body.ret_value(ctx, &usages)?;
+    let flows = body.escaping_flows(ctx)?;
+    let handler = FlowHandler::new(&flows, &ret);
+
+    let target = body.text_range();
+    acc.add(
+        AssistId("extract_function", AssistKind::RefactorExtract),
+        "Extract into function",
+        target,
+        move |builder| {
+            let fun = Function { name: "fun_name", self_param, params, ret, handler, flows, body };
+
+            let call_indent = IndentLevel::from_node(&fun.body.first_node());
+            builder.replace(target, fun.make_call(ctx, &container, call_indent));
+
+            let anchor = container.anchor(&outer_fn);
+            let indent = IndentLevel::from_node(&anchor);
+            let offset = anchor.text_range().end();
+            let fn_def = fun.make_def(ctx, module, &usages, indent);
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(
+                    cap,
+                    offset,
+                    fn_def.replacen("fn fun_name", "fn $0fun_name", 1),
+                ),
+                None => builder.insert(offset, fn_def),
+            }
+        },
+    )
+}
+
+/// The selected code that becomes the body of the new function.
+#[derive(Debug)]
+enum FunctionBody {
+    /// A single expression, replaced by the call.
+    Expr(ast::Expr),
+    /// A range of statements (and possibly the tail expression) of a block.
+    Span { parent: ast::BlockExpr, text_range: TextRange },
+}
+
+impl FunctionBody {
+    fn from_range(node: &SyntaxNode, range: TextRange) -> Option<FunctionBody> {
+        let mut node = node.ancestors().find(|it| {
+            ast::Expr::can_cast(it.kind())
+                || matches!(it.kind(), SyntaxKind::EXPR_STMT | SyntaxKind::LET_STMT)
+        })?;
+        // Block-like expression statements have no semicolon, the call replacing them needs one.
+        if let Some(stmt) = node
+            .parent()
+            .filter(|it| it.kind() == SyntaxKind::EXPR_STMT && it.text_range() == node.text_range())
+        {
+            node = stmt;
+        }
+
+        if let Some(block) = ast::BlockExpr::cast(node.clone()) {
+            let inside = TextRange::new(
+                block.l_curly_token()?.text_range().end(),
+                block.r_curly_token()?.text_range().start(),
+            );
+            if inside.contains_range(range) {
+                return FunctionBody::span(block, range);
+            }
+            if !block.is_standalone() {
+                return FunctionBody::span(block, inside);
+            }
+        }
+
+        if matches!(node.kind(), SyntaxKind::EXPR_STMT | SyntaxKind::LET_STMT) {
+            let parent = node.parent().and_then(ast::BlockExpr::cast)?;
+            return Some(FunctionBody::Span { parent, text_range: node.text_range() });
+        }
+
+        let expr = ast::Expr::cast(node)?;
+        if matches!(expr, ast::Expr::PathExpr(_)) {
+            return None;
+        }
+        Some(FunctionBody::Expr(expr))
+    }
+
+    /// The statements and tail expression of `parent` that are inside `range`, bails out if
+    /// any of them is only partially selected.
+    fn span(parent: ast::BlockExpr, range: TextRange) -> Option<FunctionBody> {
+        let children = parent
+            .statements()
+            .map(|it| it.syntax().clone())
+            .chain(parent.expr().map(|it| it.syntax().clone()))
+            .filter(|it| range.intersect(it.text_range()).map_or(false, |it| !it.is_empty()))
+            .collect::<Vec<_>>();
+        if children.iter().any(|it| !range.contains_range(it.text_range())) {
+            mark::hit!(extract_function_partial_statement);
+            return None;
+        }
+        let text_range = children.first()?.text_range().cover(children.last()?.text_range());
+        Some(FunctionBody::Span { parent, text_range })
+    }
+
+    fn node(&self) -> &SyntaxNode {
+        match self {
+            FunctionBody::Expr(expr) => expr.syntax(),
+            FunctionBody::Span { parent, .. } => parent.syntax(),
+        }
+    }
+
+    fn text_range(&self) -> TextRange {
+        match self {
+            FunctionBody::Expr(expr) => expr.syntax().text_range(),
+            FunctionBody::Span { text_range, .. } => *text_range,
+        }
+    }
+
+    fn contains(&self, range: TextRange) -> bool {
+        self.text_range().contains_range(range)
+    }
+
+    /// The top-level nodes of the body.
+    fn nodes(&self) -> Vec<SyntaxNode> {
+        match self {
+            FunctionBody::Expr(expr) => vec![expr.syntax().clone()],
+            FunctionBody::Span { parent, text_range } => parent
+                .syntax()
+                .children()
+                .filter(|it| text_range.contains_range(it.text_range()))
+                .collect(),
+        }
+    }
+
+    fn first_node(&self) -> SyntaxNode {
+        self.nodes().swap_remove(0)
+    }
+
+    /// The expression that produces the value of the body, if any.
+    fn tail_expr(&self) -> Option<ast::Expr> {
+        match self {
+            FunctionBody::Expr(expr) => Some(expr.clone()),
+            FunctionBody::Span { parent, text_range } => {
+                parent.expr().filter(|it| text_range.contains_range(it.syntax().text_range()))
+            }
+        }
+    }
+
+    /// Locals that are defined before the body and used inside of it.
+    fn params(&self, ctx: &AssistContext, usages: &[Usage]) -> Vec<Param> {
+        let db = ctx.db();
+        let mut locals = usages
+            .iter()
+            .filter(|it| self.contains(it.range))
+            .map(|it| it.local)
+            .unique()
+            .filter_map(|local| Some((local, self.def_range(ctx, local)?)))
+            .filter(|(_, def_range)| !self.contains(*def_range))
+            .collect::<Vec<_>>();
+        locals.sort_by_key(|(_, def_range)| def_range.start());
+
+        locals
+            .into_iter()
+            .map(|(local, def_range)| {
+                let ty = local.ty(db);
+                let is_ref = ty.remove_ref().is_some();
+                let mutated = usages
+                    .iter()
+                    .filter(|it| it.local == local && self.contains(it.range))
+                    .any(|it| match it.kind {
+                        UsageKind::Read => false,
+                        UsageKind::Mutate => !is_ref,
+                        UsageKind::Assign => true,
+                    });
+                let used_after = usages
+                    .iter()
+                    .any(|it| it.local == local && it.range.start() >= self.text_range().end())
+                    || self.inside_loop_of(def_range);
+                let kind = match (used_after, mutated) {
+                    (true, true) => ParamKind::MutRef,
+                    (false, true) => ParamKind::MutValue,
+                    (true, false) if is_ref || ty.is_copy(db) => ParamKind::Value,
+                    (true, false) => ParamKind::SharedRef,
+                    (false, false) => ParamKind::Value,
+                };
+                Param { local, kind }
+            })
+            .collect()
+    }
+
+    fn def_range(&self, ctx: &AssistContext, local: Local) -> Option<TextRange> {
+        let src = local.source(ctx.db());
+        if src.file_id != ctx.frange.file_id.into() {
+            return None;
+        }
+        let range = match src.value {
+            Either::Left(pat) => pat.syntax().text_range(),
+            Either::Right(self_param) => self_param.syntax().text_range(),
+        };
+        Some(range)
+    }
+
+    /// Whether the body is inside a loop that doesn't contain `def_range`, in which case
+    /// the local defined there is used again by the next iteration.
+    fn inside_loop_of(&self, def_range: TextRange) -> bool {
+        self.node()
+            .ancestors()
+            .take_while(|it| it.kind() != SyntaxKind::FN && it.kind() != SyntaxKind::CLOSURE_EXPR)
+            .filter(|it| is_loop(it))
+            .any(|it| !it.text_range().contains_range(def_range))
+    }
+
+    /// What the new function returns: the value of the tail expression, or the locals
+    /// defined by the body that are used after it.
+    fn ret_value(&self, ctx: &AssistContext, usages: &[Usage]) -> Option<RetValue> {
+        if let Some(tail) = self.tail_expr() {
+            let ty = ctx.sema.type_of_expr(&tail)?;
+            if ty.is_unit() {
+                return Some(RetValue::Unit { tail: Some(tail) });
+            }
+            return Some(RetValue::Tail(tail, ty));
+        }
+
+        let mut outputs = Vec::new();
+        for node in self.nodes() {
+            for pat in node.descendants().filter_map(ast::IdentPat::cast) {
+                let local = match ctx.sema.to_def(&pat) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let used_after = usages
+                    .iter()
+                    .any(|it| it.local == local && it.range.start() >= self.text_range().end());
+                if used_after {
+                    outputs.push(local);
+                }
+            }
+        }
+        if outputs.is_empty() {
+            Some(RetValue::Unit { tail: None })
+        } else {
+            Some(RetValue::Locals(outputs))
+        }
+    }
+
+    /// The `return`, `?`, `break` and `continue` expressions that leave the body, all of them
+    /// have to be of the same kind.
+    fn escaping_flows(&self, ctx: &AssistContext) -> Option<Vec<Flow>> {
+        let mut res = Vec::new();
+        let mut unknown_flow = false;
+        for node in self.nodes() {
+            walk_flow(&node, &mut |node| {
+                let escapes = match node.kind() {
+                    SyntaxKind::RETURN_EXPR | SyntaxKind::TRY_EXPR => true,
+                    SyntaxKind::BREAK_EXPR | SyntaxKind::CONTINUE_EXPR => {
+                        !self.contains_loop_of(node)
+                    }
+                    _ => false,
+                };
+                if escapes {
+                    match Flow::new(ctx, node.clone()) {
+                        Some(flow) => res.push(flow),
+                        None => unknown_flow = true,
+                    }
+                }
+            });
+        }
+        if unknown_flow {
+            return None;
+        }
+
+        if let Some(first) = res.first() {
+            if res.iter().any(|it| !first.same_kind(it)) {
+                mark::hit!(extract_function_mixed_flow);
+                return None;
+            }
+        }
+        Some(res)
+    }
+
+    /// Whether the loop a `break` or `continue` refers to is inside the body.
+    fn contains_loop_of(&self, flow: &SyntaxNode) -> bool {
+        let label = flow
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| it.kind() == SyntaxKind::LIFETIME);
+        flow.ancestors()
+            .take_while(|it| self.contains(it.text_range()))
+            .filter(|it| is_loop(it))
+            .any(|it| match &label {
+                Some(label) => loop_label(&it).map_or(false, |it| it.text() == label.text()),
+                None => true,
+            })
+    }
+}
+
+/// Calls `cb` for `node` and its descendants, skipping closures, async blocks and items, which
+/// have control flow of their own.
+fn walk_flow(node: &SyntaxNode, cb: &mut dyn FnMut(&SyntaxNode)) {
+    let own_flow = match node.kind() {
+        SyntaxKind::CLOSURE_EXPR => true,
+        SyntaxKind::EFFECT_EXPR => {
+            ast::EffectExpr::cast(node.clone()).map_or(false, |it| it.async_token().is_some())
+        }
+        kind => ast::Item::can_cast(kind),
+    };
+    if own_flow {
+        return;
+    }
+    cb(node);
+    for child in node.children() {
+        walk_flow(&child, cb);
+    }
+}
+
+fn is_loop(node: &SyntaxNode) -> bool {
+    matches!(node.kind(), SyntaxKind::LOOP_EXPR | SyntaxKind::WHILE_EXPR | SyntaxKind::FOR_EXPR)
+}
+
+fn loop_label(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.children().find_map(ast::Label::cast)?.lifetime_token()
+}
+
+/// Where the new function goes.
+enum Container {
+    /// Next to the outer function, which is a free function.
+    Free,
+    /// Next to the outer function in an inherent impl, as a method if it uses `self`.
+    Impl,
+    /// After the trait or trait impl containing the outer function, as a free function.
+    Trait(SyntaxNode),
+}
+
+impl Container {
+    fn new(outer_fn: &ast::Fn) -> Option<Container> {
+        let parent = outer_fn.syntax().parent()?;
+        if !ast::AssocItemList::can_cast(parent.kind()) {
+            return Some(Container::Free);
+        }
+        let item = parent.parent()?;
+        match ast::Impl::cast(item.clone()) {
+            Some(imp) if imp.trait_().is_none() => Some(Container::Impl),
+            _ => Some(Container::Trait(item)),
+        }
+    }
+
+    /// The node after which the new function is inserted.
+    fn anchor(&self, outer_fn: &ast::Fn) -> SyntaxNode {
+        match self {
+            Container::Free | Container::Impl => outer_fn.syntax().clone(),
+            Container::Trait(item) => item.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Param {
+    local: Local,
+    kind: ParamKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParamKind {
+    Value,
+    MutValue,
+    SharedRef,
+    MutRef,
+}
+
+impl Param {
+    fn is_ref(&self) -> bool {
+        matches!(self.kind, ParamKind::SharedRef | ParamKind::MutRef)
+    }
+
+    fn to_arg(&self, ctx: &AssistContext) -> String {
+        let name = local_name(ctx, self.local);
+        match self.kind {
+            ParamKind::Value | ParamKind::MutValue => name.to_string(),
+            ParamKind::SharedRef => format!("&{}", name),
+            ParamKind::MutRef => format!("&mut {}", name),
+        }
+    }
+
+    fn to_param(&self, ctx: &AssistContext, module: Module) -> String {
+        let name = local_name(ctx, self.local);
+        let ty = make_ty(ctx, module, &self.local.ty(ctx.db()));
+        match self.kind {
+            ParamKind::Value => format!("{}: {}", name, ty),
+            ParamKind::MutValue => format!("mut {}: {}", name, ty),
+            ParamKind::SharedRef => format!("{}: &{}", name, ty),
+            ParamKind::MutRef => format!("{}: &mut {}", name, ty),
+        }
+    }
+
+    fn to_self_param(&self, ctx: &AssistContext, usages: &[Usage]) -> &'static str {
+        let ty = self.local.ty(ctx.db());
+        if ty.remove_ref().is_some() {
+            let mutated =
+                usages.iter().any(|it| it.local == self.local && it.kind != UsageKind::Read);
+            return if ty.is_mutable_reference() && mutated { "&mut self" } else { "&self" };
+        }
+        match self.kind {
+            ParamKind::Value => "self",
+            ParamKind::MutValue => "mut self",
+            ParamKind::SharedRef => "&self",
+            ParamKind::MutRef => "&mut self",
+        }
+    }
+}
+
+#[derive(Debug)]
+enum RetValue {
+    /// Nothing is returned, `tail` is the unit-typed tail expression of the body.
+    Unit { tail: Option<ast::Expr> },
+    /// The value of the tail expression.
+    Tail(ast::Expr, hir::Type),
+    /// Locals defined by the body and used after it.
+    Locals(Vec<Local>),
+}
+
+impl RetValue {
+    fn is_unit(&self) -> bool {
+        matches!(self, RetValue::Unit { .. })
+    }
+}
+
+/// A `return`, `?`, `break` or `continue` that leaves the body.
+#[derive(Debug, Clone)]
+struct Flow {
+    kind: FlowKind,
+    expr: SyntaxNode,
+}
+
+#[derive(Debug, Clone)]
+enum FlowKind {
+    Return(Option<ast::Expr>),
+    Try(TryKind),
+    Break(Option<SyntaxToken>, Option<ast::Expr>),
+    Continue(Option<SyntaxToken>),
+}
+
+#[derive(Debug, Clone)]
+enum TryKind {
+    Option,
+    Result { err_ty: hir::Type },
+}
+
+impl Flow {
+    fn new(ctx: &AssistContext, expr: SyntaxNode) -> Option<Flow> {
+        let kind = match_ast! {
+            match (expr.clone()) {
+                ast::ReturnExpr(it) => FlowKind::Return(it.expr()),
+                ast::TryExpr(it) => {
+                    let ty = ctx.sema.type_of_expr(&it.expr()?)?;
+                    let adt = match ty.as_adt()? {
+                        hir::Adt::Enum(it) => it,
+                        _ => return None,
+                    };
+                    match adt.name(ctx.db()).to_string().as_str() {
+                        "Option" => FlowKind::Try(TryKind::Option),
+                        "Result" => {
+                            let err_ty = ty.type_arguments().nth(1)?;
+                            FlowKind::Try(TryKind::Result { err_ty })
+                        }
+                        _ => return None,
+                    }
+                },
+                ast::BreakExpr(it) => FlowKind::Break(it.lifetime_token(), it.expr()),
+                ast::ContinueExpr(it) => FlowKind::Continue(it.lifetime_token()),
+                _ => return None,
+            }
+        };
+        Some(Flow { kind, expr })
+    }
+
+    fn same_kind(&self, other: &Flow) -> bool {
+        let same_label = |a: &Option<SyntaxToken>, b: &Option<SyntaxToken>| {
+            a.as_ref().map(|it| it.text().clone()) == b.as_ref().map(|it| it.text().clone())
+        };
+        match (&self.kind, &other.kind) {
+            (FlowKind::Return(a), FlowKind::Return(b)) => a.is_some() == b.is_some(),
+            (FlowKind::Try(TryKind::Option), FlowKind::Try(TryKind::Option)) => true,
+            (FlowKind::Try(TryKind::Result { .. }), FlowKind::Try(TryKind::Result { .. })) => true,
+            (FlowKind::Break(a, a_expr), FlowKind::Break(b, b_expr)) => {
+                same_label(a, b) && a_expr.is_some() == b_expr.is_some()
+            }
+            (FlowKind::Continue(a), FlowKind::Continue(b)) => same_label(a, b),
+            _ => false,
+        }
+    }
+
+    /// The value carried out of the body, e.g. `x` in `return x`.
+    fn value(&self) -> Option<ast::Expr> {
+        match &self.kind {
+            FlowKind::Return(expr) | FlowKind::Break(_, expr) => expr.clone(),
+            FlowKind::Try(_) | FlowKind::Continue(_) => None,
+        }
+    }
+
+    /// The same control flow, at the call site; `value` is the name holding the carried value.
+    fn make_action(&self, value: Option<&str>) -> String {
+        let (keyword, label) = match &self.kind {
+            FlowKind::Return(_) => ("return", None),
+            FlowKind::Break(label, _) => ("break", label.as_ref()),
+            FlowKind::Continue(label) => ("continue", label.as_ref()),
+            FlowKind::Try(_) => unreachable!(),
+        };
+        let mut buf = keyword.to_string();
+        if let Some(label) = label {
+            format_to!(buf, " {}", label);
+        }
+        if let Some(value) = value {
+            format_to!(buf, " {}", value);
+        }
+        buf
+    }
+}
+
+/// How the call site reproduces the control flow that escapes the body.
+#[derive(Debug)]
+enum FlowHandler {
+    None,
+    /// `fun_name()?`
+    Try(TryKind),
+    /// `if fun_name() { return; }`
+    If(Flow),
+    /// `if let Some(value) = fun_name() { return value; }`
+    IfOption(Flow),
+    /// `match fun_name() { Some(it) => it, None => return }`
+    MatchOption(Flow),
+    /// `match fun_name() { Ok(it) => it, Err(it) => return it }`
+    MatchResult(Flow),
+}
+
+impl FlowHandler {
+    fn new(flows: &[Flow], ret: &RetValue) -> FlowHandler {
+        let flow = match flows.first() {
+            Some(it) => it.clone(),
+            None => return FlowHandler::None,
+        };
+        if let FlowKind::Try(kind) = flow.kind {
+            return FlowHandler::Try(kind);
+        }
+        match (flow.value().is_some(), ret.is_unit()) {
+            (false, true) => FlowHandler::If(flow),
+            (true, true) => FlowHandler::IfOption(flow),
+            (false, false) => FlowHandler::MatchOption(flow),
+            (true, false) => FlowHandler::MatchResult(flow),
+        }
+    }
+}
+
+struct Function {
+    name: &'static str,
+    self_param: Option<Param>,
+    params: Vec<Param>,
+    ret: RetValue,
+    handler: FlowHandler,
+    /// The escaping control flow expressions of the body.
+    flows: Vec<Flow>,
+    body: FunctionBody,
+}
+
+impl Function {
+    fn make_call(&self, ctx: &AssistContext, container: &Container, indent: IndentLevel) -> String {
+        let args = self.params.iter().map(|it| it.to_arg(ctx)).join(", ");
+        let mut call = match (container, &self.self_param) {
+            (_, Some(_)) => format!("self.{}({})", self.name, args),
+            (Container::Impl, None) => format!("Self::{}({})", self.name, args),
+            _ => format!("{}({})", self.name, args),
+        };
+        let inner = indent + 1;
+        let expr = match &self.handler {
+            FlowHandler::None => call,
+            FlowHandler::Try(_) => {
+                call.push('?');
+                call
+            }
+            FlowHandler::If(flow) => {
+                format!("if {} {{\n{}{};\n{}}}", call, inner, flow.make_action(None), indent)
+            }
+            FlowHandler::IfOption(flow) => format!(
+                "if let Some(value) = {} {{\n{}{};\n{}}}",
+                call,
+                inner,
+                flow.make_action(Some("value")),
+                indent
+            ),
+            FlowHandler::MatchOption(flow) => format!(
+                "match {} {{\n{}Some(it) => it,\n{}None => {},\n{}}}",
+                call,
+                inner,
+                inner,
+                flow.make_action(None),
+                indent
+            ),
+            FlowHandler::MatchResult(flow) => format!(
+                "match {} {{\n{}Ok(it) => it,\n{}Err(it) => {},\n{}}}",
+                call,
+                inner,
+                inner,
+                flow.make_action(Some("it")),
+                indent
+            ),
+        };
+
+        match (&self.body, &self.ret) {
+            (FunctionBody::Expr(_), _) | (_, RetValue::Tail(..)) => expr,
+            (FunctionBody::Span { .. }, RetValue::Unit { .. }) => match self.handler {
+                FlowHandler::If(_) | FlowHandler::IfOption(_) => expr,
+                _ => format!("{};", expr),
+            },
+            (FunctionBody::Span { .. }, RetValue::Locals(locals)) => {
+                let names = locals
+                    .iter()
+                    .map(|local| {
+                        let name = local_name(ctx, *local);
+                        if local.is_mut(ctx.db()) {
+                            format!("mut {}", name)
+                        } else {
+                            name.to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let pat = if names.len() == 1 {
+                    names[0].clone()
+                } else {
+                    format!("({})", names.join(", "))
+                };
+                format!("let {} = {};", pat, expr)
+            }
+        }
+    }
+
+    fn make_def(
+        &self,
+        ctx: &AssistContext,
+        module: Module,
+        usages: &[Usage],
+        indent: IndentLevel,
+    ) -> String {
+        let mut params = Vec::new();
+        if let Some(self_param) = &self.self_param {
+            params.push(self_param.to_self_param(ctx, usages).to_string());
+        }
+        params.extend(self.params.iter().map(|it| it.to_param(ctx, module)));
+
+        let mut fn_def = String::new();
+        format_to!(fn_def, "\n\n{}fn {}({})", indent, self.name, params.join(", "));
+        if let Some(ret_ty) = self.make_ret_ty(ctx, module) {
+            format_to!(fn_def, " -> {}", ret_ty);
+        }
+        fn_def.push(' ');
+        fn_def.push_str(&self.make_body(ctx, usages, indent));
+        fn_def
+    }
+
+    fn make_ret_ty(&self, ctx: &AssistContext, module: Module) -> Option<String> {
+        let value_ty = match &self.ret {
+            RetValue::Unit { .. } => None,
+            RetValue::Tail(_, ty) => Some(make_ty(ctx, module, ty)),
+            RetValue::Locals(locals) => {
+                let tys = locals.iter().map(|it| make_ty(ctx, module, &it.ty(ctx.db())));
+                if locals.len() == 1 {
+                    tys.collect::<Vec<_>>().pop()
+                } else {
+                    Some(format!("({})", tys.format(", ")))
+                }
+            }
+        };
+        let flow_ty = |flow: &Flow| {
+            flow.value()
+                .and_then(|it| ctx.sema.type_of_expr(&it))
+                .map_or_else(|| "_".to_string(), |ty| make_ty(ctx, module, &ty))
+        };
+        let value_or_unit = || value_ty.clone().unwrap_or_else(|| "()".to_string());
+        match &self.handler {
+            FlowHandler::None => value_ty,
+            FlowHandler::Try(TryKind::Option) => Some(format!("Option<{}>", value_or_unit())),
+            FlowHandler::Try(TryKind::Result { err_ty }) => {
+                Some(format!("Result<{}, {}>", value_or_unit(), make_ty(ctx, module, err_ty)))
+            }
+            FlowHandler::If(_) => Some("bool".to_string()),
+            FlowHandler::IfOption(flow) => Some(format!("Option<{}>", flow_ty(flow))),
+            FlowHandler::MatchOption(_) => Some(format!("Option<{}>", value_or_unit())),
+            FlowHandler::MatchResult(flow) => {
+                Some(format!("Result<{}, {}>", value_or_unit(), flow_ty(flow)))
+            }
+        }
+    }
+
+    fn make_body(&self, ctx: &AssistContext, usages: &[Usage], indent: IndentLevel) -> String {
+        let body_range = self.body.text_range();
+        let mut edits = Vec::new();
+
+        // Reference parameters have to be dereferenced where they are used by value.
+        for usage in usages.iter().filter(|it| body_range.contains_range(it.range)) {
+            let is_ref_param = self
+                .params
+                .iter()
+                .chain(self.self_param.iter())
+                .any(|it| it.local == usage.local && it.is_ref());
+            if !is_ref_param {
+                continue;
+            }
+            if let Some(edit) = usage.deref_edit() {
+                edits.push(edit);
+            }
+        }
+
+        // Escaping control flow becomes the value telling the call site what to do.
+        for flow in &self.flows {
+            let range = flow.expr.text_range();
+            match (&self.handler, flow.value()) {
+                (FlowHandler::If(_), _) => edits.push((range, "return true".to_string())),
+                (FlowHandler::MatchOption(_), _) => edits.push((range, "return None".to_string())),
+                (FlowHandler::IfOption(_), Some(value)) => {
+                    wrap_edits(&mut edits, range, value.syntax().text_range(), "return Some(")
+                }
+                (FlowHandler::MatchResult(_), Some(value)) => {
+                    wrap_edits(&mut edits, range, value.syntax().text_range(), "return Err(")
+                }
+                _ => (),
+            }
+        }
+
+        // The value of the body is wrapped for the call site, or returned as a new tail.
+        let wrapper = match &self.handler {
+            FlowHandler::Try(TryKind::Option) | FlowHandler::MatchOption(_) => Some("Some"),
+            FlowHandler::Try(TryKind::Result { .. }) | FlowHandler::MatchResult(_) => Some("Ok"),
+            FlowHandler::None | FlowHandler::If(_) | FlowHandler::IfOption(_) => None,
+        };
+        let mut new_tail = None;
+        match &self.ret {
+            RetValue::Tail(tail, _) => {
+                if let Some(wrapper) = wrapper {
+                    let range = tail.syntax().text_range();
+                    wrap_edits(&mut edits, range, range, &format!("{}(", wrapper));
+                }
+            }
+            RetValue::Locals(locals) => {
+                let names = locals.iter().map(|it| local_name(ctx, *it)).collect::<Vec<_>>();
+                let value = if names.len() == 1 {
+                    names[0].to_string()
+                } else {
+                    format!("({})", names.iter().format(", "))
+                };
+                new_tail = Some(match wrapper {
+                    Some(wrapper) => format!("{}({})", wrapper, value),
+                    None => value,
+                });
+            }
+            RetValue::Unit { tail } => {
+                new_tail = match &self.handler {
+                    FlowHandler::None => None,
+                    FlowHandler::Try(_) => wrapper.map(|it| format!("{}(())", it)),
+                    FlowHandler::If(_) => Some("false".to_string()),
+                    FlowHandler::IfOption(_) => Some("None".to_string()),
+                    FlowHandler::MatchOption(_) | FlowHandler::MatchResult(_) => unreachable!(),
+                };
+                if let (Some(tail), Some(_)) = (tail, &new_tail) {
+                    if !is_block_like(tail) {
+                        edits
+                            .push((TextRange::empty(tail.syntax().text_range().end()), ";".into()));
+                    }
+                }
+            }
+        }
+
+        let text = apply_edits(&self.body, edits);
+        let body_indent = indent + 1;
+        let old_indent = IndentLevel::from_node(&self.body.first_node());
+
+        if let (FunctionBody::Expr(ast::Expr::BlockExpr(_)), None, None) =
+            (&self.body, &new_tail, wrapper)
+        {
+            return reindent(&text, old_indent, indent);
+        }
+
+        let mut buf = String::from("{");
+        format_to!(buf, "\n{}{}", body_indent, reindent(&text, old_indent, body_indent));
+        if let Some(new_tail) = new_tail {
+            format_to!(buf, "\n{}{}", body_indent, new_tail);
+        }
+        format_to!(buf, "\n{}}}", indent);
+        buf
+    }
+}
+
+/// A use of a local in the function containing the selection.
+#[derive(Debug)]
+struct Usage {
+    local: Local,
+    range: TextRange,
+    kind: UsageKind,
+    /// The path expression naming the local, `None` inside of macro calls.
+    expr: Option<ast::PathExpr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UsageKind {
+    Read,
+    /// The local is mutably borrowed, e.g. by `&mut x`, `x.push(1)` or `x.field = 1`.
+    Mutate,
+    /// The local itself is assigned to.
+    Assign,
+}
+
+impl Usage {
+    /// The edit that turns the usage into one through a reference, `x` becomes `*x`.
+    fn deref_edit(&self) -> Option<(TextRange, String)> {
+        let expr = self.expr.as_ref()?;
+        let parent = expr.syntax().parent()?;
+        let is_auto_deref = match_ast! {
+            match parent {
+                ast::MethodCallExpr(it) => it.receiver().as_ref().map(|it| it.syntax()) == Some(expr.syntax()),
+                ast::FieldExpr(_it) => true,
+                ast::IndexExpr(it) => it.base().as_ref().map(|it| it.syntax()) == Some(expr.syntax()),
+                ast::CallExpr(it) => it.expr().as_ref().map(|it| it.syntax()) == Some(expr.syntax()),
+                _ => false,
+            }
+        };
+        if is_auto_deref {
+            return None;
+        }
+        let text = expr.syntax().text().to_string();
+        let is_shorthand =
+            ast::RecordExprField::cast(parent).map_or(false, |it| it.name_ref().is_none());
+        let replacement =
+            if is_shorthand { format!("{}: *{}", text, text) } else { format!("*{}", text) };
+        Some((expr.syntax().text_range(), replacement))
+    }
+}
+
+/// Finds all the uses of locals in `fn_body`, including the ones in macro calls.
+fn collect_usages(sema: &Semantics<RootDatabase>, fn_body: &ast::BlockExpr) -> Vec<Usage> {
+    let mut res = Vec::new();
+    for node in fn_body.syntax().descendants() {
+        if let Some(expr) = ast::PathExpr::cast(node.clone()) {
+            if let Some(local) = resolve_local(sema, &expr) {
+                let kind = usage_kind(sema, &expr.clone().into());
+                let range = expr.syntax().text_range();
+                res.push(Usage { local, range, kind, expr: Some(expr) });
+            }
+        } else if let Some(macro_call) = ast::MacroCall::cast(node) {
+            let tt = match macro_call.token_tree() {
+                Some(it) => it,
+                None => continue,
+            };
+            let tokens = tt.syntax().descendants_with_tokens().filter_map(|it| it.into_token());
+            for token in tokens.filter(|it| matches!(it.kind(), SyntaxKind::IDENT | T![self])) {
+                let expanded = sema.descend_into_macros(token.clone());
+                if expanded == token {
+                    continue;
+                }
+                let expr = match expanded.parent().ancestors().find_map(ast::PathExpr::cast) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if let Some(local) = resolve_local(sema, &expr) {
+                    let range = token.text_range();
+                    res.push(Usage { local, range, kind: UsageKind::Read, expr: None });
+                }
+            }
+        }
+    }
+    res
+}
+
+fn resolve_local(sema: &Semantics<RootDatabase>, expr: &ast::PathExpr) -> Option<Local> {
+    let path = expr.path()?;
+    if path.qualifier().is_some() {
+        return None;
+    }
+    match sema.resolve_path(&path)? {
+        PathResolution::Local(local) => Some(local),
+        _ => None,
+    }
+}
+
+/// How the place `expr` (a local, or a field or index projection of it) is used.
+fn usage_kind(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> UsageKind {
+    let parent = match expr.syntax().parent() {
+        Some(it) => it,
+        None => return UsageKind::Read,
+    };
+    let is_expr = |it: Option<ast::Expr>| it.as_ref() == Some(expr);
+    match_ast! {
+        match parent {
+            ast::BinExpr(it) => {
+                let is_assignment = it.op_kind().map_or(false, BinOp::is_assignment);
+                if is_assignment && is_expr(it.lhs()) {
+                    if matches!(expr, ast::Expr::PathExpr(_)) {
+                        UsageKind::Assign
+                    } else {
+                        UsageKind::Mutate
+                    }
+                } else {
+                    UsageKind::Read
+                }
+            },
+            ast::RefExpr(it) => {
+                if it.mut_token().is_some() { UsageKind::Mutate } else { UsageKind::Read }
+            },
+            ast::MethodCallExpr(it) => {
+                if !is_expr(it.receiver()) {
+                    return UsageKind::Read;
+                }
+                let access = sema
+                    .resolve_method_call(&it)
+                    .and_then(|func| func.self_param(sema.db))
+                    .map(|self_param| self_param.access(sema.db));
+                match access {
+                    Some(Access::Exclusive) => UsageKind::Mutate,
+                    _ => UsageKind::Read,
+                }
+            },
+            ast::FieldExpr(it) => match usage_kind(sema, &it.into()) {
+                UsageKind::Assign => UsageKind::Mutate,
+                kind => kind,
+            },
+            ast::IndexExpr(it) => {
+                if !is_expr(it.base()) {
+                    return UsageKind::Read;
+                }
+                match usage_kind(sema, &it.into()) {
+                    UsageKind::Assign => UsageKind::Mutate,
+                    kind => kind,
+                }
+            },
+            _ => UsageKind::Read,
+        }
+    }
+}
+
+/// Replaces `range` by `prefix` followed by `inner` and a closing parenthesis.
+fn wrap_edits(
+    edits: &mut Vec<(TextRange, String)>,
+    range: TextRange,
+    inner: TextRange,
+    prefix: &str,
+) {
+    edits.push((TextRange::new(range.start(), inner.start()), prefix.to_string()));
+    edits.push((TextRange::new(inner.end(), range.end()), ")".to_string()));
+}
+
+/// Applies edits, given in file offsets, to the text of the body.
+fn apply_edits(body: &FunctionBody, mut edits: Vec<(TextRange, String)>) -> String {
+    let body_range = body.text_range();
+    let node_start = body.node().text_range().start();
+    let mut text = body.node().text().slice(body_range - node_start).to_string();
+    edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+    for (range, replacement) in edits.into_iter().rev() {
+        let range = range - body_range.start();
+        text.replace_range(Range::<usize>::from(range), &replacement);
+    }
+    text
+}
+
+/// Moves all the lines but the first one of `text` from the `from` to the `to` indentation.
+fn reindent(text: &str, from: IndentLevel, to: IndentLevel) -> String {
+    let from = from.to_string();
+    let to = to.to_string();
+    let mut lines = text.split('\n');
+    let mut res = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        res.push('\n');
+        match line.strip_prefix(from.as_str()) {
+            Some(rest) if !line.trim().is_empty() => {
+                res.push_str(&to);
+                res.push_str(rest);
+            }
+            _ => res.push_str(line),
+        }
+    }
+    res
+}
+
+fn is_block_like(expr: &ast::Expr) -> bool {
+    matches!(
+        expr,
+        ast::Expr::BlockExpr(_)
+            | ast::Expr::IfExpr(_)
+            | ast::Expr::LoopExpr(_)
+            | ast::Expr::WhileExpr(_)
+            | ast::Expr::ForExpr(_)
+            | ast::Expr::MatchExpr(_)
+            | ast::Expr::EffectExpr(_)
+    )
+}
+
+fn local_name(ctx: &AssistContext, local: Local) -> String {
+    local.name(ctx.db()).map_or_else(|| "_".to_string(), |it| it.to_string())
+}
+
+fn make_ty(ctx: &AssistContext, module: Module, ty: &hir::Type) -> String {
+    if ty.is_unknown() {
+        return "_".to_string();
+    }
+    ty.display_source_code(ctx.db(), module.into()).ok().unwrap_or_else(|| "_".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn no_args_from_binary_expr() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    foo(<|>1 + 1<|>);
+}"#,
+            r#"
+fn foo() {
+    foo(fun_name());
+}
+
+fn $0fun_name() -> i32 {
+    1 + 1
+}"#,
+        );
+    }
+
+    #[test]
+    fn no_args_from_block_expr() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let x = <|>{
+        let a = 1;
+        a + 1
+    }<|>;
+}"#,
+            r#"
+fn foo() {
+    let x = fun_name();
+}
+
+fn $0fun_name() -> i32 {
+    let a = 1;
+    a + 1
+}"#,
+        );
+    }
+
+    #[test]
+    fn no_args_from_stmt_unit() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    <|>foo();<|>
+}"#,
+            r#"
+fn foo() {
+    fun_name();
+}
+
+fn $0fun_name() {
+    foo();
+}"#,
+        );
+    }
+
+    #[test]
+    fn args_by_value() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let n = 1;
+    <|>let m = n + 2;
+    let k = m * n;<|>
+    let g = 3;
+}"#,
+            r#"
+fn foo() {
+    let n = 1;
+    fun_name(n);
+    let g = 3;
+}
+
+fn $0fun_name(n: i32) {
+    let m = n + 2;
+    let k = m * n;
+}"#,
+        );
+    }
+
+    #[test]
+    fn args_used_after_are_borrowed() {
+        check_assist(
+            extract_function,
+            r#"
+struct Counter(i32);
+impl Counter {
+    fn get(&self) -> i32 { self.0 }
+    fn inc(&mut self) {}
+}
+fn foo() {
+    let a = Counter(0);
+    let mut b = Counter(0);
+    <|>let n = a.get();
+    b.inc();<|>
+    a.get();
+    b.get();
+}"#,
+            r#"
+struct Counter(i32);
+impl Counter {
+    fn get(&self) -> i32 { self.0 }
+    fn inc(&mut self) {}
+}
+fn foo() {
+    let a = Counter(0);
+    let mut b = Counter(0);
+    fun_name(&a, &mut b);
+    a.get();
+    b.get();
+}
+
+fn $0fun_name(a: &Counter, b: &mut Counter) {
+    let n = a.get();
+    b.inc();
+}"#,
+        );
+    }
+
+    #[test]
+    fn mut_arg_not_used_after_is_moved() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let mut n = 1;
+    <|>n += 1;<|>
+}"#,
+            r#"
+fn foo() {
+    let mut n = 1;
+    fun_name(n);
+}
+
+fn $0fun_name(mut n: i32) {
+    n += 1;
+}"#,
+        );
+    }
+
+    #[test]
+    fn mut_arg_used_after_is_dereferenced() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let mut n = 1;
+    <|>n += 1;
+    let m = n * 2;<|>
+    let k = n;
+}"#,
+            r#"
+fn foo() {
+    let mut n = 1;
+    fun_name(&mut n);
+    let k = n;
+}
+
+fn $0fun_name(n: &mut i32) {
+    *n += 1;
+    let m = *n * 2;
+}"#,
+        );
+    }
+
+    #[test]
+    fn copy_arg_used_after_is_passed_by_value() {
+        check_assist(
+            extract_function,
+            r#"
+#[lang = "copy"]
+trait Copy {}
+impl Copy for i32 {}
+fn foo() {
+    let n = 1;
+    <|>let m = n + 1;<|>
+    let k = n;
+}"#,
+            r#"
+#[lang = "copy"]
+trait Copy {}
+impl Copy for i32 {}
+fn foo() {
+    let n = 1;
+    fun_name(n);
+    let k = n;
+}
+
+fn $0fun_name(n: i32) {
+    let m = n + 1;
+}"#,
+        );
+    }
+
+    #[test]
+    fn shared_ref_arg_in_field_shorthand() {
+        check_assist(
+            extract_function,
+            r#"
+struct S { n: i32 }
+fn foo() {
+    let n = 1;
+    <|>let s = S { n };<|>
+    let k = n;
+}"#,
+            r#"
+struct S { n: i32 }
+fn foo() {
+    let n = 1;
+    fun_name(&n);
+    let k = n;
+}
+
+fn $0fun_name(n: &i32) {
+    let s = S { n: *n };
+}"#,
+        );
+    }
+
+    #[test]
+    fn arg_used_in_loop_is_borrowed() {
+        check_assist(
+            extract_function,
+            r#"
+struct S;
+fn consume(s: &S) {}
+fn foo() {
+    let s = S;
+    loop {
+        <|>consume(&s);<|>
+    }
+}"#,
+            r#"
+struct S;
+fn consume(s: &S) {}
+fn foo() {
+    let s = S;
+    loop {
+        fun_name(&s);
+    }
+}
+
+fn $0fun_name(s: &S) {
+    consume(&*s);
+}"#,
+        );
+    }
+
+    #[test]
+    fn arg_used_in_macro() {
+        check_assist(
+            extract_function,
+            r#"
+macro_rules! m { ($e:expr) => { $e }; }
+fn foo() {
+    let n = 1;
+    <|>m!(n);<|>
+}"#,
+            r#"
+macro_rules! m { ($e:expr) => { $e }; }
+fn foo() {
+    let n = 1;
+    fun_name(n);
+}
+
+fn $0fun_name(n: i32) {
+    m!(n);
+}"#,
+        );
+    }
+
+    #[test]
+    fn return_single_output() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let n = 1;
+    <|>let k = n * n;<|>
+    let m = k + 1;
+}"#,
+            r#"
+fn foo() {
+    let n = 1;
+    let k = fun_name(n);
+    let m = k + 1;
+}
+
+fn $0fun_name(n: i32) -> i32 {
+    let k = n * n;
+    k
+}"#,
+        );
+    }
+
+    #[test]
+    fn return_multiple_outputs_as_tuple() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let n = 1;
+    <|>let k = n * n;
+    let mut m = k + 1;<|>
+    m += k;
+}"#,
+            r#"
+fn foo() {
+    let n = 1;
+    let (k, mut m) = fun_name(n);
+    m += k;
+}
+
+fn $0fun_name(n: i32) -> (i32, i32) {
+    let k = n * n;
+    let mut m = k + 1;
+    (k, m)
+}"#,
+        );
+    }
+
+    #[test]
+    fn tail_expr_of_block_is_returned() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() -> i32 {
+    let n = 1;
+    <|>let k = n * n;
+    k + 1<|>
+}"#,
+            r#"
+fn foo() -> i32 {
+    let n = 1;
+    fun_name(n)
+}
+
+fn $0fun_name(n: i32) -> i32 {
+    let k = n * n;
+    k + 1
+}"#,
+        );
+    }
+
+    #[test]
+    fn method_with_self() {
+        check_assist(
+            extract_function,
+            r#"
+struct S { f: i32 }
+impl S {
+    fn foo(&self) -> i32 {
+        let n = 1;
+        <|>self.f + n<|>
+    }
+}"#,
+            r#"
+struct S { f: i32 }
+impl S {
+    fn foo(&self) -> i32 {
+        let n = 1;
+        self.fun_name(n)
+    }
+
+    fn $0fun_name(&self, n: i32) -> i32 {
+        self.f + n
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn method_with_mut_self() {
+        check_assist(
+            extract_function,
+            r#"
+struct S { f: i32 }
+impl S {
+    fn foo(&mut self) {
+        <|>self.f += 1;<|>
+    }
+}"#,
+            r#"
+struct S { f: i32 }
+impl S {
+    fn foo(&mut self) {
+        self.fun_name();
+    }
+
+    fn $0fun_name(&mut self) {
+        self.f += 1;
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn associated_fn_without_self() {
+        check_assist(
+            extract_function,
+            r#"
+struct S;
+impl S {
+    fn foo(&self) {
+        <|>let n = 1;<|>
+    }
+}"#,
+            r#"
+struct S;
+impl S {
+    fn foo(&self) {
+        Self::fun_name();
+    }
+
+    fn $0fun_name() {
+        let n = 1;
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn trait_impl_gets_free_fn() {
+        check_assist(
+            extract_function,
+            r#"
+trait T { fn foo(&self); }
+struct S;
+impl T for S {
+    fn foo(&self) {
+        <|>let n = 1;<|>
+    }
+}"#,
+            r#"
+trait T { fn foo(&self); }
+struct S;
+impl T for S {
+    fn foo(&self) {
+        fun_name();
+    }
+}
+
+fn $0fun_name() {
+    let n = 1;
+}"#,
+        );
+    }
+
+    #[test]
+    fn self_in_trait_impl_is_not_applicable() {
+        mark::check!(extract_function_self_outside_of_impl);
+        check_assist_not_applicable(
+            extract_function,
+            r#"
+trait T { fn foo(&self); }
+struct S;
+impl T for S {
+    fn foo(&self) {
+        <|>let s = self;<|>
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn return_without_value() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo(n: i32) {
+    <|>if n > 1 {
+        return;
+    }
+    let m = n;<|>
+    let k = 1;
+}"#,
+            r#"
+fn foo(n: i32) {
+    if fun_name(n) {
+        return;
+    }
+    let k = 1;
+}
+
+fn $0fun_name(n: i32) -> bool {
+    if n > 1 {
+        return true;
+    }
+    let m = n;
+    false
+}"#,
+        );
+    }
+
+    #[test]
+    fn return_with_value() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo(n: i32) -> i32 {
+    <|>if n > 1 {
+        return n;
+    }<|>
+    0
+}"#,
+            r#"
+fn foo(n: i32) -> i32 {
+    if let Some(value) = fun_name(n) {
+        return value;
+    }
+    0
+}
+
+fn $0fun_name(n: i32) -> Option<i32> {
+    if n > 1 {
+        return Some(n);
+    }
+    None
+}"#,
+        );
+    }
+
+    #[test]
+    fn return_without_value_and_output() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo(n: i32) {
+    <|>if n > 1 {
+        return;
+    }
+    let m = n;<|>
+    let k = m;
+}"#,
+            r#"
+fn foo(n: i32) {
+    let m = match fun_name(n) {
+        Some(it) => it,
+        None => return,
+    };
+    let k = m;
+}
+
+fn $0fun_name(n: i32) -> Option<i32> {
+    if n > 1 {
+        return None;
+    }
+    let m = n;
+    Some(m)
+}"#,
+        );
+    }
+
+    #[test]
+    fn return_with_value_and_output() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo(n: i32) -> i32 {
+    <|>if n > 1 {
+        return 0;
+    }
+    let m = n;<|>
+    m
+}"#,
+            r#"
+fn foo(n: i32) -> i32 {
+    let m = match fun_name(n) {
+        Ok(it) => it,
+        Err(it) => return it,
+    };
+    m
+}
+
+fn $0fun_name(n: i32) -> Result<i32, i32> {
+    if n > 1 {
+        return Err(0);
+    }
+    let m = n;
+    Ok(m)
+}"#,
+        );
+    }
+
+    #[test]
+    fn break_and_continue() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    loop {
+        let n = 1;
+        <|>if n > 1 {
+            break;
+        }<|>
+    }
+}"#,
+            r#"
+fn foo() {
+    loop {
+        let n = 1;
+        if fun_name(n) {
+            break;
+        }
+    }
+}
+
+fn $0fun_name(n: i32) -> bool {
+    if n > 1 {
+        return true;
+    }
+    false
+}"#,
+        );
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    'outer: loop {
+        for i in 0..3 {
+            <|>if i > 1 {
+                continue 'outer;
+            }<|>
+        }
+    }
+}"#,
+            r#"
+fn foo() {
+    'outer: loop {
+        for i in 0..3 {
+            if fun_name(i) {
+                continue 'outer;
+            }
+        }
+    }
+}
+
+fn $0fun_name(i: _) -> bool {
+    if i > 1 {
+        return true;
+    }
+    false
+}"#,
+        );
+    }
+
+    #[test]
+    fn break_inside_selected_loop_is_kept() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    <|>loop {
+        break;
+    }<|>
+}"#,
+            r#"
+fn foo() {
+    fun_name()
+}
+
+fn $0fun_name() {
+    loop {
+        break;
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn try_option() {
+        check_assist(
+            extract_function,
+            r#"
+enum Option<T> { None, Some(T) }
+use Option::*;
+fn bar() -> Option<i32> { None }
+fn foo() -> Option<()> {
+    <|>let n = bar()?;
+    let m = n + 1;<|>
+    let k = m;
+    Some(())
+}"#,
+            r#"
+enum Option<T> { None, Some(T) }
+use Option::*;
+fn bar() -> Option<i32> { None }
+fn foo() -> Option<()> {
+    let m = fun_name()?;
+    let k = m;
+    Some(())
+}
+
+fn $0fun_name() -> Option<i32> {
+    let n = bar()?;
+    let m = n + 1;
+    Some(m)
+}"#,
+        );
+    }
+
+    #[test]
+    fn try_result() {
+        check_assist(
+            extract_function,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn bar() -> Result<i32, u8> { Ok(1) }
+fn foo() -> Result<(), u8> {
+    <|>let n = bar()?;<|>
+    Ok(())
+}"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn bar() -> Result<i32, u8> { Ok(1) }
+fn foo() -> Result<(), u8> {
+    fun_name()?;
+    Ok(())
+}
+
+fn $0fun_name() -> Result<(), u8> {
+    let n = bar()?;
+    Ok(())
+}"#,
+        );
+    }
+
+    #[test]
+    fn return_in_closure_is_not_escaping() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    <|>let f = || {
+        return 1;
+    };<|>
+}"#,
+            r#"
+fn foo() {
+    fun_name();
+}
+
+fn $0fun_name() {
+    let f = || {
+        return 1;
+    };
+}"#,
+        );
+    }
+
+    #[test]
+    fn mixed_flow_is_not_applicable() {
+        mark::check!(extract_function_mixed_flow);
+        check_assist_not_applicable(
+            extract_function,
+            r#"
+fn foo() {
+    loop {
+        let n = 1;
+        <|>if n > 1 {
+            break;
+        }
+        if n > 2 {
+            continue;
+        }<|>
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn partial_statement_is_not_applicable() {
+        mark::check!(extract_function_partial_statement);
+        check_assist_not_applicable(
+            extract_function,
+            r#"
+fn foo() {
+    <|>let n = 1;
+    let m<|> = 2;
+}"#,
+        );
+    }
+
+    #[test]
+    fn outside_of_fn_is_not_applicable() {
+        check_assist_not_applicable(extract_function, r"const C: i32 = <|>1 + 1<|>;");
+    }
+}
@@ -0,0 +1,665 @@
+use hir::{AsAssocItem, AssocItemContainer, PathResolution, ScopeDef};
+use ide_db::{
+    base_db::FileId,
+    defs::{Definition, NameClass},
+    search::Reference,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    algo::{self, SyntaxRewriter},
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, AstNode,
+    },
+    NodeOrToken, SyntaxKind, SyntaxNode, TextRange, TextSize,
+};
+use test_utils::mark;
+use text_edit::TextEdit;
+
+use crate::{
+    utils::{insert_use, required_visibility, visibility_edit, ImportScope},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+const MODULE_NAME: &str = "modname";
+
+// Assist: extract_module
+//
+// Moves the selected items into a new module, adjusting visibilities and imports.
+//
+// ```
+// <|>struct Point {
+//     x: i32,
+// }
+//
+// fn origin() -> Point {
+//     Point { x: 0 }
+// }<|>
+//
+// fn main() {
+//     let x = origin().x;
+// }
+// ```
+// ->
+// ```
+// use self::modname::origin;
+//
+// mod modname {
+//     pub(super) struct Point {
+//         pub(super) x: i32,
+//     }
+//
+//     pub(super) fn origin() -> Point {
+//         Point { x: 0 }
+//     }
+// }
+//
+// fn main() {
+//     let x = origin().x;
+// }
+// ```
+pub(crate) fn extract_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let range = ctx.frange.range;
+    if range.is_empty() {
+        return None;
+    }
+    let covering = match ctx.covering_element() {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let container = covering
+        .ancestors()
+        .find(|it| matches!(it.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST))?;
+    let items = container
+        .children()
+        .filter(|it| range.contains_range(it.text_range()))
+        .filter_map(ast::Item::cast)
+        .collect::<Vec<_>>();
+    let moved_range =
+        items.first()?.syntax().text_range().cover(items.last()?.syntax().text_range());
+
+    let db = ctx.db();
+    let module = ctx.sema.scope(items[0].syntax()).module()?;
+    let module_scope = module.scope(db, None);
+    if module_scope.iter().any(|(name, _)| name.to_string() == MODULE_NAME) {
+        mark::hit!(extract_module_name_taken);
+        return None;
+    }
+
+    let file_id = ctx.frange.file_id;
+    let is_outside = |reference: &Reference| {
+        reference.file_range.file_id != file_id
+            || !moved_range.contains_range(reference.file_range.range)
+    };
+
+    // Widen the visibility of everything the remaining code uses.
+    let candidates = visibility_candidates(ctx, &items)
+        .into_iter()
+        .map(|(node, def)| (node, def.usages(&ctx.sema).all()))
+        .collect::<Vec<_>>();
+    let mut needs_vis = candidates
+        .iter()
+        .filter(|(_, usages)| usages.iter().any(&is_outside))
+        .map(|(node, _)| node.clone())
+        .collect::<FxHashSet<_>>();
+    // Constructing or matching a tuple struct needs all of its fields to be visible.
+    for item in &items {
+        let strukt = match item {
+            ast::Item::Struct(it) => it,
+            _ => continue,
+        };
+        let fields = match strukt.field_list() {
+            Some(ast::FieldList::TupleFieldList(it)) => it,
+            _ => continue,
+        };
+        let def = hir::ModuleDef::from(ctx.sema.to_def(strukt)?);
+        let usages = Definition::ModuleDef(def).usages(&ctx.sema).all();
+        if usages.iter().any(|it| is_outside(it) && is_tuple_struct_constructor(ctx, it)) {
+            needs_vis.extend(fields.fields().map(|it| it.syntax().clone()));
+        }
+    }
+    // Whatever the signature of a visible item names must be visible too.
+    loop {
+        let exposed = candidates
+            .iter()
+            .filter(|(node, _)| !needs_vis.contains(node))
+            .filter(|(_, usages)| {
+                usages.iter().any(|usage| {
+                    usage.file_range.file_id == file_id
+                        && needs_vis.iter().any(|it| in_signature(it, usage.file_range.range))
+                })
+            })
+            .map(|(node, _)| node.clone())
+            .collect::<Vec<_>>();
+        if exposed.is_empty() {
+            break;
+        }
+        needs_vis.extend(exposed);
+    }
+    let mut vis_edit = TextEdit::builder();
+    for node in needs_vis {
+        if let Some(new_vis) = widened_visibility(&node) {
+            let (range, text) = visibility_edit(&node, &new_vis);
+            vis_edit.replace(range - moved_range.start(), text);
+        }
+    }
+    let vis_edit = vis_edit.finish();
+
+    let mut moved_defs = Vec::new();
+    for item in &items {
+        let name = match item.syntax().children().find_map(ast::Name::cast) {
+            Some(it) => it,
+            None => continue,
+        };
+        if let Some(NameClass::Definition(Definition::ModuleDef(def))) =
+            NameClass::classify(&ctx.sema, &name)
+        {
+            moved_defs.push(def);
+        }
+    }
+
+    // Every reference from the remaining code must now go through the new module.
+    let mut container_rewriter = SyntaxRewriter::default();
+    let mut outer_imports = Vec::new();
+    let mut qualifications: FxHashMap<FileId, Vec<TextSize>> = FxHashMap::default();
+    let mut module_vis: Option<ast::Visibility> = None;
+    for &def in &moved_defs {
+        for reference in Definition::ModuleDef(def).usages(&ctx.sema).all() {
+            if !is_outside(&reference) {
+                continue;
+            }
+            let frange = reference.file_range;
+            let source_file = ctx.sema.parse(frange.file_id);
+            let name_ref =
+                algo::find_node_at_range::<ast::NameRef>(source_file.syntax(), frange.range)?;
+            let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+            let in_use_tree = path.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind()));
+            if path.qualifier().is_none() && !in_use_tree {
+                if ctx.sema.scope(path.syntax()).module() == Some(module) {
+                    outer_imports.push(name_ref.text().to_string());
+                }
+                continue;
+            }
+            let ref_module = ctx.sema.scope(path.syntax()).module()?;
+            // Only another crate's references need `pub`, and `pub(crate)` doesn't cover those.
+            let is_pub = |vis: &ast::Visibility| vis.l_paren_token().is_none();
+            if !ref_module.path_to_root(db).contains(&module) && !module_vis.iter().any(is_pub) {
+                module_vis = Some(required_visibility(ref_module, module));
+            }
+            if frange.file_id == file_id && container.text_range().contains_range(frange.range) {
+                container_rewriter.replace(path.syntax(), qualify(&path)?.syntax());
+            } else {
+                qualifications.entry(frange.file_id).or_default().push(frange.range.start());
+            }
+        }
+    }
+
+    let super_imports = super_imports(ctx, &items, &moved_defs, &module_scope);
+
+    acc.add(
+        AssistId("extract_module", AssistKind::RefactorExtract),
+        "Extract Module",
+        moved_range,
+        |builder| {
+            let indent = IndentLevel::from_node(items[0].syntax());
+            let mut text =
+                container.text().slice(moved_range - container.text_range().start()).to_string();
+            vis_edit.apply(&mut text);
+            let body = ast::SourceFile::parse(&text).tree().dedent(indent).indent(IndentLevel(1));
+            let mut new_module = make::module(
+                module_vis,
+                make::name(MODULE_NAME),
+                &format!("{}{}", IndentLevel(1), body.syntax()),
+            );
+            for name in &super_imports {
+                let path = make::path_qualified(
+                    make::path_unqualified(make::path_segment_super()),
+                    make::path_segment(make::name_ref(name)),
+                );
+                let item_list = match new_module.item_list() {
+                    Some(it) => it,
+                    None => break,
+                };
+                let scope = ImportScope::Module(item_list.clone());
+                let new_item_list = insert_use(&scope, path, ctx.config.insert_use.merge);
+                new_module = match ast::ItemList::cast(new_item_list) {
+                    Some(it) => new_module.replace_descendant(item_list, it),
+                    None => break,
+                };
+            }
+            let new_module = new_module.indent(indent);
+
+            container_rewriter.replace(items[0].syntax(), new_module.syntax());
+            let to_delete = container
+                .children_with_tokens()
+                .skip_while(|it| it.text_range().start() <= moved_range.start())
+                .take_while(|it| it.text_range().end() <= moved_range.end());
+            for element in to_delete {
+                container_rewriter.delete(&element);
+            }
+            let root = match container.ancestors().last() {
+                Some(it) => it,
+                None => return,
+            };
+            let new_root = container_rewriter.rewrite(&root);
+            let mut new_container = match new_root
+                .descendants()
+                .find(|it| {
+                    it.kind() == container.kind()
+                        && it.text_range().start() == container.text_range().start()
+                })
+                .and_then(ImportScope::from)
+            {
+                Some(it) => it,
+                None => return,
+            };
+            let mut seen = FxHashSet::default();
+            for name in outer_imports.iter().filter(|it| seen.insert(it.as_str())) {
+                let path = make::path_qualified(
+                    make::path_qualified(
+                        make::path_unqualified(make::path_segment_self()),
+                        make::path_segment(make::name_ref(MODULE_NAME)),
+                    ),
+                    make::path_segment(make::name_ref(name)),
+                );
+                let node = insert_use(&new_container, path, ctx.config.insert_use.merge);
+                new_container = match ImportScope::from(node) {
+                    Some(it) => it,
+                    None => return,
+                };
+            }
+            match (ImportScope::from(container.clone()), new_container) {
+                (Some(ImportScope::File(old)), ImportScope::File(new)) => {
+                    builder.replace_ast(old, new)
+                }
+                (Some(ImportScope::Module(old)), ImportScope::Module(new)) => {
+                    builder.replace_ast(old, new)
+                }
+                _ => return,
+            }
+
+            let mut qualifications = qualifications.into_iter().collect::<Vec<_>>();
+            qualifications.sort_by_key(|(file_id, _)| *file_id != ctx.frange.file_id);
+            for (file_id, offsets) in qualifications {
+                builder.edit_file(file_id);
+                for offset in offsets {
+                    builder.insert(offset, format!("{}::", MODULE_NAME));
+                }
+            }
+        },
+    )
+}
+
+/// The moved items, fields and inherent methods whose visibility might need widening.
+fn visibility_candidates(
+    ctx: &AssistContext,
+    items: &[ast::Item],
+) -> Vec<(SyntaxNode, Definition)> {
+    let mut res = Vec::new();
+    let named = |node: &SyntaxNode| {
+        let name = node.children().find_map(ast::Name::cast)?;
+        match NameClass::classify(&ctx.sema, &name)? {
+            NameClass::Definition(def) => Some((node.clone(), def)),
+            _ => None,
+        }
+    };
+    for item in items {
+        match item {
+            ast::Item::Impl(impl_) => {
+                if impl_.trait_().is_some() {
+                    continue;
+                }
+                let assoc_items =
+                    impl_.assoc_item_list().into_iter().flat_map(|it| it.assoc_items());
+                for assoc_item in assoc_items {
+                    res.extend(named(assoc_item.syntax()));
+                }
+            }
+            ast::Item::Use(_)
+            | ast::Item::MacroCall(_)
+            | ast::Item::ExternCrate(_)
+            | ast::Item::ExternBlock(_) => {}
+            _ => res.extend(named(item.syntax())),
+        }
+        if let ast::Item::Struct(strukt) = item {
+            match strukt.field_list() {
+                Some(ast::FieldList::RecordFieldList(fields)) => {
+                    for field in fields.fields() {
+                        res.extend(named(field.syntax()));
+                    }
+                }
+                Some(ast::FieldList::TupleFieldList(fields)) => {
+                    for field in fields.fields() {
+                        if let Some(def) = ctx.sema.to_def(&field) {
+                            res.push((field.syntax().clone(), Definition::Field(def)));
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+    res
+}
+
+fn in_signature(node: &SyntaxNode, range: TextRange) -> bool {
+    if !node.text_range().contains_range(range) {
+        return false;
+    }
+    let body = ast::Fn::cast(node.clone()).and_then(|it| it.body());
+    !body.map_or(false, |it| it.syntax().text_range().contains_range(range))
+}
+
+fn is_tuple_struct_constructor(ctx: &AssistContext, reference: &Reference) -> bool {
+    let source_file = ctx.sema.parse(reference.file_range.file_id);
+    let name_ref =
+        algo::find_node_at_range::<ast::NameRef>(source_file.syntax(), reference.file_range.range);
+    let parent = name_ref
+        .and_then(|it| it.syntax().ancestors().find_map(ast::Path::cast))
+        .and_then(|it| it.syntax().parent());
+    match parent {
+        Some(parent) if ast::TupleStructPat::can_cast(parent.kind()) => true,
+        Some(parent) if ast::PathExpr::can_cast(parent.kind()) => {
+            parent.parent().map_or(false, |it| ast::CallExpr::can_cast(it.kind()))
+        }
+        _ => false,
+    }
+}
+
+/// Items are moved one level deeper, so each visibility has to reach one level further.
+fn widened_visibility(node: &SyntaxNode) -> Option<ast::Visibility> {
+    let vis = match node.children().find_map(ast::Visibility::cast) {
+        Some(it) => it,
+        None => return Some(make::visibility_pub_super()),
+    };
+    match vis.syntax().text().to_string().replace(char::is_whitespace, "").as_str() {
+        "pub(self)" => Some(make::visibility_pub_super()),
+        "pub(super)" => Some(make::visibility_pub_crate()),
+        _ => None,
+    }
+}
+
+/// Inserts the new module in front of the last segment of `path`.
+fn qualify(path: &ast::Path) -> Option<ast::Path> {
+    let module_segment = make::path_segment(make::name_ref(MODULE_NAME));
+    let qualifier = match path.qualifier() {
+        Some(it) => make::path_qualified(it, module_segment),
+        None => make::path_unqualified(module_segment),
+    };
+    Some(make::path_qualified(qualifier, path.segment()?))
+}
+
+/// Names from the original module the moved items still refer to.
+fn super_imports(
+    ctx: &AssistContext,
+    items: &[ast::Item],
+    moved_defs: &[hir::ModuleDef],
+    module_scope: &[(hir::Name, ScopeDef)],
+) -> Vec<String> {
+    let db = ctx.db();
+    let mut res = Vec::new();
+    let mut push = |name: String, def: hir::ModuleDef| {
+        if moved_defs.contains(&def) || res.contains(&name) {
+            return;
+        }
+        let in_scope = module_scope.iter().any(|(it, scope_def)| {
+            it.to_string() == name && matches!(scope_def, ScopeDef::ModuleDef(it) if *it == def)
+        });
+        if in_scope {
+            res.push(name);
+        }
+    };
+    for item in items {
+        if let ast::Item::Use(_) = item {
+            continue;
+        }
+        for node in item.syntax().descendants() {
+            if let Some(path) = ast::Path::cast(node.clone()) {
+                if path.qualifier().is_some() {
+                    continue;
+                }
+                let name_ref = match path.segment().and_then(|it| it.name_ref()) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if let Some(PathResolution::Def(def)) = ctx.sema.resolve_path(&path) {
+                    push(name_ref.text().to_string(), def);
+                }
+            } else if let Some(call) = ast::MethodCallExpr::cast(node) {
+                let trait_ = ctx
+                    .sema
+                    .resolve_method_call(&call)
+                    .and_then(|it| it.as_assoc_item(db))
+                    .map(|it| it.container(db));
+                if let Some(AssocItemContainer::Trait(trait_)) = trait_ {
+                    push(trait_.name(db).to_string(), trait_.into());
+                }
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_single_function() {
+        check_assist(
+            extract_module,
+            r#"
+<|>fn helper() {}<|>
+
+fn main() {}
+"#,
+            r#"
+mod modname {
+    fn helper() {}
+}
+
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_imports_items_left_behind() {
+        check_assist(
+            extract_module,
+            r#"
+struct Config;
+trait Render {
+    fn render(&self);
+}
+impl Render for Config {
+    fn render(&self) {}
+}
+
+<|>fn run(config: Config) {
+    config.render();
+}<|>
+"#,
+            r#"
+struct Config;
+trait Render {
+    fn render(&self);
+}
+impl Render for Config {
+    fn render(&self) {}
+}
+
+mod modname {
+    use super::{Config, Render};
+
+    fn run(config: Config) {
+        config.render();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_fixes_visibility_of_used_items() {
+        check_assist(
+            extract_module,
+            r#"
+<|>struct Counter(u32);
+
+impl Counter {
+    fn get(&self) -> u32 {
+        self.0
+    }
+    fn reset(&mut self) {}
+}
+
+pub(super) fn unused() {}<|>
+
+fn main() {
+    let c = Counter(1);
+    c.get();
+}
+"#,
+            r#"
+use self::modname::Counter;
+
+mod modname {
+    pub(super) struct Counter(pub(super) u32);
+
+    impl Counter {
+        pub(super) fn get(&self) -> u32 {
+            self.0
+        }
+        fn reset(&mut self) {}
+    }
+
+    pub(super) fn unused() {}
+}
+
+fn main() {
+    let c = Counter(1);
+    c.get();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_in_inline_module() {
+        check_assist(
+            extract_module,
+            r#"
+mod outer {
+    <|>pub(super) fn f() {}<|>
+
+    fn g() {
+        self::f();
+    }
+}
+
+fn main() {
+    outer::f();
+}
+"#,
+            r#"
+mod outer {
+    pub(crate) mod modname {
+        pub(crate) fn f() {}
+    }
+
+    fn g() {
+        self::modname::f();
+    }
+}
+
+fn main() {
+    outer::modname::f();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_rewrites_references_in_other_files() {
+        check_assist(
+            extract_module,
+            r#"
+//- /main.rs
+mod other;
+<|>pub fn f() {}<|>
+//- /other.rs
+use crate::f;
+fn g() {
+    crate::f();
+}
+"#,
+            r#"
+//- /main.rs
+mod other;
+mod modname {
+    pub fn f() {}
+}
+//- /other.rs
+use crate::modname::f;
+fn g() {
+    crate::modname::f();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_keeps_items_public_for_other_crates() {
+        check_assist(
+            extract_module,
+            r#"
+//- /main.rs crate:main deps:dep
+fn g() {
+    dep::f();
+}
+//- /lib.rs crate:dep
+<|>pub fn f() {}<|>
+"#,
+            r#"
+//- /main.rs
+fn g() {
+    dep::modname::f();
+}
+//- /lib.rs
+pub mod modname {
+    pub fn f() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_whole_item() {
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+fn f() {
+    <|>let x = 1;<|>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_name_taken() {
+        mark::check!(extract_module_name_taken);
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+mod modname {}
+<|>fn f() {}<|>
+"#,
+        );
+    }
+}
@@ -0,0 +1,500 @@
+use hir::ModuleDef;
+use ide_db::defs::Definition;
+use rustc_hash::FxHashSet;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, edit::IndentLevel, make, AstNode, GenericParamsOwner, NameOwner, VisibilityOwner},
+    SyntaxKind, TextRange, TextSize,
+};
+use test_utils::mark;
+
+use crate::{
+    utils::{insert_use, mod_path_to_ast, ImportScope},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+const TRAIT_NAME: &str = "NewTrait";
+
+// Assist: extract_trait
+//
+// Moves the selected methods of an inherent impl into a new trait, importing it where the
+// methods are called.
+//
+// ```
+// struct S;
+//
+// impl <|>S {
+//     pub fn f(&self) -> i32 {
+//         92
+//     }
+// }
+// ```
+// ->
+// ```
+// struct S;
+//
+// pub trait NewTrait {
+//     fn f(&self) -> i32;
+// }
+//
+// impl NewTrait for S {
+//     fn f(&self) -> i32 {
+//         92
+//     }
+// }
+// ```
+pub(crate) fn extract_trait(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_def = ctx.find_node_at_offset::<ast::Impl>()?;
+    if impl_def.trait_().is_some() {
+        return None;
+    }
+    let self_ty = impl_def.self_ty()?;
+    let item_list = impl_def.assoc_item_list()?;
+    let selection = ctx.frange.range;
+    if selection.is_empty() && selection.start() > item_list.syntax().text_range().start() {
+        return None;
+    }
+    let all_fns = item_list
+        .assoc_items()
+        .filter_map(|it| match it {
+            ast::AssocItem::Fn(it) => Some(it),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let fns = all_fns
+        .iter()
+        .filter(|it| {
+            selection.is_empty()
+                || selection.intersect(it.syntax().text_range()).map_or(false, |it| !it.is_empty())
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if fns.is_empty() {
+        return None;
+    }
+    let impl_params = impl_generic_names(&impl_def);
+    if fns.iter().any(|it| signature_mentions(it, &impl_params)) {
+        mark::hit!(extract_trait_impl_generics);
+        return None;
+    }
+    let is_whole_impl = fns.len() == item_list.assoc_items().count();
+    let module = ctx.sema.scope(impl_def.syntax()).module()?;
+
+    let target = impl_def.syntax().text_range();
+    acc.add(
+        AssistId("extract_trait", AssistKind::RefactorExtract),
+        "Extract trait",
+        target,
+        |builder| {
+            let db = ctx.db();
+            let indent = IndentLevel::from_node(impl_def.syntax());
+            let visibility = fns.iter().find_map(|it| it.visibility());
+            let signatures = fns
+                .iter()
+                .filter_map(|it| signature(it))
+                .map(|it| format!("{}{};", indent + 1, it))
+                .collect::<Vec<_>>();
+            let trait_def = format!(
+                "{}trait {} {{\n{}\n{}}}\n\n{}",
+                visibility.map(|it| format!("{} ", it)).unwrap_or_default(),
+                TRAIT_NAME,
+                signatures.join("\n"),
+                indent,
+                indent,
+            );
+            let functions = fns
+                .iter()
+                .filter_map(|it| ctx.sema.to_def(it))
+                .map(ModuleDef::Function)
+                .collect::<Vec<_>>();
+
+            // Method calls outside of the impl's module need the trait in scope. Only the changed
+            // text of each import scope is replaced, as the scope may contain the impl itself.
+            let mut scopes = FxHashSet::default();
+            for function in functions {
+                for reference in Definition::ModuleDef(function).usages(&ctx.sema).all() {
+                    let file_id = reference.file_range.file_id;
+                    let source_file = ctx.sema.parse(file_id);
+                    let name_ref = match find_node_at_range::<ast::NameRef>(
+                        source_file.syntax(),
+                        reference.file_range.range,
+                    ) {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    let scope = ctx.sema.scope(name_ref.syntax());
+                    let call_module = match scope.module() {
+                        Some(it) if it != module => it,
+                        _ => continue,
+                    };
+                    let import_scope =
+                        match ImportScope::find_insert_use_container(name_ref.syntax(), ctx) {
+                            Some(it) => it,
+                            None => continue,
+                        };
+                    if !scopes.insert((file_id, import_scope.as_syntax_node().text_range())) {
+                        continue;
+                    }
+                    let qualifier = match call_module.find_use_path(db, ModuleDef::Module(module)) {
+                        Some(it) => mod_path_to_ast(&it),
+                        None => continue,
+                    };
+                    let path = make::path_qualified(
+                        qualifier,
+                        make::path_segment(make::name_ref(TRAIT_NAME)),
+                    );
+                    let old_scope = import_scope.as_syntax_node();
+                    let new_scope = insert_use(&import_scope, path, ctx.config.insert_use.merge);
+                    let (range, text) =
+                        changed_text(&old_scope.to_string(), &new_scope.to_string());
+                    builder.edit_file(file_id);
+                    builder.replace(range + old_scope.text_range().start(), text);
+                }
+            }
+            builder.edit_file(ctx.frange.file_id);
+
+            builder.insert(impl_def.syntax().text_range().start(), trait_def);
+
+            if is_whole_impl {
+                builder
+                    .insert(self_ty.syntax().text_range().start(), format!("{} for ", TRAIT_NAME));
+                for function in fns.iter() {
+                    if let Some(range) = visibility_range(function) {
+                        builder.delete(range);
+                    }
+                }
+            } else {
+                let methods = fns
+                    .iter()
+                    .map(|function| {
+                        let text = function.syntax().to_string();
+                        let text = match visibility_range(function) {
+                            Some(range) => {
+                                let range = range - function.syntax().text_range().start();
+                                let mut text = text;
+                                text.replace_range(
+                                    usize::from(range.start())..usize::from(range.end()),
+                                    "",
+                                );
+                                text
+                            }
+                            None => text,
+                        };
+                        format!("{}{}", indent + 1, text)
+                    })
+                    .collect::<Vec<_>>();
+                for function in fns.iter() {
+                    builder.delete(with_leading_whitespace(function.syntax()));
+                }
+                let generic_params =
+                    impl_def.generic_param_list().map(|it| it.to_string()).unwrap_or_default();
+                let where_clause =
+                    impl_def.where_clause().map(|it| format!(" {}", it)).unwrap_or_default();
+                let trait_impl = format!(
+                    "\n\n{}impl{} {} for {}{} {{\n{}\n{}}}",
+                    indent,
+                    generic_params,
+                    TRAIT_NAME,
+                    self_ty,
+                    where_clause,
+                    methods.join("\n\n"),
+                    indent,
+                );
+                builder.insert(impl_def.syntax().text_range().end(), trait_impl);
+            }
+        },
+    )
+}
+
+fn impl_generic_names(impl_def: &ast::Impl) -> Vec<String> {
+    let params = match impl_def.generic_param_list() {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let types = params.type_params().filter_map(|it| it.name()).map(|it| it.to_string());
+    let lifetimes = params
+        .lifetime_params()
+        .filter_map(|it| it.lifetime_token())
+        .map(|it| it.text().to_string());
+    types.chain(lifetimes).collect()
+}
+
+/// Whether the signature of `function` refers to a generic parameter of the impl.
+fn signature_mentions(function: &ast::Fn, names: &[String]) -> bool {
+    let end = function
+        .body()
+        .map_or(function.syntax().text_range().end(), |it| it.syntax().text_range().start());
+    function
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .take_while(|it| it.text_range().start() < end)
+        .filter(|it| matches!(it.kind(), SyntaxKind::IDENT | SyntaxKind::LIFETIME))
+        .any(|it| names.iter().any(|name| it.text() == name.as_str()))
+}
+
+/// The text of the function from its qualifiers up to the body.
+fn signature(function: &ast::Fn) -> Option<String> {
+    let start = function
+        .const_token()
+        .or_else(|| function.async_token())
+        .or_else(|| function.unsafe_token())
+        .map(|it| it.text_range().start())
+        .or_else(|| function.abi().map(|it| it.syntax().text_range().start()))
+        .or_else(|| function.fn_token().map(|it| it.text_range().start()))?;
+    let end = function.body()?.syntax().text_range().start();
+    let offset = function.syntax().text_range().start();
+    let range = TextRange::new(start - offset, end - offset);
+    Some(function.syntax().text().slice(range).to_string().trim_end().to_string())
+}
+
+/// The visibility of the function with the whitespace following it.
+fn visibility_range(function: &ast::Fn) -> Option<TextRange> {
+    let visibility = function.visibility()?;
+    let end = match visibility.syntax().next_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().end(),
+        _ => visibility.syntax().text_range().end(),
+    };
+    Some(TextRange::new(visibility.syntax().text_range().start(), end))
+}
+
+/// The range of `old` that differs from `new`, with its replacement.
+fn changed_text(old: &str, new: &str) -> (TextRange, String) {
+    let prefix: usize =
+        old.chars().zip(new.chars()).take_while(|(l, r)| l == r).map(|(it, _)| it.len_utf8()).sum();
+    let suffix: usize = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(l, r)| l == r)
+        .map(|(it, _)| it.len_utf8())
+        .sum();
+    let range =
+        TextRange::new(TextSize::of(&old[..prefix]), TextSize::of(&old[..old.len() - suffix]));
+    (range, new[prefix..new.len() - suffix].to_string())
+}
+
+fn with_leading_whitespace(node: &syntax::SyntaxNode) -> TextRange {
+    let start: TextSize = match node.prev_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+        _ => node.text_range().start(),
+    };
+    TextRange::new(start, node.text_range().end())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_whole_impl() {
+        check_assist(
+            extract_trait,
+            r#"
+struct S<T>(T);
+
+impl<T: Clone> <|>S<T> {
+    /// Docs.
+    pub(crate) fn get(&self, other: i32) -> i32 {
+        other
+    }
+
+    fn set<U>(&mut self, value: U) where U: Into<i32> {}
+}
+
+fn f(s: S<u8>) {
+    s.get(1);
+}
+"#,
+            r#"
+struct S<T>(T);
+
+pub(crate) trait NewTrait {
+    fn get(&self, other: i32) -> i32;
+    fn set<U>(&mut self, value: U) where U: Into<i32>;
+}
+
+impl<T: Clone> NewTrait for S<T> {
+    /// Docs.
+    fn get(&self, other: i32) -> i32 {
+        other
+    }
+
+    fn set<U>(&mut self, value: U) where U: Into<i32> {}
+}
+
+fn f(s: S<u8>) {
+    s.get(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_selected_methods() {
+        check_assist(
+            extract_trait,
+            r#"
+struct S;
+
+impl S {
+    fn new() -> S {
+        S
+    }
+
+    <|>fn a(&self) {}
+
+    fn b(&self) {}<|>
+
+    const C: i32 = 0;
+}
+"#,
+            r#"
+struct S;
+
+trait NewTrait {
+    fn a(&self);
+    fn b(&self);
+}
+
+impl S {
+    fn new() -> S {
+        S
+    }
+
+    const C: i32 = 0;
+}
+
+impl NewTrait for S {
+    fn a(&self) {}
+
+    fn b(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_imports_trait_at_call_sites() {
+        check_assist(
+            extract_trait,
+            r#"
+//- /main.rs
+mod foo;
+mod bar;
+fn main() {
+    foo::S.f();
+}
+//- /foo.rs
+pub struct S;
+impl <|>S {
+    pub fn f(&self) {}
+}
+//- /bar.rs
+use crate::foo::S;
+
+fn g() {
+    S.f();
+    S::f(&S);
+}
+"#,
+            r#"
+//- /main.rs
+use foo::NewTrait;
+
+mod foo;
+mod bar;
+fn main() {
+    foo::S.f();
+}
+//- /foo.rs
+pub struct S;
+pub trait NewTrait {
+    fn f(&self);
+}
+
+impl NewTrait for S {
+    fn f(&self) {}
+}
+//- /bar.rs
+use crate::foo::{NewTrait, S};
+
+fn g() {
+    S.f();
+    S::f(&S);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_imports_trait_in_same_file() {
+        check_assist(
+            extract_trait,
+            r#"
+mod a {
+    pub struct S;
+    impl <|>S {
+        pub fn f(&self) -> i32 {
+            92
+        }
+    }
+}
+fn g(s: &a::S) -> i32 {
+    s.f()
+}
+"#,
+            r#"
+use a::NewTrait;
+
+mod a {
+    pub struct S;
+    pub trait NewTrait {
+        fn f(&self) -> i32;
+    }
+
+    impl NewTrait for S {
+        fn f(&self) -> i32 {
+            92
+        }
+    }
+}
+fn g(s: &a::S) -> i32 {
+    s.f()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_impl_generics_in_signature() {
+        mark::check!(extract_trait_impl_generics);
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+struct S<T>(T);
+impl<T> <|>S<T> {
+    fn get(&self) -> &T { &self.0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_impl() {
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+trait Tr { fn f(&self); }
+struct S;
+impl Tr for <|>S {
+    fn f(&self) {}
+}
+"#,
+        );
+    }
+}
@@ -1,11 +1,12 @@
 use hir::{db::HirDatabase, HasSource, HasVisibility, PathResolution};
 use ide_db::base_db::FileId;
-use syntax::{
-    ast::{self, VisibilityOwner},
-    AstNode, TextRange, TextSize,
-};
+use syntax::{ast, AstNode, SyntaxNode};
 
-use crate::{utils::vis_offset, AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    assist_context::AssistBuilder,
+    utils::{required_visibility, visibility_edit},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // FIXME: this really should be a fix for diagnostic, rather than an assist.
 
@@ -51,35 +52,19 @@ fn add_vis_to_referenced_module_def(acc: &mut Assists, ctx: &AssistContext) -> O
         return None;
     };
 
-    let (offset, current_visibility, target, target_file, target_name) =
-        target_data_for_def(ctx.db(), def)?;
+    let (target_node, target_file, target_name) = target_data_for_def(ctx.db(), def)?;
 
-    let missing_visibility =
-        if current_module.krate() == target_module.krate() { "pub(crate)" } else { "pub" };
+    let missing_visibility = required_visibility(current_module, target_module);
 
     let assist_label = match target_name {
         None => format!("Change visibility to {}", missing_visibility),
         Some(name) => format!("Change visibility of {} to {}", name, missing_visibility),
     };
 
+    let target = target_node.text_range();
     acc.add(AssistId("fix_visibility", AssistKind::QuickFix), assist_label, target, |builder| {
         builder.edit_file(target_file);
-        match ctx.config.snippet_cap {
-            Some(cap) => match current_visibility {
-                Some(current_visibility) => builder.replace_snippet(
-                    cap,
-                    current_visibility.syntax().text_range(),
-                    format!("$0{}", missing_visibility),
-                ),
-                None => builder.insert_snippet(cap, offset, format!("$0{} ", missing_visibility)),
-            },
-            None => match current_visibility {
-                Some(current_visibility) => {
-                    builder.replace(current_visibility.syntax().text_range(), missing_visibility)
-                }
-                None => builder.insert(offset, format!("{} ", missing_visibility)),
-            },
-        }
+        add_visibility(ctx, builder, &target_node, &missing_visibility)
     })
 }
 
@@ -98,113 +83,93 @@ fn add_vis_to_referenced_record_field(acc: &mut Assists, ctx: &AssistContext) ->
     let target_module = parent.module(ctx.db());
 
     let in_file_source = record_field_def.source(ctx.db());
-    let (offset, current_visibility, target) = match in_file_source.value {
-        hir::FieldSource::Named(it) => {
-            let s = it.syntax();
-            (vis_offset(s), it.visibility(), s.text_range())
-        }
-        hir::FieldSource::Pos(it) => {
-            let s = it.syntax();
-            (vis_offset(s), it.visibility(), s.text_range())
-        }
+    let target_node = match in_file_source.value {
+        hir::FieldSource::Named(it) => it.syntax().clone(),
+        hir::FieldSource::Pos(it) => it.syntax().clone(),
     };
 
-    let missing_visibility =
-        if current_module.krate() == target_module.krate() { "pub(crate)" } else { "pub" };
+    let missing_visibility = required_visibility(current_module, target_module);
     let target_file = in_file_source.file_id.original_file(ctx.db());
 
     let target_name = record_field_def.name(ctx.db());
     let assist_label =
         format!("Change visibility of {}.{} to {}", parent_name, target_name, missing_visibility);
 
+    let target = target_node.text_range();
     acc.add(AssistId("fix_visibility", AssistKind::QuickFix), assist_label, target, |builder| {
         builder.edit_file(target_file);
-        match ctx.config.snippet_cap {
-            Some(cap) => match current_visibility {
-                Some(current_visibility) => builder.replace_snippet(
-                    cap,
-                    current_visibility.syntax().text_range(),
-                    format!("$0{}", missing_visibility),
-                ),
-                None => builder.insert_snippet(cap, offset, format!("$0{} ", missing_visibility)),
-            },
-            None => match current_visibility {
-                Some(current_visibility) => {
-                    builder.replace(current_visibility.syntax().text_range(), missing_visibility)
-                }
-                None => builder.insert(offset, format!("{} ", missing_visibility)),
-            },
-        }
+        add_visibility(ctx, builder, &target_node, &missing_visibility)
     })
 }
 
+fn add_visibility(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    node: &SyntaxNode,
+    missing_visibility: &ast::Visibility,
+) {
+    let (range, text) = visibility_edit(node, missing_visibility);
+    match ctx.config.snippet_cap {
+        Some(cap) => builder.replace_snippet(cap, range, format!("$0{}", text)),
+        None => builder.replace(range, text),
+    }
+}
+
 fn target_data_for_def(
     db: &dyn HirDatabase,
     def: hir::ModuleDef,
-) -> Option<(TextSize, Option<ast::Visibility>, TextRange, FileId, Option<hir::Name>)> {
-    fn offset_target_and_file_id<S, Ast>(
-        db: &dyn HirDatabase,
-        x: S,
-    ) -> (TextSize, Option<ast::Visibility>, TextRange, FileId)
+) -> Option<(SyntaxNode, FileId, Option<hir::Name>)> {
+    fn node_and_file_id<S, Ast>(db: &dyn HirDatabase, x: S) -> (SyntaxNode, FileId)
     where
         S: HasSource<Ast = Ast>,
-        Ast: AstNode + ast::VisibilityOwner,
+        Ast: AstNode,
     {
         let source = x.source(db);
         let in_file_syntax = source.syntax();
-        let file_id = in_file_syntax.file_id;
-        let syntax = in_file_syntax.value;
-        let current_visibility = source.value.visibility();
-        (
-            vis_offset(syntax),
-            current_visibility,
-            syntax.text_range(),
-            file_id.original_file(db.upcast()),
-        )
+        (in_file_syntax.value.clone(), in_file_syntax.file_id.original_file(db.upcast()))
     }
 
     let target_name;
-    let (offset, current_visibility, target, target_file) = match def {
+    let (target_node, target_file) = match def {
         hir::ModuleDef::Function(f) => {
             target_name = Some(f.name(db));
-            offset_target_and_file_id(db, f)
+            node_and_file_id(db, f)
         }
         hir::ModuleDef::Adt(adt) => {
             target_name = Some(adt.name(db));
             match adt {
-                hir::Adt::Struct(s) => offset_target_and_file_id(db, s),
-                hir::Adt::Union(u) => offset_target_and_file_id(db, u),
-                hir::Adt::Enum(e) => offset_target_and_file_id(db, e),
+                hir::Adt::Struct(s) => node_and_file_id(db, s),
+                hir::Adt::Union(u) => node_and_file_id(db, u),
+                hir::Adt::Enum(e) => node_and_file_id(db, e),
             }
         }
         hir::ModuleDef::Const(c) => {
             target_name = c.name(db);
-            offset_target_and_file_id(db, c)
+            node_and_file_id(db, c)
         }
         hir::ModuleDef::Static(s) => {
             target_name = s.name(db);
-            offset_target_and_file_id(db, s)
+            node_and_file_id(db, s)
         }
         hir::ModuleDef::Trait(t) => {
             target_name = Some(t.name(db));
-            offset_target_and_file_id(db, t)
+            node_and_file_id(db, t)
         }
         hir::ModuleDef::TypeAlias(t) => {
             target_name = Some(t.name(db));
-            offset_target_and_file_id(db, t)
+            node_and_file_id(db, t)
         }
         hir::ModuleDef::Module(m) => {
             target_name = m.name(db);
             let in_file_source = m.declaration_source(db)?;
             let file_id = in_file_source.file_id.original_file(db.upcast());
-            let syntax = in_file_source.value.syntax();
-            (vis_offset(syntax), in_file_source.value.visibility(), syntax.text_range(), file_id)
+            (in_file_source.value.syntax().clone(), file_id)
         }
         // Enum variants can't be private, we can't modify builtin types
         hir::ModuleDef::EnumVariant(_) | hir::ModuleDef::BuiltinType(_) => return None,
     };
 
-    Some((offset, current_visibility, target, target_file, target_name))
+    Some((target_node, target_file, target_name))
 }
 
 #[cfg(test)]
@@ -0,0 +1,170 @@
+use itertools::Itertools;
+use stdx::to_lower_snake_case;
+use syntax::ast::{self, AstNode, NameOwner, VisibilityOwner};
+
+use crate::{utils::AdtImpls, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_enum_as_method
+//
+// Generates an `as_` method for an enum variant with fields, returning references to them.
+//
+// ```
+// enum Value {
+//  Number(i32),
+//  Text(String)<|>,
+// }
+// ```
+// ->
+// ```
+// enum Value {
+//  Number(i32),
+//  Text(String),
+// }
+//
+// impl Value {
+//     fn $0as_text(&self) -> Option<&String> {
+//         if let Self::Text(v) = self {
+//             Some(v)
+//         } else {
+//             None
+//         }
+//     }
+// }
+// ```
+pub(crate) fn generate_enum_as_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let variant = ctx.find_node_at_offset::<ast::Variant>()?;
+    let variant_name = variant.name()?;
+    let parent_enum = variant.parent_enum();
+    let fn_name = format!("as_{}", to_lower_snake_case(variant_name.text()));
+
+    let (pattern, bindings, types) = match variant.kind() {
+        ast::StructKind::Record(fields) => {
+            let fields = fields.fields().collect::<Vec<_>>();
+            let names = fields.iter().map(|it| it.name()).collect::<Option<Vec<_>>>()?;
+            let types = fields.iter().map(|it| it.ty()).collect::<Option<Vec<_>>>()?;
+            let names = names.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+            (format!(" {{ {} }}", names.iter().format(", ")), names, types)
+        }
+        ast::StructKind::Tuple(fields) => {
+            let types = fields.fields().map(|it| it.ty()).collect::<Option<Vec<_>>>()?;
+            let names = if types.len() == 1 {
+                vec!["v".to_string()]
+            } else {
+                (0..types.len()).map(|it| format!("v{}", it)).collect()
+            };
+            (format!("({})", names.iter().format(", ")), names, types)
+        }
+        ast::StructKind::Unit => return None,
+    };
+    if types.is_empty() {
+        return None;
+    }
+
+    let impls = AdtImpls::new(ctx, parent_enum.clone().into())?;
+    if impls.has_method(&fn_name) {
+        return None;
+    }
+
+    let (ret_ty, value) = if types.len() == 1 {
+        (format!("&{}", types[0]), bindings[0].clone())
+    } else {
+        (
+            format!("({})", types.iter().map(|it| format!("&{}", it)).format(", ")),
+            format!("({})", bindings.iter().format(", ")),
+        )
+    };
+    let vis = parent_enum.visibility().map_or(String::new(), |v| format!("{} ", v));
+    let method = format!(
+        "{}fn $0{}(&self) -> Option<{}> {{
+    if let Self::{}{} = self {{
+        Some({})
+    }} else {{
+        None
+    }}
+}}",
+        vis, fn_name, ret_ty, variant_name, pattern, value,
+    );
+
+    let target = variant.syntax().text_range();
+    acc.add(
+        AssistId("generate_enum_as_method", AssistKind::Generate),
+        "Generate an `as_` method for an enum variant",
+        target,
+        |builder| impls.add_methods(builder, ctx.config.snippet_cap, &[method]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_as_method_for_record_variant() {
+        check_assist(
+            generate_enum_as_method,
+            r#"
+enum Shape {
+    Rect<|> { width: u32, height: u32 },
+    Circle { radius: u32 },
+}
+"#,
+            r#"
+enum Shape {
+    Rect { width: u32, height: u32 },
+    Circle { radius: u32 },
+}
+
+impl Shape {
+    fn $0as_rect(&self) -> Option<(&u32, &u32)> {
+        if let Self::Rect { width, height } = self {
+            Some((width, height))
+        } else {
+            None
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_as_method_for_tuple_variant() {
+        check_assist(
+            generate_enum_as_method,
+            r#"
+enum Value {
+    Pair<|>(i32, String),
+}
+"#,
+            r#"
+enum Value {
+    Pair(i32, String),
+}
+
+impl Value {
+    fn $0as_pair(&self) -> Option<(&i32, &String)> {
+        if let Self::Pair(v0, v1) = self {
+            Some((v0, v1))
+        } else {
+            None
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_as_method_not_applicable_to_unit_variant() {
+        check_assist_not_applicable(
+            generate_enum_as_method,
+            r#"
+enum Value {
+    Empty<|>,
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use stdx::to_lower_snake_case;
+use syntax::ast::{self, AstNode, NameOwner, VisibilityOwner};
+
+use crate::{utils::AdtImpls, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_enum_is_method
+//
+// Generates an `is_` method for an enum variant.
+//
+// ```
+// enum Version {
+//  Undefined,
+//  Minor<|>,
+//  Major,
+// }
+// ```
+// ->
+// ```
+// enum Version {
+//  Undefined,
+//  Minor,
+//  Major,
+// }
+//
+// impl Version {
+//     fn $0is_minor(&self) -> bool {
+//         matches!(self, Self::Minor)
+//     }
+// }
+// ```
+pub(crate) fn generate_enum_is_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let variant = ctx.find_node_at_offset::<ast::Variant>()?;
+    let variant_name = variant.name()?;
+    let parent_enum = variant.parent_enum();
+    let fn_name = format!("is_{}", to_lower_snake_case(variant_name.text()));
+
+    let impls = AdtImpls::new(ctx, parent_enum.clone().into())?;
+    if impls.has_method(&fn_name) {
+        return None;
+    }
+
+    let pattern_suffix = match variant.kind() {
+        ast::StructKind::Record(_) => " { .. }",
+        ast::StructKind::Tuple(_) => "(..)",
+        ast::StructKind::Unit => "",
+    };
+    let vis = parent_enum.visibility().map_or(String::new(), |v| format!("{} ", v));
+    let method = format!(
+        "{}fn $0{}(&self) -> bool {{\n    matches!(self, Self::{}{})\n}}",
+        vis, fn_name, variant_name, pattern_suffix,
+    );
+
+    let target = variant.syntax().text_range();
+    acc.add(
+        AssistId("generate_enum_is_method", AssistKind::Generate),
+        "Generate an `is_` method for an enum variant",
+        target,
+        |builder| impls.add_methods(builder, ctx.config.snippet_cap, &[method]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_is_method_for_variants() {
+        check_assist(
+            generate_enum_is_method,
+            r#"
+pub(crate) enum Event {
+    KeyPress<|> { code: u32 },
+    Resize(u32, u32),
+}
+"#,
+            r#"
+pub(crate) enum Event {
+    KeyPress { code: u32 },
+    Resize(u32, u32),
+}
+
+impl Event {
+    pub(crate) fn $0is_key_press(&self) -> bool {
+        matches!(self, Self::KeyPress { .. })
+    }
+}
+"#,
+        );
+        check_assist(
+            generate_enum_is_method,
+            r#"
+enum Event {
+    KeyPress { code: u32 },
+    Resize<|>(u32, u32),
+}
+
+impl Event {
+    fn is_key_press(&self) -> bool {
+        matches!(self, Self::KeyPress { .. })
+    }
+}
+"#,
+            r#"
+enum Event {
+    KeyPress { code: u32 },
+    Resize(u32, u32),
+}
+
+impl Event {
+    fn is_key_press(&self) -> bool {
+        matches!(self, Self::KeyPress { .. })
+    }
+
+    fn $0is_resize(&self) -> bool {
+        matches!(self, Self::Resize(..))
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_is_method_not_applicable_if_exists() {
+        check_assist_not_applicable(
+            generate_enum_is_method,
+            r#"
+enum Variant {
+    Undefined,
+    Minor<|>,
+}
+
+impl Variant {
+    fn is_minor(&self) -> bool {
+        matches!(self, Self::Minor)
+    }
+}
+"#,
+        );
+    }
+}
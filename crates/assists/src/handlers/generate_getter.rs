@@ -0,0 +1,302 @@
+use syntax::ast::{self, AstNode, NameOwner, VisibilityOwner};
+
+use crate::{
+    utils::{selected_record_fields, AdtImpls},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_getter
+//
+// Generates a getter method for the selected fields.
+//
+// ```
+// struct Person {
+//     nam<|>e: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn $0name(&self) -> &str {
+//         self.name.as_str()
+//     }
+// }
+// ```
+pub(crate) fn generate_getter(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_getter_impl(acc, ctx, false)
+}
+
+// Assist: generate_getter_mut
+//
+// Generates a mutable getter method for the selected fields.
+//
+// ```
+// struct Person {
+//     nam<|>e: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn $0name_mut(&mut self) -> &mut String {
+//         &mut self.name
+//     }
+// }
+// ```
+pub(crate) fn generate_getter_mut(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_getter_impl(acc, ctx, true)
+}
+
+fn generate_getter_impl(acc: &mut Assists, ctx: &AssistContext, mutable: bool) -> Option<()> {
+    let (strukt, fields) = selected_record_fields(ctx)?;
+    let impls = AdtImpls::new(ctx, strukt.clone().into())?;
+    let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+
+    let mut methods = Vec::new();
+    for field in &fields {
+        let field_name = field.name()?;
+        let ty = field.ty()?;
+        let fn_name = if mutable { format!("{}_mut", field_name) } else { field_name.to_string() };
+        if impls.has_method(&fn_name) {
+            continue;
+        }
+        let (ret_ty, body) = if mutable {
+            (format!("&mut {}", ty), format!("&mut self.{}", field_name))
+        } else {
+            getter_shape(&field_name.to_string(), &ty)
+        };
+        let self_param = if mutable { "&mut self" } else { "&self" };
+        let tab_stop = if methods.is_empty() { "$0" } else { "" };
+        methods.push(format!(
+            "{}fn {}{}({}) -> {} {{\n    {}\n}}",
+            vis, tab_stop, fn_name, self_param, ret_ty, body
+        ));
+    }
+    if methods.is_empty() {
+        return None;
+    }
+
+    let (id, label) = if mutable {
+        ("generate_getter_mut", "Generate a mut getter method")
+    } else {
+        ("generate_getter", "Generate a getter method")
+    };
+    let target = fields[0].syntax().text_range().cover(fields.last()?.syntax().text_range());
+    acc.add(AssistId(id, AssistKind::Generate), label, target, |builder| {
+        impls.add_methods(builder, ctx.config.snippet_cap, &methods);
+    })
+}
+
+/// The return type and the body of a shared getter, borrowing the contents of owning types.
+fn getter_shape(field_name: &str, ty: &ast::Type) -> (String, String) {
+    let owned = match ty {
+        ast::Type::PathType(it) => it.path().filter(|it| it.qualifier().is_none()),
+        _ => None,
+    };
+    let segment = owned.and_then(|it| it.segment());
+    let type_arg = segment.as_ref().and_then(|it| it.generic_arg_list()).and_then(|it| {
+        match it.generic_args().next()? {
+            ast::GenericArg::TypeArg(it) => it.ty(),
+            _ => None,
+        }
+    });
+    let name = segment.and_then(|it| it.name_ref()).map(|it| it.text().to_string());
+    match (name.as_deref(), type_arg) {
+        (Some("String"), None) => ("&str".to_string(), format!("self.{}.as_str()", field_name)),
+        (Some("Vec"), Some(arg)) => {
+            (format!("&[{}]", arg), format!("self.{}.as_slice()", field_name))
+        }
+        (Some("Option"), Some(arg)) => {
+            (format!("Option<&{}>", arg), format!("self.{}.as_ref()", field_name))
+        }
+        _ => (format!("&{}", ty), format!("&self.{}", field_name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_getter_for_plain_field() {
+        check_assist(
+            generate_getter,
+            r#"
+struct Context<T: Clone> {
+    dat<|>a: T,
+}
+"#,
+            r#"
+struct Context<T: Clone> {
+    data: T,
+}
+
+impl<T: Clone> Context<T> {
+    fn $0data(&self) -> &T {
+        &self.data
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_getter_borrows_contents() {
+        check_assist(
+            generate_getter,
+            r#"
+pub(crate) struct S {
+    <|>name: String,
+    items: Vec<u32>,
+    parent: Option<Box<S>>,<|>
+}
+"#,
+            r#"
+pub(crate) struct S {
+    name: String,
+    items: Vec<u32>,
+    parent: Option<Box<S>>,
+}
+
+impl S {
+    pub(crate) fn $0name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub(crate) fn items(&self) -> &[u32] {
+        self.items.as_slice()
+    }
+
+    pub(crate) fn parent(&self) -> Option<&Box<S>> {
+        self.parent.as_ref()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_getter_in_existing_impl() {
+        check_assist(
+            generate_getter,
+            r#"
+struct S {
+    a: u32,
+    <|>b: u32,
+}
+
+impl S {
+    fn a(&self) -> &u32 {
+        &self.a
+    }
+}
+"#,
+            r#"
+struct S {
+    a: u32,
+    b: u32,
+}
+
+impl S {
+    fn a(&self) -> &u32 {
+        &self.a
+    }
+
+    fn $0b(&self) -> &u32 {
+        &self.b
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_getter_skips_existing_methods() {
+        check_assist(
+            generate_getter,
+            r#"
+struct S {
+    <|>a: u32,
+    b: u32,<|>
+}
+
+impl S {}
+
+impl S {
+    fn a(&self) -> &u32 {
+        &self.a
+    }
+}
+"#,
+            r#"
+struct S {
+    a: u32,
+    b: u32,
+}
+
+impl S {
+    fn $0b(&self) -> &u32 {
+        &self.b
+    }
+}
+
+impl S {
+    fn a(&self) -> &u32 {
+        &self.a
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_getter_not_applicable_if_exists() {
+        check_assist_not_applicable(
+            generate_getter,
+            r#"
+struct S {
+    <|>a: u32,
+}
+
+impl S {
+    fn a(&self) -> &u32 {
+        &self.a
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_getter_mut_for_field() {
+        check_assist(
+            generate_getter_mut,
+            r#"
+struct S {
+    <|>items: Vec<u32>,
+}
+"#,
+            r#"
+struct S {
+    items: Vec<u32>,
+}
+
+impl S {
+    fn $0items_mut(&mut self) -> &mut Vec<u32> {
+        &mut self.items
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use syntax::ast::{AstNode, NameOwner, VisibilityOwner};
+
+use crate::{
+    utils::{selected_record_fields, AdtImpls},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_setter
+//
+// Generates a setter method for the selected fields.
+//
+// ```
+// struct Person {
+//     nam<|>e: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn $0set_name(&mut self, name: String) {
+//         self.name = name;
+//     }
+// }
+// ```
+pub(crate) fn generate_setter(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (strukt, fields) = selected_record_fields(ctx)?;
+    let impls = AdtImpls::new(ctx, strukt.clone().into())?;
+    let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+
+    let mut methods = Vec::new();
+    for field in &fields {
+        let field_name = field.name()?;
+        let ty = field.ty()?;
+        let fn_name = format!("set_{}", field_name);
+        if impls.has_method(&fn_name) {
+            continue;
+        }
+        let tab_stop = if methods.is_empty() { "$0" } else { "" };
+        methods.push(format!(
+            "{vis}fn {tab_stop}{fn_name}(&mut self, {field}: {ty}) {{\n    self.{field} = {field};\n}}",
+            vis = vis,
+            tab_stop = tab_stop,
+            fn_name = fn_name,
+            field = field_name,
+            ty = ty,
+        ));
+    }
+    if methods.is_empty() {
+        return None;
+    }
+
+    let target = fields[0].syntax().text_range().cover(fields.last()?.syntax().text_range());
+    acc.add(
+        AssistId("generate_setter", AssistKind::Generate),
+        "Generate a setter method",
+        target,
+        |builder| impls.add_methods(builder, ctx.config.snippet_cap, &methods),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_setter_for_selected_fields() {
+        check_assist(
+            generate_setter,
+            r#"
+pub struct S {
+    <|>a: u32,
+    b: Vec<u32>,<|>
+    c: bool,
+}
+
+impl S {
+    pub fn c(&self) -> bool {
+        self.c
+    }
+}
+"#,
+            r#"
+pub struct S {
+    a: u32,
+    b: Vec<u32>,
+    c: bool,
+}
+
+impl S {
+    pub fn c(&self) -> bool {
+        self.c
+    }
+
+    pub fn $0set_a(&mut self, a: u32) {
+        self.a = a;
+    }
+
+    pub fn set_b(&mut self, b: Vec<u32>) {
+        self.b = b;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_setter_not_applicable_if_exists() {
+        check_assist_not_applicable(
+            generate_setter,
+            r#"
+struct S {
+    <|>a: u32,
+}
+
+impl S {
+    fn set_a(&mut self, a: u32) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_setter_not_applicable_to_tuple_struct() {
+        check_assist_not_applicable(generate_setter, "struct S(<|>u32);");
+    }
+}
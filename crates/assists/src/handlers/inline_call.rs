@@ -0,0 +1,1255 @@
+use hir::{HasSource, HirFileId, Local, ModuleDef, PathResolution, Semantics};
+use ide_db::{base_db::FileId, defs::Definition, RootDatabase};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    algo::{find_node_at_range, SyntaxRewriter},
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, ArgListOwner, AstNode, GenericParamsOwner, NameOwner, VisibilityOwner,
+    },
+    SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, T,
+};
+use test_utils::mark;
+
+use crate::{utils::mod_path_to_ast, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: inline_call
+//
+// Inlines a function or method body at the call site.
+//
+// ```
+// fn add(a: u32, b: u32) -> u32 { a + b }
+// fn main() {
+//     let x = add<|>(1, 2);
+// }
+// ```
+// ->
+// ```
+// fn add(a: u32, b: u32) -> u32 { a + b }
+// fn main() {
+//     let x = 1 + 2;
+// }
+// ```
+pub(crate) fn inline_call(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name_ref = ctx.find_node_at_offset::<ast::NameRef>()?;
+    let call = CallInfo::from_name_ref(&ctx.sema, &name_ref)?;
+    let fn_ = fn_source(ctx, call.function)?.1;
+    let (range, replacement) = inline(ctx, &fn_, &call)?;
+
+    let label = format!("Inline `{}`", call.function.name(ctx.db()));
+    acc.add(AssistId("inline_call", AssistKind::RefactorInline), label, range, |builder| {
+        builder.replace(range, replacement)
+    })
+}
+
+// Assist: inline_into_callers
+//
+// Inlines a function or method body into all of its call sites and removes its definition.
+//
+// ```
+// fn add<|>(a: u32, b: u32) -> u32 { a + b }
+// fn main() {
+//     let x = add(1, 2);
+//     let y = add(x, 3);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = 1 + 2;
+//     let y = x + 3;
+// }
+// ```
+pub(crate) fn inline_into_callers(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let function = match ctx.find_node_at_offset::<ast::NameRef>() {
+        Some(name_ref) => CallInfo::from_name_ref(&ctx.sema, &name_ref)?.function,
+        None => {
+            let name = ctx.find_node_at_offset::<ast::Name>()?;
+            let fn_ = name.syntax().parent().and_then(ast::Fn::cast)?;
+            ctx.sema.to_def(&fn_)?
+        }
+    };
+    let (def_file, fn_) = fn_source(ctx, function)?;
+    if fn_.visibility().map_or(false, |it| it.syntax().text() == "pub") {
+        mark::hit!(inline_into_callers_pub_fn);
+        return None;
+    }
+    if let Some(ast::Item::Impl(imp)) = fn_.syntax().ancestors().nth(2).and_then(ast::Item::cast) {
+        if imp.trait_().is_some() {
+            return None;
+        }
+    }
+
+    let refs = Definition::ModuleDef(function.into()).usages(&ctx.sema).all();
+    if refs.is_empty() {
+        return None;
+    }
+    let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+    for reference in refs {
+        let file_id = reference.file_range.file_id;
+        let source_file = ctx.sema.parse(file_id);
+        // Every use has to be a call: a function pointer or an import would dangle.
+        let name_ref =
+            find_node_at_range::<ast::NameRef>(source_file.syntax(), reference.file_range.range)?;
+        let call = CallInfo::from_name_ref(&ctx.sema, &name_ref)?;
+        if file_id == def_file
+            && fn_.syntax().text_range().contains_range(call.node.syntax().text_range())
+        {
+            mark::hit!(inline_into_callers_recursive);
+            return None;
+        }
+        let (range, replacement) = inline(ctx, &fn_, &call)?;
+        edits.entry(file_id).or_default().push((range, replacement));
+    }
+    for file_edits in edits.values_mut() {
+        file_edits.sort_by_key(|(range, _)| range.start());
+        let nested = file_edits.windows(2).any(|it| it[0].0.end() > it[1].0.start());
+        if nested {
+            mark::hit!(inline_into_callers_nested_calls);
+            return None;
+        }
+    }
+
+    let label = format!("Inline all calls to `{}` and remove it", function.name(ctx.db()));
+    let target = fn_.syntax().text_range();
+    acc.add(AssistId("inline_into_callers", AssistKind::RefactorInline), label, target, |builder| {
+        for (file_id, file_edits) in edits {
+            builder.edit_file(file_id);
+            for (range, replacement) in file_edits {
+                builder.replace(range, replacement);
+            }
+        }
+        builder.edit_file(def_file);
+        builder.delete(definition_range(&fn_));
+    })
+}
+
+/// A call to a function, either `foo(a)`, `Foo::foo(x, a)` or `x.foo(a)`.
+struct CallInfo {
+    /// The call expression replaced by the inlined body.
+    node: ast::Expr,
+    /// The receiver of a method call.
+    receiver: Option<ast::Expr>,
+    args: Vec<ast::Expr>,
+    function: hir::Function,
+}
+
+impl CallInfo {
+    fn from_name_ref(sema: &Semantics<RootDatabase>, name_ref: &ast::NameRef) -> Option<CallInfo> {
+        let parent = name_ref.syntax().parent()?;
+        if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+            let function = sema.resolve_method_call(&call)?;
+            return Some(CallInfo {
+                receiver: Some(call.receiver()?),
+                args: call.arg_list()?.args().collect(),
+                node: call.into(),
+                function,
+            });
+        }
+
+        let segment = ast::PathSegment::cast(parent)?;
+        let path = segment.syntax().parent().and_then(ast::Path::cast)?;
+        if path.segment()? != segment {
+            return None;
+        }
+        let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+        let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+        if call.expr()?.syntax() != path_expr.syntax() {
+            return None;
+        }
+        let function = match sema.resolve_path(&path)? {
+            PathResolution::Def(ModuleDef::Function(it)) => it,
+            PathResolution::AssocItem(hir::AssocItem::Function(it)) => it,
+            _ => return None,
+        };
+        Some(CallInfo {
+            receiver: None,
+            args: call.arg_list()?.args().collect(),
+            node: call.into(),
+            function,
+        })
+    }
+}
+
+/// The definition of `function`, in the tree parsed by `ctx.sema`.
+fn fn_source(ctx: &AssistContext, function: hir::Function) -> Option<(FileId, ast::Fn)> {
+    let src = function.source(ctx.db());
+    let file_id = src.file_id.original_file(ctx.db());
+    if src.file_id != HirFileId::from(file_id) {
+        mark::hit!(inline_call_fn_from_macro);
+        return None;
+    }
+    let source_file = ctx.sema.parse(file_id);
+    let fn_ = find_node_at_range::<ast::Fn>(source_file.syntax(), src.value.syntax().text_range())?;
+    Some((file_id, fn_))
+}
+
+/// The definition and its leading whitespace, or its trailing one if it starts the file.
+fn definition_range(fn_: &ast::Fn) -> TextRange {
+    let range = fn_.syntax().text_range();
+    let is_ws = |it: &SyntaxElement| it.kind() == SyntaxKind::WHITESPACE;
+    if let Some(ws) = fn_.syntax().prev_sibling_or_token().filter(is_ws) {
+        return TextRange::new(ws.text_range().start(), range.end());
+    }
+    match fn_.syntax().next_sibling_or_token().filter(is_ws) {
+        Some(ws) => TextRange::new(range.start(), ws.text_range().end()),
+        None => range,
+    }
+}
+
+/// Returns the text replacing `call`, along with the replaced range, which includes the `;` of
+/// an expression statement when a block without a tail expression replaces it.
+fn inline(ctx: &AssistContext, fn_: &ast::Fn, call: &CallInfo) -> Option<(TextRange, String)> {
+    let sema = &ctx.sema;
+    let body = fn_.body()?;
+    if fn_.async_token().is_some() || fn_.unsafe_token().is_some() {
+        return None;
+    }
+    if has_return(body.syntax()) {
+        mark::hit!(inline_call_with_return);
+        return None;
+    }
+
+    // Generic parameters can't be named at the call site.
+    let mut generic_names = FxHashSet::default();
+    let impl_ = fn_.syntax().ancestors().find_map(ast::Impl::cast);
+    let generic_lists = fn_
+        .generic_param_list()
+        .into_iter()
+        .chain(impl_.as_ref().and_then(|it| it.generic_param_list()));
+    for list in generic_lists {
+        for param in list.type_params() {
+            generic_names.extend(param.name().map(|it| it.text().to_string()));
+        }
+    }
+    let name_refs = body.syntax().descendants().filter_map(ast::NameRef::cast).collect::<Vec<_>>();
+    if name_refs.iter().any(|it| generic_names.contains(it.text().as_str())) {
+        mark::hit!(inline_call_generic_param);
+        return None;
+    }
+
+    // `Self` is replaced by the type it stands for, which has to be nameable without the
+    // generic arguments of the impl.
+    if name_refs.iter().any(|it| it.text() == "Self") {
+        match impl_.as_ref().and_then(|it| it.self_ty()) {
+            Some(ast::Type::PathType(ty))
+                if !ty
+                    .syntax()
+                    .descendants()
+                    .any(|it| ast::GenericArgList::can_cast(it.kind())) => {}
+            _ => return None,
+        }
+    }
+
+    // Pair up the parameters with the arguments.
+    let param_list = fn_.param_list()?;
+    let params =
+        param_list.params().map(|it| Some((it.pat()?, it.ty()))).collect::<Option<Vec<_>>>()?;
+    let mut args = call.args.clone();
+    let self_arg = match (param_list.self_param(), &call.receiver) {
+        (Some(self_param), Some(receiver)) => Some(match self_param.kind() {
+            ast::SelfParamKind::Owned => receiver.clone(),
+            ast::SelfParamKind::Ref => make::expr_ref(receiver.clone(), false),
+            ast::SelfParamKind::MutRef => make::expr_ref(receiver.clone(), true),
+        }),
+        (Some(_), None) if !args.is_empty() => Some(args.remove(0)),
+        (None, None) => None,
+        _ => return None,
+    };
+    if params.len() != args.len() {
+        return None;
+    }
+
+    let usages = collect_usages(sema, fn_);
+    let mut rewriter = SyntaxRewriter::default();
+    qualify_paths(sema, body.syntax(), &call.node, &mut rewriter)?;
+
+    // Locals of the body that would shadow names used by the arguments are renamed.
+    let mut taken = FxHashSet::default();
+    for arg in call.args.iter().chain(call.receiver.iter()) {
+        taken.extend(idents(arg.syntax()));
+    }
+    let mut all_names = taken.clone();
+    all_names.extend(idents(fn_.syntax()));
+    let ident_pats = param_list
+        .params()
+        .filter_map(|it| it.pat())
+        .flat_map(|it| it.syntax().descendants().collect::<Vec<_>>())
+        .chain(body.syntax().descendants())
+        .filter_map(ast::IdentPat::cast);
+    for ident_pat in ident_pats {
+        let name = match ident_pat.name() {
+            Some(it) => it,
+            None => continue,
+        };
+        if !taken.contains(name.text().as_str()) {
+            continue;
+        }
+        let local = match sema.to_def(&ident_pat) {
+            Some(it) => it,
+            None => continue,
+        };
+        mark::hit!(inline_call_renames_local);
+        let new_name = fresh_name(&mut all_names, name.text());
+        rewriter.replace_ast(&name, &make::name(&new_name));
+        let new_path = path_expr(&new_name);
+        for usage in usages.iter().filter(|it| it.local == local) {
+            replace_usage(&mut rewriter, usage, &new_path, &new_name);
+        }
+    }
+
+    // Arguments are substituted when trivial, bound to the parameter otherwise.
+    let mut lets = Vec::new();
+    for ((pat, ty), arg) in params.iter().zip(args) {
+        // The type of the parameter is kept, otherwise literals could get a different one.
+        let ty = ty.as_ref().and_then(|it| binding_type(sema, it, &call.node, &generic_names));
+        let ident_pat = match pat {
+            ast::Pat::IdentPat(it) if it.mut_token().is_none() && it.pat().is_none() => it,
+            _ => {
+                lets.push(make::let_stmt(rewrite(&rewriter, pat), ty, Some(arg)));
+                continue;
+            }
+        };
+        let local = sema.to_def(ident_pat)?;
+        let param_usages = usages.iter().filter(|it| it.local == local).collect::<Vec<_>>();
+        let in_macro = param_usages.iter().any(|it| it.expr.is_none());
+        // The type of `3.pow(2)` can't be inferred.
+        let literal_receiver = matches!(arg, ast::Expr::Literal(_))
+            && param_usages.iter().any(|it| it.expr.as_ref().map_or(false, is_auto_deref));
+        let substitute = is_trivial(&arg)
+            && !in_macro
+            && !literal_receiver
+            && (param_usages.len() <= 1 || matches!(arg, ast::Expr::PathExpr(_)));
+        if substitute {
+            for usage in param_usages {
+                replace_usage(&mut rewriter, usage, &arg, "");
+            }
+        } else if param_usages.is_empty() {
+            lets.push(make::let_stmt(make::wildcard_pat().into(), None, Some(arg)));
+        } else {
+            lets.push(make::let_stmt(rewrite(&rewriter, pat), ty, Some(arg)));
+        }
+    }
+
+    // `self` is replaced by the receiver, or bound to `this`.
+    if let Some(self_arg) = self_arg {
+        let self_usages = usages.iter().filter(|it| it.local.is_self(ctx.db())).collect::<Vec<_>>();
+        let auto_deref = self_usages.iter().all(|it| match &it.expr {
+            Some(expr) => is_auto_deref(expr),
+            None => false,
+        });
+        match call.receiver.as_ref() {
+            Some(receiver) if auto_deref && is_trivial(receiver) => {
+                for usage in self_usages {
+                    replace_usage(&mut rewriter, usage, receiver, "");
+                }
+            }
+            None if is_trivial(&self_arg) && self_usages.iter().all(|it| it.expr.is_some()) => {
+                for usage in self_usages {
+                    replace_usage(&mut rewriter, usage, &self_arg, "");
+                }
+            }
+            _ if self_usages.is_empty() => {
+                if !is_trivial(&self_arg) {
+                    lets.insert(
+                        0,
+                        make::let_stmt(make::wildcard_pat().into(), None, Some(self_arg)),
+                    );
+                }
+            }
+            _ => {
+                let name = fresh_name(&mut all_names, "this");
+                let this = path_expr(&name);
+                for usage in self_usages {
+                    replace_usage(&mut rewriter, usage, &this, &name);
+                }
+                let pat = make::ident_pat(make::name(&name));
+                lets.insert(0, make::let_stmt(pat.into(), None, Some(self_arg)));
+            }
+        }
+    }
+
+    let body = rewrite(&rewriter, &body);
+    let fn_indent = IndentLevel::from_node(fn_.syntax());
+    let call_indent = IndentLevel::from_node(call.node.syntax());
+
+    let range = call.node.syntax().text_range();
+    if lets.is_empty() && body.statements().next().is_none() {
+        if let Some(tail) = body.expr() {
+            let tail = tail.dedent(fn_indent + 1).indent(call_indent);
+            if needs_parens(&tail, &call.node) {
+                return Some((range, make::expr_paren(tail).to_string()));
+            }
+            return Some((range, tail.to_string()));
+        }
+    }
+
+    let body = if lets.is_empty() {
+        body.dedent(fn_indent)
+    } else if !body.syntax().text().contains_char('\n') {
+        let stmts = lets.into_iter().map(ast::Stmt::from).chain(body.statements());
+        make::block_expr(stmts, body.expr())
+    } else {
+        let l_curly = body.l_curly_token()?;
+        let mut to_insert = Vec::new();
+        for let_stmt in lets {
+            let ws = make::tokens::whitespace(&format!("\n{}", fn_indent + 1));
+            to_insert.push(SyntaxElement::Token(ws));
+            to_insert.push(SyntaxElement::Node(let_stmt.syntax().clone()));
+        }
+        let mut rewriter = SyntaxRewriter::default();
+        rewriter.insert_many_after(&l_curly, to_insert);
+        rewrite(&rewriter, &body).dedent(fn_indent)
+    };
+    let stmt = call.node.syntax().parent().and_then(ast::ExprStmt::cast);
+    let range = match stmt {
+        Some(stmt) if body.expr().is_none() && stmt.semicolon_token().is_some() => {
+            stmt.syntax().text_range()
+        }
+        _ => range,
+    };
+    Some((range, body.indent(call_indent).to_string()))
+}
+
+/// The type of a `let` binding the argument of a parameter of type `ty`, if it can be named
+/// at the call site.
+fn binding_type(
+    sema: &Semantics<RootDatabase>,
+    ty: &ast::Type,
+    call: &ast::Expr,
+    generic_names: &FxHashSet<String>,
+) -> Option<ast::Type> {
+    for element in ty.syntax().descendants_with_tokens() {
+        let unnameable = match element.kind() {
+            SyntaxKind::IMPL_TRAIT_TYPE => true,
+            SyntaxKind::LIFETIME => !matches!(element.to_string().as_str(), "'static" | "'_"),
+            SyntaxKind::IDENT => {
+                let text = element.to_string();
+                text == "Self" || generic_names.contains(&text)
+            }
+            _ => false,
+        };
+        if unnameable {
+            return None;
+        }
+    }
+    let mut rewriter = SyntaxRewriter::default();
+    qualify_paths(sema, ty.syntax(), call, &mut rewriter)?;
+    Some(rewrite(&rewriter, ty))
+}
+
+/// Requalifies the paths in `node`, `Self` included, which refer to something else or to
+/// nothing at `call`. Fails if one of the items can't be named there.
+fn qualify_paths(
+    sema: &Semantics<RootDatabase>,
+    node: &SyntaxNode,
+    call: &ast::Expr,
+    rewriter: &mut SyntaxRewriter,
+) -> Option<()> {
+    let db = sema.db;
+    let target_scope = sema.scope(call.syntax());
+    let target_module = target_scope.module()?;
+    let paths = node
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .filter(|it| it.qualifier().is_none())
+        .filter(|it| {
+            !it.syntax()
+                .ancestors()
+                .any(|it| ast::Use::can_cast(it.kind()) || ast::MacroCall::can_cast(it.kind()))
+        });
+    for path in paths {
+        let resolution = match sema.resolve_path(&path) {
+            Some(PathResolution::Local(_)) | None => continue,
+            Some(it) => it,
+        };
+        if target_scope.speculative_resolve(&path).as_ref() == Some(&resolution) {
+            continue;
+        }
+        let def = match resolution {
+            PathResolution::Def(it) => it,
+            PathResolution::SelfType(impl_def) => impl_def.target_ty(db).as_adt()?.into(),
+            _ => return None,
+        };
+        let new_path = match target_module.find_use_path(db, def) {
+            Some(it) => mod_path_to_ast(&it),
+            None => {
+                mark::hit!(inline_call_unreachable_path);
+                return None;
+            }
+        };
+        // The path found may still be shadowed, e.g. by a local.
+        if target_scope.speculative_resolve(&new_path) != Some(PathResolution::Def(def)) {
+            return None;
+        }
+        let shorthand = path
+            .syntax()
+            .parent()
+            .and_then(|it| it.parent())
+            .and_then(ast::RecordExprField::cast)
+            .map_or(false, |it| it.name_ref().is_none());
+        if shorthand {
+            return None;
+        }
+        let new_path = match path.segment().and_then(|it| it.generic_arg_list()) {
+            Some(generic_args) => {
+                let segment = new_path.segment()?;
+                new_path.with_segment(segment.with_generic_args(generic_args))
+            }
+            None => new_path,
+        };
+        rewriter.replace_ast(&path, &new_path);
+    }
+    Some(())
+}
+
+/// A use of a local of the inlined function.
+struct Usage {
+    local: Local,
+    /// The path expression naming the local, `None` inside of macro calls.
+    expr: Option<ast::PathExpr>,
+    /// The identifier token, inside of the macro call for uses in macros.
+    token: SyntaxToken,
+}
+
+fn collect_usages(sema: &Semantics<RootDatabase>, fn_: &ast::Fn) -> Vec<Usage> {
+    let mut res = Vec::new();
+    for node in fn_.syntax().descendants() {
+        if let Some(expr) = ast::PathExpr::cast(node.clone()) {
+            let token = expr.syntax().first_token();
+            if let (Some(local), Some(token)) = (resolve_local(sema, &expr), token) {
+                res.push(Usage { local, expr: Some(expr), token });
+            }
+        } else if let Some(tt) = ast::MacroCall::cast(node).and_then(|it| it.token_tree()) {
+            let tokens = tt.syntax().descendants_with_tokens().filter_map(|it| it.into_token());
+            for token in tokens.filter(|it| matches!(it.kind(), SyntaxKind::IDENT | T![self])) {
+                let expanded = sema.descend_into_macros(token.clone());
+                if expanded == token {
+                    continue;
+                }
+                let expr = expanded.parent().ancestors().find_map(ast::PathExpr::cast);
+                if let Some(local) = expr.and_then(|it| resolve_local(sema, &it)) {
+                    res.push(Usage { local, expr: None, token });
+                }
+            }
+        }
+    }
+    res
+}
+
+fn resolve_local(sema: &Semantics<RootDatabase>, expr: &ast::PathExpr) -> Option<Local> {
+    let path = expr.path()?;
+    if path.qualifier().is_some() {
+        return None;
+    }
+    match sema.resolve_path(&path)? {
+        PathResolution::Local(local) => Some(local),
+        _ => None,
+    }
+}
+
+/// Replaces a use of a local by `new_expr`; `new_name` is used for uses inside of macro calls.
+fn replace_usage(
+    rewriter: &mut SyntaxRewriter,
+    usage: &Usage,
+    new_expr: &ast::Expr,
+    new_name: &str,
+) {
+    let expr = match &usage.expr {
+        Some(it) => it,
+        None => {
+            let ident = make::tokens::ident(new_name);
+            rewriter.replace(&SyntaxElement::Token(usage.token.clone()), &ident.into());
+            return;
+        }
+    };
+    let shorthand = expr
+        .syntax()
+        .parent()
+        .and_then(ast::RecordExprField::cast)
+        .filter(|it| it.name_ref().is_none());
+    match shorthand {
+        Some(field) => {
+            let name_ref = make::name_ref(&expr.syntax().text().to_string());
+            let new_field = make::record_expr_field(name_ref, Some(new_expr.clone()));
+            rewriter.replace_ast(&field, &new_field);
+        }
+        None => rewriter.replace(expr.syntax(), new_expr.syntax()),
+    }
+}
+
+fn rewrite<N: AstNode>(rewriter: &SyntaxRewriter, node: &N) -> N {
+    N::cast(rewriter.rewrite(node.syntax())).unwrap()
+}
+
+fn path_expr(name: &str) -> ast::Expr {
+    make::expr_path(make::path_unqualified(make::path_segment(make::name_ref(name))))
+}
+
+/// Returns `name` if it is not taken yet, `name` with a numeric suffix otherwise.
+fn fresh_name(taken: &mut FxHashSet<String>, name: &str) -> String {
+    let new_name = std::iter::once(name.to_string())
+        .chain((1..).map(|it| format!("{}{}", name, it)))
+        .find(|it| !taken.contains(it))
+        .unwrap();
+    taken.insert(new_name.clone());
+    new_name
+}
+
+fn idents(node: &SyntaxNode) -> impl Iterator<Item = String> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::IDENT)
+        .map(|it| it.text().to_string())
+}
+
+/// Whether `node` contains a `return` that isn't part of a closure or a nested item.
+fn has_return(node: &SyntaxNode) -> bool {
+    node.children().any(|child| match child.kind() {
+        SyntaxKind::RETURN_EXPR => true,
+        SyntaxKind::CLOSURE_EXPR => false,
+        kind if ast::Item::can_cast(kind) => false,
+        _ => has_return(&child),
+    })
+}
+
+/// Expressions without side effects that can be duplicated.
+fn is_trivial(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Literal(_) | ast::Expr::PathExpr(_) => true,
+        ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| is_trivial(&it)),
+        _ => false,
+    }
+}
+
+/// Whether a use of `self` works with a reference as well, e.g. `self.field`.
+fn is_auto_deref(expr: &ast::PathExpr) -> bool {
+    let parent = match expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    let expr = Some(expr.syntax().clone());
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return call.receiver().map(|it| it.syntax().clone()) == expr;
+    }
+    ast::FieldExpr::can_cast(parent.kind())
+}
+
+/// Whether the inlined expression has to be parenthesized in place of `call`.
+fn needs_parens(inlined: &ast::Expr, call: &ast::Expr) -> bool {
+    let atomic = matches!(
+        inlined,
+        ast::Expr::Literal(_)
+            | ast::Expr::PathExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::TupleExpr(_)
+            | ast::Expr::ArrayExpr(_)
+            | ast::Expr::BlockExpr(_)
+            | ast::Expr::MacroCall(_)
+            | ast::Expr::RecordExpr(_)
+    );
+    if atomic {
+        return false;
+    }
+    match call.syntax().parent().and_then(ast::Expr::cast) {
+        None => false,
+        Some(parent) => !matches!(
+            parent,
+            ast::Expr::ParenExpr(_)
+                | ast::Expr::TupleExpr(_)
+                | ast::Expr::ArrayExpr(_)
+                | ast::Expr::BlockExpr(_)
+                | ast::Expr::ReturnExpr(_)
+                | ast::Expr::BreakExpr(_)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_tail_expr() {
+        check_assist(
+            inline_call,
+            r#"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = add<|>(1, 2);
+}
+"#,
+            r#"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = 1 + 2;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_adds_parens() {
+        check_assist(
+            inline_call,
+            r#"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = add<|>(1, 2) * 3;
+}
+"#,
+            r#"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = (1 + 2) * 3;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_binds_non_trivial_args() {
+        check_assist(
+            inline_call,
+            r#"
+fn double(a: u32) -> u32 { a + a }
+fn get() -> u32 { 1 }
+fn main() {
+    let x = double<|>(get());
+}
+"#,
+            r#"
+fn double(a: u32) -> u32 { a + a }
+fn get() -> u32 { 1 }
+fn main() {
+    let x = {
+        let a: u32 = get();
+        a + a
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_keeps_side_effects_of_unused_args() {
+        check_assist(
+            inline_call,
+            r#"
+fn one(_a: u32) -> u32 { 1 }
+fn get() -> u32 { 1 }
+fn main() {
+    let x = one<|>(get());
+}
+"#,
+            r#"
+fn one(_a: u32) -> u32 { 1 }
+fn get() -> u32 { 1 }
+fn main() {
+    let x = {
+        let _ = get();
+        1
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_multi_line_body() {
+        check_assist(
+            inline_call,
+            r#"
+fn foo(a: u32, b: u32) -> u32 {
+    // sum them up
+    let c = a + b;
+    c * 2
+}
+fn main() {
+    if true {
+        let x = foo<|>(1, 2 + 3);
+    }
+}
+"#,
+            r#"
+fn foo(a: u32, b: u32) -> u32 {
+    // sum them up
+    let c = a + b;
+    c * 2
+}
+fn main() {
+    if true {
+        let x = {
+            let b: u32 = 2 + 3;
+            // sum them up
+            let c = 1 + b;
+            c * 2
+        };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_renames_clashing_locals() {
+        mark::check!(inline_call_renames_local);
+        check_assist(
+            inline_call,
+            r#"
+fn foo(a: u32) -> u32 {
+    let b = 2;
+    a * b
+}
+fn main() {
+    let b = 1;
+    let x = foo<|>(b);
+}
+"#,
+            r#"
+fn foo(a: u32) -> u32 {
+    let b = 2;
+    a * b
+}
+fn main() {
+    let b = 1;
+    let x = {
+        let b1 = 2;
+        b * b1
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_method_call() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { x: u32 }
+impl S {
+    fn get(&self) -> u32 { self.x }
+}
+fn main() {
+    let s = S { x: 1 };
+    let x = s.get<|>();
+}
+"#,
+            r#"
+struct S { x: u32 }
+impl S {
+    fn get(&self) -> u32 { self.x }
+}
+fn main() {
+    let s = S { x: 1 };
+    let x = s.x;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_method_call_binds_self() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { x: u32 }
+fn take(s: &S) -> u32 { s.x }
+impl S {
+    fn get(&self) -> u32 {
+        let y = 1;
+        take(self) + y
+    }
+}
+fn main() {
+    let s = S { x: 1 };
+    let x = s.get<|>();
+}
+"#,
+            r#"
+struct S { x: u32 }
+fn take(s: &S) -> u32 { s.x }
+impl S {
+    fn get(&self) -> u32 {
+        let y = 1;
+        take(self) + y
+    }
+}
+fn main() {
+    let s = S { x: 1 };
+    let x = {
+        let this = &s;
+        let y = 1;
+        take(this) + y
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_associated_call_with_self_type() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { x: u32 }
+impl S {
+    fn new(x: u32) -> Self { Self { x } }
+}
+fn main() {
+    let s = S::new<|>(1);
+}
+"#,
+            r#"
+struct S { x: u32 }
+impl S {
+    fn new(x: u32) -> Self { Self { x } }
+}
+fn main() {
+    let s = S { x: 1 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_keeps_param_types_of_bound_literals() {
+        check_assist(
+            inline_call,
+            r#"
+fn f(a: u64, b: u64) -> u64 { a + b + a }
+fn main() {
+    let x = f<|>(3, 4);
+}
+"#,
+            r#"
+fn f(a: u64, b: u64) -> u64 { a + b + a }
+fn main() {
+    let x = {
+        let a: u64 = 3;
+        a + 4 + a
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_binds_literal_receivers() {
+        check_assist(
+            inline_call,
+            r#"
+fn sq(a: i64) -> i64 { a.pow(2) }
+fn main() {
+    let x = sq<|>(3);
+}
+"#,
+            r#"
+fn sq(a: i64) -> i64 { a.pow(2) }
+fn main() {
+    let x = {
+        let a: i64 = 3;
+        a.pow(2)
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_unit_body_into_expr_stmt() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { x: u32 }
+impl S {
+    fn bump(&mut self) { self.x += 1; }
+}
+fn main() {
+    let mut s = S { x: 0 };
+    s.bump<|>();
+}
+"#,
+            r#"
+struct S { x: u32 }
+impl S {
+    fn bump(&mut self) { self.x += 1; }
+}
+fn main() {
+    let mut s = S { x: 0 };
+    { s.x += 1; }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_qualifies_paths_of_other_module() {
+        check_assist(
+            inline_call,
+            r#"
+mod m {
+    pub fn helper() -> u32 { 1 }
+    pub fn foo() -> u32 { helper() + 1 }
+}
+fn main() {
+    let x = m::foo<|>();
+}
+"#,
+            r#"
+mod m {
+    pub fn helper() -> u32 { 1 }
+    pub fn foo() -> u32 { helper() + 1 }
+}
+fn main() {
+    let x = m::helper() + 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_qualifies_self_type() {
+        check_assist(
+            inline_call,
+            r#"
+mod m {
+    pub struct S;
+    impl S {
+        pub fn new() -> Self { Self }
+        pub fn make() -> Self { Self::new() }
+    }
+}
+fn main() {
+    let s = m::S::make<|>();
+}
+"#,
+            r#"
+mod m {
+    pub struct S;
+    impl S {
+        pub fn new() -> Self { Self }
+        pub fn make() -> Self { Self::new() }
+    }
+}
+fn main() {
+    let s = m::S::new();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_private_item_of_other_module() {
+        mark::check!(inline_call_unreachable_path);
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+mod m {
+    fn helper() -> u32 { 1 }
+    pub fn foo() -> u32 { helper() + 1 }
+}
+fn main() {
+    let x = m::foo<|>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_return() {
+        mark::check!(inline_call_with_return);
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+fn foo(a: u32) -> u32 {
+    if a > 1 {
+        return 0;
+    }
+    a
+}
+fn main() {
+    foo<|>(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_generic_params() {
+        mark::check!(inline_call_generic_param);
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+fn foo<T: Default>(_a: u32) -> T { T::default() }
+fn main() {
+    let x: u32 = foo<|>(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_fn_from_macro() {
+        mark::check!(inline_call_fn_from_macro);
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+macro_rules! define { () => { fn foo() -> u32 { 1 } } }
+define!();
+fn main() {
+    foo<|>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_all_callers() {
+        check_assist(
+            inline_into_callers,
+            r#"
+fn main() {
+    let x = add(1, 2);
+    let y = add(x, 3);
+}
+
+fn add<|>(a: u32, b: u32) -> u32 { a + b }
+"#,
+            r#"
+fn main() {
+    let x = 1 + 2;
+    let y = x + 3;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_in_other_files() {
+        check_assist(
+            inline_into_callers,
+            r#"
+//- /main.rs
+mod foo;
+fn main() {
+    foo::double(2);
+}
+//- /foo.rs
+pub(crate) fn double<|>(a: u32) -> u32 { a * 2 }
+fn bar() -> u32 {
+    double(1)
+}
+"#,
+            r#"
+//- /main.rs
+mod foo;
+fn main() {
+    2 * 2;
+}
+//- /foo.rs
+fn bar() -> u32 {
+    1 * 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_qualifies_paths() {
+        check_assist(
+            inline_into_callers,
+            r#"
+//- /main.rs
+mod foo;
+fn offset() -> u32 { 1 }
+fn shift<|>(a: u32) -> u32 { a + offset() }
+fn main() {
+    shift(1);
+}
+//- /foo.rs
+fn bar() -> u32 {
+    crate::shift(3)
+}
+"#,
+            r#"
+//- /main.rs
+mod foo;
+fn offset() -> u32 { 1 }
+fn main() {
+    1 + offset();
+}
+//- /foo.rs
+fn bar() -> u32 {
+    3 + crate::offset()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_not_applicable_to_pub_fn() {
+        mark::check!(inline_into_callers_pub_fn);
+        check_assist_not_applicable(
+            inline_into_callers,
+            r#"
+pub fn foo<|>() -> u32 { 1 }
+fn main() {
+    foo();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_not_applicable_to_recursive_fn() {
+        mark::check!(inline_into_callers_recursive);
+        check_assist_not_applicable(
+            inline_into_callers,
+            r#"
+fn foo<|>(a: u32) -> u32 { if a == 0 { 0 } else { foo(a - 1) } }
+fn main() {
+    foo(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_not_applicable_to_nested_calls() {
+        mark::check!(inline_into_callers_nested_calls);
+        check_assist_not_applicable(
+            inline_into_callers,
+            r#"
+fn add<|>(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    add(add(1, 2), 3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_not_applicable_to_fn_pointers() {
+        check_assist_not_applicable(
+            inline_into_callers,
+            r#"
+fn foo<|>() -> u32 { 1 }
+fn main() {
+    foo();
+    let f = foo;
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use hir::{HirFileId, ModuleSource};
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        AstNode,
+    },
+    T,
+};
+use test_utils::mark;
+
+use crate::{
+    handlers::move_module_to_file::path_attr, AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: inline_module_file
+//
+// Moves the contents of a module's file into an inline module and deletes the file.
+//
+// ```
+// //- /main.rs
+// mod <|>foo;
+// //- /foo.rs
+// fn t() {}
+// ```
+// ->
+// ```
+// //- /main.rs
+// mod foo {
+//     fn t() {}
+// }
+// ```
+pub(crate) fn inline_module_file(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    if module_ast.item_list().is_some() {
+        return None;
+    }
+    if path_attr(&module_ast).is_some() {
+        mark::hit!(inline_module_file_path_attr);
+        return None;
+    }
+    let semicolon = module_ast.syntax().last_token().filter(|it| it.kind() == T![;])?;
+    let module_def = ctx.sema.to_def(&module_ast)?;
+    let src = module_def.definition_source(ctx.db());
+    let file_id = match src.value {
+        ModuleSource::SourceFile(_) => src.file_id.original_file(ctx.db()),
+        ModuleSource::Module(_) => return None,
+    };
+    if src.file_id != HirFileId::from(file_id) {
+        return None;
+    }
+
+    let target = module_ast.syntax().text_range();
+    acc.add(
+        AssistId("inline_module_file", AssistKind::RefactorInline),
+        "Inline module file",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(module_ast.syntax());
+            let source_file = ctx.sema.parse(file_id).indent(indent + 1);
+            let contents = source_file.syntax().to_string();
+            let contents = contents.trim();
+            let body = if contents.is_empty() {
+                " {}".to_string()
+            } else {
+                format!(" {{\n{}{}\n{}}}", indent + 1, contents, indent)
+            };
+            builder.replace(semicolon.text_range(), body);
+            builder.delete_file(file_id);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_file() {
+        check_assist(
+            inline_module_file,
+            r#"
+//- /main.rs
+mod <|>foo;
+fn main() {}
+//- /foo.rs
+//! Docs.
+fn f() {
+    let x = 1;
+}
+"#,
+            r#"
+//- /main.rs
+mod foo {
+    //! Docs.
+    fn f() {
+        let x = 1;
+    }
+}
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_nested_mod_rs() {
+        check_assist(
+            inline_module_file,
+            r#"
+//- /main.rs
+mod a;
+//- /a/mod.rs
+mod inner {
+    mod <|>b;
+}
+//- /a/inner/b.rs
+struct S;
+"#,
+            r#"
+//- /main.rs
+mod a;
+//- /a/mod.rs
+mod inner {
+    mod b {
+        struct S;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_inline_module() {
+        check_assist_not_applicable(inline_module_file, "mod <|>foo {}");
+    }
+
+    #[test]
+    fn not_applicable_with_path_attr() {
+        mark::check!(inline_module_file_path_attr);
+        check_assist_not_applicable(
+            inline_module_file,
+            r#"
+//- /main.rs
+#[path = "bar.rs"]
+mod <|>foo;
+//- /bar.rs
+"#,
+        );
+    }
+}
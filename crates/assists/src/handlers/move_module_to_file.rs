@@ -0,0 +1,258 @@
+use ide_db::base_db::{FileLoader, SourceDatabaseExt};
+use syntax::{
+    ast::{self, edit::AstNodeEdit, edit::IndentLevel, AstNode, AttrsOwner, NameOwner},
+    SyntaxKind, TextRange,
+};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: move_module_to_file
+//
+// Moves inline module's contents to a separate file.
+//
+// ```
+// //- /main.rs
+// mod <|>foo {
+//     fn t() {}
+// }
+// ```
+// ->
+// ```
+// //- /main.rs
+// mod foo;
+// //- /foo.rs
+// fn t() {}
+// ```
+pub(crate) fn move_module_to_file(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    let module_items = module_ast.item_list()?;
+    if ctx.offset() > module_items.syntax().text_range().start() {
+        mark::hit!(move_module_to_file_in_body);
+        return None;
+    }
+    let module_name = module_ast.name()?;
+    let module_def = ctx.sema.to_def(&module_ast)?;
+    let parent_module = module_def.parent(ctx.db())?;
+    let db = ctx.db();
+    let file_id = ctx.frange.file_id;
+
+    // The directory the parent module's children live in, relative to the current file.
+    let mut dir = String::new();
+    let file_module = ctx.sema.to_module_def(file_id)?;
+    if file_module.parent(db).is_some() && !file_module.is_mod_rs(db) {
+        let source_root = db.source_root(db.file_source_root(file_id));
+        let (file_stem, _) = source_root.path_for_file(&file_id)?.name_and_extension()?;
+        dir.push_str(file_stem);
+        dir.push('/');
+    }
+    let mut inline_parents = Vec::new();
+    for parent in module_ast.syntax().ancestors().skip(1).filter_map(ast::Module::cast) {
+        if path_attr(&parent).is_some() {
+            return None;
+        }
+        inline_parents.push(parent.name()?.to_string());
+    }
+    for parent in inline_parents.iter().rev() {
+        dir.push_str(parent);
+        dir.push('/');
+    }
+
+    // Follow the layout of the sibling modules, `foo.rs` unless they use `foo/mod.rs`.
+    let use_mod_rs =
+        parent_module.children(db).filter(|it| *it != module_def).any(|it| it.is_mod_rs(db));
+    let (dst, path_attr_value) = match path_attr(&module_ast) {
+        Some((_, value)) => {
+            let value = format!("{}/mod.rs", value.trim_end_matches('/'));
+            (format!("{}{}", dir, value), Some(value))
+        }
+        None if use_mod_rs => (format!("{}{}/mod.rs", dir, module_name), None),
+        None => (format!("{}{}.rs", dir, module_name), None),
+    };
+    if db.resolve_path(file_id, &dst).is_some() {
+        mark::hit!(move_module_to_file_exists);
+        return None;
+    }
+
+    let target = module_ast.syntax().text_range();
+    acc.add(
+        AssistId("move_module_to_file", AssistKind::RefactorExtract),
+        "Extract module to file",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(module_ast.syntax()) + 1;
+            let items = module_items.dedent(indent).syntax().to_string();
+            let items = items[1..items.len() - 1].trim();
+            let contents = if items.is_empty() { String::new() } else { format!("{}\n", items) };
+
+            let items_range = module_items.syntax().text_range();
+            let start = match module_items.syntax().prev_sibling_or_token() {
+                Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+                _ => items_range.start(),
+            };
+            builder.replace(TextRange::new(start, items_range.end()), ";");
+            if let (Some((attr, _)), Some(value)) = (path_attr(&module_ast), path_attr_value) {
+                builder.replace(attr.syntax().text_range(), format!("#[path = \"{}\"]", value));
+            }
+            builder.create_file(file_id, dst, contents);
+        },
+    )
+}
+
+pub(crate) fn path_attr(module: &ast::Module) -> Option<(ast::Attr, String)> {
+    module.attrs().find_map(|attr| match attr.as_simple_key_value()? {
+        (key, value) if key == "path" => Some((attr, value.to_string())),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::mark;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_from_root() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod <|>tests {
+    #[test] fn t() {}
+}
+"#,
+            r#"
+//- /main.rs
+mod tests;
+//- /tests.rs
+#[test] fn t() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_from_submodule() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod submod;
+//- /submod.rs
+mod inner<|> {
+    fn f() {
+        let x = 1;
+    }
+}
+fn g() {}
+"#,
+            r#"
+//- /main.rs
+mod submod;
+//- /submod.rs
+mod inner;
+fn g() {}
+//- /submod/inner.rs
+fn f() {
+    let x = 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_from_mod_rs_with_inline_parent() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod submodule;
+//- /submodule/mod.rs
+mod inner {
+    mod <|>deep {
+        fn f() {}
+    }
+}
+"#,
+            r#"
+//- /main.rs
+mod submodule;
+//- /submodule/mod.rs
+mod inner {
+    mod deep;
+}
+//- /submodule/inner/deep.rs
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_follows_mod_rs_layout() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod a;
+mod <|>b {}
+//- /a/mod.rs
+"#,
+            r#"
+//- /main.rs
+mod a;
+mod b;
+//- /a/mod.rs
+//- /b/mod.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_path_attr() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+#[path = "other"]
+mod <|>b {
+    struct S;
+}
+"#,
+            r#"
+//- /main.rs
+#[path = "other/mod.rs"]
+mod b;
+//- /other/mod.rs
+struct S;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        mark::check!(move_module_to_file_in_body);
+        check_assist_not_applicable(
+            move_module_to_file,
+            r#"
+mod foo {
+    fn <|>f() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_file_exists() {
+        mark::check!(move_module_to_file_exists);
+        check_assist_not_applicable(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod <|>foo {}
+//- /foo.rs
+"#,
+        );
+    }
+}
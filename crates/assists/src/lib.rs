@@ -128,10 +128,16 @@ mod handlers {
     mod auto_import;
     mod change_return_type_to_result;
     mod change_visibility;
+    mod convert_for_to_iter_for_each;
     mod convert_integer_literal;
+    mod convert_tuple_struct_to_named_struct;
+    mod destructure_binding;
     mod early_return;
     mod expand_glob_import;
+    mod extract_function;
+    mod extract_module;
     mod extract_struct_from_enum_variant;
+    mod extract_trait;
     mod extract_variable;
     mod fill_match_arms;
     mod fix_visibility;
@@ -139,17 +145,24 @@ mod handlers {
     mod flip_comma;
     mod flip_trait_bound;
     mod generate_derive;
+    mod generate_enum_as_method;
+    mod generate_enum_is_method;
     mod generate_from_impl_for_enum;
     mod generate_function;
+    mod generate_getter;
     mod generate_impl;
     mod generate_new;
+    mod generate_setter;
+    mod inline_call;
     mod inline_local_variable;
+    mod inline_module_file;
     mod introduce_named_lifetime;
     mod invert_if;
     mod merge_imports;
     mod merge_match_arms;
     mod move_bounds;
     mod move_guard;
+    mod move_module_to_file;
     mod qualify_path;
     mod raw_string;
     mod remove_dbg;
@@ -175,10 +188,19 @@ mod handlers {
             auto_import::auto_import,
             change_return_type_to_result::change_return_type_to_result,
             change_visibility::change_visibility,
+            convert_for_to_iter_for_each::convert_for_to_iter_for_each,
+            convert_for_to_iter_for_each::convert_iter_for_each_to_for,
             convert_integer_literal::convert_integer_literal,
+            convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
+            convert_tuple_struct_to_named_struct::convert_named_struct_to_tuple_struct,
+            destructure_binding::destructure_tuple_binding,
+            destructure_binding::destructure_struct_binding,
             early_return::convert_to_guarded_return,
             expand_glob_import::expand_glob_import,
+            extract_function::extract_function,
+            extract_module::extract_module,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
+            extract_trait::extract_trait,
             extract_variable::extract_variable,
             fill_match_arms::fill_match_arms,
             fix_visibility::fix_visibility,
@@ -186,11 +208,19 @@ mod handlers {
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
             generate_derive::generate_derive,
+            generate_enum_as_method::generate_enum_as_method,
+            generate_enum_is_method::generate_enum_is_method,
             generate_from_impl_for_enum::generate_from_impl_for_enum,
             generate_function::generate_function,
+            generate_getter::generate_getter,
+            generate_getter::generate_getter_mut,
             generate_impl::generate_impl,
             generate_new::generate_new,
+            generate_setter::generate_setter,
+            inline_call::inline_call,
+            inline_call::inline_into_callers,
             inline_local_variable::inline_local_variable,
+            inline_module_file::inline_module_file,
             introduce_named_lifetime::introduce_named_lifetime,
             invert_if::invert_if,
             merge_imports::merge_imports,
@@ -198,6 +228,7 @@ mod handlers {
             move_bounds::move_bounds_to_where_clause,
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
+            move_module_to_file::move_module_to_file,
             qualify_path::qualify_path,
             raw_string::add_hash,
             raw_string::make_usual_string,
@@ -2,12 +2,15 @@ mod generated;
 
 use hir::Semantics;
 use ide_db::base_db::{fixture::WithFixture, FileId, FileRange, SourceDatabaseExt};
-use ide_db::RootDatabase;
+use ide_db::{
+    source_change::{FileSystemEdit, SourceChange},
+    RootDatabase,
+};
 use syntax::TextRange;
-use test_utils::{assert_eq_text, extract_offset, extract_range};
+use test_utils::{assert_eq_text, extract_offset, extract_range, Fixture};
 
 use crate::{handlers::Handler, Assist, AssistConfig, AssistContext, AssistKind, Assists};
-use stdx::trim_indent;
+use stdx::{format_to, trim_indent};
 
 pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
     RootDatabase::with_single_file(text)
@@ -41,9 +44,9 @@ pub(crate) fn check_assist_not_applicable(assist: Handler, ra_fixture: &str) {
     check(assist, ra_fixture, ExpectedResult::NotApplicable, None);
 }
 
-fn check_doc_test(assist_id: &str, before: &str, after: &str) {
+fn check_doc_test(assist_id: &str, ra_fixture: &str, after: &str) {
     let after = trim_indent(after);
-    let (db, file_id, selection) = RootDatabase::with_range_or_offset(&before);
+    let (db, file_id, selection) = RootDatabase::with_range_or_offset(&ra_fixture);
     let before = db.file_text(file_id).to_string();
     let frange = FileRange { file_id, range: selection.into() };
 
@@ -62,6 +65,10 @@ fn check_doc_test(assist_id: &str, before: &str, after: &str) {
             )
         });
 
+    if after.contains("//-") {
+        assert_eq_text!(&after, &apply_to_fixture(&db, ra_fixture, &assist.source_change));
+        return;
+    }
     let actual = {
         let change = assist.source_change.source_file_edits.pop().unwrap();
         let mut actual = before;
@@ -98,6 +105,10 @@ fn check(handler: Handler, before: &str, expected: ExpectedResult, assist_label:
     match (assist, expected) {
         (Some(assist), ExpectedResult::After(after)) => {
             let mut source_change = assist.source_change;
+            if after.contains("//-") {
+                assert_eq_text!(after, &apply_to_fixture(&db, before, &source_change));
+                return;
+            }
             let change = source_change.source_file_edits.pop().unwrap();
 
             let mut actual = db.file_text(change.file_id).as_ref().to_owned();
@@ -116,6 +127,36 @@ fn check(handler: Handler, before: &str, expected: ExpectedResult, assist_label:
     };
 }
 
+/// Applies `source_change` to all files of a multi-file fixture and prints them as a fixture again.
+fn apply_to_fixture(db: &RootDatabase, ra_fixture: &str, source_change: &SourceChange) -> String {
+    // Fixture files get their `FileId`s in the order they are listed in.
+    let fixture = Fixture::parse(ra_fixture);
+    let mut actual = String::new();
+    for (idx, fixture) in fixture.iter().enumerate() {
+        let file_id = FileId(idx as u32);
+        let deleted = source_change
+            .file_system_edits
+            .iter()
+            .any(|it| matches!(it, FileSystemEdit::DeleteFile { file } if *file == file_id));
+        if deleted {
+            continue;
+        }
+        let mut text = db.file_text(file_id).as_ref().to_owned();
+        for change in source_change.source_file_edits.iter().filter(|it| it.file_id == file_id) {
+            change.edit.apply(&mut text);
+        }
+        format_to!(actual, "//- {}\n{}", fixture.path, text);
+    }
+    for edit in &source_change.file_system_edits {
+        if let FileSystemEdit::CreateFile { anchor, dst, initial_contents } = edit {
+            let anchor = &fixture[anchor.0 as usize].path;
+            let dir = &anchor[..anchor.rfind('/').unwrap() + 1];
+            format_to!(actual, "//- {}{}\n{}", dir, dst, initial_contents);
+        }
+    }
+    actual
+}
+
 #[test]
 fn assist_order_field_struct() {
     let before = "struct Foo { <|>bar: u32 }";
@@ -129,6 +170,12 @@ fn assist_order_field_struct() {
         assists.next().expect("expected assist").assist.label,
         "Change visibility to pub(crate)"
     );
+    assert_eq!(assists.next().expect("expected assist").assist.label, "Generate a getter method");
+    assert_eq!(
+        assists.next().expect("expected assist").assist.label,
+        "Generate a mut getter method"
+    );
+    assert_eq!(assists.next().expect("expected assist").assist.label, "Generate a setter method");
     assert_eq!(assists.next().expect("expected assist").assist.label, "Add `#[derive]`");
 }
 
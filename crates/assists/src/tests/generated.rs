@@ -203,6 +203,29 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_for_to_iter_for_each() {
+    check_doc_test(
+        "convert_for_to_iter_for_each",
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    for<|> v in x {
+        let y = v * 2;
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    x.into_iter().for_each(|v| {
+        let y = v * 2;
+    });
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_integer_literal() {
     check_doc_test(
@@ -216,6 +239,54 @@ const _: i32 = 0b1010;
     )
 }
 
+#[test]
+fn doctest_convert_iter_for_each_to_for() {
+    check_doc_test(
+        "convert_iter_for_each_to_for",
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    x.iter().for_each<|>(|v| {
+        let y = v * 2;
+    });
+}
+"#####,
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    for v in &x {
+        let y = v * 2;
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check_doc_test(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct Point<|> { x: f32, y: f32 }
+
+fn main() {
+    let p = Point { y: 2.0, x: 1.0 };
+    let Point { x, .. } = p;
+    let y = p.y;
+}
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+fn main() {
+    let p = Point(1.0, 2.0);
+    let Point(x, ..) = p;
+    let y = p.1;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -240,6 +311,73 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_tuple_struct_to_named_struct() {
+    check_doc_test(
+        "convert_tuple_struct_to_named_struct",
+        r#####"
+struct Point<|>(f32, f32);
+
+fn main() {
+    let p = Point(1.0, 2.0);
+    let Point(x, _) = p;
+    let y = p.1;
+}
+"#####,
+        r#####"
+struct Point { field1: f32, field2: f32 }
+
+fn main() {
+    let p = Point { field1: 1.0, field2: 2.0 };
+    let Point { field1: x, field2: _ } = p;
+    let y = p.field2;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_struct_binding() {
+    check_doc_test(
+        "destructure_struct_binding",
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let <|>p = Point { x: 1, y: 2 };
+    let v = p.x + p.y;
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let Point { x, y } = Point { x: 1, y: 2 };
+    let v = x + y;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_tuple_binding() {
+    check_doc_test(
+        "destructure_tuple_binding",
+        r#####"
+fn main() {
+    let <|>t = (1, 2);
+    let v = t.0 + t.1;
+}
+"#####,
+        r#####"
+fn main() {
+    let (t_0, t_1) = (1, 2);
+    let v = t_0 + t_1;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_expand_glob_import() {
     check_doc_test(
@@ -267,6 +405,70 @@ fn qux(bar: Bar, baz: Baz) {}
     )
 }
 
+#[test]
+fn doctest_extract_function() {
+    check_doc_test(
+        "extract_function",
+        r#####"
+fn main() {
+    let n = 1;
+    <|>let m = n + 2;
+    let k = m * n;<|>
+    let g = 3;
+}
+"#####,
+        r#####"
+fn main() {
+    let n = 1;
+    fun_name(n);
+    let g = 3;
+}
+
+fn $0fun_name(n: i32) {
+    let m = n + 2;
+    let k = m * n;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_module() {
+    check_doc_test(
+        "extract_module",
+        r#####"
+<|>struct Point {
+    x: i32,
+}
+
+fn origin() -> Point {
+    Point { x: 0 }
+}<|>
+
+fn main() {
+    let x = origin().x;
+}
+"#####,
+        r#####"
+use self::modname::origin;
+
+mod modname {
+    pub(super) struct Point {
+        pub(super) x: i32,
+    }
+
+    pub(super) fn origin() -> Point {
+        Point { x: 0 }
+    }
+}
+
+fn main() {
+    let x = origin().x;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_struct_from_enum_variant() {
     check_doc_test(
@@ -282,6 +484,35 @@ enum A { One(One) }
     )
 }
 
+#[test]
+fn doctest_extract_trait() {
+    check_doc_test(
+        "extract_trait",
+        r#####"
+struct S;
+
+impl <|>S {
+    pub fn f(&self) -> i32 {
+        92
+    }
+}
+"#####,
+        r#####"
+struct S;
+
+pub trait NewTrait {
+    fn f(&self) -> i32;
+}
+
+impl NewTrait for S {
+    fn f(&self) -> i32 {
+        92
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_variable() {
     check_doc_test(
@@ -416,6 +647,62 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_generate_enum_as_method() {
+    check_doc_test(
+        "generate_enum_as_method",
+        r#####"
+enum Value {
+ Number(i32),
+ Text(String)<|>,
+}
+"#####,
+        r#####"
+enum Value {
+ Number(i32),
+ Text(String),
+}
+
+impl Value {
+    fn $0as_text(&self) -> Option<&String> {
+        if let Self::Text(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_enum_is_method() {
+    check_doc_test(
+        "generate_enum_is_method",
+        r#####"
+enum Version {
+ Undefined,
+ Minor<|>,
+ Major,
+}
+"#####,
+        r#####"
+enum Version {
+ Undefined,
+ Minor,
+ Major,
+}
+
+impl Version {
+    fn $0is_minor(&self) -> bool {
+        matches!(self, Self::Minor)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_from_impl_for_enum() {
     check_doc_test(
@@ -462,6 +749,52 @@ fn bar(arg: &str, baz: Baz) ${0:-> ()} {
     )
 }
 
+#[test]
+fn doctest_generate_getter() {
+    check_doc_test(
+        "generate_getter",
+        r#####"
+struct Person {
+    nam<|>e: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn $0name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_getter_mut() {
+    check_doc_test(
+        "generate_getter_mut",
+        r#####"
+struct Person {
+    nam<|>e: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn $0name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_impl() {
     check_doc_test(
@@ -505,6 +838,68 @@ impl<T: Clone> Ctx<T> {
     )
 }
 
+#[test]
+fn doctest_generate_setter() {
+    check_doc_test(
+        "generate_setter",
+        r#####"
+struct Person {
+    nam<|>e: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn $0set_name(&mut self, name: String) {
+        self.name = name;
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_call() {
+    check_doc_test(
+        "inline_call",
+        r#####"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = add<|>(1, 2);
+}
+"#####,
+        r#####"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = 1 + 2;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_into_callers() {
+    check_doc_test(
+        "inline_into_callers",
+        r#####"
+fn add<|>(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = add(1, 2);
+    let y = add(x, 3);
+}
+"#####,
+        r#####"
+fn main() {
+    let x = 1 + 2;
+    let y = x + 3;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_local_variable() {
     check_doc_test(
@@ -523,6 +918,25 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_module_file() {
+    check_doc_test(
+        "inline_module_file",
+        r#####"
+//- /main.rs
+mod <|>foo;
+//- /foo.rs
+fn t() {}
+"#####,
+        r#####"
+//- /main.rs
+mod foo {
+    fn t() {}
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_lifetime() {
     check_doc_test(
@@ -712,6 +1126,25 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_module_to_file() {
+    check_doc_test(
+        "move_module_to_file",
+        r#####"
+//- /main.rs
+mod <|>foo {
+    fn t() {}
+}
+"#####,
+        r#####"
+//- /main.rs
+mod foo;
+//- /foo.rs
+fn t() {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_qualify_path() {
     check_doc_test(
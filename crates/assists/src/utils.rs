@@ -4,17 +4,23 @@ pub(crate) mod import_assets;
 
 use std::ops;
 
-use hir::{Crate, Enum, Module, ScopeDef, Semantics, Trait};
+use hir::{Crate, Enum, HasSource, Module, ScopeDef, Semantics, Trait};
 use ide_db::RootDatabase;
 use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use stdx::format_to;
 use syntax::{
-    ast::{self, make, ArgListOwner},
-    AstNode, Direction,
+    algo::find_node_at_range,
+    ast::{self, edit::IndentLevel, make, ArgListOwner, GenericParamsOwner, NameOwner},
+    AstNode, Direction, NodeOrToken,
     SyntaxKind::*,
-    SyntaxNode, TextSize, T,
+    SyntaxNode, TextRange, TextSize, T,
 };
 
-use crate::assist_config::SnippetCap;
+use crate::{
+    assist_config::SnippetCap,
+    assist_context::{AssistBuilder, AssistContext},
+};
 
 pub use insert_use::MergeBehaviour;
 pub(crate) use insert_use::{insert_use, ImportScope};
@@ -121,6 +127,185 @@ pub(crate) fn vis_offset(node: &SyntaxNode) -> TextSize {
         .unwrap_or_else(|| node.text_range().start())
 }
 
+/// The visibility an item of `target` needs to be reachable from `from`.
+pub(crate) fn required_visibility(from: Module, target: Module) -> ast::Visibility {
+    if from.krate() == target.krate() {
+        make::visibility_pub_crate()
+    } else {
+        make::visibility_pub()
+    }
+}
+
+/// The edit giving the item or field `node` the visibility `new_vis`, replacing its current one.
+pub(crate) fn visibility_edit(node: &SyntaxNode, new_vis: &ast::Visibility) -> (TextRange, String) {
+    match node.children().find_map(ast::Visibility::cast) {
+        Some(vis) => (vis.syntax().text_range(), new_vis.to_string()),
+        None => (TextRange::empty(vis_offset(node)), format!("{} ", new_vis)),
+    }
+}
+
+/// The inherent impls of an ADT, for assists adding methods to it.
+pub(crate) struct AdtImpls {
+    adt: ast::AdtDef,
+    /// The first inherent impl in the file of the ADT, new methods go there.
+    impl_: Option<ast::Impl>,
+    /// Names of the methods of all inherent impls, including the ones in other files.
+    method_names: FxHashSet<String>,
+}
+
+impl AdtImpls {
+    pub(crate) fn new(ctx: &AssistContext, adt: ast::AdtDef) -> Option<AdtImpls> {
+        let db = ctx.db();
+        let def: hir::Adt = match &adt {
+            ast::AdtDef::Struct(it) => ctx.sema.to_def(it)?.into(),
+            ast::AdtDef::Enum(it) => ctx.sema.to_def(it)?.into(),
+            ast::AdtDef::Union(it) => ctx.sema.to_def(it)?.into(),
+        };
+
+        let mut impl_: Option<ast::Impl> = None;
+        let mut method_names = FxHashSet::default();
+        for impl_def in def.inherent_impls(db) {
+            for item in impl_def.items(db) {
+                if let hir::AssocItem::Function(it) = item {
+                    method_names.insert(it.name(db).to_string());
+                }
+            }
+
+            let src = impl_def.source(db);
+            if src.file_id != ctx.frange.file_id.into() {
+                continue;
+            }
+            let range = src.value.syntax().text_range();
+            let candidate = find_node_at_range::<ast::Impl>(
+                &ctx.sema.parse(ctx.frange.file_id).syntax(),
+                range,
+            );
+            let is_first =
+                impl_.as_ref().map_or(true, |it| range.start() < it.syntax().text_range().start());
+            if is_first && candidate.is_some() {
+                impl_ = candidate;
+            }
+        }
+
+        Some(AdtImpls { adt, impl_, method_names })
+    }
+
+    pub(crate) fn has_method(&self, name: &str) -> bool {
+        self.method_names.contains(name)
+    }
+
+    /// Adds methods, given unindented and in order, to the impl or to a new impl after the ADT.
+    /// The first method may contain a `$0` tab stop, which is removed without snippet support.
+    pub(crate) fn add_methods(
+        &self,
+        builder: &mut AssistBuilder,
+        cap: Option<SnippetCap>,
+        methods: &[String],
+    ) {
+        let (range, buf) = match &self.impl_ {
+            Some(impl_) => {
+                let indent = IndentLevel::from_node(impl_.syntax());
+                let methods = indent_methods(methods, indent + 1);
+                match impl_.assoc_item_list() {
+                    Some(list) => match list.assoc_items().last() {
+                        Some(last) => {
+                            let offset = last.syntax().text_range().end();
+                            (TextRange::empty(offset), format!("\n\n{}", methods))
+                        }
+                        None => {
+                            (list.syntax().text_range(), format!("{{\n{}\n{}}}", methods, indent))
+                        }
+                    },
+                    None => return,
+                }
+            }
+            None => {
+                let offset = self.adt.syntax().text_range().end();
+                let methods = indent_methods(methods, IndentLevel(1));
+                (TextRange::empty(offset), generate_impl_text(&self.adt, &methods))
+            }
+        };
+        match cap {
+            Some(cap) => builder.replace_snippet(cap, range, buf),
+            None => builder.replace(range, buf.replacen("$0", "", 1)),
+        }
+    }
+}
+
+fn indent_methods(methods: &[String], indent: IndentLevel) -> String {
+    let mut lines = methods.iter().map(|method| {
+        method
+            .lines()
+            .map(
+                |line| {
+                    if line.is_empty() {
+                        line.to_string()
+                    } else {
+                        format!("{}{}", indent, line)
+                    }
+                },
+            )
+            .join("\n")
+    });
+    lines.join("\n\n")
+}
+
+/// The fields of a record struct intersecting the selection, or the field at the cursor.
+pub(crate) fn selected_record_fields(
+    ctx: &AssistContext,
+) -> Option<(ast::Struct, Vec<ast::RecordField>)> {
+    let range = ctx.frange.range;
+    let (field_list, fields) = if range.is_empty() {
+        let field = ctx.find_node_at_offset::<ast::RecordField>()?;
+        (field.syntax().parent().and_then(ast::RecordFieldList::cast)?, vec![field])
+    } else {
+        let node = match ctx.covering_element() {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent(),
+        };
+        let field_list = node.ancestors().find_map(ast::RecordFieldList::cast)?;
+        let fields = field_list
+            .fields()
+            .filter(|it| {
+                it.syntax().text_range().intersect(range).map_or(false, |it| !it.is_empty())
+            })
+            .collect::<Vec<_>>();
+        (field_list, fields)
+    };
+    if fields.is_empty() {
+        return None;
+    }
+    let strukt = field_list.syntax().parent().and_then(ast::Struct::cast)?;
+    Some((strukt, fields))
+}
+
+/// Generates the surrounding `impl Type { <code> }` including type and lifetime parameters.
+pub(crate) fn generate_impl_text(adt: &ast::AdtDef, code: &str) -> String {
+    let type_params = adt.generic_param_list();
+    let mut buf = String::with_capacity(code.len());
+    buf.push_str("\n\nimpl");
+    if let Some(type_params) = &type_params {
+        format_to!(buf, "{}", type_params.syntax());
+    }
+    buf.push_str(" ");
+    if let Some(name) = adt.name() {
+        buf.push_str(name.text().as_str());
+    }
+    if let Some(type_params) = type_params {
+        let lifetime_params = type_params
+            .lifetime_params()
+            .filter_map(|it| it.lifetime_token())
+            .map(|it| it.text().clone());
+        let type_params =
+            type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).format(", "))
+    }
+
+    format_to!(buf, " {{\n{}\n}}", code);
+
+    buf
+}
+
 pub(crate) fn invert_boolean_expression(expr: ast::Expr) -> ast::Expr {
     if let Some(expr) = invert_special_case(&expr) {
         return expr;
@@ -108,6 +108,10 @@ pub trait SourceDatabase: CheckCanceled + FileLoader + std::fmt::Debug {
     #[salsa::invoke(parse_query)]
     fn parse(&self, file_id: FileId) -> Parse<ast::SourceFile>;
 
+    /// The edition the file is lexed with.
+    #[salsa::invoke(file_edition_query)]
+    fn file_edition(&self, file_id: FileId) -> Edition;
+
     /// The crate graph.
     #[salsa::input]
     fn crate_graph(&self) -> Arc<CrateGraph>;
@@ -116,7 +120,22 @@ pub trait SourceDatabase: CheckCanceled + FileLoader + std::fmt::Debug {
 fn parse_query(db: &dyn SourceDatabase, file_id: FileId) -> Parse<ast::SourceFile> {
     let _p = profile::span("parse_query").detail(|| format!("{:?}", file_id));
     let text = db.file_text(file_id);
-    SourceFile::parse(&*text)
+    SourceFile::parse_with_edition(&*text, db.file_edition(file_id))
+}
+
+fn file_edition_query(db: &dyn SourceDatabase, file_id: FileId) -> Edition {
+    let crate_graph = db.crate_graph();
+    // A file may belong to several crates, but lexing only changed in 2021, so
+    // it's enough to know whether any of them is on it.
+    let is_2021 = db
+        .relevant_crates(file_id)
+        .iter()
+        .any(|&krate| crate_graph[krate].edition == Edition::Edition2021);
+    if is_2021 {
+        Edition::Edition2021
+    } else {
+        Edition::Edition2018
+    }
 }
 
 /// We don't want to give HIR knowledge of source roots, hence we extract these
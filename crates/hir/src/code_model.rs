@@ -9,7 +9,7 @@ use hir_def::{
     adt::StructKind,
     adt::VariantData,
     builtin_type::BuiltinType,
-    expr::{BindingAnnotation, Pat, PatId},
+    expr::{BindingAnnotation, ExprId, Pat, PatId},
     import_map,
     item_tree::ItemTreeNode,
     lang_item::LangItemTarget,
@@ -19,8 +19,9 @@ use hir_def::{
     src::HasSource as _,
     type_ref::{Mutability, TypeRef},
     AdtId, AssocContainerId, AssocItemId, AssocItemLoc, AttrDefId, ConstId, DefWithBodyId, EnumId,
-    FunctionId, GenericDefId, HasModule, ImplId, LocalEnumVariantId, LocalFieldId, LocalModuleId,
-    Lookup, ModuleId, StaticId, StructId, TraitId, TypeAliasId, TypeParamId, UnionId,
+    FunctionId, GenericDefId, HasModule, ImplId, LifetimeParamId, LocalEnumVariantId, LocalFieldId,
+    LocalModuleId, Lookup, ModuleId, StaticId, StructId, TraitId, TypeAliasId, TypeParamId,
+    UnionId,
 };
 use hir_def::{find_path::PrefixKind, item_scope::ItemInNs, visibility::Visibility};
 use hir_expand::{
@@ -33,12 +34,12 @@ use hir_ty::{
     display::{HirDisplayError, HirFormatter},
     method_resolution,
     traits::{FnTrait, Solution, SolutionVariables},
-    ApplicationTy, BoundVar, CallableDefId, Canonical, DebruijnIndex, FnSig, GenericPredicate,
-    InEnvironment, Obligation, ProjectionPredicate, ProjectionTy, Substs, TraitEnvironment, Ty,
-    TyDefId, TyKind, TypeCtor,
+    ApplicationTy, BoundVar, CallableDefId, Canonical, CaptureKind, CapturedPlace, ConstEvalError,
+    ConstValue, DebruijnIndex, FnSig, GenericPredicate, InEnvironment, Obligation,
+    ProjectionPredicate, ProjectionTy, Substs, TraitEnvironment, Ty, TyDefId, TyKind, TypeCtor,
 };
 use rustc_hash::FxHashSet;
-use stdx::impl_from;
+use stdx::{format_to, impl_from};
 use syntax::{
     ast::{self, AttrsOwner, NameOwner},
     AstNode, SmolStr,
@@ -509,6 +510,10 @@ impl Struct {
         db.struct_data(self.id).repr.clone()
     }
 
+    pub fn kind(self, db: &dyn HirDatabase) -> StructKind {
+        self.variant_data(db).kind()
+    }
+
     fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
         db.struct_data(self.id).variant_data.clone()
     }
@@ -654,6 +659,12 @@ impl Adt {
             Adt::Enum(e) => e.name(db),
         }
     }
+
+    /// The inherent impls of this ADT. They can only live in the crate defining it.
+    pub fn inherent_impls(self, db: &dyn HirDatabase) -> Vec<ImplDef> {
+        let impls = db.inherent_impls_in_crate(self.module(db).krate().id);
+        impls.for_self_ty(&self.ty(db).ty.value).iter().map(|&id| ImplDef::from(id)).collect()
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -827,7 +838,7 @@ impl SelfParam {
             .params
             .first()
             .map(|param| match *param {
-                TypeRef::Reference(_, mutability) => mutability.into(),
+                TypeRef::Reference(.., mutability) => mutability.into(),
                 _ => Access::Owned,
             })
             .unwrap_or(Access::Owned)
@@ -859,6 +870,10 @@ impl Const {
     pub fn name(self, db: &dyn HirDatabase) -> Option<Name> {
         db.const_data(self.id).name.clone()
     }
+
+    pub fn eval(self, db: &dyn HirDatabase) -> Result<ConstValue, ConstEvalError> {
+        db.const_eval(self.id)
+    }
 }
 
 impl HasVisibility for Const {
@@ -913,6 +928,12 @@ impl Trait {
     pub fn is_auto(self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).auto
     }
+
+    /// The traits named in the bounds on `Self` of this trait, such as `Debug` in
+    /// `trait Tr: Debug {}`.
+    pub fn direct_supertraits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        hir_ty::direct_super_traits(db.upcast(), self.id).into_iter().map(Trait::from).collect()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1102,6 +1123,17 @@ impl GenericDef {
             .map(|(local_id, _)| TypeParam { id: TypeParamId { parent: self.into(), local_id } })
             .collect()
     }
+
+    pub fn lifetime_params(self, db: &dyn HirDatabase) -> Vec<LifetimeParam> {
+        let generics = db.generic_params(self.into());
+        generics
+            .lifetimes
+            .iter()
+            .map(|(local_id, _)| LifetimeParam {
+                id: LifetimeParamId { parent: self.into(), local_id },
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -1211,6 +1243,26 @@ impl TypeParam {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LifetimeParam {
+    pub(crate) id: LifetimeParamId,
+}
+
+impl LifetimeParam {
+    pub fn name(self, db: &dyn HirDatabase) -> Name {
+        let params = db.generic_params(self.id.parent);
+        params.lifetimes[self.id.local_id].name.clone()
+    }
+
+    pub fn module(self, db: &dyn HirDatabase) -> Module {
+        self.id.parent.module(db.upcast()).into()
+    }
+
+    pub fn parent(self, _db: &dyn HirDatabase) -> GenericDef {
+        self.id.parent.into()
+    }
+}
+
 // FIXME: rename from `ImplDef` to `Impl`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ImplDef {
@@ -1233,6 +1285,11 @@ impl ImplDef {
         db.impl_data(self.id).target_trait.clone()
     }
 
+    /// The implemented trait, `None` for inherent impls or if the trait can't be resolved.
+    pub fn trait_(self, db: &dyn HirDatabase) -> Option<Trait> {
+        db.impl_trait(self.id).map(|it| Trait::from(it.value.trait_))
+    }
+
     pub fn target_type(self, db: &dyn HirDatabase) -> TypeRef {
         db.impl_data(self.id).target_type.clone()
     }
@@ -1479,6 +1536,15 @@ impl Type {
         matches!(&self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { .. }, .. }))
     }
 
+    pub fn as_closure(&self) -> Option<Closure> {
+        match self.ty.value {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { def, expr }, .. }) => {
+                Some(Closure { owner: def, expr_id: expr })
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_fn(&self) -> bool {
         matches!(&self.ty.value,
             Ty::Apply(ApplicationTy { ctor: TypeCtor::FnDef(..), .. }) |
@@ -1646,6 +1712,16 @@ impl Type {
         Some(adt.into())
     }
 
+    /// Returns the generic arguments of an ADT type, e.g. `T` and `E` for `Result<T, E>`.
+    pub fn type_arguments(&self) -> impl Iterator<Item = Type> + '_ {
+        self.ty
+            .value
+            .as_adt()
+            .into_iter()
+            .flat_map(|(_, substs)| substs.iter())
+            .map(move |ty| self.derived(ty.clone()))
+    }
+
     pub fn as_dyn_trait(&self) -> Option<Trait> {
         self.ty.value.dyn_trait().map(Into::into)
     }
@@ -1843,6 +1919,61 @@ impl Callable {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Closure {
+    pub(crate) owner: DefWithBodyId,
+    pub(crate) expr_id: ExprId,
+}
+
+impl Closure {
+    /// The places of the enclosing body captured by the closure, in the order
+    /// of their first use.
+    pub fn captured_items(&self, db: &dyn HirDatabase) -> Vec<ClosureCapture> {
+        let captures = db.closure_captures(self.owner);
+        captures
+            .closure(self.expr_id)
+            .map(|info| info.captures.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|place| ClosureCapture { owner: self.owner, place })
+            .collect()
+    }
+
+    /// The most general `Fn*` trait the closure implements.
+    pub fn fn_trait(&self, db: &dyn HirDatabase) -> FnTrait {
+        let captures = db.closure_captures(self.owner);
+        captures.closure(self.expr_id).map_or(FnTrait::Fn, |info| info.kind)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClosureCapture {
+    owner: DefWithBodyId,
+    place: CapturedPlace,
+}
+
+impl ClosureCapture {
+    pub fn local(&self) -> Local {
+        Local { parent: self.owner, pat_id: self.place.local }
+    }
+
+    pub fn kind(&self) -> CaptureKind {
+        self.place.kind
+    }
+
+    /// The captured place as it would be written in source, like `x` or `x.a.b`.
+    pub fn display_place(&self, db: &dyn HirDatabase) -> String {
+        let mut buf = match self.local().name(db) {
+            Some(name) => name.to_string(),
+            None => "_".to_string(),
+        };
+        for projection in &self.place.projections {
+            format_to!(buf, ".{}", projection);
+        }
+        buf
+    }
+}
+
 /// For IDE only
 #[derive(Debug)]
 pub enum ScopeDef {
@@ -1,7 +1,9 @@
 //! FIXME: write short doc here
-pub use hir_def::diagnostics::{InactiveCode, UnresolvedModule};
-pub use hir_expand::diagnostics::{Diagnostic, DiagnosticSink, DiagnosticSinkBuilder};
+pub use hir_def::diagnostics::{InactiveCode, MacroError, UnresolvedMacroCall, UnresolvedModule};
+pub use hir_expand::diagnostics::{
+    Diagnostic, DiagnosticCode, DiagnosticSink, DiagnosticSinkBuilder,
+};
 pub use hir_ty::diagnostics::{
-    IncorrectCase, MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkInTailExpr,
-    NoSuchField,
+    IncorrectCase, MismatchAdjustment, MismatchedArgCount, MissingFields, MissingMatchArms,
+    MissingOkInTailExpr, NoSuchField, TypeMismatch,
 };
@@ -33,10 +33,11 @@ mod has_source;
 pub use crate::{
     attrs::{HasAttrs, Namespace},
     code_model::{
-        Access, Adt, AsAssocItem, AssocItem, AssocItemContainer, Callable, CallableKind, Const,
-        Crate, CrateDependency, DefWithBody, Enum, EnumVariant, Field, FieldSource, Function,
-        GenericDef, HasVisibility, ImplDef, Local, MacroDef, Module, ModuleDef, ScopeDef, Static,
-        Struct, Trait, Type, TypeAlias, TypeParam, Union, VariantDef,
+        Access, Adt, AsAssocItem, AssocItem, AssocItemContainer, Callable, CallableKind, Closure,
+        ClosureCapture, Const, Crate, CrateDependency, DefWithBody, Enum, EnumVariant, Field,
+        FieldSource, Function, GenericDef, HasVisibility, ImplDef, LifetimeParam, Local, MacroDef,
+        Module, ModuleDef, ScopeDef, Static, Struct, Trait, Type, TypeAlias, TypeParam, Union,
+        VariantDef,
     },
     has_source::HasSource,
     semantics::{original_range, PathResolution, Semantics, SemanticsScope},
@@ -59,7 +60,7 @@ pub use hir_expand::{
     name::known, name::AsName, name::Name, HirFileId, InFile, MacroCallId, MacroCallLoc,
     /* FIXME */ MacroDefId, MacroFile, Origin,
 };
-pub use hir_ty::display::HirDisplay;
+pub use hir_ty::{display::HirDisplay, traits::FnTrait, CaptureKind, ConstEvalError, ConstValue};
 
 // These are negative re-exports: pub using these names is forbidden, they
 // should remain private to hir internals.
@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 
-use arena::{map::ArenaMap, Arena};
+use arena::{map::ArenaMap, Arena, Idx};
 use either::Either;
 use hir_expand::{
     name::{AsName, Name},
@@ -66,8 +66,17 @@ pub enum ReprKind {
     Other,
 }
 
-fn repr_from_value(item_tree: &ItemTree, of: AttrOwner) -> Option<ReprKind> {
-    item_tree.attrs(of).by_key("repr").tt_values().find_map(parse_repr_tt)
+fn repr_from_value(
+    item_tree: &ItemTree,
+    cfg_options: &CfgOptions,
+    of: AttrOwner,
+) -> Option<ReprKind> {
+    item_tree
+        .raw_attrs(of)
+        .expand_cfg_attr(cfg_options)
+        .by_key("repr")
+        .tt_values()
+        .find_map(parse_repr_tt)
 }
 
 fn parse_repr_tt(tt: &Subtree) -> Option<ReprKind> {
@@ -87,8 +96,8 @@ impl StructData {
     pub(crate) fn struct_data_query(db: &dyn DefDatabase, id: StructId) -> Arc<StructData> {
         let loc = id.lookup(db);
         let item_tree = db.item_tree(loc.id.file_id);
-        let repr = repr_from_value(&item_tree, ModItem::from(loc.id.value).into());
         let cfg_options = db.crate_graph()[loc.container.module(db).krate].cfg_options.clone();
+        let repr = repr_from_value(&item_tree, &cfg_options, ModItem::from(loc.id.value).into());
 
         let strukt = &item_tree[loc.id.value];
         let variant_data = lower_fields(&item_tree, &cfg_options, &strukt.fields, None);
@@ -101,8 +110,8 @@ impl StructData {
     pub(crate) fn union_data_query(db: &dyn DefDatabase, id: UnionId) -> Arc<StructData> {
         let loc = id.lookup(db);
         let item_tree = db.item_tree(loc.id.file_id);
-        let repr = repr_from_value(&item_tree, ModItem::from(loc.id.value).into());
         let cfg_options = db.crate_graph()[loc.container.module(db).krate].cfg_options.clone();
+        let repr = repr_from_value(&item_tree, &cfg_options, ModItem::from(loc.id.value).into());
 
         let union = &item_tree[loc.id.value];
         let variant_data = lower_fields(&item_tree, &cfg_options, &union.fields, None);
@@ -124,7 +133,11 @@ impl EnumData {
         let enum_ = &item_tree[loc.id.value];
         let mut variants = Arena::new();
         for var_id in enum_.variants.clone() {
-            if item_tree.attrs(var_id.into()).is_cfg_enabled(&cfg_options) {
+            if item_tree
+                .raw_attrs(var_id.into())
+                .expand_cfg_attr(&cfg_options)
+                .is_cfg_enabled(&cfg_options)
+            {
                 let var = &item_tree[var_id];
                 let var_data =
                     lower_fields(&item_tree, &cfg_options, &var.fields, Some(enum_.visibility));
@@ -297,6 +310,10 @@ fn lower_struct(
     }
 }
 
+fn is_field_enabled(item_tree: &ItemTree, cfg_options: &CfgOptions, field: Idx<Field>) -> bool {
+    item_tree.raw_attrs(field.into()).expand_cfg_attr(cfg_options).is_cfg_enabled(cfg_options)
+}
+
 fn lower_fields(
     item_tree: &ItemTree,
     cfg_options: &CfgOptions,
@@ -307,7 +324,7 @@ fn lower_fields(
         Fields::Record(flds) => {
             let mut arena = Arena::new();
             for field_id in flds.clone() {
-                if item_tree.attrs(field_id.into()).is_cfg_enabled(cfg_options) {
+                if is_field_enabled(item_tree, cfg_options, field_id) {
                     arena.alloc(lower_field(item_tree, &item_tree[field_id], override_visibility));
                 }
             }
@@ -316,7 +333,7 @@ fn lower_fields(
         Fields::Tuple(flds) => {
             let mut arena = Arena::new();
             for field_id in flds.clone() {
-                if item_tree.attrs(field_id.into()).is_cfg_enabled(cfg_options) {
+                if is_field_enabled(item_tree, cfg_options, field_id) {
                     arena.alloc(lower_field(item_tree, &item_tree[field_id], override_visibility));
                 }
             }
@@ -2,31 +2,38 @@
 
 use std::{ops, sync::Arc};
 
+use base_db::CrateId;
 use cfg::{CfgExpr, CfgOptions};
 use either::Either;
-use hir_expand::{hygiene::Hygiene, AstId, InFile};
+use hir_expand::{hygiene::Hygiene, name::AsName, AstId, InFile};
 use mbe::ast_to_token_tree;
 use syntax::{
-    ast::{self, AstNode, AttrsOwner},
+    ast::{self, AstNode, AstToken, AttrsOwner, HasStringValue},
     SmolStr,
 };
-use tt::Subtree;
+use tt::{Leaf, Subtree, TokenTree};
 
 use crate::{
     db::DefDatabase,
+    docs::Documentation,
     item_tree::{ItemTreeId, ItemTreeNode},
     nameres::ModuleSource,
-    path::ModPath,
+    path::{ModPath, PathKind},
     src::HasChildSource,
-    AdtId, AttrDefId, Lookup,
+    AdtId, AttrDefId, HasModule, Lookup,
 };
 
+/// Syntactical attributes, without filtering of `cfg_attr`s.
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
-pub struct Attrs {
+pub(crate) struct RawAttrs {
     entries: Option<Arc<[Attr]>>,
 }
 
-impl ops::Deref for Attrs {
+/// Attributes of an item, with `cfg_attr`s expanded against the crate's `CfgOptions`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Attrs(RawAttrs);
+
+impl ops::Deref for RawAttrs {
     type Target = [Attr];
 
     fn deref(&self) -> &[Attr] {
@@ -37,96 +44,192 @@ impl ops::Deref for Attrs {
     }
 }
 
+impl ops::Deref for Attrs {
+    type Target = [Attr];
+
+    fn deref(&self) -> &[Attr] {
+        &self.0
+    }
+}
+
+impl RawAttrs {
+    pub(crate) const EMPTY: RawAttrs = RawAttrs { entries: None };
+
+    pub(crate) fn new(owner: &dyn AttrsOwner, hygiene: &Hygiene) -> RawAttrs {
+        let docs = ast::CommentIter::from_syntax_node(owner.syntax()).doc_comment_text().map(
+            |docs_text| Attr {
+                input: Some(AttrInput::Literal(SmolStr::new(docs_text))),
+                path: ModPath::from(hir_expand::name!(doc)),
+            },
+        );
+        let mut attrs = owner.attrs().peekable();
+        let entries = if attrs.peek().is_none() && docs.is_none() {
+            // Avoid heap allocation
+            None
+        } else {
+            // Doc comments go first, so that they precede `#[doc]` attributes in `Attrs::docs`
+            Some(
+                docs.into_iter()
+                    .chain(attrs.flat_map(|ast| Attr::from_src(ast, hygiene)))
+                    .collect(),
+            )
+        };
+        RawAttrs { entries }
+    }
+
+    fn from_attrs_owner(db: &dyn DefDatabase, owner: InFile<&dyn AttrsOwner>) -> RawAttrs {
+        let hygiene = Hygiene::new(db.upcast(), owner.file_id);
+        RawAttrs::new(owner.value, &hygiene)
+    }
+
+    pub(crate) fn merge(&self, other: RawAttrs) -> RawAttrs {
+        match (&self.entries, &other.entries) {
+            (None, None) => RawAttrs::EMPTY,
+            (Some(entries), None) | (None, Some(entries)) => {
+                RawAttrs { entries: Some(entries.clone()) }
+            }
+            (Some(a), Some(b)) => {
+                RawAttrs { entries: Some(a.iter().chain(b.iter()).cloned().collect()) }
+            }
+        }
+    }
+
+    pub(crate) fn by_key(&self, key: &'static str) -> AttrQuery<'_> {
+        AttrQuery { attrs: self, key }
+    }
+
+    /// Expands the `cfg_attr`s using the `CfgOptions` of `krate`.
+    pub(crate) fn filter(&self, db: &dyn DefDatabase, krate: CrateId) -> Attrs {
+        let crate_graph = db.crate_graph();
+        self.expand_cfg_attr(&crate_graph[krate].cfg_options)
+    }
+
+    /// Replaces every `#[cfg_attr(pred, attr1, attr2)]` by `attr1` and `attr2` if `pred` is
+    /// enabled, and drops it otherwise.
+    pub(crate) fn expand_cfg_attr(&self, cfg_options: &CfgOptions) -> Attrs {
+        let has_cfg_attrs = self.iter().any(|attr| attr.is_cfg_attr());
+        if !has_cfg_attrs {
+            return Attrs(self.clone());
+        }
+
+        let mut entries = Vec::new();
+        for attr in self.iter() {
+            attr.expand_cfg_attr_into(cfg_options, &mut entries);
+        }
+        let entries = if entries.is_empty() { None } else { Some(entries.into()) };
+        Attrs(RawAttrs { entries })
+    }
+}
+
 impl Attrs {
-    pub const EMPTY: Attrs = Attrs { entries: None };
+    pub const EMPTY: Attrs = Attrs(RawAttrs::EMPTY);
 
     pub(crate) fn attrs_query(db: &dyn DefDatabase, def: AttrDefId) -> Attrs {
-        match def {
+        let (raw_attrs, krate) = match def {
             AttrDefId::ModuleId(module) => {
                 let def_map = db.crate_def_map(module.krate);
                 let mod_data = &def_map[module.local_id];
-                match mod_data.declaration_source(db) {
+                let raw_attrs = match mod_data.declaration_source(db) {
                     Some(it) => {
-                        Attrs::from_attrs_owner(db, it.as_ref().map(|it| it as &dyn AttrsOwner))
+                        RawAttrs::from_attrs_owner(db, it.as_ref().map(|it| it as &dyn AttrsOwner))
                     }
-                    None => Attrs::from_attrs_owner(
+                    None => RawAttrs::from_attrs_owner(
                         db,
                         mod_data.definition_source(db).as_ref().map(|src| match src {
                             ModuleSource::SourceFile(file) => file as &dyn AttrsOwner,
                             ModuleSource::Module(module) => module as &dyn AttrsOwner,
                         }),
                     ),
-                }
+                };
+                (raw_attrs, module.krate)
             }
             AttrDefId::FieldId(it) => {
                 let src = it.parent.child_source(db);
-                match &src.value[it.local_id] {
-                    Either::Left(_tuple) => Attrs::default(),
-                    Either::Right(record) => Attrs::from_attrs_owner(db, src.with_value(record)),
-                }
+                let raw_attrs = match &src.value[it.local_id] {
+                    Either::Left(_tuple) => RawAttrs::default(),
+                    Either::Right(record) => RawAttrs::from_attrs_owner(db, src.with_value(record)),
+                };
+                (raw_attrs, it.parent.module(db).krate)
             }
             AttrDefId::EnumVariantId(var_id) => {
                 let src = var_id.parent.child_source(db);
                 let src = src.as_ref().map(|it| &it[var_id.local_id]);
-                Attrs::from_attrs_owner(db, src.map(|it| it as &dyn AttrsOwner))
+                let raw_attrs = RawAttrs::from_attrs_owner(db, src.map(|it| it as &dyn AttrsOwner));
+                (raw_attrs, var_id.parent.lookup(db).container.module(db).krate)
             }
-            AttrDefId::AdtId(it) => match it {
-                AdtId::StructId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-                AdtId::EnumId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-                AdtId::UnionId(it) => attrs_from_item_tree(it.lookup(db).id, db),
+            AttrDefId::AdtId(it) => {
+                let raw_attrs = match it {
+                    AdtId::StructId(it) => attrs_from_item_tree(it.lookup(db).id, db),
+                    AdtId::EnumId(it) => attrs_from_item_tree(it.lookup(db).id, db),
+                    AdtId::UnionId(it) => attrs_from_item_tree(it.lookup(db).id, db),
+                };
+                (raw_attrs, it.module(db).krate)
+            }
+            AttrDefId::TraitId(it) => {
+                let loc = it.lookup(db);
+                (attrs_from_item_tree(loc.id, db), loc.container.module(db).krate)
+            }
+            AttrDefId::MacroDefId(it) => match (it.ast_id, it.krate) {
+                (Some(ast_id), Some(krate)) => (attrs_from_ast(ast_id, db), krate),
+                _ => return Attrs::default(),
             },
-            AttrDefId::TraitId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-            AttrDefId::MacroDefId(it) => {
-                it.ast_id.map_or_else(Default::default, |ast_id| attrs_from_ast(ast_id, db))
+            AttrDefId::ImplId(it) => {
+                let loc = it.lookup(db);
+                (attrs_from_item_tree(loc.id, db), loc.container.module(db).krate)
             }
-            AttrDefId::ImplId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-            AttrDefId::ConstId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-            AttrDefId::StaticId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-            AttrDefId::FunctionId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-            AttrDefId::TypeAliasId(it) => attrs_from_item_tree(it.lookup(db).id, db),
-        }
+            AttrDefId::ConstId(it) => {
+                let loc = it.lookup(db);
+                (attrs_from_item_tree(loc.id, db), loc.module(db).krate)
+            }
+            AttrDefId::StaticId(it) => {
+                let loc = it.lookup(db);
+                (attrs_from_item_tree(loc.id, db), loc.module(db).krate)
+            }
+            AttrDefId::FunctionId(it) => {
+                let loc = it.lookup(db);
+                (attrs_from_item_tree(loc.id, db), loc.module(db).krate)
+            }
+            AttrDefId::TypeAliasId(it) => {
+                let loc = it.lookup(db);
+                (attrs_from_item_tree(loc.id, db), loc.module(db).krate)
+            }
+        };
+        raw_attrs.filter(db, krate)
     }
 
+    /// Collects the attributes of `owner` without expanding `cfg_attr`, since the crate (and
+    /// thus the enabled cfgs) isn't known here.
     pub fn from_attrs_owner(db: &dyn DefDatabase, owner: InFile<&dyn AttrsOwner>) -> Attrs {
-        let hygiene = Hygiene::new(db.upcast(), owner.file_id);
-        Attrs::new(owner.value, &hygiene)
-    }
-
-    pub(crate) fn new(owner: &dyn AttrsOwner, hygiene: &Hygiene) -> Attrs {
-        let docs = ast::CommentIter::from_syntax_node(owner.syntax()).doc_comment_text().map(
-            |docs_text| Attr {
-                input: Some(AttrInput::Literal(SmolStr::new(docs_text))),
-                path: ModPath::from(hir_expand::name!(doc)),
-            },
-        );
-        let mut attrs = owner.attrs().peekable();
-        let entries = if attrs.peek().is_none() {
-            // Avoid heap allocation
-            None
-        } else {
-            Some(attrs.flat_map(|ast| Attr::from_src(ast, hygiene)).chain(docs).collect())
-        };
-        Attrs { entries }
+        Attrs(RawAttrs::from_attrs_owner(db, owner))
     }
 
     pub fn merge(&self, other: Attrs) -> Attrs {
-        match (&self.entries, &other.entries) {
-            (None, None) => Attrs { entries: None },
-            (Some(entries), None) | (None, Some(entries)) => {
-                Attrs { entries: Some(entries.clone()) }
-            }
-            (Some(a), Some(b)) => {
-                Attrs { entries: Some(a.iter().chain(b.iter()).cloned().collect()) }
-            }
-        }
+        Attrs(self.0.merge(other.0))
     }
 
     pub fn by_key(&self, key: &'static str) -> AttrQuery<'_> {
         AttrQuery { attrs: self, key }
     }
 
+    /// Returns the doc comments and `#[doc = "..."]` attributes of the item, separated by blank
+    /// lines.
+    pub fn docs(&self) -> Option<Documentation> {
+        let docs = self
+            .by_key("doc")
+            .attrs()
+            .filter_map(|attr| match attr.input.as_ref()? {
+                AttrInput::Literal(it) => Some(it.as_str()),
+                AttrInput::TokenTree(_) => None,
+            })
+            .collect::<Vec<_>>();
+        if docs.is_empty() {
+            None
+        } else {
+            Some(Documentation::new(&docs.join("\n\n")))
+        }
+    }
+
     pub fn cfg(&self) -> Option<CfgExpr> {
-        // FIXME: handle cfg_attr :-)
         let mut cfgs = self.by_key("cfg").tt_values().map(CfgExpr::parse).collect::<Vec<_>>();
         match cfgs.len() {
             0 => None,
@@ -160,8 +263,12 @@ impl Attr {
     fn from_src(ast: ast::Attr, hygiene: &Hygiene) -> Option<Attr> {
         let path = ModPath::from_src(ast.path()?, hygiene)?;
         let input = if let Some(lit) = ast.literal() {
-            // FIXME: escape? raw string?
-            let value = lit.syntax().first_token()?.text().trim_matches('"').into();
+            let token = lit.syntax().first_token()?;
+            let value = ast::String::cast(token.clone())
+                .and_then(|it| it.value().map(SmolStr::new))
+                .or_else(|| ast::RawString::cast(token.clone())?.value().map(SmolStr::new))
+                // FIXME: invalid escapes, e.g. in Windows paths
+                .unwrap_or_else(|| token.text().trim_matches('"').into());
             Some(AttrInput::Literal(value))
         } else if let Some(tt) = ast.token_tree() {
             Some(AttrInput::TokenTree(ast_to_token_tree(&tt)?.0))
@@ -170,11 +277,81 @@ impl Attr {
         };
         Some(Attr { path, input })
     }
+
+    /// Parses an attribute from the tokens inside `#[...]`, as they appear in a `cfg_attr`.
+    fn from_tt(tokens: &[TokenTree]) -> Option<Attr> {
+        let mut segments = Vec::new();
+        let mut tokens = tokens.iter().peekable();
+        loop {
+            match tokens.next()? {
+                TokenTree::Leaf(Leaf::Ident(ident)) => segments.push(ident.as_name()),
+                _ => return None,
+            }
+            match tokens.peek() {
+                Some(TokenTree::Leaf(Leaf::Punct(p))) if p.char == ':' => {
+                    tokens.next();
+                    match tokens.next()? {
+                        TokenTree::Leaf(Leaf::Punct(p)) if p.char == ':' => {}
+                        _ => return None,
+                    }
+                }
+                _ => break,
+            }
+        }
+        let path = ModPath::from_segments(PathKind::Plain, segments);
+        let input = match tokens.next() {
+            None => None,
+            Some(TokenTree::Leaf(Leaf::Punct(p))) if p.char == '=' => match tokens.next()? {
+                TokenTree::Leaf(Leaf::Literal(lit)) => {
+                    // FIXME: escapes
+                    let text = lit.text.trim_start_matches('r').trim_matches('#');
+                    Some(AttrInput::Literal(text.trim_matches('"').into()))
+                }
+                _ => return None,
+            },
+            Some(TokenTree::Subtree(subtree)) => Some(AttrInput::TokenTree(subtree.clone())),
+            Some(_) => return None,
+        };
+        if tokens.next().is_some() {
+            return None;
+        }
+        Some(Attr { path, input })
+    }
+
+    fn is_cfg_attr(&self) -> bool {
+        self.path.as_ident().map_or(false, |s| s.to_string() == "cfg_attr")
+    }
+
+    /// Pushes `self` to `acc`, or, if `self` is a `cfg_attr`, the attributes it expands to.
+    fn expand_cfg_attr_into(&self, cfg_options: &CfgOptions, acc: &mut Vec<Attr>) {
+        if !self.is_cfg_attr() {
+            acc.push(self.clone());
+            return;
+        }
+        let subtree = match &self.input {
+            Some(AttrInput::TokenTree(it)) => it,
+            _ => return,
+        };
+        let mut parts = subtree
+            .token_trees
+            .split(|tt| matches!(tt, TokenTree::Leaf(Leaf::Punct(p)) if p.char == ','));
+        let cfg = match parts.next() {
+            Some(it) => CfgExpr::parse(&Subtree { delimiter: None, token_trees: it.to_vec() }),
+            None => return,
+        };
+        if cfg_options.check(&cfg) == Some(false) {
+            return;
+        }
+        for attr in parts.filter(|it| !it.is_empty()).filter_map(Attr::from_tt) {
+            // `cfg_attr`s can be nested
+            attr.expand_cfg_attr_into(cfg_options, acc);
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct AttrQuery<'a> {
-    attrs: &'a Attrs,
+    attrs: &'a [Attr],
     key: &'static str,
 }
 
@@ -205,16 +382,16 @@ impl<'a> AttrQuery<'a> {
     }
 }
 
-fn attrs_from_ast<N>(src: AstId<N>, db: &dyn DefDatabase) -> Attrs
+fn attrs_from_ast<N>(src: AstId<N>, db: &dyn DefDatabase) -> RawAttrs
 where
     N: ast::AttrsOwner,
 {
     let src = InFile::new(src.file_id, src.to_node(db.upcast()));
-    Attrs::from_attrs_owner(db, src.as_ref().map(|it| it as &dyn AttrsOwner))
+    RawAttrs::from_attrs_owner(db, src.as_ref().map(|it| it as &dyn AttrsOwner))
 }
 
-fn attrs_from_item_tree<N: ItemTreeNode>(id: ItemTreeId<N>, db: &dyn DefDatabase) -> Attrs {
+fn attrs_from_item_tree<N: ItemTreeNode>(id: ItemTreeId<N>, db: &dyn DefDatabase) -> RawAttrs {
     let tree = db.item_tree(id.file_id);
     let mod_item = N::id_to_mod_item(id.value);
-    tree.attrs(mod_item.into()).clone()
+    tree.raw_attrs(mod_item.into()).clone()
 }
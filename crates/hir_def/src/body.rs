@@ -17,6 +17,7 @@ use hir_expand::{
     ast_id_map::AstIdMap, diagnostics::DiagnosticSink, hygiene::Hygiene, AstId, HirFileId, InFile,
     MacroDefId,
 };
+use mbe::ExpandResult;
 use rustc_hash::FxHashMap;
 use syntax::{ast, AstNode, AstPtr};
 use test_utils::mark;
@@ -24,7 +25,7 @@ use test_utils::mark;
 pub(crate) use lower::LowerCtx;
 
 use crate::{
-    attr::Attrs,
+    attr::{Attrs, RawAttrs},
     db::DefDatabase,
     expr::{Expr, ExprId, Pat, PatId},
     item_scope::BuiltinShadowMode,
@@ -42,6 +43,10 @@ pub(crate) struct CfgExpander {
     hygiene: Hygiene,
 }
 
+/// Returned by `Expander::enter_expand` when the path of the macro call can't be resolved.
+#[derive(Debug)]
+pub(crate) struct UnresolvedMacro;
+
 pub(crate) struct Expander {
     cfg_expander: CfgExpander,
     crate_def_map: Arc<CrateDefMap>,
@@ -69,7 +74,7 @@ impl CfgExpander {
     }
 
     pub(crate) fn parse_attrs(&self, owner: &dyn ast::AttrsOwner) -> Attrs {
-        Attrs::new(owner, &self.hygiene)
+        RawAttrs::new(owner, &self.hygiene).expand_cfg_attr(&self.cfg_options)
     }
 
     pub(crate) fn is_cfg_enabled(&self, owner: &dyn ast::AttrsOwner) -> bool {
@@ -102,11 +107,11 @@ impl Expander {
         db: &dyn DefDatabase,
         local_scope: Option<&ItemScope>,
         macro_call: ast::MacroCall,
-    ) -> Option<(Mark, T)> {
+    ) -> Result<ExpandResult<Option<(Mark, T)>>, UnresolvedMacro> {
         self.recursion_limit += 1;
         if self.recursion_limit > EXPANSION_RECURSION_LIMIT {
             mark::hit!(your_stack_belongs_to_me);
-            return None;
+            return Ok(ExpandResult::ok(None));
         }
 
         let macro_call = InFile::new(self.current_file_id, &macro_call);
@@ -120,28 +125,31 @@ impl Expander {
             self.resolve_path_as_macro(db, &path)
         };
 
-        if let Some(call_id) = macro_call.as_call_id(db, self.crate_def_map.krate, resolver) {
-            let file_id = call_id.as_file();
-            if let Some(node) = db.parse_or_expand(file_id) {
-                if let Some(expr) = T::cast(node) {
-                    log::debug!("macro expansion {:#?}", expr.syntax());
-
-                    let mark = Mark {
-                        file_id: self.current_file_id,
-                        ast_id_map: mem::take(&mut self.ast_id_map),
-                        bomb: DropBomb::new("expansion mark dropped"),
-                    };
-                    self.cfg_expander.hygiene = Hygiene::new(db.upcast(), file_id);
-                    self.current_file_id = file_id;
-                    self.ast_id_map = db.ast_id_map(file_id);
-                    return Some((mark, expr));
-                }
+        let call_id = match macro_call.as_call_id(db, self.crate_def_map.krate, resolver) {
+            Some(it) => it,
+            None => return Err(UnresolvedMacro),
+        };
+
+        let err = db.macro_expand_error(call_id);
+
+        let file_id = call_id.as_file();
+        if let Some(node) = db.parse_or_expand(file_id) {
+            if let Some(expr) = T::cast(node) {
+                log::debug!("macro expansion {:#?}", expr.syntax());
+
+                let mark = Mark {
+                    file_id: self.current_file_id,
+                    ast_id_map: mem::take(&mut self.ast_id_map),
+                    bomb: DropBomb::new("expansion mark dropped"),
+                };
+                self.cfg_expander.hygiene = Hygiene::new(db.upcast(), file_id);
+                self.current_file_id = file_id;
+                self.ast_id_map = db.ast_id_map(file_id);
+                return Ok(ExpandResult(Some((mark, expr)), err));
             }
         }
 
-        // FIXME: Instead of just dropping the error from expansion
-        // report it
-        None
+        Ok(ExpandResult(None, err))
     }
 
     pub(crate) fn exit(&mut self, db: &dyn DefDatabase, mut mark: Mark) {
@@ -2,11 +2,13 @@
 
 use hir_expand::diagnostics::DiagnosticSink;
 
-use crate::diagnostics::InactiveCode;
+use crate::diagnostics::{InactiveCode, MacroError, UnresolvedMacroCall};
 
 #[derive(Debug, Eq, PartialEq)]
 pub(crate) enum BodyDiagnostic {
     InactiveCode(InactiveCode),
+    MacroError(MacroError),
+    UnresolvedMacroCall(UnresolvedMacroCall),
 }
 
 impl BodyDiagnostic {
@@ -15,6 +17,12 @@ impl BodyDiagnostic {
             BodyDiagnostic::InactiveCode(diag) => {
                 sink.push(diag.clone());
             }
+            BodyDiagnostic::MacroError(diag) => {
+                sink.push(diag.clone());
+            }
+            BodyDiagnostic::UnresolvedMacroCall(diag) => {
+                sink.push(diag.clone());
+            }
         }
     }
 }
@@ -25,7 +25,7 @@ use crate::{
     body::{Body, BodySourceMap, Expander, PatPtr, SyntheticSyntax},
     builtin_type::{BuiltinFloat, BuiltinInt},
     db::DefDatabase,
-    diagnostics::InactiveCode,
+    diagnostics::{InactiveCode, MacroError, UnresolvedMacroCall},
     expr::{
         dummy_expr_id, ArithOp, Array, BinaryOp, BindingAnnotation, CmpOp, Expr, ExprId, Literal,
         LogicOp, MatchArm, Ordering, Pat, PatId, RecordFieldPat, RecordLitField, Statement,
@@ -38,7 +38,7 @@ use crate::{
     StaticLoc, StructLoc, TraitLoc, TypeAliasLoc, UnionLoc,
 };
 
-use super::{diagnostics::BodyDiagnostic, ExprSource, PatSource};
+use super::{diagnostics::BodyDiagnostic, ExprSource, PatSource, UnresolvedMacro};
 
 pub(crate) struct LowerCtx {
     hygiene: Hygiene,
@@ -495,7 +495,11 @@ impl ExprCollector<'_> {
                 let ret_type =
                     e.ret_type().and_then(|r| r.ty()).map(|it| TypeRef::from_ast(&self.ctx(), it));
                 let body = self.collect_expr_opt(e.body());
-                self.alloc_expr(Expr::Lambda { args, arg_types, ret_type, body }, syntax_ptr)
+                let is_move = e.move_token().is_some();
+                self.alloc_expr(
+                    Expr::Lambda { args, arg_types, ret_type, body, is_move },
+                    syntax_ptr,
+                )
             }
             ast::Expr::BinExpr(e) => {
                 let lhs = self.collect_expr_opt(e.lhs());
@@ -531,7 +535,7 @@ impl ExprCollector<'_> {
                 }
             }
 
-            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(e.kind().into()), syntax_ptr),
+            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(Literal::from(&e)), syntax_ptr),
             ast::Expr::IndexExpr(e) => {
                 let base = self.collect_expr_opt(e.base());
                 let index = self.collect_expr_opt(e.index());
@@ -561,7 +565,31 @@ impl ExprCollector<'_> {
                     self.alloc_expr(Expr::Missing, syntax_ptr)
                 } else {
                     let macro_call = self.expander.to_source(AstPtr::new(&e));
-                    match self.expander.enter_expand(self.db, Some(&self.body.item_scope), e) {
+                    let res = self.expander.enter_expand(self.db, Some(&self.body.item_scope), e);
+
+                    match &res {
+                        Ok(res) => {
+                            if let Some(err) = &res.1 {
+                                self.source_map.diagnostics.push(BodyDiagnostic::MacroError(
+                                    MacroError {
+                                        file: macro_call.file_id,
+                                        node: macro_call.value.clone().into(),
+                                        message: err.to_string(),
+                                    },
+                                ));
+                            }
+                        }
+                        Err(UnresolvedMacro) => {
+                            self.source_map.diagnostics.push(BodyDiagnostic::UnresolvedMacroCall(
+                                UnresolvedMacroCall {
+                                    file: macro_call.file_id,
+                                    node: macro_call.value.clone(),
+                                },
+                            ));
+                        }
+                    }
+
+                    match res.ok().and_then(|res| res.0) {
                         Some((mark, expansion)) => {
                             self.source_map
                                 .expansions
@@ -834,7 +862,7 @@ impl ExprCollector<'_> {
             }
             ast::Pat::LiteralPat(lit) => {
                 if let Some(ast_lit) = lit.literal() {
-                    let expr = Expr::Literal(ast_lit.kind().into());
+                    let expr = Expr::Literal(Literal::from(&ast_lit));
                     let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
                     let expr_id = self.alloc_expr(expr, expr_ptr);
                     Pat::Lit(expr_id)
@@ -950,13 +978,13 @@ impl From<ast::BinOp> for BinaryOp {
     }
 }
 
-impl From<ast::LiteralKind> for Literal {
-    fn from(ast_lit_kind: ast::LiteralKind) -> Self {
-        match ast_lit_kind {
+impl From<&ast::Literal> for Literal {
+    fn from(ast_lit: &ast::Literal) -> Self {
+        match ast_lit.kind() {
             LiteralKind::IntNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinInt::from_suffix(&it));
 
-                Literal::Int(Default::default(), known_name)
+                Literal::Int(ast_lit.int_value().unwrap_or_default(), known_name)
             }
             LiteralKind::FloatNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinFloat::from_suffix(&it));
@@ -73,3 +73,35 @@ fn f() {
     ",
     );
 }
+
+#[test]
+fn macro_diag_builtin() {
+    check_diagnostics(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! format_args { () => {} }
+
+fn f() {
+    format_args!();
+  //^^^^^^^^^^^^^^ no rule matches input tokens
+}
+      "#,
+    );
+}
+
+#[test]
+fn macro_diag_rules() {
+    check_diagnostics(
+        r#"
+macro_rules! m { (a) => { 0 } }
+
+fn f() {
+    m!(b);
+  //^^^^^ unexpected token in input
+    m!(a);
+    unresolved!();
+  //^^^^^^^^^^^^^ unresolved macro call
+}
+      "#,
+    );
+}
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use hir_expand::{name::Name, InFile};
+use mbe::ExpandResult;
 use syntax::ast;
 
 use crate::{
@@ -41,7 +42,7 @@ impl FunctionData {
             name: func.name.clone(),
             params: func.params.to_vec(),
             ret_type: func.ret_type.clone(),
-            attrs: item_tree.attrs(ModItem::from(loc.id.value).into()).clone(),
+            attrs: item_tree.attrs(db, loc.module(db).krate, ModItem::from(loc.id.value).into()),
             has_self_param: func.has_self_param,
             has_body: func.has_body,
             is_unsafe: func.is_unsafe,
@@ -85,6 +86,10 @@ pub struct TraitData {
     pub name: Name,
     pub items: Vec<(Name, AssocItemId)>,
     pub auto: bool,
+    /// Whether the trait is skipped during method resolution on arrays before
+    /// the 2021 edition, so that `array.into_iter()` keeps resolving to
+    /// `<&[T; N] as IntoIterator>::into_iter`.
+    pub skip_array_during_method_dispatch: bool,
 }
 
 impl TraitData {
@@ -95,6 +100,10 @@ impl TraitData {
         let name = tr_def.name.clone();
         let auto = tr_def.auto;
         let module_id = tr_loc.container.module(db);
+        let skip_array_during_method_dispatch = item_tree
+            .attrs(db, module_id.krate, ModItem::from(tr_loc.id.value).into())
+            .by_key("rustc_skip_array_during_method_dispatch")
+            .exists();
         let container = AssocContainerId::TraitId(tr);
         let mut expander = Expander::new(db, tr_loc.id.file_id, module_id);
 
@@ -108,7 +117,7 @@ impl TraitData {
             100,
         );
 
-        Arc::new(TraitData { name, items, auto })
+        Arc::new(TraitData { name, items, auto, skip_array_during_method_dispatch })
     }
 
     pub fn associated_types(&self) -> impl Iterator<Item = TypeAliasId> + '_ {
@@ -229,8 +238,8 @@ fn collect_items(
         match item {
             AssocItem::Function(id) => {
                 let item = &item_tree[id];
-                let attrs = item_tree.attrs(ModItem::from(id).into());
-                if !attrs.is_cfg_enabled(&cfg_options) {
+                let attrs = item_tree.raw_attrs(ModItem::from(id).into());
+                if !attrs.expand_cfg_attr(&cfg_options).is_cfg_enabled(&cfg_options) {
                     continue;
                 }
                 let def = FunctionLoc { container, id: ItemTreeId::new(file_id, id) }.intern(db);
@@ -257,7 +266,10 @@ fn collect_items(
                 let root = db.parse_or_expand(file_id).unwrap();
                 let call = ast_id_map.get(call.ast_id).to_node(&root);
 
-                if let Some((mark, mac)) = expander.enter_expand(db, None, call) {
+                let res = expander.enter_expand(db, None, call);
+
+                // FIXME: report errors from macro calls in impls and traits
+                if let Ok(ExpandResult(Some((mark, mac)), _)) = res {
                     let src: InFile<ast::MacroItems> = expander.to_source(mac);
                     let item_tree = db.item_tree(src.file_id);
                     let iter =
@@ -5,14 +5,9 @@
 
 use std::sync::Arc;
 
-use either::Either;
 use syntax::ast;
 
-use crate::{
-    db::DefDatabase,
-    src::{HasChildSource, HasSource},
-    AdtId, AttrDefId, Lookup,
-};
+use crate::{db::DefDatabase, AttrDefId};
 
 /// Holds documentation
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,7 +20,7 @@ impl Into<String> for Documentation {
 }
 
 impl Documentation {
-    fn new(s: &str) -> Documentation {
+    pub(crate) fn new(s: &str) -> Documentation {
         Documentation(s.into())
     }
 
@@ -46,34 +41,14 @@ impl Documentation {
     ) -> Option<Documentation> {
         match def {
             AttrDefId::ModuleId(module) => {
+                // Only the outer docs of the `mod` item are considered.
                 let def_map = db.crate_def_map(module.krate);
-                let src = def_map[module.local_id].declaration_source(db)?;
-                docs_from_ast(&src.value)
+                def_map[module.local_id].declaration_source(db)?;
             }
-            AttrDefId::FieldId(it) => {
-                let src = it.parent.child_source(db);
-                match &src.value[it.local_id] {
-                    Either::Left(_tuple) => None,
-                    Either::Right(record) => docs_from_ast(record),
-                }
-            }
-            AttrDefId::AdtId(it) => match it {
-                AdtId::StructId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-                AdtId::EnumId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-                AdtId::UnionId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-            },
-            AttrDefId::EnumVariantId(it) => {
-                let src = it.parent.child_source(db);
-                docs_from_ast(&src.value[it.local_id])
-            }
-            AttrDefId::TraitId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-            AttrDefId::MacroDefId(it) => docs_from_ast(&it.ast_id?.to_node(db.upcast())),
-            AttrDefId::ConstId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-            AttrDefId::StaticId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-            AttrDefId::FunctionId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-            AttrDefId::TypeAliasId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-            AttrDefId::ImplId(_) => None,
+            AttrDefId::ImplId(_) => return None,
+            _ => {}
         }
+        db.attrs(def).docs()
     }
 }
 
@@ -22,7 +22,7 @@ use crate::{
     src::HasChildSource,
     src::HasSource,
     type_ref::{TypeBound, TypeRef},
-    AdtId, GenericDefId, LocalTypeParamId, Lookup, TypeParamId,
+    AdtId, GenericDefId, LocalLifetimeParamId, LocalTypeParamId, Lookup, TypeParamId,
 };
 
 /// Data about a generic parameter (to a function, struct, impl, ...).
@@ -33,6 +33,12 @@ pub struct TypeParamData {
     pub provenance: TypeParamProvenance,
 }
 
+/// Data about a lifetime parameter, like `'a` in `struct Foo<'a>`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LifetimeParamData {
+    pub name: Name,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum TypeParamProvenance {
     TypeParamList,
@@ -44,7 +50,7 @@ pub enum TypeParamProvenance {
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct GenericParams {
     pub types: Arena<TypeParamData>,
-    // lifetimes: Arena<LocalLifetimeParamId, LifetimeParamData>,
+    pub lifetimes: Arena<LifetimeParamData>,
     pub where_predicates: Vec<WherePredicate>,
 }
 
@@ -123,7 +129,7 @@ impl GenericParams {
     }
 
     fn new(db: &dyn DefDatabase, def: GenericDefId) -> (GenericParams, InFile<SourceMap>) {
-        let mut generics = GenericParams { types: Arena::default(), where_predicates: Vec::new() };
+        let mut generics = GenericParams::default();
         let mut sm = ArenaMap::default();
 
         // FIXME: add `: Sized` bound for everything except for `Self` in traits
@@ -233,6 +239,12 @@ impl GenericParams {
         sm: &mut SourceMap,
         params: ast::GenericParamList,
     ) {
+        for lifetime_param in params.lifetime_params() {
+            let name = lifetime_param
+                .lifetime_token()
+                .map_or_else(Name::missing, |it| Name::new_lifetime(&it));
+            self.lifetimes.alloc(LifetimeParamData { name });
+        }
         for type_param in params.type_params() {
             let name = type_param.name().map_or_else(Name::missing, |it| it.as_name());
             // FIXME: Use `Path::from_src`
@@ -252,13 +264,29 @@ impl GenericParams {
 
     fn fill_where_predicates(&mut self, lower_ctx: &LowerCtx, where_clause: ast::WhereClause) {
         for pred in where_clause.predicates() {
+            // FIXME: lower lifetime predicates like `'a: 'b`
             let type_ref = match pred.ty() {
                 Some(type_ref) => type_ref,
                 None => continue,
             };
             let type_ref = TypeRef::from_ast(lower_ctx, type_ref);
+            // `where for<'a> T: Trait<'a>` is treated like `where T: for<'a> Trait<'a>`
+            let for_lifetimes = pred.generic_param_list().map(|params| lifetime_names(&params));
             for bound in pred.type_bound_list().iter().flat_map(|l| l.bounds()) {
-                self.add_where_predicate_from_bound(lower_ctx, bound, type_ref.clone());
+                if bound.question_mark_token().is_some() {
+                    // FIXME: remove this bound
+                    continue;
+                }
+                let bound = match (TypeBound::from_ast(lower_ctx, bound), &for_lifetimes) {
+                    (TypeBound::Path(path), Some(lifetimes)) => {
+                        TypeBound::ForLifetime(lifetimes.clone(), path)
+                    }
+                    (bound, _) => bound,
+                };
+                self.where_predicates.push(WherePredicate {
+                    target: WherePredicateTarget::TypeRef(type_ref.clone()),
+                    bound,
+                });
             }
         }
     }
@@ -297,6 +325,10 @@ impl GenericParams {
         });
     }
 
+    pub fn find_lifetime_by_name(&self, name: &Name) -> Option<LocalLifetimeParamId> {
+        self.lifetimes.iter().find_map(|(id, p)| if &p.name == name { Some(id) } else { None })
+    }
+
     pub fn find_by_name(&self, name: &Name) -> Option<LocalTypeParamId> {
         self.types
             .iter()
@@ -314,6 +346,14 @@ impl GenericParams {
     }
 }
 
+pub(crate) fn lifetime_names(params: &ast::GenericParamList) -> Vec<Name> {
+    params
+        .lifetime_params()
+        .filter_map(|it| it.lifetime_token())
+        .map(|it| Name::new_lifetime(&it))
+        .collect()
+}
+
 impl HasChildSource for GenericDefId {
     type ChildId = LocalTypeParamId;
     type Value = Either<ast::Trait, ast::TypeParam>;
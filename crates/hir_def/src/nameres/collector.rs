@@ -5,7 +5,7 @@
 
 use std::iter;
 
-use base_db::{CrateId, FileId, ProcMacroId};
+use base_db::{CrateId, Edition, FileId, ProcMacroId};
 use cfg::{CfgExpr, CfgOptions};
 use hir_expand::InFile;
 use hir_expand::{
@@ -14,7 +14,7 @@ use hir_expand::{
     builtin_macro::find_builtin_macro,
     name::{name, AsName, Name},
     proc_macro::ProcMacroExpander,
-    HirFileId, MacroCallId, MacroDefId, MacroDefKind,
+    HirFileId, MacroCallId, MacroCallKind, MacroDefId, MacroDefKind,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::ast;
@@ -25,7 +25,9 @@ use crate::{
     attr::Attrs,
     db::DefDatabase,
     item_scope::{ImportType, PerNsGlobImports},
-    item_tree::{self, ItemTree, ItemTreeId, MacroCall, Mod, ModItem, ModKind, StructDefKind},
+    item_tree::{
+        self, AttrOwner, ItemTree, ItemTreeId, MacroCall, Mod, ModItem, ModKind, StructDefKind,
+    },
     nameres::{
         diagnostics::DefDiagnostic, mod_resolution::ModDir, path_resolution::ReachedFixedPoint,
         BuiltinShadowMode, CrateDefMap, ModuleData, ModuleOrigin, ResolveMode,
@@ -63,6 +65,22 @@ pub(super) fn collect_defs(db: &dyn DefDatabase, mut def_map: CrateDefMap) -> Cr
         }
     }
 
+    // The 2021 edition adds `TryFrom`, `TryInto` and `FromIterator` to the
+    // prelude. `std` defines it in a `rust_2021` module next to `prelude::v1`,
+    // which is what `#[prelude_import]` points at.
+    if def_map.edition == Edition::Edition2021 {
+        if let Some(prelude) = def_map.prelude {
+            let prelude_def_map = db.crate_def_map(prelude.krate);
+            let rust_2021 = prelude_def_map[prelude.local_id]
+                .parent
+                .and_then(|parent| prelude_def_map[parent].children.get(&name![rust_2021]))
+                .copied();
+            if let Some(local_id) = rust_2021 {
+                def_map.prelude = Some(ModuleId { krate: prelude.krate, local_id });
+            }
+        }
+    }
+
     let cfg_options = &crate_graph[def_map.krate].cfg_options;
     let proc_macros = &crate_graph[def_map.krate].proc_macro;
     let proc_macros = proc_macros
@@ -226,7 +244,11 @@ impl DefCollector<'_> {
             item_tree: &item_tree,
             mod_dir: ModDir::root(),
         };
-        if item_tree.top_level_attrs().cfg().map_or(true, |cfg| root_collector.is_cfg_enabled(&cfg))
+        if item_tree
+            .top_level_raw_attrs()
+            .expand_cfg_attr(root_collector.def_collector.cfg_options)
+            .cfg()
+            .map_or(true, |cfg| root_collector.is_cfg_enabled(&cfg))
         {
             root_collector.collect(item_tree.top_level_items());
         }
@@ -730,7 +752,12 @@ impl DefCollector<'_> {
         macros.retain(|directive| {
             if let Some(call_id) = directive.legacy {
                 res = ReachedFixedPoint::No;
-                resolved.push((directive.module_id, call_id, directive.depth));
+                resolved.push((
+                    directive.module_id,
+                    call_id,
+                    MacroCallKind::FnLike(directive.ast_id.ast_id),
+                    directive.depth,
+                ));
                 return false;
             }
 
@@ -746,7 +773,12 @@ impl DefCollector<'_> {
                     resolved_res.resolved_def.take_macros()
                 })
             {
-                resolved.push((directive.module_id, call_id, directive.depth));
+                resolved.push((
+                    directive.module_id,
+                    call_id,
+                    MacroCallKind::FnLike(directive.ast_id.ast_id),
+                    directive.depth,
+                ));
                 res = ReachedFixedPoint::No;
                 return false;
             }
@@ -759,7 +791,18 @@ impl DefCollector<'_> {
                     self.resolve_attribute_macro(&directive, &path)
                 })
             {
-                resolved.push((directive.module_id, call_id, 0));
+                let name = directive
+                    .ast_id
+                    .path
+                    .segments
+                    .last()
+                    .map_or_else(String::new, |it| it.to_string());
+                resolved.push((
+                    directive.module_id,
+                    call_id,
+                    MacroCallKind::Attr(directive.ast_id.ast_id, name),
+                    0,
+                ));
                 res = ReachedFixedPoint::No;
                 return false;
             }
@@ -770,8 +813,8 @@ impl DefCollector<'_> {
         self.unexpanded_macros = macros;
         self.unexpanded_attribute_macros = attribute_macros;
 
-        for (module_id, macro_call_id, depth) in resolved {
-            self.collect_macro_expansion(module_id, macro_call_id, depth);
+        for (module_id, macro_call_id, kind, depth) in resolved {
+            self.collect_macro_expansion(module_id, macro_call_id, kind, depth);
         }
 
         res
@@ -805,6 +848,7 @@ impl DefCollector<'_> {
         &mut self,
         module_id: LocalModuleId,
         macro_call_id: MacroCallId,
+        kind: MacroCallKind,
         depth: usize,
     ) {
         if depth > EXPANSION_DEPTH_LIMIT {
@@ -812,6 +856,13 @@ impl DefCollector<'_> {
             log::warn!("macro expansion is too deep");
             return;
         }
+        if let Some(err) = self.db.macro_expand_error(macro_call_id) {
+            self.def_map.diagnostics.push(DefDiagnostic::macro_error(
+                module_id,
+                kind,
+                err.to_string(),
+            ));
+        }
         let file_id: HirFileId = macro_call_id.as_file();
         let item_tree = self.db.item_tree(file_id);
         let mod_dir = self.mod_dirs[&module_id].clone();
@@ -827,6 +878,13 @@ impl DefCollector<'_> {
     }
 
     fn finish(mut self) -> CrateDefMap {
+        for directive in &self.unexpanded_macros {
+            self.def_map.diagnostics.push(DefDiagnostic::unresolved_macro_call(
+                directive.module_id,
+                directive.ast_id.ast_id,
+            ));
+        }
+
         // Emit diagnostics for all remaining unresolved imports.
 
         // We'd like to avoid emitting a diagnostics avalanche when some `extern crate` doesn't
@@ -903,7 +961,7 @@ impl ModCollector<'_, '_> {
         // `#[macro_use] extern crate` is hoisted to imports macros before collecting
         // any other items.
         for item in items {
-            let attrs = self.item_tree.attrs((*item).into());
+            let attrs = self.attrs((*item).into());
             if attrs.cfg().map_or(true, |cfg| self.is_cfg_enabled(&cfg)) {
                 if let ModItem::ExternCrate(id) = item {
                     let import = self.item_tree[*id].clone();
@@ -915,7 +973,7 @@ impl ModCollector<'_, '_> {
         }
 
         for &item in items {
-            let attrs = self.item_tree.attrs(item.into());
+            let attrs = self.attrs(item.into());
             if let Some(cfg) = attrs.cfg() {
                 if !self.is_cfg_enabled(&cfg) {
                     self.emit_unconfigured_diagnostic(item, &cfg);
@@ -928,7 +986,7 @@ impl ModCollector<'_, '_> {
 
             let mut def = None;
             match item {
-                ModItem::Mod(m) => self.collect_module(&self.item_tree[m], attrs),
+                ModItem::Mod(m) => self.collect_module(&self.item_tree[m], &attrs),
                 ModItem::Import(import_id) => {
                     self.def_collector.unresolved_imports.push(ImportDirective {
                         module_id: self.module_id,
@@ -963,7 +1021,7 @@ impl ModCollector<'_, '_> {
                 ModItem::Function(id) => {
                     let func = &self.item_tree[id];
 
-                    self.collect_proc_macro_def(&func.name, attrs);
+                    self.collect_proc_macro_def(&func.name, &attrs);
 
                     def = Some(DefData {
                         id: FunctionLoc {
@@ -983,7 +1041,7 @@ impl ModCollector<'_, '_> {
                     // FIXME: check attrs to see if this is an attribute macro invocation;
                     // in which case we don't add the invocation, just a single attribute
                     // macro invocation
-                    self.collect_derives(attrs, it.ast_id.upcast());
+                    self.collect_derives(&attrs, it.ast_id.upcast());
 
                     def = Some(DefData {
                         id: StructLoc { container, id: ItemTreeId::new(self.file_id, id) }
@@ -1000,7 +1058,7 @@ impl ModCollector<'_, '_> {
                     // FIXME: check attrs to see if this is an attribute macro invocation;
                     // in which case we don't add the invocation, just a single attribute
                     // macro invocation
-                    self.collect_derives(attrs, it.ast_id.upcast());
+                    self.collect_derives(&attrs, it.ast_id.upcast());
 
                     def = Some(DefData {
                         id: UnionLoc { container, id: ItemTreeId::new(self.file_id, id) }
@@ -1017,7 +1075,7 @@ impl ModCollector<'_, '_> {
                     // FIXME: check attrs to see if this is an attribute macro invocation;
                     // in which case we don't add the invocation, just a single attribute
                     // macro invocation
-                    self.collect_derives(attrs, it.ast_id.upcast());
+                    self.collect_derives(&attrs, it.ast_id.upcast());
 
                     def = Some(DefData {
                         id: EnumLoc { container, id: ItemTreeId::new(self.file_id, id) }
@@ -1327,6 +1385,11 @@ impl ModCollector<'_, '_> {
         }
     }
 
+    /// Returns the attributes of `item`, with `cfg_attr`s expanded.
+    fn attrs(&self, item: AttrOwner) -> Attrs {
+        self.item_tree.raw_attrs(item).expand_cfg_attr(self.def_collector.cfg_options)
+    }
+
     fn is_cfg_enabled(&self, cfg: &CfgExpr) -> bool {
         self.def_collector.cfg_options.check(cfg) != Some(false)
     }
@@ -65,7 +65,7 @@ impl ModDir {
 
         for candidate in candidate_files.iter() {
             if let Some(file_id) = db.resolve_path(file_id, candidate.as_str()) {
-                let is_mod_rs = candidate.ends_with("mod.rs");
+                let is_mod_rs = candidate == "mod.rs" || candidate.ends_with("/mod.rs");
 
                 let (dir_path, root_non_dir_owner) = if is_mod_rs || attr_path.is_some() {
                     (DirPath::empty(), false)
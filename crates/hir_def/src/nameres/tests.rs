@@ -475,6 +475,58 @@ mod prelude {
     );
 }
 
+#[test]
+fn edition_2021_prelude() {
+    check(
+        r#"
+//- /main.rs crate:main deps:std edition:2021
+use {Foo, TryFrom};
+
+//- /std.rs crate:std
+#[prelude_import]
+pub use self::prelude::v1::*;
+pub mod prelude {
+    pub mod v1 {
+        pub struct Foo;
+    }
+    pub mod rust_2021 {
+        pub use super::v1::*;
+        pub trait TryFrom {}
+    }
+}
+"#,
+        expect![[r#"
+            crate
+            Foo: t v
+            TryFrom: t
+        "#]],
+    );
+    check(
+        r#"
+//- /main.rs crate:main deps:std edition:2018
+use {Foo, TryFrom};
+
+//- /std.rs crate:std
+#[prelude_import]
+pub use self::prelude::v1::*;
+pub mod prelude {
+    pub mod v1 {
+        pub struct Foo;
+    }
+    pub mod rust_2021 {
+        pub use super::v1::*;
+        pub trait TryFrom {}
+    }
+}
+"#,
+        expect![[r#"
+            crate
+            Foo: t v
+            TryFrom: _
+        "#]],
+    );
+}
+
 #[test]
 fn cfg_not_test() {
     check(
@@ -531,6 +583,35 @@ mod prelude {
     );
 }
 
+#[test]
+fn cfg_attr_test() {
+    check(
+        r#"
+//- /main.rs crate:main deps:std
+use {Foo, Bar, Baz};
+
+//- /lib.rs crate:std cfg:test,feature=foo
+#[prelude_import]
+pub use self::prelude::*;
+mod prelude {
+    #[cfg_attr(test, cfg(feature = "foo"))]
+    pub struct Foo;
+    #[cfg_attr(test, cfg(not(test)))]
+    pub struct Bar;
+    #[cfg_attr(not(test), cfg(not(test)))]
+    #[cfg_attr(test, cfg_attr(feature = "foo", cfg(feature = "foo")))]
+    pub struct Baz;
+}
+"#,
+        expect![[r#"
+            crate
+            Bar: _
+            Baz: t v
+            Foo: t v
+        "#]],
+    );
+}
+
 #[test]
 fn infer_multiple_namespace() {
     check(
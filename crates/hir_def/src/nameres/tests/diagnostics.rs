@@ -119,3 +119,44 @@ fn inactive_item() {
         "#,
     );
 }
+
+#[test]
+fn inactive_item_in_cfg_attr() {
+    check_diagnostics(
+        r#"
+        //- /lib.rs crate:lib cfg:test
+          #[cfg_attr(test, cfg(no))] pub fn f() {}
+        //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ code is inactive due to #[cfg] directives: no is disabled
+
+          #[cfg_attr(no, cfg(no))] pub fn g() {}
+        "#,
+    );
+}
+
+#[test]
+fn unresolved_macro_call() {
+    check_diagnostics(
+        r#"
+        //- /lib.rs
+          unresolved!();
+        //^^^^^^^^^^^^^^ unresolved macro call
+
+          macro_rules! m { () => {} }
+          m!();
+        "#,
+    );
+}
+
+#[test]
+fn macro_expansion_error() {
+    check_diagnostics(
+        r#"
+        //- /lib.rs
+          macro_rules! m { (a) => {} }
+          m!(b);
+        //^^^^^^ unexpected token in input
+
+          m!(a);
+        "#,
+    );
+}
@@ -641,6 +641,42 @@ fn expand_derive() {
     assert_eq!(map.modules[map.root].scope.impls().len(), 2);
 }
 
+#[test]
+fn expand_derive_in_cfg_attr() {
+    let map = compute_crate_def_map(
+        "
+        //- /main.rs crate:main cfg:feature=serde
+        #[cfg_attr(feature = \"serde\", derive(Copy))]
+        #[cfg_attr(feature = \"std\", derive(Clone))]
+        struct Foo;
+        ",
+    );
+    assert_eq!(map.modules[map.root].scope.impls().len(), 1);
+}
+
+#[test]
+fn macro_use_in_cfg_attr() {
+    check(
+        r#"
+//- /main.rs crate:main cfg:test
+#[cfg_attr(test, macro_use)]
+mod m {
+    macro_rules! structs {
+        ($($i:ident),*) => { $(struct $i;)* }
+    }
+}
+structs!(Foo);
+"#,
+        expect![[r#"
+            crate
+            Foo: t v
+            m: t
+
+            crate::m
+        "#]],
+    );
+}
+
 #[test]
 fn macro_expansion_overflow() {
     mark::check!(macro_expansion_overflow);
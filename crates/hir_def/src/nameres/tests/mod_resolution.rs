@@ -96,6 +96,32 @@ pub trait Iterator;
     );
 }
 
+#[test]
+fn module_resolution_for_file_ending_in_mod() {
+    check(
+        r#"
+//- /lib.rs
+mod submod;
+
+//- /submod.rs
+mod inner;
+
+//- /submod/inner.rs
+struct X;
+"#,
+        expect![[r#"
+            crate
+            submod: t
+
+            crate::submod
+            inner: t
+
+            crate::submod::inner
+            X: t v
+        "#]],
+    );
+}
+
 #[test]
 fn module_resolution_works_for_non_standard_filenames() {
     check(
@@ -163,6 +189,30 @@ pub struct Bar;
     );
 }
 
+#[test]
+fn module_resolution_decl_path_in_cfg_attr() {
+    check(
+        r#"
+//- /lib.rs crate:lib cfg:test
+#[cfg_attr(test, path = "bar/baz/foo.rs")]
+#[cfg_attr(not(test), path = "bar/foo.rs")]
+mod foo;
+use self::foo::Bar;
+
+//- /bar/baz/foo.rs
+pub struct Bar;
+"#,
+        expect![[r#"
+            crate
+            Bar: t v
+            foo: t
+
+            crate::foo
+            Bar: t v
+        "#]],
+    );
+}
+
 #[test]
 fn module_resolution_module_with_path_in_mod_rs() {
     check(
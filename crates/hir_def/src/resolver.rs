@@ -21,8 +21,8 @@ use crate::{
     per_ns::PerNs,
     visibility::{RawVisibility, Visibility},
     AdtId, AssocContainerId, ConstId, ContainerId, DefWithBodyId, EnumId, EnumVariantId,
-    FunctionId, GenericDefId, HasModule, ImplId, LocalModuleId, Lookup, ModuleDefId, ModuleId,
-    StaticId, StructId, TraitId, TypeAliasId, TypeParamId, VariantId,
+    FunctionId, GenericDefId, HasModule, ImplId, LifetimeParamId, LocalModuleId, Lookup,
+    ModuleDefId, ModuleId, StaticId, StructId, TraitId, TypeAliasId, TypeParamId, VariantId,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -458,6 +458,17 @@ impl Resolver {
             .flat_map(|params| params.where_predicates.iter())
     }
 
+    /// Resolves a named lifetime, like `'a`, to the generic parameter that
+    /// declares it.
+    pub fn resolve_lifetime(&self, name: &Name) -> Option<LifetimeParamId> {
+        self.scopes.iter().rev().find_map(|scope| match scope {
+            Scope::GenericParams { params, def } => params
+                .find_lifetime_by_name(name)
+                .map(|local_id| LifetimeParamId { parent: *def, local_id }),
+            _ => None,
+        })
+    }
+
     pub fn generic_def(&self) -> Option<GenericDefId> {
         self.scopes.iter().rev().find_map(|scope| match scope {
             Scope::GenericParams { def, .. } => Some(*def),
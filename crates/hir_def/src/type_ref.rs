@@ -1,8 +1,13 @@
 //! HIR for references to types. Paths in these are not yet resolved. They can
 //! be directly created from an ast::TypeRef, without further queries.
-use syntax::ast::{self};
+use hir_expand::name::Name;
+use syntax::{ast, SyntaxToken};
 
-use crate::{body::LowerCtx, path::Path};
+use crate::{
+    body::LowerCtx,
+    generics::lifetime_names,
+    path::{GenericArg, Path},
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Mutability {
@@ -58,8 +63,8 @@ pub enum TypeRef {
     Tuple(Vec<TypeRef>),
     Path(Path),
     RawPtr(Box<TypeRef>, Mutability),
-    Reference(Box<TypeRef>, Mutability),
-    Array(Box<TypeRef> /*, Expr*/),
+    Reference(Box<TypeRef>, Option<LifetimeRef>, Mutability),
+    Array(Box<TypeRef>, ConstRef),
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>, bool /*varargs*/),
@@ -69,11 +74,62 @@ pub enum TypeRef {
     Error,
 }
 
+/// A constant in a type, like the length of an array type. Unlike in bodies,
+/// only the simplest forms are lowered here; they are evaluated in `hir_ty`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ConstRef {
+    /// An integer literal, e.g. the `32` in `[u8; 32]`.
+    Literal(u128),
+    /// A path to a constant, e.g. the `N` in `[u8; N]`.
+    Path(Path),
+    /// Any other expression.
+    Unknown,
+}
+
+impl ConstRef {
+    pub(crate) fn from_ast_opt(ctx: &LowerCtx, node: Option<ast::Expr>) -> Self {
+        match node {
+            Some(ast::Expr::Literal(lit)) => {
+                lit.int_value().map(ConstRef::Literal).unwrap_or(ConstRef::Unknown)
+            }
+            Some(ast::Expr::PathExpr(path)) => path
+                .path()
+                .and_then(|it| ctx.lower_path(it))
+                .map(ConstRef::Path)
+                .unwrap_or(ConstRef::Unknown),
+            _ => ConstRef::Unknown,
+        }
+    }
+}
+
+/// A reference to a lifetime, like `'a` in `&'a str`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LifetimeRef {
+    pub name: Name,
+}
+
+impl LifetimeRef {
+    pub(crate) fn new(lifetime: &SyntaxToken) -> Self {
+        LifetimeRef { name: Name::new_lifetime(lifetime) }
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.name.to_string() == "'static"
+    }
+
+    /// Whether this is the anonymous lifetime `'_`.
+    pub fn is_anonymous(&self) -> bool {
+        self.name.to_string() == "'_"
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TypeBound {
     Path(Path),
-    // also for<> bounds
-    // also Lifetimes
+    /// A trait bound with a higher-ranked lifetime binder, like
+    /// `for<'a> Fn(&'a u8)`.
+    ForLifetime(Vec<Name>, Path),
+    Lifetime(LifetimeRef),
     Error,
 }
 
@@ -100,15 +156,17 @@ impl TypeRef {
                 TypeRef::RawPtr(Box::new(inner_ty), mutability)
             }
             ast::Type::ArrayType(inner) => {
-                TypeRef::Array(Box::new(TypeRef::from_ast_opt(&ctx, inner.ty())))
+                let len = ConstRef::from_ast_opt(&ctx, inner.expr());
+                TypeRef::Array(Box::new(TypeRef::from_ast_opt(&ctx, inner.ty())), len)
             }
             ast::Type::SliceType(inner) => {
                 TypeRef::Slice(Box::new(TypeRef::from_ast_opt(&ctx, inner.ty())))
             }
             ast::Type::RefType(inner) => {
                 let inner_ty = TypeRef::from_ast_opt(&ctx, inner.ty());
+                let lifetime = inner.lifetime_token().map(|it| LifetimeRef::new(&it));
                 let mutability = Mutability::from_mutable(inner.mut_token().is_some());
-                TypeRef::Reference(Box::new(inner_ty), mutability)
+                TypeRef::Reference(Box::new(inner_ty), lifetime, mutability)
             }
             ast::Type::InferType(_inner) => TypeRef::Placeholder,
             ast::Type::FnPtrType(inner) => {
@@ -163,14 +221,13 @@ impl TypeRef {
                     types.iter().for_each(|t| go(t, f))
                 }
                 TypeRef::RawPtr(type_ref, _)
-                | TypeRef::Reference(type_ref, _)
-                | TypeRef::Array(type_ref)
+                | TypeRef::Reference(type_ref, _, _)
+                | TypeRef::Array(type_ref, _)
                 | TypeRef::Slice(type_ref) => go(&type_ref, f),
                 TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
                     for bound in bounds {
-                        match bound {
-                            TypeBound::Path(path) => go_path(path, f),
-                            TypeBound::Error => (),
+                        if let Some(path) = bound.as_path() {
+                            go_path(path, f)
                         }
                     }
                 }
@@ -186,17 +243,17 @@ impl TypeRef {
             for segment in path.segments().iter() {
                 if let Some(args_and_bindings) = segment.args_and_bindings {
                     for arg in &args_and_bindings.args {
-                        let crate::path::GenericArg::Type(type_ref) = arg;
-                        go(type_ref, f);
+                        if let GenericArg::Type(type_ref) = arg {
+                            go(type_ref, f);
+                        }
                     }
                     for binding in &args_and_bindings.bindings {
                         if let Some(type_ref) = &binding.type_ref {
                             go(type_ref, f);
                         }
                         for bound in &binding.bounds {
-                            match bound {
-                                TypeBound::Path(path) => go_path(path, f),
-                                TypeBound::Error => (),
+                            if let Some(path) = bound.as_path() {
+                                go_path(path, f)
                             }
                         }
                     }
@@ -221,24 +278,32 @@ impl TypeBound {
     pub(crate) fn from_ast(ctx: &LowerCtx, node: ast::TypeBound) -> Self {
         match node.kind() {
             ast::TypeBoundKind::PathType(path_type) => {
-                let path = match path_type.path() {
-                    Some(p) => p,
-                    None => return TypeBound::Error,
-                };
-
-                let path = match ctx.lower_path(path) {
-                    Some(p) => p,
-                    None => return TypeBound::Error,
+                match path_type.path().and_then(|it| ctx.lower_path(it)) {
+                    Some(path) => TypeBound::Path(path),
+                    None => TypeBound::Error,
+                }
+            }
+            ast::TypeBoundKind::ForType(for_type) => {
+                let lifetimes =
+                    for_type.generic_param_list().map(|it| lifetime_names(&it)).unwrap_or_default();
+                let path = match for_type.ty() {
+                    Some(ast::Type::PathType(path_type)) => path_type.path(),
+                    _ => None,
                 };
-                TypeBound::Path(path)
+                match path.and_then(|it| ctx.lower_path(it)) {
+                    Some(path) => TypeBound::ForLifetime(lifetimes, path),
+                    None => TypeBound::Error,
+                }
+            }
+            ast::TypeBoundKind::Lifetime(lifetime) => {
+                TypeBound::Lifetime(LifetimeRef::new(&lifetime))
             }
-            ast::TypeBoundKind::ForType(_) | ast::TypeBoundKind::Lifetime(_) => TypeBound::Error,
         }
     }
 
     pub fn as_path(&self) -> Option<&Path> {
         match self {
-            TypeBound::Path(p) => Some(p),
+            TypeBound::Path(p) | TypeBound::ForLifetime(_, p) => Some(p),
             _ => None,
         }
     }
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use base_db::{salsa, SourceDatabase};
-use mbe::{ExpandResult, MacroRules};
+use mbe::{ExpandError, ExpandResult, MacroRules};
 use parser::FragmentKind;
 use syntax::{algo::diff, AstNode, GreenNode, Parse, SyntaxKind::*, SyntaxNode};
 
@@ -77,7 +77,12 @@ pub trait AstDatabase: SourceDatabase {
     fn macro_def(&self, id: MacroDefId) -> Option<Arc<(TokenExpander, mbe::TokenMap)>>;
     fn parse_macro(&self, macro_file: MacroFile)
         -> Option<(Parse<SyntaxNode>, Arc<mbe::TokenMap>)>;
-    fn macro_expand(&self, macro_call: MacroCallId) -> (Option<Arc<tt::Subtree>>, Option<String>);
+    fn macro_expand(
+        &self,
+        macro_call: MacroCallId,
+    ) -> (Option<Arc<tt::Subtree>>, Option<ExpandError>);
+    /// Firewall query that returns the error from the `macro_expand` query.
+    fn macro_expand_error(&self, macro_call: MacroCallId) -> Option<ExpandError>;
 
     #[salsa::interned]
     fn intern_eager_expansion(&self, eager: EagerCallLoc) -> EagerMacroId;
@@ -174,10 +179,14 @@ pub(crate) fn macro_arg(
 pub(crate) fn macro_expand(
     db: &dyn AstDatabase,
     id: MacroCallId,
-) -> (Option<Arc<tt::Subtree>>, Option<String>) {
+) -> (Option<Arc<tt::Subtree>>, Option<ExpandError>) {
     macro_expand_with_arg(db, id, None)
 }
 
+pub(crate) fn macro_expand_error(db: &dyn AstDatabase, id: MacroCallId) -> Option<ExpandError> {
+    db.macro_expand(id).1
+}
+
 fn expander(db: &dyn AstDatabase, id: MacroCallId) -> Option<Arc<(TokenExpander, mbe::TokenMap)>> {
     let lazy_id = match id {
         MacroCallId::LazyMacro(id) => id,
@@ -195,14 +204,16 @@ fn macro_expand_with_arg(
     db: &dyn AstDatabase,
     id: MacroCallId,
     arg: Option<Arc<(tt::Subtree, mbe::TokenMap)>>,
-) -> (Option<Arc<tt::Subtree>>, Option<String>) {
+) -> (Option<Arc<tt::Subtree>>, Option<ExpandError>) {
     let lazy_id = match id {
         MacroCallId::LazyMacro(id) => id,
         MacroCallId::EagerMacro(id) => {
             if arg.is_some() {
                 return (
                     None,
-                    Some("hypothetical macro expansion not implemented for eager macro".to_owned()),
+                    Some(ExpandError::Other(
+                        "hypothetical macro expansion not implemented for eager macro".to_owned(),
+                    )),
                 );
             } else {
                 return (Some(db.lookup_intern_eager_expansion(id).subtree), None);
@@ -213,20 +224,26 @@ fn macro_expand_with_arg(
     let loc = db.lookup_intern_macro(lazy_id);
     let macro_arg = match arg.or_else(|| db.macro_arg(id)) {
         Some(it) => it,
-        None => return (None, Some("Fail to args in to tt::TokenTree".into())),
+        None => return (None, Some(ExpandError::Other("Fail to args in to tt::TokenTree".into()))),
     };
 
     let macro_rules = match db.macro_def(loc.def) {
         Some(it) => it,
-        None => return (None, Some("Fail to find macro definition".into())),
+        None => return (None, Some(ExpandError::Other("Fail to find macro definition".into()))),
     };
     let ExpandResult(tt, err) = macro_rules.0.expand(db, lazy_id, &macro_arg.0);
     // Set a hard limit for the expanded tt
     let count = tt.count();
     if count > 262144 {
-        return (None, Some(format!("Total tokens count exceed limit : count = {}", count)));
+        return (
+            None,
+            Some(ExpandError::Other(format!(
+                "Total tokens count exceed limit : count = {}",
+                count
+            ))),
+        );
     }
-    (Some(Arc::new(tt)), err.map(|e| format!("{:?}", e)))
+    (Some(Arc::new(tt)), err)
 }
 
 pub(crate) fn expand_proc_macro(
@@ -316,7 +316,7 @@ pub struct ExpansionInfo {
     exp_map: Arc<mbe::TokenMap>,
 }
 
-pub use mbe::Origin;
+pub use mbe::{ExpandError, Origin};
 use parser::FragmentKind;
 
 impl ExpansionInfo {
@@ -162,7 +162,11 @@ pub mod known {
         ops,
         future,
         result,
+        option,
+        convert,
         boxed,
+        mem,
+        rust_2021,
         // Components of known path (type name)
         Iterator,
         IntoIterator,
@@ -171,6 +175,8 @@ pub mod known {
         Ok,
         Future,
         Result,
+        Option,
+        Into,
         Output,
         Target,
         Box,
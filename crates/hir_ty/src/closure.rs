@@ -0,0 +1,508 @@
+//! Capture analysis for closures.
+//!
+//! For every closure in a body, this computes the places of the enclosing body
+//! the closure captures, how each of them is captured, and which of the `Fn*`
+//! traits the closure implements as a consequence. This roughly follows
+//! `ExprUseVisitor` and `upvar.rs` in rustc, in a much simplified way.
+
+use std::sync::Arc;
+
+use base_db::{CrateId, Edition};
+use hir_def::{
+    body::Body,
+    expr::{BinaryOp, BindingAnnotation, Expr, ExprId, Pat, PatId, Statement, UnaryOp},
+    resolver::{resolver_for_expr, HasResolver, ValueNs},
+    type_ref::{Mutability, TypeRef},
+    DefWithBodyId, HasModule,
+};
+use hir_expand::name::Name;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    db::HirDatabase,
+    method_resolution::implements_trait,
+    traits::{FnTrait, TraitEnvironment},
+    ApplicationTy, Canonical, InferenceResult, Ty, TypeCtor,
+};
+
+/// How a place is captured by a closure.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CaptureKind {
+    ByRef,
+    ByMutRef,
+    ByValue,
+}
+
+/// A place of the enclosing body that's captured by a closure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedPlace {
+    /// The binding of the captured variable.
+    pub local: PatId,
+    /// Field accesses on the variable, like `a.b` in `x.a.b`.
+    pub projections: Vec<Name>,
+    pub kind: CaptureKind,
+    /// The type of the captured place.
+    pub ty: Ty,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosureInfo {
+    /// The captured places, in the order of their first use.
+    pub captures: Vec<CapturedPlace>,
+    /// The most general `Fn*` trait the closure implements.
+    pub kind: FnTrait,
+}
+
+/// The result of capture analysis for all closures in a body.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ClosureCaptures {
+    closures: FxHashMap<ExprId, ClosureInfo>,
+}
+
+impl ClosureCaptures {
+    pub fn closure(&self, expr: ExprId) -> Option<&ClosureInfo> {
+        self.closures.get(&expr)
+    }
+}
+
+pub(crate) fn closure_captures_query(
+    db: &dyn HirDatabase,
+    def: DefWithBodyId,
+) -> Arc<ClosureCaptures> {
+    let _p = profile::span("closure_captures_query");
+    let body = db.body(def);
+    let infer = db.infer(def);
+    let mut ctx = CaptureCtx::new(db, def, &body, Some(&infer));
+    for (expr, _) in body.exprs.iter() {
+        if matches!(body[expr], Expr::Lambda { .. }) {
+            ctx.analyze_closure(expr);
+        }
+    }
+    Arc::new(ClosureCaptures { closures: ctx.closures })
+}
+
+/// Computes the kind of a closure without looking at inference results.
+///
+/// This is what we tell Chalk, as it is queried while the body containing the
+/// closure is still being inferred. As types are unknown, every use of a
+/// captured place by value is assumed to copy it, so this never returns
+/// `FnOnce`.
+pub(crate) fn closure_kind_from_body(
+    db: &dyn HirDatabase,
+    def: DefWithBodyId,
+    closure: ExprId,
+) -> FnTrait {
+    let body = db.body(def);
+    let mut ctx = CaptureCtx::new(db, def, &body, None);
+    ctx.analyze_closure(closure).kind
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    /// The value is used by value, i.e. it's either copied or moved.
+    Consume,
+    Borrow(Mutability),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum UseKind {
+    Read,
+    MutBorrow,
+    Move,
+}
+
+struct Place {
+    local: PatId,
+    projections: Vec<Name>,
+    /// If the place goes through a deref, the type of the place being
+    /// dereferenced. Captures stop at derefs, so that's what gets captured.
+    deref_ty: Option<Ty>,
+}
+
+struct CaptureCtx<'a> {
+    db: &'a dyn HirDatabase,
+    owner: DefWithBodyId,
+    body: &'a Body,
+    /// `None` if we don't have inference results, see `closure_kind_from_body`.
+    infer: Option<&'a InferenceResult>,
+    krate: CrateId,
+    env: Arc<TraitEnvironment>,
+    closures: FxHashMap<ExprId, ClosureInfo>,
+}
+
+/// The state for the closure that's currently being analyzed.
+#[derive(Default)]
+struct CurrentClosure {
+    /// Bindings introduced inside the closure; uses of these aren't captures.
+    locals: FxHashSet<PatId>,
+    uses: Vec<(Place, UseKind, Ty)>,
+}
+
+impl<'a> CaptureCtx<'a> {
+    fn new(
+        db: &'a dyn HirDatabase,
+        owner: DefWithBodyId,
+        body: &'a Body,
+        infer: Option<&'a InferenceResult>,
+    ) -> CaptureCtx<'a> {
+        let krate = owner.module(db.upcast()).krate;
+        let env = TraitEnvironment::lower(db, &owner.resolver(db.upcast()));
+        CaptureCtx { db, owner, body, infer, krate, env, closures: FxHashMap::default() }
+    }
+
+    fn analyze_closure(&mut self, closure: ExprId) -> &ClosureInfo {
+        if !self.closures.contains_key(&closure) {
+            let info = self.compute_closure_info(closure);
+            self.closures.insert(closure, info);
+        }
+        &self.closures[&closure]
+    }
+
+    fn compute_closure_info(&mut self, closure: ExprId) -> ClosureInfo {
+        let (args, body_expr, is_move) = match &self.body[closure] {
+            Expr::Lambda { args, body, is_move, .. } => (args.clone(), *body, *is_move),
+            _ => panic!("expected a closure expression"),
+        };
+
+        let mut current = CurrentClosure::default();
+        for arg in args {
+            self.collect_bindings(arg, &mut current.locals);
+        }
+        self.walk_expr(&mut current, body_expr, Mode::Consume);
+
+        // Edition 2021 captures disjoint fields instead of whole variables.
+        let precise = self.db.crate_graph()[self.krate].edition == Edition::Edition2021;
+        let mut kind = FnTrait::Fn;
+        let mut captures: Vec<CapturedPlace> = Vec::new();
+        for (place, use_kind, ty) in current.uses {
+            kind = match (use_kind, kind) {
+                (UseKind::Move, _) | (_, FnTrait::FnOnce) => FnTrait::FnOnce,
+                (UseKind::MutBorrow, _) | (_, FnTrait::FnMut) => FnTrait::FnMut,
+                (UseKind::Read, FnTrait::Fn) => FnTrait::Fn,
+            };
+            let capture_kind = if is_move {
+                CaptureKind::ByValue
+            } else {
+                match use_kind {
+                    UseKind::Read => CaptureKind::ByRef,
+                    UseKind::MutBorrow => CaptureKind::ByMutRef,
+                    UseKind::Move => CaptureKind::ByValue,
+                }
+            };
+            let Place { local, projections, deref_ty } = place;
+            let (projections, ty) = if precise {
+                (projections, deref_ty.unwrap_or(ty))
+            } else if projections.is_empty() && deref_ty.is_none() {
+                (projections, ty)
+            } else {
+                (Vec::new(), self.pat_ty(local))
+            };
+            match captures.iter_mut().find(|it| it.local == local && it.projections == projections)
+            {
+                Some(capture) => capture.kind = capture.kind.max(capture_kind),
+                None => captures.push(CapturedPlace { local, projections, kind: capture_kind, ty }),
+            }
+        }
+        if precise {
+            captures = merge_nested_captures(captures);
+        }
+        ClosureInfo { captures, kind }
+    }
+
+    fn walk_expr(&mut self, current: &mut CurrentClosure, expr: ExprId, mode: Mode) {
+        let body = self.body;
+        if let Some(place) = self.place_of_expr(expr) {
+            if !current.locals.contains(&place.local) {
+                let ty = self.expr_ty(expr);
+                self.record_use(current, place, mode, ty);
+            }
+            return;
+        }
+
+        match &body[expr] {
+            Expr::Ref { expr, mutability, .. } => {
+                self.walk_expr(current, *expr, Mode::Borrow(*mutability))
+            }
+            Expr::BinaryOp { lhs, rhs, op } => {
+                let lhs_mode = match op {
+                    Some(BinaryOp::Assignment { .. }) => Mode::Borrow(Mutability::Mut),
+                    Some(BinaryOp::CmpOp(_)) => Mode::Borrow(Mutability::Shared),
+                    Some(BinaryOp::ArithOp(_)) | Some(BinaryOp::LogicOp(_)) | None => Mode::Consume,
+                };
+                let rhs_mode = match op {
+                    Some(BinaryOp::CmpOp(_)) => Mode::Borrow(Mutability::Shared),
+                    _ => Mode::Consume,
+                };
+                self.walk_expr(current, *lhs, lhs_mode);
+                self.walk_expr(current, *rhs, rhs_mode);
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                let receiver_mode = self.method_receiver_mode(expr);
+                self.walk_expr(current, *receiver, receiver_mode);
+                for arg in args {
+                    self.walk_expr(current, *arg, Mode::Consume);
+                }
+            }
+            Expr::Call { callee, args } => {
+                let callee_mode = self.callee_mode(*callee);
+                self.walk_expr(current, *callee, callee_mode);
+                for arg in args {
+                    self.walk_expr(current, *arg, Mode::Consume);
+                }
+            }
+            Expr::Index { base, index } => {
+                let base_mode = match mode {
+                    Mode::Borrow(Mutability::Mut) => Mode::Borrow(Mutability::Mut),
+                    _ => Mode::Borrow(Mutability::Shared),
+                };
+                self.walk_expr(current, *base, base_mode);
+                self.walk_expr(current, *index, Mode::Consume);
+            }
+            Expr::Field { expr, .. } | Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
+                // not a place, so the base is a temporary
+                self.walk_expr(current, *expr, Mode::Consume)
+            }
+            Expr::Block { statements, tail, .. } => {
+                for statement in statements {
+                    match statement {
+                        Statement::Let { pat, initializer, .. } => {
+                            if let Some(initializer) = initializer {
+                                let mode = self.pat_mode(*pat, *initializer);
+                                self.walk_expr(current, *initializer, mode);
+                            }
+                            self.collect_bindings(*pat, &mut current.locals);
+                        }
+                        Statement::Expr(expr) => self.walk_expr(current, *expr, Mode::Consume),
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.walk_expr(current, *tail, Mode::Consume);
+                }
+            }
+            Expr::Match { expr, arms } => {
+                let mode = arms
+                    .iter()
+                    .map(|arm| self.pat_mode(arm.pat, *expr))
+                    .max_by_key(|mode| match mode {
+                        Mode::Borrow(Mutability::Shared) => 0,
+                        Mode::Borrow(Mutability::Mut) => 1,
+                        Mode::Consume => 2,
+                    })
+                    .unwrap_or(Mode::Borrow(Mutability::Shared));
+                self.walk_expr(current, *expr, mode);
+                for arm in arms {
+                    self.collect_bindings(arm.pat, &mut current.locals);
+                    if let Some(guard) = arm.guard {
+                        self.walk_expr(current, guard, Mode::Consume);
+                    }
+                    self.walk_expr(current, arm.expr, Mode::Consume);
+                }
+            }
+            Expr::For { iterable, pat, body, .. } => {
+                self.walk_expr(current, *iterable, Mode::Consume);
+                self.collect_bindings(*pat, &mut current.locals);
+                self.walk_expr(current, *body, Mode::Consume);
+            }
+            Expr::Lambda { .. } => {
+                let nested = self.analyze_closure(expr).captures.clone();
+                for capture in nested {
+                    if current.locals.contains(&capture.local) {
+                        continue;
+                    }
+                    let mode = match capture.kind {
+                        CaptureKind::ByRef => Mode::Borrow(Mutability::Shared),
+                        CaptureKind::ByMutRef => Mode::Borrow(Mutability::Mut),
+                        CaptureKind::ByValue => Mode::Consume,
+                    };
+                    let place = Place {
+                        local: capture.local,
+                        projections: capture.projections,
+                        deref_ty: None,
+                    };
+                    self.record_use(current, place, mode, capture.ty);
+                }
+            }
+            e => e.walk_child_exprs(|child| self.walk_expr(current, child, Mode::Consume)),
+        }
+    }
+
+    /// If `expr` is a place expression based on a local, like `x.a.b` or `*x`,
+    /// returns that place.
+    fn place_of_expr(&self, expr: ExprId) -> Option<Place> {
+        match &self.body[expr] {
+            Expr::Path(path) => {
+                let resolver = resolver_for_expr(self.db.upcast(), self.owner, expr);
+                match resolver.resolve_path_in_value_ns_fully(self.db.upcast(), path.mod_path())? {
+                    ValueNs::LocalBinding(local) => {
+                        Some(Place { local, projections: Vec::new(), deref_ty: None })
+                    }
+                    _ => None,
+                }
+            }
+            Expr::Field { expr, name } => {
+                let mut place = self.place_of_expr(*expr)?;
+                if place.deref_ty.is_none() {
+                    place.projections.push(name.clone());
+                }
+                Some(place)
+            }
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
+                let mut place = self.place_of_expr(*expr)?;
+                if place.deref_ty.is_none() {
+                    place.deref_ty = Some(self.expr_ty(*expr));
+                }
+                Some(place)
+            }
+            _ => None,
+        }
+    }
+
+    fn record_use(&mut self, current: &mut CurrentClosure, place: Place, mode: Mode, ty: Ty) {
+        let use_kind = match mode {
+            Mode::Borrow(Mutability::Shared) => UseKind::Read,
+            Mode::Borrow(Mutability::Mut) => UseKind::MutBorrow,
+            // using a mutable reference by value reborrows it
+            Mode::Consume if matches!(ty.as_reference(), Some((_, Mutability::Mut))) => {
+                UseKind::MutBorrow
+            }
+            Mode::Consume if self.is_copy(&ty) => UseKind::Read,
+            Mode::Consume => UseKind::Move,
+        };
+        current.uses.push((place, use_kind, ty));
+    }
+
+    /// The mode in which the value matched against `pat` is used.
+    fn pat_mode(&self, pat: PatId, scrutinee: ExprId) -> Mode {
+        let scrutinee_is_ref = self.expr_ty(scrutinee).as_reference().is_some();
+        let mut mode = Mode::Borrow(Mutability::Shared);
+        walk_bindings(self.body, pat, &mut |binding| {
+            let binding_mode = match &self.body[binding] {
+                Pat::Bind { mode: BindingAnnotation::Ref, .. } => Mode::Borrow(Mutability::Shared),
+                Pat::Bind { mode: BindingAnnotation::RefMut, .. } => Mode::Borrow(Mutability::Mut),
+                // bindings through a reference don't move out of the scrutinee
+                _ if scrutinee_is_ref => Mode::Consume,
+                _ if self.is_copy(&self.pat_ty(binding)) => Mode::Borrow(Mutability::Shared),
+                _ => Mode::Consume,
+            };
+            mode = match (mode, binding_mode) {
+                (Mode::Consume, _) | (_, Mode::Consume) => Mode::Consume,
+                (Mode::Borrow(Mutability::Mut), _) | (_, Mode::Borrow(Mutability::Mut)) => {
+                    Mode::Borrow(Mutability::Mut)
+                }
+                _ => Mode::Borrow(Mutability::Shared),
+            };
+        });
+        mode
+    }
+
+    fn method_receiver_mode(&self, method_call: ExprId) -> Mode {
+        let func = match self.infer.and_then(|infer| infer.method_resolution(method_call)) {
+            Some(func) => func,
+            None => return Mode::Borrow(Mutability::Shared),
+        };
+        let data = self.db.function_data(func);
+        if !data.has_self_param {
+            return Mode::Consume;
+        }
+        match data.params.first() {
+            Some(TypeRef::Reference(.., mutability)) => Mode::Borrow(*mutability),
+            _ => Mode::Consume,
+        }
+    }
+
+    fn callee_mode(&mut self, callee: ExprId) -> Mode {
+        let ty = self.expr_ty(callee);
+        let fn_trait = match &ty {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { def, expr }, .. })
+                if *def == self.owner =>
+            {
+                self.analyze_closure(*expr).kind
+            }
+            _ if self.is_copy(&ty) => FnTrait::Fn,
+            _ => [FnTrait::Fn, FnTrait::FnMut]
+                .iter()
+                .copied()
+                .find(|fn_trait| self.implements_fn_trait(&ty, *fn_trait))
+                .unwrap_or(FnTrait::FnOnce),
+        };
+        match fn_trait {
+            FnTrait::Fn => Mode::Borrow(Mutability::Shared),
+            FnTrait::FnMut => Mode::Borrow(Mutability::Mut),
+            FnTrait::FnOnce => Mode::Consume,
+        }
+    }
+
+    fn collect_bindings(&self, pat: PatId, locals: &mut FxHashSet<PatId>) {
+        walk_bindings(self.body, pat, &mut |binding| {
+            locals.insert(binding);
+        });
+    }
+
+    fn expr_ty(&self, expr: ExprId) -> Ty {
+        self.infer.map_or(Ty::Unknown, |infer| infer[expr].clone())
+    }
+
+    fn pat_ty(&self, pat: PatId) -> Ty {
+        self.infer.map_or(Ty::Unknown, |infer| infer[pat].clone())
+    }
+
+    /// Whether the type is `Copy`. Types we don't know are assumed to be, so
+    /// that we don't report moves that don't happen.
+    fn is_copy(&self, ty: &Ty) -> bool {
+        if matches!(ty, Ty::Unknown) {
+            return true;
+        }
+        let copy_trait = match self.db.lang_item(self.krate, "copy".into()) {
+            Some(hir_def::lang_item::LangItemTarget::TraitId(it)) => it,
+            _ => return true,
+        };
+        let canonical = Canonical { value: ty.clone(), kinds: Arc::new([]) };
+        implements_trait(&canonical, self.db, self.env.clone(), self.krate, copy_trait)
+    }
+
+    fn implements_fn_trait(&self, ty: &Ty, fn_trait: FnTrait) -> bool {
+        let trait_ = match fn_trait.get_id(self.db, self.krate) {
+            Some(it) => it,
+            None => return false,
+        };
+        let canonical = Canonical { value: ty.clone(), kinds: Arc::new([]) };
+        implements_trait(&canonical, self.db, self.env.clone(), self.krate, trait_)
+    }
+}
+
+/// Merges captures of places nested in other captured places into the latter,
+/// e.g. if both `x` and `x.a` are captured, only `x` is.
+fn merge_nested_captures(captures: Vec<CapturedPlace>) -> Vec<CapturedPlace> {
+    let mut res: Vec<CapturedPlace> = Vec::new();
+    for mut capture in captures {
+        let is_prefix = |prefix: &CapturedPlace, place: &CapturedPlace| {
+            prefix.local == place.local && place.projections.starts_with(&prefix.projections)
+        };
+        if let Some(ancestor) = res.iter_mut().find(|it| is_prefix(it, &capture)) {
+            ancestor.kind = ancestor.kind.max(capture.kind);
+            continue;
+        }
+        match res.iter().position(|it| is_prefix(&capture, it)) {
+            Some(idx) => {
+                res.retain(|it| {
+                    if is_prefix(&capture, it) {
+                        capture.kind = capture.kind.max(it.kind);
+                        false
+                    } else {
+                        true
+                    }
+                });
+                res.insert(idx, capture);
+            }
+            None => res.push(capture),
+        }
+    }
+    res
+}
+
+fn walk_bindings(body: &Body, pat: PatId, f: &mut impl FnMut(PatId)) {
+    if let Pat::Bind { .. } = body[pat] {
+        f(pat);
+    }
+    body[pat].walk_child_pats(|child| walk_bindings(body, child, f));
+}
@@ -0,0 +1,339 @@
+//! Evaluation of constant expressions: the bodies of `const` items and array
+//! lengths.
+//!
+//! Only a small subset of Rust is supported: integer and boolean literals,
+//! arithmetic, comparisons, casts between integer types, references to other
+//! constants and `core::mem::size_of` of primitive types.
+
+use std::{convert::TryFrom, fmt};
+
+use hir_def::{
+    body::Body,
+    builtin_type::{BuiltinInt, BuiltinType, IntBitness, Signedness},
+    expr::{ArithOp, BinaryOp, CmpOp, Expr, ExprId, Literal, LogicOp, Ordering, UnaryOp},
+    path::{path, GenericArg, Path},
+    resolver::{HasResolver, Resolver, TypeNs, ValueNs},
+    type_ref::{ConstRef, TypeRef},
+    ConstId,
+};
+
+use crate::db::HirDatabase;
+
+/// The value of a successfully evaluated constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstValue {
+    Bool(bool),
+    Int(i128),
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstValue::Bool(it) => write!(f, "{}", it),
+            ConstValue::Int(it) => write!(f, "{}", it),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstEvalError {
+    /// The expression contains something the evaluator can't handle.
+    NotSupported(&'static str),
+    /// A path in the expression couldn't be resolved.
+    UnresolvedPath,
+    /// The computation overflowed its type.
+    Overflow,
+    DivisionByZero,
+    /// The constant refers to itself.
+    Cycle,
+}
+
+impl fmt::Display for ConstEvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstEvalError::NotSupported(what) => write!(f, "{} is not supported", what),
+            ConstEvalError::UnresolvedPath => write!(f, "unresolved path"),
+            ConstEvalError::Overflow => write!(f, "arithmetic overflow"),
+            ConstEvalError::DivisionByZero => write!(f, "division by zero"),
+            ConstEvalError::Cycle => write!(f, "cycle in constant evaluation"),
+        }
+    }
+}
+
+pub(crate) fn const_eval_query(
+    db: &dyn HirDatabase,
+    konst: ConstId,
+) -> Result<ConstValue, ConstEvalError> {
+    let _p = profile::span("const_eval_query");
+    let body = db.body(konst.into());
+    let resolver = konst.resolver(db.upcast());
+    let data = db.const_data(konst);
+    let declared_int = builtin_int(db, &resolver, &data.type_ref);
+
+    let ctx = ConstEvalCtx { db, resolver: &resolver, body: &body, int_ty: declared_int };
+    let value = ctx.eval(body.body_expr)?;
+    match (value, declared_int) {
+        (ConstValue::Int(it), Some(int_ty)) if !fits_in(it, int_ty) => {
+            Err(ConstEvalError::Overflow)
+        }
+        _ => Ok(value),
+    }
+}
+
+pub(crate) fn const_eval_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _konst: &ConstId,
+) -> Result<ConstValue, ConstEvalError> {
+    Err(ConstEvalError::Cycle)
+}
+
+/// Evaluates the length of an array type, like the `N` in `[u8; N]`.
+pub(crate) fn eval_array_len(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    len: &ConstRef,
+) -> Option<u64> {
+    let value = match len {
+        ConstRef::Literal(it) => return u64::try_from(*it).ok(),
+        ConstRef::Path(path) => eval_path(db, resolver, path).ok()?,
+        ConstRef::Unknown => return None,
+    };
+    as_usize(value)
+}
+
+/// Evaluates the length of an array expression, like the `N` in `[0; N]`.
+pub(crate) fn eval_array_repeat_len(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    body: &Body,
+    repeat: ExprId,
+) -> Option<u64> {
+    let ctx = ConstEvalCtx { db, resolver, body, int_ty: Some(BuiltinInt::USIZE) };
+    as_usize(ctx.eval(repeat).ok()?)
+}
+
+fn as_usize(value: ConstValue) -> Option<u64> {
+    match value {
+        ConstValue::Int(it) => u64::try_from(it).ok(),
+        ConstValue::Bool(_) => None,
+    }
+}
+
+struct ConstEvalCtx<'a> {
+    db: &'a dyn HirDatabase,
+    resolver: &'a Resolver,
+    body: &'a Body,
+    /// The integer type the whole expression is expected to have, used for
+    /// bitwise negation.
+    int_ty: Option<BuiltinInt>,
+}
+
+impl ConstEvalCtx<'_> {
+    fn eval(&self, expr: ExprId) -> Result<ConstValue, ConstEvalError> {
+        match &self.body[expr] {
+            Expr::Literal(Literal::Int(it, _)) => {
+                i128::try_from(*it).map(ConstValue::Int).map_err(|_| ConstEvalError::Overflow)
+            }
+            Expr::Literal(Literal::Bool(it)) => Ok(ConstValue::Bool(*it)),
+            Expr::Literal(_) => Err(ConstEvalError::NotSupported("non-integer literal")),
+            Expr::Path(path) => eval_path(self.db, self.resolver, path),
+            Expr::Block { statements, tail: Some(tail), .. } if statements.is_empty() => {
+                self.eval(*tail)
+            }
+            Expr::UnaryOp { expr, op } => match (op, self.eval(*expr)?) {
+                (UnaryOp::Neg, ConstValue::Int(it)) => {
+                    it.checked_neg().map(ConstValue::Int).ok_or(ConstEvalError::Overflow)
+                }
+                (UnaryOp::Not, ConstValue::Bool(it)) => Ok(ConstValue::Bool(!it)),
+                (UnaryOp::Not, ConstValue::Int(it)) => match self.int_ty {
+                    Some(int_ty) => Ok(ConstValue::Int(wrap_to(!it, int_ty))),
+                    None => Err(ConstEvalError::NotSupported("bitwise negation of unknown type")),
+                },
+                _ => Err(ConstEvalError::NotSupported("unary operator")),
+            },
+            Expr::BinaryOp { lhs, rhs, op: Some(op) } => self.eval_binary_op(*lhs, *rhs, *op),
+            Expr::Cast { expr, type_ref } => {
+                let int_ty = builtin_int(self.db, self.resolver, type_ref)
+                    .ok_or(ConstEvalError::NotSupported("cast to a non-integer type"))?;
+                match self.eval(*expr)? {
+                    ConstValue::Int(it) => Ok(ConstValue::Int(wrap_to(it, int_ty))),
+                    ConstValue::Bool(it) => Ok(ConstValue::Int(it as i128)),
+                }
+            }
+            Expr::Call { callee, args } if args.is_empty() => self.eval_size_of(*callee),
+            _ => Err(ConstEvalError::NotSupported("expression")),
+        }
+    }
+
+    fn eval_binary_op(
+        &self,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: BinaryOp,
+    ) -> Result<ConstValue, ConstEvalError> {
+        if let BinaryOp::LogicOp(op) = op {
+            let lhs = self.eval_bool(lhs)?;
+            return match (op, lhs) {
+                (LogicOp::And, false) => Ok(ConstValue::Bool(false)),
+                (LogicOp::Or, true) => Ok(ConstValue::Bool(true)),
+                _ => self.eval_bool(rhs).map(ConstValue::Bool),
+            };
+        }
+
+        let (lhs, rhs) = match (self.eval(lhs)?, self.eval(rhs)?) {
+            (ConstValue::Int(lhs), ConstValue::Int(rhs)) => (lhs, rhs),
+            (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => (lhs as i128, rhs as i128),
+            _ => return Err(ConstEvalError::NotSupported("operands of different types")),
+        };
+        let value = match op {
+            BinaryOp::ArithOp(op) => {
+                let value = match op {
+                    ArithOp::Add => lhs.checked_add(rhs),
+                    ArithOp::Sub => lhs.checked_sub(rhs),
+                    ArithOp::Mul => lhs.checked_mul(rhs),
+                    ArithOp::Div | ArithOp::Rem if rhs == 0 => {
+                        return Err(ConstEvalError::DivisionByZero)
+                    }
+                    ArithOp::Div => lhs.checked_div(rhs),
+                    ArithOp::Rem => lhs.checked_rem(rhs),
+                    ArithOp::Shl => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)),
+                    ArithOp::Shr => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs)),
+                    ArithOp::BitXor => Some(lhs ^ rhs),
+                    ArithOp::BitOr => Some(lhs | rhs),
+                    ArithOp::BitAnd => Some(lhs & rhs),
+                };
+                ConstValue::Int(value.ok_or(ConstEvalError::Overflow)?)
+            }
+            BinaryOp::CmpOp(op) => ConstValue::Bool(match op {
+                CmpOp::Eq { negated } => (lhs == rhs) != negated,
+                CmpOp::Ord { ordering: Ordering::Less, strict: true } => lhs < rhs,
+                CmpOp::Ord { ordering: Ordering::Less, strict: false } => lhs <= rhs,
+                CmpOp::Ord { ordering: Ordering::Greater, strict: true } => lhs > rhs,
+                CmpOp::Ord { ordering: Ordering::Greater, strict: false } => lhs >= rhs,
+            }),
+            BinaryOp::LogicOp(_) | BinaryOp::Assignment { .. } => {
+                return Err(ConstEvalError::NotSupported("assignment"))
+            }
+        };
+        Ok(value)
+    }
+
+    fn eval_bool(&self, expr: ExprId) -> Result<bool, ConstEvalError> {
+        match self.eval(expr)? {
+            ConstValue::Bool(it) => Ok(it),
+            ConstValue::Int(_) => Err(ConstEvalError::NotSupported("integer in boolean context")),
+        }
+    }
+
+    /// Evaluates `size_of::<T>()` for a primitive `T`.
+    fn eval_size_of(&self, callee: ExprId) -> Result<ConstValue, ConstEvalError> {
+        let path = match &self.body[callee] {
+            Expr::Path(path) => path,
+            _ => return Err(ConstEvalError::NotSupported("call of a non-path expression")),
+        };
+        let size_of = self
+            .resolver
+            .resolve_path_in_value_ns_fully(self.db.upcast(), &path![core::mem::size_of]);
+        let callee =
+            self.resolver.resolve_path_in_value_ns_fully(self.db.upcast(), path.mod_path());
+        match (callee, size_of) {
+            (Some(ValueNs::FunctionId(callee)), Some(ValueNs::FunctionId(size_of)))
+                if callee == size_of => {}
+            (None, _) => return Err(ConstEvalError::UnresolvedPath),
+            _ => return Err(ConstEvalError::NotSupported("function call")),
+        }
+
+        let type_ref = path
+            .segments()
+            .last()
+            .and_then(|it| it.args_and_bindings)
+            .and_then(|it| {
+                it.args.iter().find_map(|arg| match arg {
+                    GenericArg::Type(it) => Some(it),
+                    GenericArg::Lifetime(_) => None,
+                })
+            })
+            .ok_or(ConstEvalError::NotSupported("`size_of` without type argument"))?;
+        let size = match type_ref {
+            TypeRef::Tuple(fields) if fields.is_empty() => 0,
+            TypeRef::Path(path) => {
+                match self.resolver.resolve_path_in_type_ns_fully(self.db.upcast(), path.mod_path())
+                {
+                    Some(TypeNs::BuiltinType(BuiltinType::Bool)) => 1,
+                    Some(TypeNs::BuiltinType(BuiltinType::Char)) => 4,
+                    Some(TypeNs::BuiltinType(BuiltinType::Int(it))) => int_bits(it) / 8,
+                    Some(TypeNs::BuiltinType(BuiltinType::Float(it))) => match it.bitness {
+                        hir_def::builtin_type::FloatBitness::X32 => 4,
+                        hir_def::builtin_type::FloatBitness::X64 => 8,
+                    },
+                    _ => return Err(ConstEvalError::NotSupported("`size_of` of this type")),
+                }
+            }
+            _ => return Err(ConstEvalError::NotSupported("`size_of` of this type")),
+        };
+        Ok(ConstValue::Int(size as i128))
+    }
+}
+
+fn eval_path(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    path: &Path,
+) -> Result<ConstValue, ConstEvalError> {
+    match resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path()) {
+        Some(ValueNs::ConstId(konst)) => db.const_eval(konst),
+        Some(_) => Err(ConstEvalError::NotSupported("path to a non-constant value")),
+        None => Err(ConstEvalError::UnresolvedPath),
+    }
+}
+
+fn builtin_int(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    type_ref: &TypeRef,
+) -> Option<BuiltinInt> {
+    let path = match type_ref {
+        TypeRef::Path(path) => path,
+        _ => return None,
+    };
+    match resolver.resolve_path_in_type_ns_fully(db.upcast(), path.mod_path())? {
+        TypeNs::BuiltinType(BuiltinType::Int(it)) => Some(it),
+        _ => None,
+    }
+}
+
+fn int_bits(int_ty: BuiltinInt) -> u32 {
+    match int_ty.bitness {
+        // FIXME: this should depend on the target
+        IntBitness::Xsize => 64,
+        IntBitness::X8 => 8,
+        IntBitness::X16 => 16,
+        IntBitness::X32 => 32,
+        IntBitness::X64 => 64,
+        IntBitness::X128 => 128,
+    }
+}
+
+/// Truncates `value` to `int_ty`, like an `as` cast does.
+fn wrap_to(value: i128, int_ty: BuiltinInt) -> i128 {
+    let bits = int_bits(int_ty);
+    if bits == 128 {
+        return value;
+    }
+    let truncated = value & ((1 << bits) - 1);
+    match int_ty.signedness {
+        Signedness::Signed if truncated >= 1 << (bits - 1) => truncated - (1 << bits),
+        _ => truncated,
+    }
+}
+
+fn fits_in(value: i128, int_ty: BuiltinInt) -> bool {
+    let bits = int_bits(int_ty);
+    match int_ty.signedness {
+        Signedness::Signed if bits == 128 => true,
+        Signedness::Signed => -(1 << (bits - 1)) <= value && value < 1 << (bits - 1),
+        Signedness::Unsigned if bits == 128 => value >= 0,
+        Signedness::Unsigned => 0 <= value && value < 1 << bits,
+    }
+}
@@ -294,6 +294,57 @@ impl Diagnostic for MismatchedArgCount {
     }
 }
 
+// Diagnostic: type-mismatch
+//
+// This diagnostic is triggered if the type of an expression differs from the type expected by its context.
+//
+// Example:
+//
+// ```rust
+// fn foo(x: &u32) {}
+//
+// foo(92);
+// ```
+#[derive(Debug)]
+pub struct TypeMismatch {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub expected: String,
+    pub actual: String,
+    pub adjustment: Option<MismatchAdjustment>,
+}
+
+/// A single change to the mismatched expression which makes it type-check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MismatchAdjustment {
+    /// `expr` -> `&expr`
+    Ref,
+    /// `expr` -> `&mut expr`
+    RefMut,
+    /// `expr` -> `expr.into()`
+    Into,
+    /// `expr` -> `Some(expr)`
+    WrapInSome,
+}
+
+impl Diagnostic for TypeMismatch {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("type-mismatch")
+    }
+    fn message(&self) -> String {
+        format!("Expected {}, found {}", self.expected, self.actual)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
 #[derive(Debug)]
 pub enum CaseType {
     // `some_var`
@@ -591,6 +642,71 @@ fn baz(s: S) -> i32 {
         )
     }
 
+    #[test]
+    fn type_mismatch() {
+        check_diagnostics(
+            r#"
+fn takes_ref(x: &u32) {}
+fn returns_u32() -> u32 {
+    true
+  //^^^^ Expected u32, found bool
+}
+fn main() {
+    let x: u32 = 92;
+    takes_ref(x);
+            //^ Expected &u32, found u32
+    let y: bool = x;
+                //^ Expected bool, found u32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn type_mismatch_array_len() {
+        check_diagnostics(
+            r#"
+const N: usize = 2;
+fn main() {
+    let a: [u8; N] = [1, 2, 3];
+                   //^^^^^^^^^ Expected [u8; 2], found [u8; 3]
+    let b: [u8; N + 1] = [1, 2, 3];
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn type_mismatch_not_reported_for_unknown_types() {
+        check_diagnostics(
+            r#"
+fn main() {
+    let x: u32 = unresolved();
+    let y: Unresolved = 92u32;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn type_mismatch_not_reported_with_missing_ok() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:core
+use core::result::Result::{self, Ok, Err};
+
+fn foo() -> Result<(), i32> { 0 }
+                            //^ Expected Result<(), i32>, found i32
+fn bar(x: u32) -> Result<u32, ()> { x }
+                                  //^ wrap return expression in Ok
+//- /core/lib.rs crate:core
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+"#,
+        );
+    }
+
     #[test]
     fn break_outside_of_loop() {
         check_diagnostics(
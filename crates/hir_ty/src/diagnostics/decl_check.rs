@@ -16,7 +16,7 @@ use hir_def::{
     adt::VariantData,
     expr::{Pat, PatId},
     src::HasSource,
-    AdtId, ConstId, EnumId, FunctionId, Lookup, ModuleDefId, StaticId, StructId,
+    AdtId, AttrDefId, ConstId, EnumId, FunctionId, Lookup, ModuleDefId, StaticId, StructId,
 };
 use hir_expand::{
     diagnostics::DiagnosticSink,
@@ -32,6 +32,12 @@ use crate::{
     diagnostics::{decl_check::case_conv::*, CaseType, IncorrectCase},
 };
 
+mod allow {
+    pub(super) const NON_SNAKE_CASE: &str = "non_snake_case";
+    pub(super) const NON_UPPER_CASE_GLOBAL: &str = "non_upper_case_globals";
+    pub(super) const NON_CAMEL_CASE_TYPES: &str = "non_camel_case_types";
+}
+
 pub(super) struct DeclValidator<'a, 'b: 'a> {
     owner: ModuleDefId,
     sink: &'a mut DiagnosticSink<'b>,
@@ -62,6 +68,15 @@ impl<'a, 'b> DeclValidator<'a, 'b> {
         }
     }
 
+    /// Checks whether the item has an `#[allow(...)]` attribute for the lint `allow_name`.
+    fn allowed(&self, db: &dyn HirDatabase, id: AttrDefId, allow_name: &str) -> bool {
+        db.attrs(id).by_key("allow").tt_values().any(|tt| {
+            tt.to_string()
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .any(|it| it == allow_name)
+        })
+    }
+
     fn validate_adt(&mut self, db: &dyn HirDatabase, adt: AdtId) {
         match adt {
             AdtId::StructId(struct_id) => self.validate_struct(db, struct_id),
@@ -73,6 +88,9 @@ impl<'a, 'b> DeclValidator<'a, 'b> {
     }
 
     fn validate_func(&mut self, db: &dyn HirDatabase, func: FunctionId) {
+        if self.allowed(db, func.into(), allow::NON_SNAKE_CASE) {
+            return;
+        }
         let data = db.function_data(func);
         let body = db.body(func.into());
 
@@ -314,7 +332,11 @@ impl<'a, 'b> DeclValidator<'a, 'b> {
 
         // 1. Check the structure name.
         let struct_name = data.name.to_string();
-        let struct_name_replacement = if let Some(new_name) = to_camel_case(&struct_name) {
+        let non_camel_case_allowed =
+            self.allowed(db, struct_id.into(), allow::NON_CAMEL_CASE_TYPES);
+        let struct_name_replacement = if non_camel_case_allowed {
+            None
+        } else if let Some(new_name) = to_camel_case(&struct_name) {
             let replacement = Replacement {
                 current_name: data.name.clone(),
                 suggested_text: new_name,
@@ -328,7 +350,10 @@ impl<'a, 'b> DeclValidator<'a, 'b> {
         // 2. Check the field names.
         let mut struct_fields_replacements = Vec::new();
 
-        if let VariantData::Record(fields) = data.variant_data.as_ref() {
+        let non_snake_case_allowed = self.allowed(db, struct_id.into(), allow::NON_SNAKE_CASE);
+        if let (VariantData::Record(fields), false) =
+            (data.variant_data.as_ref(), non_snake_case_allowed)
+        {
             for (_, field) in fields.iter() {
                 let field_name = field.name.to_string();
                 if let Some(new_name) = to_lower_snake_case(&field_name) {
@@ -440,6 +465,9 @@ impl<'a, 'b> DeclValidator<'a, 'b> {
     }
 
     fn validate_enum(&mut self, db: &dyn HirDatabase, enum_id: EnumId) {
+        if self.allowed(db, enum_id.into(), allow::NON_CAMEL_CASE_TYPES) {
+            return;
+        }
         let data = db.enum_data(enum_id);
 
         // 1. Check the enum name.
@@ -570,6 +598,9 @@ impl<'a, 'b> DeclValidator<'a, 'b> {
     }
 
     fn validate_const(&mut self, db: &dyn HirDatabase, const_id: ConstId) {
+        if self.allowed(db, const_id.into(), allow::NON_UPPER_CASE_GLOBAL) {
+            return;
+        }
         let data = db.const_data(const_id);
 
         let name = match &data.name {
@@ -610,6 +641,9 @@ impl<'a, 'b> DeclValidator<'a, 'b> {
     }
 
     fn validate_static(&mut self, db: &dyn HirDatabase, static_id: StaticId) {
+        if self.allowed(db, static_id.into(), allow::NON_UPPER_CASE_GLOBAL) {
+            return;
+        }
         let data = db.static_data(static_id);
 
         let name = match &data.name {
@@ -851,6 +885,40 @@ fn main() {
         Some => (),
     }
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn allow_attributes() {
+        check_diagnostics(
+            r#"
+#[allow(non_snake_case)]
+fn NonSnakeCaseName(SOME_VAR: u8) -> u8 {
+    let OtherVar = SOME_VAR + 1;
+    OtherVar
+}
+
+#[allow(non_camel_case_types)]
+struct non_camel_case_name {}
+
+#[allow(non_upper_case_globals)]
+const lower_case: u8 = 0;
+"#,
+        );
+    }
+
+    #[test]
+    fn allow_attributes_in_cfg_attr() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:lib cfg:test
+#[cfg_attr(test, allow(non_snake_case))]
+fn NonSnakeCaseName() {}
+
+#[cfg_attr(not(test), allow(non_snake_case))]
+fn OtherNonSnakeCaseName() {}
+// ^^^^^^^^^^^^^^^^^^^^^ Function `OtherNonSnakeCaseName` should have snake_case name, e.g. `other_non_snake_case_name`
 "#,
         );
     }
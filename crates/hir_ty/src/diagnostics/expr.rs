@@ -2,19 +2,30 @@
 
 use std::sync::Arc;
 
-use hir_def::{path::path, resolver::HasResolver, AdtId, DefWithBodyId};
+use hir_def::{
+    path::path,
+    resolver::{HasResolver, Resolver},
+    type_ref::Mutability,
+    AdtId, DefWithBodyId,
+};
 use hir_expand::diagnostics::DiagnosticSink;
 use rustc_hash::FxHashSet;
 use syntax::{ast, AstPtr};
 
 use crate::{
+    autoderef,
     db::HirDatabase,
     diagnostics::{
         match_check::{is_useful, MatchCheckCtx, Matrix, PatStack, Usefulness},
-        MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkInTailExpr, MissingPatFields,
+        MismatchAdjustment, MismatchedArgCount, MissingFields, MissingMatchArms,
+        MissingOkInTailExpr, MissingPatFields, TypeMismatch,
     },
+    display::HirDisplay,
+    infer,
+    traits::{Solution, TraitEnvironment},
     utils::variant_data,
-    ApplicationTy, InferenceResult, Ty, TypeCtor,
+    ApplicationTy, Canonical, InEnvironment, InferenceResult, Obligation, Substs, TraitRef, Ty,
+    TypeCtor, TypeWalk,
 };
 
 pub(crate) use hir_def::{
@@ -76,9 +87,13 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             }
         }
         let body_expr = &body[body.body_expr];
+        let mut reported_tail = None;
         if let Expr::Block { tail: Some(t), .. } = body_expr {
-            self.validate_results_in_tail_expr(body.body_expr, *t, db);
+            if self.validate_results_in_tail_expr(body.body_expr, *t, db) {
+                reported_tail = Some(*t);
+            }
         }
+        self.validate_type_mismatches(db, &body, reported_tail);
     }
 
     fn create_record_literal_missing_fields_diagnostic(
@@ -285,11 +300,17 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         }
     }
 
-    fn validate_results_in_tail_expr(&mut self, body_id: ExprId, id: ExprId, db: &dyn HirDatabase) {
+    /// Returns `true` if the missing `Ok` was reported for the tail expression.
+    fn validate_results_in_tail_expr(
+        &mut self,
+        body_id: ExprId,
+        id: ExprId,
+        db: &dyn HirDatabase,
+    ) -> bool {
         // the mismatch will be on the whole block currently
         let mismatch = match self.infer.type_mismatch_for_expr(body_id) {
             Some(m) => m,
-            None => return,
+            None => return false,
         };
 
         let core_result_path = path![core::result::Result];
@@ -297,7 +318,7 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         let resolver = self.owner.resolver(db.upcast());
         let core_result_enum = match resolver.resolve_known_enum(db.upcast(), &core_result_path) {
             Some(it) => it,
-            _ => return,
+            _ => return false,
         };
 
         let core_result_ctor = TypeCtor::Adt(AdtId::EnumId(core_result_enum));
@@ -305,7 +326,7 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             Ty::Apply(ApplicationTy { ctor, parameters }) if ctor == &core_result_ctor => {
                 parameters
             }
-            _ => return,
+            _ => return false,
         };
 
         if params.len() == 2 && params[0] == mismatch.actual {
@@ -314,11 +335,115 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             if let Ok(source_ptr) = source_map.expr_syntax(id) {
                 self.sink
                     .push(MissingOkInTailExpr { file: source_ptr.file_id, expr: source_ptr.value });
+                return true;
+            }
+        }
+        false
+    }
+
+    fn validate_type_mismatches(
+        &mut self,
+        db: &dyn HirDatabase,
+        body: &Body,
+        reported_tail: Option<ExprId>,
+    ) {
+        let mut mismatches = self.infer.type_mismatches.iter().peekable();
+        if mismatches.peek().is_none() {
+            return;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.owner.into());
+        let resolver = self.owner.resolver(db.upcast());
+        let env = TraitEnvironment::lower(db, &resolver);
+
+        for (id, mismatch) in mismatches {
+            if Some(id) == reported_tail {
+                continue;
+            }
+            // The mismatch of a block is also recorded for its tail expression, report it there.
+            if let Expr::Block { tail: Some(_), .. } = &body[id] {
+                continue;
+            }
+            // Don't report anything if inference gave up on either side, the mismatch is most
+            // likely spurious then.
+            if !is_fully_known(&mismatch.expected) || !is_fully_known(&mismatch.actual) {
+                continue;
+            }
+
+            if let Ok(source_ptr) = source_map.expr_syntax(id) {
+                self.sink.push(TypeMismatch {
+                    file: source_ptr.file_id,
+                    expr: source_ptr.value,
+                    expected: mismatch.expected.display(db).to_string(),
+                    actual: mismatch.actual.display(db).to_string(),
+                    adjustment: mismatch_adjustment(db, &resolver, env.clone(), mismatch),
+                });
             }
         }
     }
 }
 
+fn is_fully_known(ty: &Ty) -> bool {
+    let mut known = true;
+    ty.walk(&mut |ty| {
+        if matches!(ty, Ty::Unknown | Ty::Infer(_) | Ty::Projection(_) | Ty::Opaque(_)) {
+            known = false;
+        }
+    });
+    known
+}
+
+/// Looks for a single adjustment of the actual type (borrowing, wrapping in `Some` or calling
+/// `.into()`) which turns it into the expected one.
+fn mismatch_adjustment(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    env: Arc<TraitEnvironment>,
+    mismatch: &infer::TypeMismatch,
+) -> Option<MismatchAdjustment> {
+    let krate = resolver.krate()?;
+    let infer::TypeMismatch { expected, actual } = mismatch;
+
+    if let Some((inner, mutability)) = expected.as_reference() {
+        let canonical_ty = Canonical { value: actual.clone(), kinds: Arc::new([]) };
+        let mut derefs = autoderef(db, Some(krate), InEnvironment::new(env.clone(), canonical_ty));
+        if derefs.any(|derefed| &derefed.value == inner) {
+            return Some(match mutability {
+                Mutability::Shared => MismatchAdjustment::Ref,
+                Mutability::Mut => MismatchAdjustment::RefMut,
+            });
+        }
+    }
+
+    if let Some((AdtId::EnumId(enum_id), substs)) = expected.as_adt() {
+        let core_option_path = path![core::option::Option];
+        if resolver.resolve_known_enum(db.upcast(), &core_option_path) == Some(enum_id)
+            && substs.len() == 1
+            && &substs[0] == actual
+        {
+            return Some(MismatchAdjustment::WrapInSome);
+        }
+    }
+
+    let core_into_path = path![core::convert::Into];
+    let into_trait = resolver.resolve_known_trait(db.upcast(), &core_into_path)?;
+    let trait_ref = TraitRef {
+        trait_: into_trait,
+        substs: Substs::build_for_def(db, into_trait)
+            .push(actual.clone())
+            .push(expected.clone())
+            .build(),
+    };
+    let goal = Canonical {
+        value: InEnvironment::new(env, Obligation::Trait(trait_ref)),
+        kinds: Arc::new([]),
+    };
+    match db.trait_solve(krate, goal) {
+        Some(Solution::Unique(_)) => Some(MismatchAdjustment::Into),
+        _ => None,
+    }
+}
+
 pub fn record_literal_missing_fields(
     db: &dyn HirDatabase,
     infer: &InferenceResult,
@@ -1077,6 +1077,7 @@ fn main() {
     match (true, false) {
         (true, false, true) => (),
         (true) => (),
+       //^^^^ Expected (bool, bool), found bool
     }
     match (0) { () => () }
     match Unresolved::Bar { Unresolved::Baz => () }
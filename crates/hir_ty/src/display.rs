@@ -4,7 +4,7 @@ use std::fmt;
 
 use crate::{
     db::HirDatabase, utils::generics, ApplicationTy, CallableDefId, FnSig, GenericPredicate,
-    Obligation, OpaqueTyId, ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
+    Lifetime, Obligation, OpaqueTyId, ProjectionTy, Substs, TraitRef, Ty, TypeCtor, TypeWalk,
 };
 use hir_def::{
     find_path, generics::TypeParamProvenance, item_scope::ItemInNs, AdtId, AssocContainerId,
@@ -151,6 +151,19 @@ impl<'a> HirFormatter<'a> {
     pub fn omit_verbose_types(&self) -> bool {
         self.omit_verbose_types
     }
+
+    /// Whether `lifetime` should be written out. Unknown lifetimes are
+    /// elided, and when generating code only `'static` is kept, as other
+    /// lifetimes might not be in scope at the insertion point.
+    fn should_write_lifetime(&self, lifetime: &Lifetime) -> bool {
+        match lifetime {
+            Lifetime::Unknown => false,
+            Lifetime::Static => true,
+            Lifetime::Parameter(_) | Lifetime::HigherRanked(_) => {
+                !self.display_target.is_source_code()
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -250,11 +263,14 @@ impl HirDisplay for ApplicationTy {
                 t.hir_fmt(f)?;
                 write!(f, "]")?;
             }
-            TypeCtor::Array => {
+            TypeCtor::Array { len } => {
                 let t = self.parameters.as_single();
                 write!(f, "[")?;
                 t.hir_fmt(f)?;
-                write!(f, "; _]")?;
+                match len {
+                    Some(len) => write!(f, "; {}]", len)?,
+                    None => write!(f, "; _]")?,
+                }
             }
             TypeCtor::RawPtr(m) => {
                 let t = self.parameters.as_single();
@@ -273,7 +289,14 @@ impl HirDisplay for ApplicationTy {
                 let ty_display =
                     t.into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target);
 
-                write!(f, "&{}", m.as_keyword_for_ref())?;
+                write!(f, "&")?;
+                if let Some(lifetime) = self.parameters.lifetimes().first() {
+                    if f.should_write_lifetime(lifetime) {
+                        lifetime.hir_fmt(f)?;
+                        write!(f, " ")?;
+                    }
+                }
+                write!(f, "{}", m.as_keyword_for_ref())?;
                 if matches!(t, Ty::Dyn(predicates) if predicates.len() > 1) {
                     write!(f, "(")?;
                     write!(f, "{}", ty_display)?;
@@ -381,7 +404,13 @@ impl HirDisplay for ApplicationTy {
                     }
                 }
 
-                if self.parameters.len() > 0 {
+                let lifetimes_to_write: Vec<_> = self
+                    .parameters
+                    .lifetimes()
+                    .iter()
+                    .filter(|lifetime| f.should_write_lifetime(lifetime))
+                    .collect();
+                if self.parameters.len() > 0 || !lifetimes_to_write.is_empty() {
                     let parameters_to_write =
                         if f.display_target.is_source_code() || f.omit_verbose_types() {
                             match self
@@ -414,8 +443,12 @@ impl HirDisplay for ApplicationTy {
                         } else {
                             self.parameters.0.as_ref()
                         };
-                    if !parameters_to_write.is_empty() {
+                    if !lifetimes_to_write.is_empty() || !parameters_to_write.is_empty() {
                         write!(f, "<")?;
+                        f.write_joined(lifetimes_to_write.iter().copied(), ", ")?;
+                        if !lifetimes_to_write.is_empty() && !parameters_to_write.is_empty() {
+                            write!(f, ", ")?;
+                        }
                         f.write_joined(parameters_to_write, ", ")?;
                         write!(f, ">")?;
                     }
@@ -606,6 +639,7 @@ fn write_bounds_like_dyn_trait(
                 if !first {
                     write!(f, " + ")?;
                 }
+                write_higher_ranked_binder(&trait_ref.substs, f)?;
                 // We assume that the self type is $0 (i.e. the
                 // existential) here, which is the only thing that's
                 // possible in actual Rust, and hence don't print it
@@ -630,6 +664,19 @@ fn write_bounds_like_dyn_trait(
                 write!(f, "{} = ", type_alias.name)?;
                 projection_pred.ty.hir_fmt(f)?;
             }
+            GenericPredicate::TypeOutlives(_, lifetime) => {
+                if !f.should_write_lifetime(lifetime) {
+                    continue;
+                }
+                if angle_open {
+                    write!(f, ">")?;
+                    angle_open = false;
+                }
+                if !first {
+                    write!(f, " + ")?;
+                }
+                lifetime.hir_fmt(f)?;
+            }
             GenericPredicate::Error => {
                 if angle_open {
                     // impl Trait<X, {error}>
@@ -649,6 +696,40 @@ fn write_bounds_like_dyn_trait(
     Ok(())
 }
 
+/// Writes the `for<'a, ..> ` binder for the higher-ranked lifetimes used in
+/// the given trait parameters, if there are any.
+fn write_higher_ranked_binder(
+    substs: &Substs,
+    f: &mut HirFormatter,
+) -> Result<(), HirDisplayError> {
+    if f.display_target.is_source_code() {
+        return Ok(());
+    }
+    let mut names: Vec<Name> = Vec::new();
+    substs.walk(&mut |ty| {
+        if let Ty::Apply(a_ty) = ty {
+            for lifetime in a_ty.parameters.lifetimes() {
+                if let Lifetime::HigherRanked(name) = lifetime {
+                    if !names.contains(name) {
+                        names.push(name.clone());
+                    }
+                }
+            }
+        }
+    });
+    if !names.is_empty() {
+        write!(f, "for<")?;
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", name)?;
+        }
+        write!(f, "> ")?;
+    }
+    Ok(())
+}
+
 impl TraitRef {
     fn hir_fmt_ext(&self, f: &mut HirFormatter, use_as: bool) -> Result<(), HirDisplayError> {
         if f.should_truncate() {
@@ -690,7 +771,10 @@ impl HirDisplay for GenericPredicate {
         }
 
         match self {
-            GenericPredicate::Implemented(trait_ref) => trait_ref.hir_fmt(f)?,
+            GenericPredicate::Implemented(trait_ref) => {
+                write_higher_ranked_binder(&trait_ref.substs, f)?;
+                trait_ref.hir_fmt(f)?
+            }
             GenericPredicate::Projection(projection_pred) => {
                 write!(f, "<")?;
                 projection_pred.projection_ty.trait_ref(f.db).hir_fmt_ext(f, true)?;
@@ -701,12 +785,37 @@ impl HirDisplay for GenericPredicate {
                 )?;
                 projection_pred.ty.hir_fmt(f)?;
             }
+            GenericPredicate::TypeOutlives(ty, lifetime) => {
+                ty.hir_fmt(f)?;
+                write!(f, ": ")?;
+                lifetime.hir_fmt(f)?;
+            }
             GenericPredicate::Error => write!(f, "{{error}}")?,
         }
         Ok(())
     }
 }
 
+impl HirDisplay for &Lifetime {
+    fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
+        HirDisplay::hir_fmt(*self, f)
+    }
+}
+
+impl HirDisplay for Lifetime {
+    fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
+        match self {
+            Lifetime::Static => write!(f, "'static"),
+            Lifetime::Parameter(id) => {
+                let generics = f.db.generic_params(id.parent);
+                write!(f, "{}", generics.lifetimes[id.local_id].name)
+            }
+            Lifetime::HigherRanked(name) => write!(f, "{}", name),
+            Lifetime::Unknown => write!(f, "'_"),
+        }
+    }
+}
+
 impl HirDisplay for Obligation {
     fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
         match self {
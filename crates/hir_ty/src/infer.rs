@@ -257,6 +257,12 @@ impl<'a> InferenceContext<'a> {
             let resolved = self.table.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
             *ty = resolved;
         }
+        for mismatch in result.type_mismatches.values_mut() {
+            mismatch.expected =
+                self.table.resolve_ty_completely(mem::replace(&mut mismatch.expected, Ty::Unknown));
+            mismatch.actual =
+                self.table.resolve_ty_completely(mem::replace(&mut mismatch.actual, Ty::Unknown));
+        }
         result
     }
 
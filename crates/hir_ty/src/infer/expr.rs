@@ -15,7 +15,7 @@ use syntax::ast::RangeOp;
 use test_utils::mark;
 
 use crate::{
-    autoderef, method_resolution, op,
+    autoderef, consteval, method_resolution, op,
     traits::{FnTrait, InEnvironment},
     utils::{generics, variant_data, Generics},
     ApplicationTy, Binders, CallableDefId, InferTy, IntTy, Mutability, Obligation, OpaqueTyId,
@@ -206,7 +206,7 @@ impl<'a> InferenceContext<'a> {
                 self.diverges = Diverges::Maybe;
                 Ty::unit()
             }
-            Expr::Lambda { body, args, ret_type, arg_types } => {
+            Expr::Lambda { body, args, ret_type, arg_types, .. } => {
                 assert_eq!(args.len(), arg_types.len());
 
                 let mut sig_tys = Vec::new();
@@ -229,7 +229,7 @@ impl<'a> InferenceContext<'a> {
                 sig_tys.push(ret_ty.clone());
                 let sig_ty = Ty::apply(
                     TypeCtor::FnPtr { num_args: sig_tys.len() as u16 - 1, is_varargs: false },
-                    Substs(sig_tys.clone().into()),
+                    Substs(sig_tys.clone().into(), None),
                 );
                 let closure_ty =
                     Ty::apply_one(TypeCtor::Closure { def: self.owner, expr: tgt_expr }, sig_ty);
@@ -630,21 +630,25 @@ impl<'a> InferenceContext<'a> {
                     self.infer_expr_coerce(*expr, &Expectation::has_type(ty.clone()));
                 }
 
-                Ty::apply(TypeCtor::Tuple { cardinality: tys.len() as u16 }, Substs(tys.into()))
+                Ty::apply(
+                    TypeCtor::Tuple { cardinality: tys.len() as u16 },
+                    Substs(tys.into(), None),
+                )
             }
             Expr::Array(array) => {
                 let elem_ty = match &expected.ty {
-                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => {
+                    ty_app!(TypeCtor::Array { .. }, st) | ty_app!(TypeCtor::Slice, st) => {
                         st.as_single().clone()
                     }
                     _ => self.table.new_type_var(),
                 };
 
-                match array {
+                let len = match array {
                     Array::ElementList(items) => {
                         for expr in items.iter() {
                             self.infer_expr_coerce(*expr, &Expectation::has_type(elem_ty.clone()));
                         }
+                        Some(items.len() as u64)
                     }
                     Array::Repeat { initializer, repeat } => {
                         self.infer_expr_coerce(
@@ -655,10 +659,16 @@ impl<'a> InferenceContext<'a> {
                             *repeat,
                             &Expectation::has_type(Ty::simple(TypeCtor::Int(IntTy::usize()))),
                         );
+                        consteval::eval_array_repeat_len(
+                            self.db,
+                            &self.resolver,
+                            &self.body,
+                            *repeat,
+                        )
                     }
-                }
+                };
 
-                Ty::apply_one(TypeCtor::Array, elem_ty)
+                Ty::apply_one(TypeCtor::Array { len }, elem_ty)
             }
             Expr::Literal(lit) => match lit {
                 Literal::Bool(..) => Ty::simple(TypeCtor::Bool),
@@ -667,7 +677,7 @@ impl<'a> InferenceContext<'a> {
                 }
                 Literal::ByteString(..) => {
                     let byte_type = Ty::simple(TypeCtor::Int(IntTy::u8()));
-                    let array_type = Ty::apply_one(TypeCtor::Array, byte_type);
+                    let array_type = Ty::apply_one(TypeCtor::Array { len: None }, byte_type);
                     Ty::apply_one(TypeCtor::Ref(Mutability::Shared), array_type)
                 }
                 Literal::Char(..) => Ty::simple(TypeCtor::Char),
@@ -842,13 +852,13 @@ impl<'a> InferenceContext<'a> {
         // handle provided type arguments
         if let Some(generic_args) = generic_args {
             // if args are provided, it should be all of them, but we can't rely on that
-            for arg in generic_args.args.iter().take(type_params) {
-                match arg {
-                    GenericArg::Type(type_ref) => {
-                        let ty = self.make_ty(type_ref);
-                        substs.push(ty);
-                    }
-                }
+            let type_args = generic_args.args.iter().filter_map(|arg| match arg {
+                GenericArg::Type(type_ref) => Some(type_ref),
+                GenericArg::Lifetime(_) => None,
+            });
+            for type_ref in type_args.take(type_params) {
+                let ty = self.make_ty(type_ref);
+                substs.push(ty);
             }
         };
         let supplied_params = substs.len();
@@ -856,7 +866,7 @@ impl<'a> InferenceContext<'a> {
             substs.push(Ty::Unknown);
         }
         assert_eq!(substs.len(), total_len);
-        Substs(substs.into())
+        Substs(substs.into(), None)
     }
 
     fn register_obligations_for_call(&mut self, callable_ty: &Ty) {
@@ -124,7 +124,10 @@ impl<'a> InferenceContext<'a> {
                     .map(|(&pat, ty)| self.infer_pat(pat, ty, default_bm))
                     .collect();
 
-                Ty::apply(TypeCtor::Tuple { cardinality: args.len() as u16 }, Substs(inner_tys))
+                Ty::apply(
+                    TypeCtor::Tuple { cardinality: args.len() as u16 },
+                    Substs(inner_tys, None),
+                )
             }
             Pat::Or(ref pats) => {
                 if let Some((first_pat, rest)) = pats.split_first() {
@@ -186,7 +189,7 @@ impl<'a> InferenceContext<'a> {
             }
             Pat::Slice { prefix, slice, suffix } => {
                 let (container_ty, elem_ty) = match &expected {
-                    ty_app!(TypeCtor::Array, st) => (TypeCtor::Array, st.as_single().clone()),
+                    ty_app!(ctor @ TypeCtor::Array { .. }, st) => (*ctor, st.as_single().clone()),
                     ty_app!(TypeCtor::Slice, st) => (TypeCtor::Slice, st.as_single().clone()),
                     _ => (TypeCtor::Slice, Ty::Unknown),
                 };
@@ -157,6 +157,7 @@ impl<T> Canonicalized<T> {
                     TyKind::Float => ctx.table.new_float_var(),
                 })
                 .collect(),
+            None,
         );
         for (i, ty) in solution.value.into_iter().enumerate() {
             let var = self.free_vars[i];
@@ -177,6 +178,7 @@ pub(crate) fn unify(tys: &Canonical<(Ty, Ty)>) -> Option<Substs> {
             // fallback to Unknown in the end (kind of hacky, as below)
             .map(|_| table.new_type_var())
             .collect(),
+        None,
     );
     let ty1_with_vars = tys.value.0.clone().subst_bound_vars(&vars);
     let ty2_with_vars = tys.value.1.clone().subst_bound_vars(&vars);
@@ -258,7 +260,7 @@ impl InferenceTable {
         let ty1 = self.resolve_ty_shallow(ty1);
         let ty2 = self.resolve_ty_shallow(ty2);
         match (&*ty1, &*ty2) {
-            (Ty::Apply(a_ty1), Ty::Apply(a_ty2)) if a_ty1.ctor == a_ty2.ctor => {
+            (Ty::Apply(a_ty1), Ty::Apply(a_ty2)) if ctors_unify(a_ty1.ctor, a_ty2.ctor) => {
                 self.unify_substs(&a_ty1.parameters, &a_ty2.parameters, depth + 1)
             }
 
@@ -481,3 +483,13 @@ impl UnifyValue for TypeVarValue {
         }
     }
 }
+
+/// Whether two type constructors are the same, treating an unknown array
+/// length as compatible with any length.
+pub(super) fn ctors_unify(ctor1: TypeCtor, ctor2: TypeCtor) -> bool {
+    match (ctor1, ctor2) {
+        (TypeCtor::Array { len: Some(len1) }, TypeCtor::Array { len: Some(len2) }) => len1 == len2,
+        (TypeCtor::Array { .. }, TypeCtor::Array { .. }) => true,
+        _ => ctor1 == ctor2,
+    }
+}
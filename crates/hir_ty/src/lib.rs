@@ -11,6 +11,8 @@ pub mod traits;
 pub mod method_resolution;
 mod op;
 mod lower;
+mod consteval;
+mod closure;
 pub(crate) mod infer;
 pub(crate) mod utils;
 
@@ -29,9 +31,10 @@ use base_db::{salsa, CrateId};
 use hir_def::{
     expr::ExprId,
     type_ref::{Mutability, Rawness},
-    AdtId, AssocContainerId, DefWithBodyId, GenericDefId, HasModule, Lookup, TraitId, TypeAliasId,
-    TypeParamId,
+    AdtId, AssocContainerId, DefWithBodyId, GenericDefId, HasModule, LifetimeParamId, Lookup,
+    TraitId, TypeAliasId, TypeParamId,
 };
+use hir_expand::name::Name;
 use itertools::Itertools;
 
 use crate::{
@@ -42,6 +45,8 @@ use crate::{
 };
 
 pub use autoderef::autoderef;
+pub use closure::{CaptureKind, CapturedPlace, ClosureCaptures, ClosureInfo};
+pub use consteval::{ConstEvalError, ConstValue};
 pub use infer::{InferTy, InferenceResult};
 pub use lower::CallableDefId;
 pub use lower::{
@@ -49,6 +54,7 @@ pub use lower::{
     TyLoweringContext, ValueTyDefId,
 };
 pub use traits::{InEnvironment, Obligation, ProjectionPredicate, TraitEnvironment};
+pub use utils::direct_super_traits;
 
 pub use chalk_ir::{BoundVar, DebruijnIndex};
 
@@ -79,8 +85,9 @@ pub enum TypeCtor {
     /// The pointee of an array slice.  Written as `[T]`.
     Slice,
 
-    /// An array with the given length. Written as `[T; n]`.
-    Array,
+    /// An array with the given length. Written as `[T; n]`. The length is
+    /// `None` if it couldn't be evaluated.
+    Array { len: Option<u64> },
 
     /// A raw pointer. Written as `*mut T` or `*const T`
     RawPtr(Mutability),
@@ -153,7 +160,7 @@ impl TypeCtor {
             | TypeCtor::Str
             | TypeCtor::Never => 0,
             TypeCtor::Slice
-            | TypeCtor::Array
+            | TypeCtor::Array { .. }
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::Closure { .. } // 1 param representing the signature of the closure
@@ -198,7 +205,7 @@ impl TypeCtor {
             | TypeCtor::Str
             | TypeCtor::Never
             | TypeCtor::Slice
-            | TypeCtor::Array
+            | TypeCtor::Array { .. }
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
@@ -231,7 +238,7 @@ impl TypeCtor {
             | TypeCtor::Str
             | TypeCtor::Never
             | TypeCtor::Slice
-            | TypeCtor::Array
+            | TypeCtor::Array { .. }
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
@@ -353,9 +360,27 @@ pub enum Ty {
     Unknown,
 }
 
+/// A lifetime. Lifetimes don't take part in type inference yet, they're only
+/// tracked so that types can be shown the way they were written.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum Lifetime {
+    Static,
+    /// A lifetime parameter of an item, like `'a` in `fn foo<'a>()`.
+    Parameter(LifetimeParamId),
+    /// A named lifetime that isn't a parameter of an enclosing item. These are
+    /// introduced by `for<'a>` binders.
+    HigherRanked(Name),
+    /// An elided or inferred lifetime.
+    Unknown,
+}
+
 /// A list of substitutions for generic parameters.
+///
+/// The lifetime arguments are kept separately from the types. They are only
+/// present if they were written out or are otherwise known, e.g. `&'a str`
+/// has one, while `&str` or an inferred reference has none.
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
-pub struct Substs(Arc<[Ty]>);
+pub struct Substs(Arc<[Ty]>, Option<Arc<[Lifetime]>>);
 
 impl TypeWalk for Substs {
     fn walk(&self, f: &mut impl FnMut(&Ty)) {
@@ -377,19 +402,33 @@ impl TypeWalk for Substs {
 
 impl Substs {
     pub fn empty() -> Substs {
-        Substs(Arc::new([]))
+        Substs(Arc::new([]), None)
     }
 
     pub fn single(ty: Ty) -> Substs {
-        Substs(Arc::new([ty]))
+        Substs(Arc::new([ty]), None)
     }
 
     pub fn prefix(&self, n: usize) -> Substs {
-        Substs(self.0[..std::cmp::min(self.0.len(), n)].into())
+        Substs(self.0[..std::cmp::min(self.0.len(), n)].into(), None)
     }
 
     pub fn suffix(&self, n: usize) -> Substs {
-        Substs(self.0[self.0.len() - std::cmp::min(self.0.len(), n)..].into())
+        Substs(self.0[self.0.len() - std::cmp::min(self.0.len(), n)..].into(), None)
+    }
+
+    pub fn with_lifetimes(self, lifetimes: Vec<Lifetime>) -> Substs {
+        let lifetimes = if lifetimes.iter().all(|it| *it == Lifetime::Unknown) {
+            None
+        } else {
+            Some(lifetimes.into())
+        };
+        Substs(self.0, lifetimes)
+    }
+
+    /// The lifetime arguments, if any are known.
+    pub fn lifetimes(&self) -> &[Lifetime] {
+        self.1.as_deref().unwrap_or(&[])
     }
 
     pub fn as_single(&self) -> &Ty {
@@ -401,7 +440,7 @@ impl Substs {
 
     /// Return Substs that replace each parameter by itself (i.e. `Ty::Param`).
     pub(crate) fn type_params_for_generics(generic_params: &Generics) -> Substs {
-        Substs(generic_params.iter().map(|(id, _)| Ty::Placeholder(id)).collect())
+        Substs(generic_params.iter().map(|(id, _)| Ty::Placeholder(id)).collect(), None)
     }
 
     /// Return Substs that replace each parameter by itself (i.e. `Ty::Param`).
@@ -418,6 +457,7 @@ impl Substs {
                 .enumerate()
                 .map(|(idx, _)| Ty::Bound(BoundVar::new(debruijn, idx)))
                 .collect(),
+            None,
         )
     }
 
@@ -455,7 +495,7 @@ pub struct SubstsBuilder {
 impl SubstsBuilder {
     pub fn build(self) -> Substs {
         assert_eq!(self.vec.len(), self.param_count);
-        Substs(self.vec.into())
+        Substs(self.vec.into(), None)
     }
 
     pub fn push(mut self, ty: Ty) -> Self {
@@ -592,6 +632,8 @@ pub enum GenericPredicate {
     Implemented(TraitRef),
     /// An associated type bindings like in `Iterator<Item = T>`.
     Projection(ProjectionPredicate),
+    /// The type must outlive the lifetime, like in `T: 'a`.
+    TypeOutlives(Ty, Lifetime),
     /// We couldn't resolve the trait reference. (If some type parameters can't
     /// be resolved, they will just be Unknown).
     Error,
@@ -610,7 +652,7 @@ impl GenericPredicate {
         match self {
             GenericPredicate::Implemented(tr) => Some(tr.clone()),
             GenericPredicate::Projection(proj) => Some(proj.projection_ty.trait_ref(db)),
-            GenericPredicate::Error => None,
+            GenericPredicate::TypeOutlives(..) | GenericPredicate::Error => None,
         }
     }
 }
@@ -620,6 +662,7 @@ impl TypeWalk for GenericPredicate {
         match self {
             GenericPredicate::Implemented(trait_ref) => trait_ref.walk(f),
             GenericPredicate::Projection(projection_pred) => projection_pred.walk(f),
+            GenericPredicate::TypeOutlives(ty, _) => ty.walk(f),
             GenericPredicate::Error => {}
         }
     }
@@ -634,6 +677,7 @@ impl TypeWalk for GenericPredicate {
             GenericPredicate::Projection(projection_pred) => {
                 projection_pred.walk_mut_binders(f, binders)
             }
+            GenericPredicate::TypeOutlives(ty, _) => ty.walk_mut_binders(f, binders),
             GenericPredicate::Error => {}
         }
     }
@@ -728,7 +772,7 @@ impl Ty {
     pub fn fn_ptr(sig: FnSig) -> Self {
         Ty::apply(
             TypeCtor::FnPtr { num_args: sig.params().len() as u16, is_varargs: sig.is_varargs },
-            Substs(sig.params_and_return),
+            Substs(sig.params_and_return, None),
         )
     }
 
@@ -15,26 +15,28 @@ use hir_def::{
     generics::{TypeParamProvenance, WherePredicate, WherePredicateTarget},
     path::{GenericArg, Path, PathSegment, PathSegments},
     resolver::{HasResolver, Resolver, TypeNs},
-    type_ref::{TypeBound, TypeRef},
+    type_ref::{LifetimeRef, TypeBound, TypeRef},
     AdtId, AssocContainerId, AssocItemId, ConstId, EnumId, EnumVariantId, FunctionId, GenericDefId,
     HasModule, ImplId, LocalFieldId, Lookup, StaticId, StructId, TraitId, TypeAliasId, TypeParamId,
     UnionId, VariantId,
 };
 use hir_expand::name::Name;
+use itertools::Either;
 use smallvec::SmallVec;
 use stdx::impl_from;
 use test_utils::mark;
 
 use crate::{
+    consteval,
     db::HirDatabase,
     primitive::{FloatTy, IntTy},
     utils::{
         all_super_trait_refs, associated_type_by_name_including_super_traits, generics,
         make_mut_slice, variant_data,
     },
-    Binders, BoundVar, DebruijnIndex, FnSig, GenericPredicate, OpaqueTy, OpaqueTyId, PolyFnSig,
-    ProjectionPredicate, ProjectionTy, ReturnTypeImplTrait, ReturnTypeImplTraits, Substs,
-    TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk,
+    Binders, BoundVar, DebruijnIndex, FnSig, GenericPredicate, Lifetime, OpaqueTy, OpaqueTyId,
+    PolyFnSig, ProjectionPredicate, ProjectionTy, ReturnTypeImplTrait, ReturnTypeImplTraits,
+    Substs, TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk,
 };
 
 #[derive(Debug)]
@@ -151,7 +153,7 @@ impl Ty {
                 let inner_tys: Arc<[Ty]> = inner.iter().map(|tr| Ty::from_hir(ctx, tr)).collect();
                 Ty::apply(
                     TypeCtor::Tuple { cardinality: inner_tys.len() as u16 },
-                    Substs(inner_tys),
+                    Substs(inner_tys, None),
                 )
             }
             TypeRef::Path(path) => {
@@ -163,21 +165,27 @@ impl Ty {
                 let inner_ty = Ty::from_hir(ctx, inner);
                 Ty::apply_one(TypeCtor::RawPtr(*mutability), inner_ty)
             }
-            TypeRef::Array(inner) => {
+            TypeRef::Array(inner, len) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
-                Ty::apply_one(TypeCtor::Array, inner_ty)
+                let len = consteval::eval_array_len(ctx.db, ctx.resolver, len);
+                Ty::apply_one(TypeCtor::Array { len }, inner_ty)
             }
             TypeRef::Slice(inner) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
                 Ty::apply_one(TypeCtor::Slice, inner_ty)
             }
-            TypeRef::Reference(inner, mutability) => {
+            TypeRef::Reference(inner, lifetime, mutability) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
-                Ty::apply_one(TypeCtor::Ref(*mutability), inner_ty)
+                let lifetime =
+                    lifetime.as_ref().map_or(Lifetime::Unknown, |it| Lifetime::from_hir(ctx, it));
+                Ty::apply(
+                    TypeCtor::Ref(*mutability),
+                    Substs::single(inner_ty).with_lifetimes(vec![lifetime]),
+                )
             }
             TypeRef::Placeholder => Ty::Unknown,
             TypeRef::Fn(params, is_varargs) => {
-                let sig = Substs(params.iter().map(|tr| Ty::from_hir(ctx, tr)).collect());
+                let sig = Substs(params.iter().map(|tr| Ty::from_hir(ctx, tr)).collect(), None);
                 Ty::apply(
                     TypeCtor::FnPtr { num_args: sig.len() as u16 - 1, is_varargs: *is_varargs },
                     sig,
@@ -500,7 +508,11 @@ impl Ty {
             TyDefId::TypeAliasId(it) => Some(it.into()),
         };
         let substs = substs_from_path_segment(ctx, segment, generic_def, infer_args);
-        ctx.db.ty(typable).subst(&substs)
+        match typable {
+            // built directly instead of going through `db.ty`, to keep the lifetime arguments
+            TyDefId::AdtId(it) => Ty::apply(TypeCtor::Adt(it), substs),
+            _ => ctx.db.ty(typable).subst(&substs),
+        }
     }
 
     /// Collect generic arguments from a path into a `Substs`. See also
@@ -556,6 +568,7 @@ fn substs_from_path_segment(
     substs.extend(iter::repeat(Ty::Unknown).take(parent_params));
 
     let mut had_explicit_args = false;
+    let mut lifetimes = Vec::new();
 
     if let Some(generic_args) = &segment.args_and_bindings {
         if !generic_args.has_self_type {
@@ -564,16 +577,20 @@ fn substs_from_path_segment(
         let expected_num =
             if generic_args.has_self_type { self_params + type_params } else { type_params };
         let skip = if generic_args.has_self_type && self_params == 0 { 1 } else { 0 };
+        let type_args = generic_args.args.iter().filter_map(|arg| match arg {
+            GenericArg::Type(type_ref) => Some(type_ref),
+            GenericArg::Lifetime(_) => None,
+        });
         // if args are provided, it should be all of them, but we can't rely on that
-        for arg in generic_args.args.iter().skip(skip).take(expected_num) {
-            match arg {
-                GenericArg::Type(type_ref) => {
-                    had_explicit_args = true;
-                    let ty = Ty::from_hir(ctx, type_ref);
-                    substs.push(ty);
-                }
-            }
+        for type_ref in type_args.skip(skip).take(expected_num) {
+            had_explicit_args = true;
+            let ty = Ty::from_hir(ctx, type_ref);
+            substs.push(ty);
         }
+        lifetimes.extend(generic_args.args.iter().filter_map(|arg| match arg {
+            GenericArg::Lifetime(lifetime) => Some(Lifetime::from_hir(ctx, lifetime)),
+            GenericArg::Type(_) => None,
+        }));
     }
 
     // handle defaults. In expression or pattern path segments without
@@ -586,7 +603,7 @@ fn substs_from_path_segment(
 
             for default_ty in defaults.iter().skip(substs.len()) {
                 // each default can depend on the previous parameters
-                let substs_so_far = Substs(substs.clone().into());
+                let substs_so_far = Substs(substs.clone().into(), None);
                 substs.push(default_ty.clone().subst(&substs_so_far));
             }
         }
@@ -599,7 +616,7 @@ fn substs_from_path_segment(
     }
     assert_eq!(substs.len(), total_len);
 
-    Substs(substs.into())
+    Substs(substs.into(), None).with_lifetimes(lifetimes)
 }
 
 impl TraitRef {
@@ -656,8 +673,10 @@ impl TraitRef {
         self_ty: Ty,
     ) -> Option<TraitRef> {
         match bound {
-            TypeBound::Path(path) => TraitRef::from_path(ctx, path, Some(self_ty)),
-            TypeBound::Error => None,
+            TypeBound::Path(path) | TypeBound::ForLifetime(_, path) => {
+                TraitRef::from_path(ctx, path, Some(self_ty))
+            }
+            TypeBound::Lifetime(_) | TypeBound::Error => None,
         }
     }
 }
@@ -690,13 +709,34 @@ impl GenericPredicate {
         bound: &'a TypeBound,
         self_ty: Ty,
     ) -> impl Iterator<Item = GenericPredicate> + 'a {
+        if let TypeBound::Lifetime(lifetime) = bound {
+            let lifetime = Lifetime::from_hir(ctx, lifetime);
+            return Either::Left(iter::once(GenericPredicate::TypeOutlives(self_ty, lifetime)));
+        }
         let trait_ref = TraitRef::from_type_bound(ctx, bound, self_ty);
-        iter::once(trait_ref.clone().map_or(GenericPredicate::Error, GenericPredicate::Implemented))
-            .chain(
-                trait_ref
-                    .into_iter()
-                    .flat_map(move |tr| assoc_type_bindings_from_type_bound(ctx, bound, tr)),
-            )
+        let preds = iter::once(
+            trait_ref.clone().map_or(GenericPredicate::Error, GenericPredicate::Implemented),
+        )
+        .chain(
+            trait_ref
+                .into_iter()
+                .flat_map(move |tr| assoc_type_bindings_from_type_bound(ctx, bound, tr)),
+        );
+        Either::Right(preds)
+    }
+}
+
+impl Lifetime {
+    pub(crate) fn from_hir(ctx: &TyLoweringContext<'_>, lifetime: &LifetimeRef) -> Lifetime {
+        if lifetime.is_static() {
+            Lifetime::Static
+        } else if lifetime.is_anonymous() {
+            Lifetime::Unknown
+        } else if let Some(param) = ctx.resolver.resolve_lifetime(&lifetime.name) {
+            Lifetime::Parameter(param)
+        } else {
+            Lifetime::HigherRanked(lifetime.name.clone())
+        }
     }
 }
 
@@ -705,10 +745,7 @@ fn assoc_type_bindings_from_type_bound<'a>(
     bound: &'a TypeBound,
     trait_ref: TraitRef,
 ) -> impl Iterator<Item = GenericPredicate> + 'a {
-    let last_segment = match bound {
-        TypeBound::Path(path) => path.segments().last(),
-        TypeBound::Error => None,
-    };
+    let last_segment = bound.as_path().and_then(|path| path.segments().last());
     last_segment
         .into_iter()
         .flat_map(|segment| segment.args_and_bindings.into_iter())
@@ -5,7 +5,7 @@
 use std::{iter, sync::Arc};
 
 use arrayvec::ArrayVec;
-use base_db::CrateId;
+use base_db::{CrateId, Edition};
 use hir_def::{
     builtin_type::{IntBitness, Signedness},
     lang_item::LangItemTarget,
@@ -37,6 +37,11 @@ impl TyFingerprint {
     /// `impl &S`. Hence, this will return `None` for reference types and such.
     pub(crate) fn for_impl(ty: &Ty) -> Option<TyFingerprint> {
         match ty {
+            // Impls can't be specialized on the array length yet, so arrays of
+            // any length share a fingerprint.
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Array { .. }, .. }) => {
+                Some(TyFingerprint::Apply(TypeCtor::Array { len: None }))
+            }
             Ty::Apply(a_ty) => Some(TyFingerprint::Apply(a_ty.ctor)),
             _ => None,
         }
@@ -549,8 +554,16 @@ fn iterate_trait_method_candidates(
     };
     let traits =
         inherent_trait.chain(env_traits.into_iter()).chain(traits_in_scope.iter().copied());
+    // before the 2021 edition, method calls on arrays skip `IntoIterator` so
+    // that `array.into_iter()` still autorefs, see `TraitData`
+    let skip_array_traits = receiver_ty.is_some()
+        && matches!(self_ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Array { .. }, .. }))
+        && db.crate_graph()[krate].edition != Edition::Edition2021;
     'traits: for t in traits {
         let data = db.trait_data(t);
+        if skip_array_traits && data.skip_array_during_method_dispatch {
+            continue;
+        }
 
         // we'll be lazy about checking whether the type implements the
         // trait, but if we find out it doesn't, we'll skip the rest of the
@@ -779,7 +792,7 @@ fn autoderef_method_receiver(
 ) -> Vec<Canonical<Ty>> {
     let mut deref_chain: Vec<_> = autoderef::autoderef(db, Some(krate), ty).collect();
     // As a last step, we can do array unsizing (that's the only unsizing that rustc does for method receivers!)
-    if let Some(Ty::Apply(ApplicationTy { ctor: TypeCtor::Array, parameters })) =
+    if let Some(Ty::Apply(ApplicationTy { ctor: TypeCtor::Array { .. }, parameters })) =
         deref_chain.last().map(|ty| &ty.value)
     {
         let kinds = deref_chain.last().unwrap().kinds.clone();
@@ -7,6 +7,7 @@ mod traits;
 mod method_resolution;
 mod macros;
 mod display_source_code;
+mod closure_captures;
 
 use std::{env, sync::Arc};
 
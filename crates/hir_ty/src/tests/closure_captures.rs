@@ -0,0 +1,315 @@
+use base_db::{fixture::WithFixture, FileRange, SourceDatabase};
+use hir_def::{child_by_source::ChildBySource, db::DefDatabase, expr::Pat, keys};
+use hir_expand::InFile;
+use stdx::format_to;
+use syntax::{
+    algo,
+    ast::{self, AstNode},
+};
+
+use crate::{db::HirDatabase, test_db::TestDB};
+
+/// Checks the kind and the captures of the closures marked with `//^`, in the
+/// form `FnMut; x ByMutRef, y ByRef`.
+fn check(ra_fixture: &str) {
+    let db = TestDB::with_files(ra_fixture);
+    let mut checked_one = false;
+    for (file_id, annotations) in db.extract_annotations() {
+        for (range, expected) in annotations {
+            let actual = closure_at_range(&db, FileRange { file_id, range });
+            assert_eq!(expected, actual);
+            checked_one = true;
+        }
+    }
+    assert!(checked_one, "no `//^` annotations found");
+}
+
+fn closure_at_range(db: &TestDB, pos: FileRange) -> String {
+    let file = db.parse(pos.file_id).ok().unwrap();
+    let closure = algo::find_node_at_range::<ast::ClosureExpr>(file.syntax(), pos.range).unwrap();
+    let fn_def = closure.syntax().ancestors().find_map(ast::Fn::cast).unwrap();
+    let module = db.module_for_file(pos.file_id);
+    let func = *module.child_by_source(db)[keys::FUNCTION]
+        .get(&InFile::new(pos.file_id.into(), fn_def))
+        .unwrap();
+
+    let (body, source_map) = db.body_with_source_map(func.into());
+    let expr = source_map
+        .node_expr(InFile::new(pos.file_id.into(), &ast::Expr::from(closure)))
+        .expect("can't find closure expression");
+    let captures = db.closure_captures(func.into());
+    let info = captures.closure(expr).unwrap();
+
+    let mut buf = format!("{:?}", info.kind);
+    for (i, capture) in info.captures.iter().enumerate() {
+        let name = match &body[capture.local] {
+            Pat::Bind { name, .. } => name,
+            _ => unreachable!(),
+        };
+        format_to!(buf, "{} {}", if i == 0 { ";" } else { "," }, name);
+        for projection in &capture.projections {
+            format_to!(buf, ".{}", projection);
+        }
+        format_to!(buf, " {:?}", capture.kind);
+    }
+    buf
+}
+
+#[test]
+fn no_captures() {
+    check(
+        r#"
+fn test() {
+    let f = |x: i32| x + 1;
+          //^^^^^^^^^^^^^^ Fn
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_by_ref() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+struct S;
+fn consume(s: &S) {}
+fn test() {
+    let s = S;
+    let f = || consume(&s);
+          //^^^^^^^^^^^^^^ Fn; s ByRef
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_copy_by_ref() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+impl Copy for i32 {}
+fn test() {
+    let a = 1;
+    let b = 2;
+    let f = || a + b;
+          //^^^^^^^^ Fn; a ByRef, b ByRef
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_by_mut_ref() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+fn test() {
+    let mut count = 0;
+    let mut f = || count += 1;
+              //^^^^^^^^^^^^^ FnMut; count ByMutRef
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_by_value() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+struct S;
+fn consume(s: S) {}
+fn test() {
+    let s = S;
+    let t = S;
+    let f = || { consume(s); &t; };
+          //^^^^^^^^^^^^^^^^^^^^^^ FnOnce; s ByValue, t ByRef
+}
+"#,
+    );
+}
+
+#[test]
+fn move_closure_captures_by_value() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+struct S;
+fn test() {
+    let s = S;
+    let f = move || &s;
+          //^^^^^^^^^^ Fn; s ByValue
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_field_captures_whole_variable() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+struct S { a: A, b: A }
+struct A;
+fn test() {
+    let mut s = S { a: A, b: A };
+    let f = || { s.a = A; &s.b; };
+          //^^^^^^^^^^^^^^^^^^^^^ FnMut; s ByMutRef
+}
+"#,
+    );
+}
+
+#[test]
+fn closure_locals_are_not_captured() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+struct S;
+fn consume(s: S) {}
+fn test() {
+    let f = |s: S| { let t = S; consume(s); consume(t); };
+          //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ Fn
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_by_method_receiver() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+struct V;
+impl V {
+    fn len(&self) -> usize { 0 }
+    fn push(&mut self) {}
+    fn into_inner(self) {}
+}
+fn test() {
+    let a = V;
+    let mut b = V;
+    let c = V;
+    let f = || { a.len(); b.push(); c.into_inner(); };
+          //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ FnOnce; a ByRef, b ByMutRef, c ByValue
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_through_mutable_reference() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+fn test(r: &mut i32) {
+    let f = || *r = 1;
+          //^^^^^^^^^ FnMut; r ByMutRef
+}
+"#,
+    );
+}
+
+#[test]
+fn nested_closure_captures() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+fn test() {
+    let mut x = 0;
+    let f = || {
+        let g = || x = 1;
+              //^^^^^^^^ FnMut; x ByMutRef
+    };
+}
+"#,
+    );
+}
+
+#[test]
+fn calling_captured_closure() {
+    check(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+fn test() {
+    let mut x = 0;
+    let mut inc = || x += 1;
+    let f = || inc();
+          //^^^^^^^^ FnMut; inc ByMutRef
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_disjoint_fields_in_2021() {
+    check(
+        r#"
+//- /main.rs crate:main edition:2021
+#[lang = "copy"]
+trait Copy {}
+struct S { a: A, b: A, c: C }
+struct C { d: A }
+struct A;
+fn consume(a: A) {}
+fn test() {
+    let mut s = S { a: A, b: A, c: C { d: A } };
+    let f = || { s.a = A; &s.b; consume(s.c.d); };
+          //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ FnOnce; s.a ByMutRef, s.b ByRef, s.c.d ByValue
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_ancestor_place_in_2021() {
+    check(
+        r#"
+//- /main.rs crate:main edition:2021
+#[lang = "copy"]
+trait Copy {}
+struct S { a: A, b: A }
+struct A;
+fn test() {
+    let mut s = S { a: A, b: A };
+    let f = || { s.a = A; &s; };
+          //^^^^^^^^^^^^^^^^^^^ FnMut; s ByMutRef
+}
+"#,
+    );
+}
+
+#[test]
+fn capture_stops_at_deref_in_2021() {
+    check(
+        r#"
+//- /main.rs crate:main edition:2021
+#[lang = "copy"]
+trait Copy {}
+struct S { a: A, r: &mut A }
+struct A { x: u32 }
+fn test(s: S) {
+    let f = || { (*s.r).x = 1; };
+          //^^^^^^^^^^^^^^^^^^^^ FnMut; s.r ByMutRef
+}
+"#,
+    );
+}
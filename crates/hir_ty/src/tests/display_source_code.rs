@@ -17,6 +17,19 @@ fn bar() {
     );
 }
 
+#[test]
+fn elide_non_static_lifetimes() {
+    check_types_source_code(
+        r#"
+struct S<'a, T>(&'a T);
+fn bar<'a>(x: &'a str, y: S<'a, u8>, z: &'static str) {
+    let t = (x, y, z);
+    t;
+} //^ (&str, S<u8>, &'static str)
+"#,
+    );
+}
+
 #[test]
 fn omit_default_type_parameters() {
     check_types_source_code(
@@ -1077,13 +1077,71 @@ fn method_resolution_foreign_opaque_type() {
             89..109 '{     ...     }': bool
             99..103 'true': bool
             123..167 '{     ...o(); }': ()
-            133..134 's': &S
-            137..151 'unsafe { f() }': &S
-            144..151 '{ f() }': &S
-            146..147 'f': fn f() -> &S
-            146..149 'f()': &S
-            157..158 's': &S
+            133..134 's': &'static S
+            137..151 'unsafe { f() }': &'static S
+            144..151 '{ f() }': &'static S
+            146..147 'f': fn f() -> &'static S
+            146..149 'f()': &'static S
+            157..158 's': &'static S
             157..164 's.foo()': bool
         "#]],
     );
 }
+
+#[test]
+fn array_into_iter_autorefs_before_2021() {
+    check_types(
+        r#"
+//- /main.rs crate:main deps:core edition:2018
+use core::IntoIterator;
+fn test() {
+    let a = [1u8, 2];
+    a.into_iter();
+} //^^^^^^^^^^^^^ SliceIter<u8>
+
+//- /core.rs crate:core
+#[rustc_skip_array_during_method_dispatch]
+pub trait IntoIterator {
+    type IntoIter;
+    fn into_iter(self) -> Self::IntoIter;
+}
+pub struct ArrayIter<T>;
+pub struct SliceIter<T>;
+impl<T, const N: usize> IntoIterator for [T; N] {
+    type IntoIter = ArrayIter<T>;
+}
+impl<'a, T, const N: usize> IntoIterator for &'a [T; N] {
+    type IntoIter = SliceIter<T>;
+}
+"#,
+    );
+}
+
+#[test]
+fn array_into_iter_by_value_in_2021() {
+    check_types(
+        r#"
+//- /main.rs crate:main deps:core edition:2021
+use core::IntoIterator;
+fn test() {
+    let a = [1u8, 2];
+    a.into_iter();
+} //^^^^^^^^^^^^^ ArrayIter<u8>
+
+//- /core.rs crate:core
+#[rustc_skip_array_during_method_dispatch]
+pub trait IntoIterator {
+    type IntoIter;
+    fn into_iter(self) -> Self::IntoIter;
+}
+pub struct ArrayIter<T>;
+pub struct SliceIter<T>;
+impl<T, const N: usize> IntoIterator for [T; N] {
+    type IntoIter = ArrayIter<T>;
+}
+impl<'a, T, const N: usize> IntoIterator for &'a [T; N] {
+    type IntoIter = SliceIter<T>;
+}
+"#,
+    );
+}
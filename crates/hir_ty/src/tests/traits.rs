@@ -542,7 +542,7 @@ fn indexing_arrays() {
         expect![[r#"
             10..26 '{ &mut...[2]; }': ()
             12..23 '&mut [9][2]': &mut {unknown}
-            17..20 '[9]': [i32; _]
+            17..20 '[9]': [i32; 1]
             17..23 '[9][2]': {unknown}
             18..19 '9': i32
             21..22 '2': i32
@@ -1409,10 +1409,10 @@ fn weird_bounds() {
         fn test(a: impl Trait + 'lifetime, b: impl 'lifetime, c: impl (Trait), d: impl ('lifetime), e: impl ?Sized, f: impl Trait + ?Sized) {}
         "#,
         expect![[r#"
-            23..24 'a': impl Trait + {error}
-            50..51 'b': impl {error}
+            23..24 'a': impl Trait + 'lifetime
+            50..51 'b': impl 'lifetime
             69..70 'c': impl Trait
-            86..87 'd': impl {error}
+            86..87 'd': impl 'lifetime
             107..108 'e': impl {error}
             123..124 'f': impl Trait + {error}
             147..149 '{}': ()
@@ -1420,6 +1420,33 @@ fn weird_bounds() {
     );
 }
 
+#[test]
+fn lifetimes_in_types() {
+    check_infer(
+        r#"
+        trait Trait<T> {}
+        struct S<'a, T>(&'a T);
+        fn test<'a>(
+            a: &'a str,
+            b: &'static mut u32,
+            c: S<'a, u8>,
+            d: impl Trait<u8> + 'static,
+            e: &'a (dyn Trait<u8> + 'a),
+            f: &dyn for<'b> Trait<&'b str>,
+        ) {}
+        "#,
+        expect![[r#"
+            59..60 'a': &'a str
+            75..76 'b': &'static mut u32
+            100..101 'c': S<'a, u8>
+            118..119 'd': impl Trait<u8> + 'static
+            151..152 'e': &'a (dyn Trait<u8> + 'a)
+            184..185 'f': &dyn for<'b> Trait<&'b str>
+            218..220 '{}': ()
+        "#]],
+    );
+}
+
 #[test]
 #[ignore]
 fn error_bound_chalk() {
@@ -1950,6 +1977,39 @@ fn closure_2() {
     );
 }
 
+#[test]
+fn closure_kind_from_captures() {
+    check_types(
+        r#"
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+
+trait IsFn { fn is_fn(&self) -> bool; }
+impl<F: Fn()> IsFn for F {}
+trait IsFnMut { fn is_fn_mut(&self) -> bool; }
+impl<F: FnMut()> IsFnMut for F {}
+
+fn test() {
+    let mut x = 0;
+    let read = || { x; };
+    let write = || { x = 1; };
+    read.is_fn();
+  //^^^^^^^^^^^^ bool
+    write.is_fn();
+  //^^^^^^^^^^^^^ {unknown}
+    write.is_fn_mut();
+  //^^^^^^^^^^^^^^^^^ bool
+}
+"#,
+    );
+}
+
 #[test]
 fn closure_as_argument_inference_order() {
     check_infer(
@@ -92,7 +92,8 @@ impl Obligation {
             GenericPredicate::Projection(projection_pred) => {
                 Some(Obligation::Projection(projection_pred))
             }
-            GenericPredicate::Error => None,
+            // lifetimes aren't inferred, so there's nothing to prove here
+            GenericPredicate::TypeOutlives(..) | GenericPredicate::Error => None,
         }
     }
 }
@@ -18,6 +18,7 @@ use crate::{
     db::HirDatabase,
     display::HirDisplay,
     method_resolution::{TyFingerprint, ALL_FLOAT_FPS, ALL_INT_FPS},
+    traits::FnTrait,
     utils::generics,
     BoundVar, CallableDefId, DebruijnIndex, FnSig, GenericPredicate, ProjectionPredicate,
     ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
@@ -268,11 +269,15 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
 
     fn closure_kind(
         &self,
-        _closure_id: chalk_ir::ClosureId<Interner>,
+        closure_id: chalk_ir::ClosureId<Interner>,
         _substs: &chalk_ir::Substitution<Interner>,
     ) -> rust_ir::ClosureKind {
-        // Fn is the closure kind that implements all three traits
-        rust_ir::ClosureKind::Fn
+        let (def, expr) = self.db.lookup_intern_closure(closure_id.into());
+        match crate::closure::closure_kind_from_body(self.db, def, expr) {
+            FnTrait::Fn => rust_ir::ClosureKind::Fn,
+            FnTrait::FnMut => rust_ir::ClosureKind::FnMut,
+            FnTrait::FnOnce => rust_ir::ClosureKind::FnOnce,
+        }
     }
     fn closure_inputs_and_output(
         &self,
@@ -30,7 +30,8 @@ impl chalk_ir::interner::Interner for Interner {
     type InternedType = Arc<chalk_ir::TyData<Self>>;
     type InternedLifetime = chalk_ir::LifetimeData<Self>;
     type InternedConst = Arc<chalk_ir::ConstData<Self>>;
-    type InternedConcreteConst = ();
+    /// The length of an array, `None` if it's not known.
+    type InternedConcreteConst = Option<u64>;
     type InternedGenericArg = chalk_ir::GenericArgData<Self>;
     type InternedGoal = Arc<GoalData<Self>>;
     type InternedGoals = Vec<Goal<Self>>;
@@ -224,8 +225,17 @@ impl chalk_ir::interner::Interner for Interner {
         constant
     }
 
-    fn const_eq(&self, _ty: &Arc<chalk_ir::TyData<Self>>, _c1: &(), _c2: &()) -> bool {
-        true
+    fn const_eq(
+        &self,
+        _ty: &Arc<chalk_ir::TyData<Self>>,
+        c1: &Option<u64>,
+        c2: &Option<u64>,
+    ) -> bool {
+        // An unknown length could be anything, so it's compatible with every other length.
+        match (c1, c2) {
+            (Some(c1), Some(c2)) => c1 == c2,
+            _ => true,
+        }
     }
 
     fn intern_generic_arg(
@@ -16,7 +16,7 @@ use crate::{
     db::HirDatabase,
     primitive::{FloatBitness, FloatTy, IntBitness, IntTy, Signedness},
     traits::{Canonical, Obligation},
-    ApplicationTy, CallableDefId, GenericPredicate, InEnvironment, OpaqueTy, OpaqueTyId,
+    ApplicationTy, CallableDefId, GenericPredicate, InEnvironment, Lifetime, OpaqueTy, OpaqueTyId,
     ProjectionPredicate, ProjectionTy, Substs, TraitEnvironment, TraitRef, Ty, TyKind, TypeCtor,
 };
 
@@ -29,7 +29,7 @@ impl ToChalk for Ty {
         match self {
             Ty::Apply(apply_ty) => match apply_ty.ctor {
                 TypeCtor::Ref(m) => ref_to_chalk(db, m, apply_ty.parameters),
-                TypeCtor::Array => array_to_chalk(db, apply_ty.parameters),
+                TypeCtor::Array { len } => array_to_chalk(db, len, apply_ty.parameters),
                 TypeCtor::FnPtr { num_args: _, is_varargs } => {
                     let substitution = apply_ty.parameters.to_chalk(db).shifted_in(&Interner);
                     chalk_ir::TyKind::Function(chalk_ir::FnPointer {
@@ -128,13 +128,25 @@ impl ToChalk for Ty {
             Ty::Bound(idx) => chalk_ir::TyKind::BoundVar(idx).intern(&Interner),
             Ty::Infer(_infer_ty) => panic!("uncanonicalized infer ty"),
             Ty::Dyn(predicates) => {
+                let mut lifetime = Lifetime::Unknown;
                 let where_clauses = chalk_ir::QuantifiedWhereClauses::from_iter(
                     &Interner,
-                    predicates.iter().filter(|p| !p.is_error()).cloned().map(|p| p.to_chalk(db)),
+                    predicates
+                        .iter()
+                        .filter(|p| match p {
+                            // Chalk keeps the `+ 'a` bound of a trait object separately
+                            GenericPredicate::TypeOutlives(_, it) => {
+                                lifetime = it.clone();
+                                false
+                            }
+                            p => !p.is_error(),
+                        })
+                        .cloned()
+                        .map(|p| p.to_chalk(db)),
                 );
                 let bounded_ty = chalk_ir::DynTy {
                     bounds: make_binders(where_clauses, 1),
-                    lifetime: LifetimeData::Static.intern(&Interner),
+                    lifetime: lifetime.to_chalk(db),
                 };
                 chalk_ir::TyKind::Dyn(bounded_ty).intern(&Interner)
             }
@@ -153,8 +165,12 @@ impl ToChalk for Ty {
     fn from_chalk(db: &dyn HirDatabase, chalk: chalk_ir::Ty<Interner>) -> Self {
         match chalk.data(&Interner).kind.clone() {
             chalk_ir::TyKind::Error => Ty::Unknown,
-            chalk_ir::TyKind::Array(ty, _size) => {
-                Ty::apply(TypeCtor::Array, Substs::single(from_chalk(db, ty)))
+            chalk_ir::TyKind::Array(ty, size) => {
+                let len = match &size.data(&Interner).value {
+                    chalk_ir::ConstValue::Concrete(c) => c.interned,
+                    _ => None,
+                };
+                Ty::apply(TypeCtor::Array { len }, Substs::single(from_chalk(db, ty)))
             }
             chalk_ir::TyKind::Placeholder(idx) => {
                 assert_eq!(idx.ui, UniverseIndex::ROOT);
@@ -197,13 +213,18 @@ impl ToChalk for Ty {
             chalk_ir::TyKind::InferenceVar(_iv, _kind) => Ty::Unknown,
             chalk_ir::TyKind::Dyn(where_clauses) => {
                 assert_eq!(where_clauses.bounds.binders.len(&Interner), 1);
-                let predicates = where_clauses
+                let mut predicates: Vec<_> = where_clauses
                     .bounds
                     .skip_binders()
                     .iter(&Interner)
                     .map(|c| from_chalk(db, c.clone()))
                     .collect();
-                Ty::Dyn(predicates)
+                let lifetime = from_chalk(db, where_clauses.lifetime);
+                if lifetime != Lifetime::Unknown {
+                    let self_ty = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0));
+                    predicates.push(GenericPredicate::TypeOutlives(self_ty, lifetime));
+                }
+                Ty::Dyn(predicates.into())
             }
 
             chalk_ir::TyKind::Adt(struct_id, subst) => {
@@ -241,8 +262,10 @@ impl ToChalk for Ty {
                 Ty::apply_one(TypeCtor::RawPtr(from_chalk(db, mutability)), from_chalk(db, ty))
             }
             chalk_ir::TyKind::Slice(ty) => Ty::apply_one(TypeCtor::Slice, from_chalk(db, ty)),
-            chalk_ir::TyKind::Ref(mutability, _lifetime, ty) => {
-                Ty::apply_one(TypeCtor::Ref(from_chalk(db, mutability)), from_chalk(db, ty))
+            chalk_ir::TyKind::Ref(mutability, lifetime, ty) => {
+                let parameters = Substs::single(from_chalk(db, ty))
+                    .with_lifetimes(vec![from_chalk(db, lifetime)]);
+                Ty::apply(TypeCtor::Ref(from_chalk(db, mutability)), parameters)
             }
             chalk_ir::TyKind::Str => Ty::simple(TypeCtor::Str),
             chalk_ir::TyKind::Never => Ty::simple(TypeCtor::Never),
@@ -282,20 +305,61 @@ fn ref_to_chalk(
     mutability: Mutability,
     subst: Substs,
 ) -> chalk_ir::Ty<Interner> {
+    let lifetime = subst.lifetimes().first().cloned().unwrap_or(Lifetime::Unknown).to_chalk(db);
     let arg = subst[0].clone().to_chalk(db);
-    let lifetime = LifetimeData::Static.intern(&Interner);
     chalk_ir::TyKind::Ref(mutability.to_chalk(db), lifetime, arg).intern(&Interner)
 }
 
+/// Lifetimes that aren't item parameters or `'static` are passed to Chalk as
+/// this placeholder. Lifetimes don't affect whether a goal can be proven, so
+/// it's fine to erase them.
+const UNKNOWN_LIFETIME: PlaceholderIndex =
+    PlaceholderIndex { ui: UniverseIndex::ROOT, idx: usize::MAX };
+
+impl ToChalk for Lifetime {
+    type Chalk = chalk_ir::Lifetime<Interner>;
+
+    fn to_chalk(self, db: &dyn HirDatabase) -> chalk_ir::Lifetime<Interner> {
+        match self {
+            Lifetime::Static => LifetimeData::Static.intern(&Interner),
+            Lifetime::Parameter(id) => {
+                let interned_id = db.intern_lifetime_param_id(id);
+                LifetimeData::Placeholder(PlaceholderIndex {
+                    ui: UniverseIndex::ROOT,
+                    idx: interned_id.as_intern_id().as_usize(),
+                })
+                .intern(&Interner)
+            }
+            Lifetime::HigherRanked(_) | Lifetime::Unknown => {
+                LifetimeData::Placeholder(UNKNOWN_LIFETIME).intern(&Interner)
+            }
+        }
+    }
+
+    fn from_chalk(db: &dyn HirDatabase, lifetime: chalk_ir::Lifetime<Interner>) -> Lifetime {
+        match lifetime.data(&Interner) {
+            LifetimeData::Static => Lifetime::Static,
+            LifetimeData::Placeholder(idx) if *idx != UNKNOWN_LIFETIME => {
+                assert_eq!(idx.ui, UniverseIndex::ROOT);
+                let interned_id = crate::db::GlobalLifetimeParamId::from_intern_id(
+                    crate::salsa::InternId::from(idx.idx),
+                );
+                Lifetime::Parameter(db.lookup_intern_lifetime_param_id(interned_id))
+            }
+            _ => Lifetime::Unknown,
+        }
+    }
+}
+
 /// We currently don't model constants, but Chalk does. So, we have to insert a
 /// fake constant here, because Chalks built-in logic may expect it to be there.
-fn array_to_chalk(db: &dyn HirDatabase, subst: Substs) -> chalk_ir::Ty<Interner> {
+fn array_to_chalk(db: &dyn HirDatabase, len: Option<u64>, subst: Substs) -> chalk_ir::Ty<Interner> {
     let arg = subst[0].clone().to_chalk(db);
     let usize_ty =
         chalk_ir::TyKind::Scalar(Scalar::Uint(chalk_ir::UintTy::Usize)).intern(&Interner);
     let const_ = chalk_ir::ConstData {
         ty: usize_ty,
-        value: chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst { interned: () }),
+        value: chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst { interned: len }),
     }
     .intern(&Interner);
     chalk_ir::TyKind::Array(arg, const_).intern(&Interner)
@@ -316,7 +380,7 @@ impl ToChalk for Substs {
                 None => unimplemented!(),
             })
             .collect();
-        Substs(tys)
+        Substs(tys, None)
     }
 }
 
@@ -525,6 +589,14 @@ impl ToChalk for GenericPredicate {
                 let alias = chalk_ir::AliasTy::Projection(projection);
                 make_binders(chalk_ir::WhereClause::AliasEq(chalk_ir::AliasEq { alias, ty }), 0)
             }
+            GenericPredicate::TypeOutlives(ty, lifetime) => {
+                let ty = ty.to_chalk(db).shifted_in(&Interner);
+                let lifetime = lifetime.to_chalk(db).shifted_in(&Interner);
+                make_binders(
+                    chalk_ir::WhereClause::TypeOutlives(chalk_ir::TypeOutlives { ty, lifetime }),
+                    0,
+                )
+            }
             GenericPredicate::Error => panic!("tried passing GenericPredicate::Error to Chalk"),
         }
     }
@@ -559,10 +631,10 @@ impl ToChalk for GenericPredicate {
                 panic!("encountered LifetimeOutlives from Chalk")
             }
 
-            chalk_ir::WhereClause::TypeOutlives(_) => {
-                // we shouldn't get these from Chalk
-                panic!("encountered TypeOutlives from Chalk")
-            }
+            chalk_ir::WhereClause::TypeOutlives(type_outlives) => GenericPredicate::TypeOutlives(
+                from_chalk(db, type_outlives.ty),
+                from_chalk(db, type_outlives.lifetime),
+            ),
         }
     }
 }
@@ -792,6 +864,6 @@ pub(super) fn generic_predicate_to_inline_bound(
             };
             Some(rust_ir::InlineBound::AliasEqBound(alias_eq_bound))
         }
-        GenericPredicate::Error => None,
+        GenericPredicate::TypeOutlives(..) | GenericPredicate::Error => None,
     }
 }
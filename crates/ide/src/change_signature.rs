@@ -0,0 +1,727 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+use hir::{
+    AsAssocItem, AssocItem, AssocItemContainer, Crate, Function, HasSource, HirFileId, ImplDef,
+    ModuleDef, PathResolution, Semantics, Trait,
+};
+use ide_db::{base_db::FileId, defs::Definition, RootDatabase};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    algo::{find_node_at_offset, find_node_at_range},
+    ast::{self, edit::IndentLevel, ArgListOwner, NameOwner},
+    AstNode, SyntaxKind, SyntaxNode, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{FilePosition, SourceChange, SourceFileEdit};
+
+/// A parameter of the changed signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureParam {
+    /// The parameter at this index in the current signature, not counting `self`.
+    Existing(usize),
+    /// A new parameter, along with the argument existing callers should pass to it.
+    New { pattern: String, ty: String, default_value: String },
+}
+
+#[derive(Debug)]
+pub struct ChangeSignatureError(pub(crate) String);
+
+impl fmt::Display for ChangeSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for ChangeSignatureError {}
+
+macro_rules! format_err {
+    ($fmt:expr) => {ChangeSignatureError(format!($fmt))};
+    ($fmt:expr, $($arg:tt)+) => {ChangeSignatureError(format!($fmt, $($arg)+))}
+}
+
+macro_rules! bail {
+    ($($tokens:tt)*) => {return Err(format_err!($($tokens)*))}
+}
+
+// Feature: Change Signature
+//
+// Adds, removes and reorders the parameters of a function. Call sites, including method calls,
+// are updated: new parameters get the given default argument, and a call with removed arguments
+// that have side effects becomes a block evaluating them in their original order. For trait
+// methods, the trait declaration and every impl are changed together.
+//
+// Exposed through the `experimental/changeSignature` request.
+pub(crate) fn change_signature(
+    db: &RootDatabase,
+    position: FilePosition,
+    new_params: &[SignatureParam],
+) -> Result<SourceChange, ChangeSignatureError> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+    let fn_ast = find_node_at_offset::<ast::Fn>(source_file.syntax(), position.offset)
+        .ok_or_else(|| format_err!("No function at the cursor"))?;
+    let function = sema.to_def(&fn_ast).ok_or_else(|| format_err!("Unresolved function"))?;
+    let param_count = fn_ast.param_list().map_or(0, |it| it.params().count());
+    for (idx, param) in new_params.iter().enumerate() {
+        if let SignatureParam::Existing(old) = param {
+            if *old >= param_count {
+                bail!("The function has no parameter at index {}", old);
+            }
+            if new_params[..idx].contains(param) {
+                bail!("The parameter at index {} is used twice", old);
+            }
+        }
+    }
+    let removed = (0..param_count)
+        .filter(|it| !new_params.contains(&SignatureParam::Existing(*it)))
+        .collect::<Vec<_>>();
+    let param_names = fn_ast
+        .param_list()
+        .into_iter()
+        .flat_map(|it| it.params())
+        .map(|param| match param.pat() {
+            Some(ast::Pat::IdentPat(it)) => it.name().map_or("arg".into(), |it| it.to_string()),
+            _ => "arg".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut edits = WorkspaceEdits::default();
+    let functions = related_functions(&sema, function);
+    for function in functions.iter() {
+        change_definition(&sema, &mut edits, *function, new_params, &removed)?;
+    }
+
+    let has_self = function.self_param(db).is_some();
+    let mut calls = Vec::new();
+    for function in functions.iter() {
+        for reference in Definition::ModuleDef(ModuleDef::Function(*function)).usages(&sema).all() {
+            let file_id = reference.file_range.file_id;
+            let source_file = sema.parse(file_id);
+            let name_ref = match find_node_at_range::<ast::NameRef>(
+                source_file.syntax(),
+                reference.file_range.range,
+            ) {
+                Some(it) => it,
+                None => continue,
+            };
+            if let Some(call) = Call::from_name_ref(&name_ref, has_self)? {
+                if !calls.iter().any(|(id, it): &(FileId, Call)| {
+                    *id == file_id && it.node.text_range() == call.node.text_range()
+                }) {
+                    calls.push((file_id, call));
+                }
+            }
+        }
+    }
+    // Innermost calls first, outer calls take over their edited text.
+    calls.sort_by_key(|(_, call)| call.node.text_range().len());
+    for (file_id, call) in calls {
+        change_call(&mut edits, file_id, &call, new_params, &removed, &param_names)?;
+    }
+
+    Ok(edits.into_source_change())
+}
+
+/// The function along with its trait declaration and all implementations, if it is a trait
+/// method.
+fn related_functions(sema: &Semantics<RootDatabase>, function: Function) -> Vec<Function> {
+    let db = sema.db;
+    let trait_ = match function.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Trait(it)) => Some(it),
+        Some(AssocItemContainer::ImplDef(impl_def)) => implemented_trait(sema, impl_def),
+        None => None,
+    };
+    let trait_ = match trait_ {
+        Some(it) => it,
+        None => return vec![function],
+    };
+    let name = function.name(db);
+    let same_name = |item: AssocItem| match item {
+        AssocItem::Function(it) if it.name(db) == name => Some(it),
+        _ => None,
+    };
+    let mut res = trait_.items(db).into_iter().filter_map(same_name).collect::<Vec<_>>();
+    for krate in Crate::all(db) {
+        for impl_def in ImplDef::for_trait(db, krate, trait_) {
+            res.extend(impl_def.items(db).into_iter().filter_map(same_name));
+        }
+    }
+    res
+}
+
+fn implemented_trait(sema: &Semantics<RootDatabase>, impl_def: ImplDef) -> Option<Trait> {
+    let src = impl_def.source(sema.db);
+    let source_file = sema.parse(src.file_id.original_file(sema.db));
+    let impl_ast =
+        find_node_at_range::<ast::Impl>(source_file.syntax(), src.value.syntax().text_range())?;
+    let path = match impl_ast.trait_()? {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    match sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::Trait(it)) => Some(it),
+        _ => None,
+    }
+}
+
+fn change_definition(
+    sema: &Semantics<RootDatabase>,
+    edits: &mut WorkspaceEdits,
+    function: Function,
+    new_params: &[SignatureParam],
+    removed: &[usize],
+) -> Result<(), ChangeSignatureError> {
+    let db = sema.db;
+    let src = function.source(db);
+    let file_id = src.file_id.original_file(db);
+    if src.file_id != HirFileId::from(file_id) {
+        bail!("`{}` is defined by a macro", function.name(db));
+    }
+    let source_file = sema.parse(file_id);
+    let fn_ast =
+        find_node_at_range::<ast::Fn>(source_file.syntax(), src.value.syntax().text_range())
+            .ok_or_else(|| format_err!("Missing source of `{}`", function.name(db)))?;
+    let param_list = fn_ast
+        .param_list()
+        .ok_or_else(|| format_err!("`{}` has no parameters", function.name(db)))?;
+    let params = param_list.params().collect::<Vec<_>>();
+    if params.len() != removed.len() + new_params.iter().filter(|it| is_existing(it)).count() {
+        bail!(
+            "The implementations of `{}` have a different number of parameters",
+            function.name(db)
+        );
+    }
+
+    for idx in removed {
+        let is_used = params[*idx]
+            .pat()
+            .into_iter()
+            .flat_map(|it| {
+                it.syntax().descendants().filter_map(ast::IdentPat::cast).collect::<Vec<_>>()
+            })
+            .filter_map(|it| sema.to_def(&it))
+            .any(|it| Definition::Local(it).usages(sema).at_least_one());
+        if is_used {
+            bail!("The parameter `{}` of `{}` is used", params[*idx], function.name(db));
+        }
+    }
+
+    let mut items =
+        param_list.self_param().map(|it| it.to_string()).into_iter().collect::<Vec<_>>();
+    items.extend(new_params.iter().map(|param| match param {
+        SignatureParam::Existing(idx) => params[*idx].to_string(),
+        SignatureParam::New { pattern, ty, .. } => format!("{}: {}", pattern, ty),
+    }));
+    let text = format_list(param_list.syntax(), &items);
+    edits.file(file_id).replace(param_list.syntax().text_range(), text);
+    Ok(())
+}
+
+struct Call {
+    node: SyntaxNode,
+    arg_list: ast::ArgList,
+    /// The number of leading arguments which are not parameters, the receiver of a
+    /// `Type::method(receiver, ..)` call.
+    skip: usize,
+}
+
+impl Call {
+    fn from_name_ref(
+        name_ref: &ast::NameRef,
+        has_self: bool,
+    ) -> Result<Option<Call>, ChangeSignatureError> {
+        let parent = match name_ref.syntax().parent() {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+            return Ok(call.arg_list().map(|arg_list| Call {
+                node: call.syntax().clone(),
+                arg_list,
+                skip: 0,
+            }));
+        }
+        let path = match name_ref.syntax().ancestors().find_map(ast::Path::cast) {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let path_expr = match path.syntax().parent().and_then(ast::PathExpr::cast) {
+            Some(it) => it,
+            // Imports and other paths which are not expressions stay the same.
+            None => return Ok(None),
+        };
+        let call = path_expr
+            .syntax()
+            .parent()
+            .and_then(ast::CallExpr::cast)
+            .filter(|call| call.expr().map_or(false, |it| it.syntax() == path_expr.syntax()));
+        match call.and_then(|call| Some((call.arg_list()?, call))) {
+            Some((arg_list, call)) => {
+                Ok(Some(Call { node: call.syntax().clone(), arg_list, skip: has_self as usize }))
+            }
+            None => bail!("`{}` is used as a value, its callers can't be updated", path_expr),
+        }
+    }
+}
+
+fn change_call(
+    edits: &mut WorkspaceEdits,
+    file_id: FileId,
+    call: &Call,
+    new_params: &[SignatureParam],
+    removed: &[usize],
+    param_names: &[String],
+) -> Result<(), ChangeSignatureError> {
+    let edits = edits.file(file_id);
+    let args = call.arg_list.args().collect::<Vec<_>>();
+    if args.len()
+        != call.skip + removed.len() + new_params.iter().filter(|it| is_existing(it)).count()
+    {
+        // The call doesn't type check anyway, leave it alone.
+        return Ok(());
+    }
+    let mut arg_texts = args.iter().map(|it| edits.text_of(it.syntax())).collect::<Vec<_>>();
+
+    // Removed arguments with side effects are evaluated in a block replacing the call, along
+    // with the arguments before them which have side effects, so that the order is kept.
+    let last_effect =
+        removed.iter().map(|idx| call.skip + idx).filter(|&idx| has_side_effects(&args[idx])).max();
+    let mut stmts = Vec::new();
+    if let Some(last_effect) = last_effect {
+        let receiver = ast::MethodCallExpr::cast(call.node.clone()).and_then(|it| it.receiver());
+        if receiver.map_or(false, |it| has_side_effects(&it)) {
+            bail!("Can't evaluate the removed arguments of `{}` after its receiver", call.node);
+        }
+        let mut taken = call
+            .node
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.kind() == SyntaxKind::IDENT)
+            .map(|it| it.text().to_string())
+            .collect::<FxHashSet<_>>();
+        for idx in 0..=last_effect {
+            if !has_side_effects(&args[idx]) {
+                continue;
+            }
+            if idx >= call.skip && removed.contains(&(idx - call.skip)) {
+                stmts.push(format!("let _ = {};", arg_texts[idx]));
+            } else {
+                let name = match idx.checked_sub(call.skip) {
+                    Some(param) => fresh_name(&mut taken, &param_names[param]),
+                    None => fresh_name(&mut taken, "receiver"),
+                };
+                stmts.push(format!("let {} = {};", name, arg_texts[idx]));
+                arg_texts[idx] = name;
+            }
+        }
+    }
+
+    let mut items = arg_texts[..call.skip].to_vec();
+    items.extend(new_params.iter().map(|param| match param {
+        SignatureParam::Existing(idx) => arg_texts[call.skip + idx].clone(),
+        SignatureParam::New { default_value, .. } => default_value.clone(),
+    }));
+    let text = format_list(call.arg_list.syntax(), &items);
+    if stmts.is_empty() {
+        edits.replace(call.arg_list.syntax().text_range(), text);
+    } else {
+        // The argument list ends the call, and the edits inside of it were taken already.
+        let call_text = edits.text_of(&call.node);
+        let callee =
+            &call_text[..call_text.len() - usize::from(call.arg_list.syntax().text_range().len())];
+        let text = format!("{{ {} {}{} }}", stmts.join(" "), callee, text);
+        edits.replace(call.node.text_range(), text);
+    }
+    Ok(())
+}
+
+/// Returns `name` if it is not taken yet, `name` with a numeric suffix otherwise.
+fn fresh_name(taken: &mut FxHashSet<String>, name: &str) -> String {
+    let new_name = std::iter::once(name.to_string())
+        .chain((1..).map(|it| format!("{}{}", name, it)))
+        .find(|it| !taken.contains(it))
+        .unwrap();
+    taken.insert(new_name.clone());
+    new_name
+}
+
+fn is_existing(param: &SignatureParam) -> bool {
+    matches!(param, SignatureParam::Existing(_))
+}
+
+fn has_side_effects(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Literal(_) | ast::Expr::PathExpr(_) | ast::Expr::ClosureExpr(_) => false,
+        ast::Expr::RefExpr(it) => it.expr().map_or(false, |it| has_side_effects(&it)),
+        ast::Expr::ParenExpr(it) => it.expr().map_or(false, |it| has_side_effects(&it)),
+        ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| has_side_effects(&it)),
+        ast::Expr::PrefixExpr(it) => it.expr().map_or(false, |it| has_side_effects(&it)),
+        ast::Expr::BinExpr(it) => {
+            it.op_kind().map_or(true, |it| it.is_assignment())
+                || it.lhs().map_or(false, |it| has_side_effects(&it))
+                || it.rhs().map_or(false, |it| has_side_effects(&it))
+        }
+        ast::Expr::TupleExpr(it) => it.fields().any(|it| has_side_effects(&it)),
+        ast::Expr::ArrayExpr(it) => it.exprs().any(|it| has_side_effects(&it)),
+        _ => true,
+    }
+}
+
+/// Formats `items` as a parenthesized list, one item per line if `list` was laid out that way.
+fn format_list(list: &SyntaxNode, items: &[String]) -> String {
+    if !list.text().contains_char('\n') || items.is_empty() {
+        return format!("({})", items.join(", "));
+    }
+    let indent = IndentLevel::from_node(list);
+    let mut res = "(\n".to_string();
+    for item in items {
+        res.push_str(&format!("{}{},\n", indent + 1, item));
+    }
+    res.push_str(&format!("{})", indent));
+    res
+}
+
+#[derive(Default)]
+struct WorkspaceEdits {
+    files: FxHashMap<FileId, FileEdits>,
+}
+
+impl WorkspaceEdits {
+    fn file(&mut self, file_id: FileId) -> &mut FileEdits {
+        self.files.entry(file_id).or_default()
+    }
+
+    fn into_source_change(self) -> SourceChange {
+        let edits = self
+            .files
+            .into_iter()
+            .map(|(file_id, mut edits)| {
+                edits.edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+                let mut builder = TextEdit::builder();
+                for (range, text) in edits.edits {
+                    builder.replace(range, text);
+                }
+                SourceFileEdit { file_id, edit: builder.finish() }
+            })
+            .collect::<Vec<_>>();
+        SourceChange::from(edits)
+    }
+}
+
+#[derive(Default)]
+struct FileEdits {
+    edits: Vec<(TextRange, String)>,
+}
+
+impl FileEdits {
+    fn replace(&mut self, range: TextRange, text: String) {
+        self.edits.push((range, text));
+    }
+
+    /// The text of `node` with the edits inside of it applied, those edits are dropped.
+    fn text_of(&mut self, node: &SyntaxNode) -> String {
+        let range = node.text_range();
+        let is_inside = |it: TextRange| {
+            range.contains_range(it)
+                && !(it.is_empty() && (it.start() == range.start() || it.end() == range.end()))
+        };
+        let mut nested = Vec::new();
+        self.edits.retain(|(it, text)| {
+            if is_inside(*it) {
+                nested.push((*it, text.clone()));
+                false
+            } else {
+                true
+            }
+        });
+        nested.sort_by_key(|(it, _)| (it.start(), it.end()));
+        let mut text = node.to_string();
+        for (it, replacement) in nested.into_iter().rev() {
+            let it = it - range.start();
+            text.replace_range(usize::from(it.start())..usize::from(it.end()), &replacement);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::fixture;
+
+    use super::SignatureParam::{self, Existing};
+
+    fn new(pattern: &str, ty: &str, default_value: &str) -> SignatureParam {
+        SignatureParam::New {
+            pattern: pattern.to_string(),
+            ty: ty.to_string(),
+            default_value: default_value.to_string(),
+        }
+    }
+
+    fn check(new_params: &[SignatureParam], ra_fixture_before: &str, ra_fixture_after: &str) {
+        let ra_fixture_after = &trim_indent(ra_fixture_after);
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let source_change = analysis.change_signature(position, new_params).unwrap();
+        match source_change {
+            Ok(source_change) => {
+                assert_eq!(source_change.source_file_edits.len(), 1);
+                let edit = source_change.source_file_edits.into_iter().next().unwrap();
+                let mut result = analysis.file_text(edit.file_id).unwrap().to_string();
+                edit.edit.apply(&mut result);
+                assert_eq_text!(ra_fixture_after, &*result);
+            }
+            Err(err) => {
+                let expected = ra_fixture_after
+                    .strip_prefix("error:")
+                    .unwrap_or_else(|| panic!("Change signature failed unexpectedly: {}", err));
+                assert_eq!(expected.trim(), err.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn reorder_and_add_params() {
+        check(
+            &[Existing(1), new("flag", "bool", "false"), Existing(0)],
+            r#"
+fn foo<|>(a: i32, b: &str) {}
+
+fn main() {
+    foo(1, "x");
+    foo(foo(2, "y"), "z");
+}
+"#,
+            r#"
+fn foo(b: &str, flag: bool, a: i32) {}
+
+fn main() {
+    foo("x", false, 1);
+    foo("z", false, foo("y", false, 2));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_params_keeping_side_effects() {
+        check(
+            &[Existing(1)],
+            r#"
+fn bar() -> i32 { 0 }
+fn foo<|>(a: i32, b: i32) -> i32 { b }
+
+fn main() {
+    let x = 1;
+    foo(x + 1, 2);
+    let y = foo(bar(), x);
+}
+"#,
+            r#"
+fn bar() -> i32 { 0 }
+fn foo(b: i32) -> i32 { b }
+
+fn main() {
+    let x = 1;
+    foo(2);
+    let y = { let _ = bar(); foo(x) };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_params_keeping_evaluation_order() {
+        check(
+            &[Existing(0), Existing(2)],
+            r#"
+fn bar() -> i32 { 0 }
+fn foo<|>(a: i32, b: i32, c: i32) -> i32 { a + c }
+
+fn main() {
+    let a = 1;
+    foo(bar(), bar(), bar());
+    foo(a, bar(), 2);
+}
+"#,
+            r#"
+fn bar() -> i32 { 0 }
+fn foo(a: i32, c: i32) -> i32 { a + c }
+
+fn main() {
+    let a = 1;
+    { let a = bar(); let _ = bar(); foo(a, bar()) };
+    { let _ = bar(); foo(a, 2) };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_params_keeping_side_effects_in_conditions() {
+        check(
+            &[],
+            r#"
+fn bar() -> i32 { 0 }
+fn foo<|>(_a: i32) -> bool { true }
+
+fn main() {
+    let x = true && foo(bar());
+    while foo(bar()) {}
+}
+"#,
+            r#"
+fn bar() -> i32 { 0 }
+fn foo() -> bool { true }
+
+fn main() {
+    let x = true && { let _ = bar(); foo() };
+    while { let _ = bar(); foo() } {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_params_keeping_side_effects_in_closures() {
+        check(
+            &[],
+            r#"
+fn bar() -> i32 { 0 }
+fn foo<|>(_a: i32) {}
+
+fn main() {
+    let f = || foo(bar());
+}
+"#,
+            r#"
+fn bar() -> i32 { 0 }
+fn foo() {}
+
+fn main() {
+    let f = || { let _ = bar(); foo() };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn update_methods_and_trait_impls() {
+        check(
+            &[Existing(1), Existing(0)],
+            r#"
+trait Tr {
+    fn f(&self, a: i32, b: u8);
+}
+struct S;
+impl Tr for S {
+    fn f<|>(&self, x: i32, y: u8) {}
+}
+
+fn main() {
+    S.f(1, 2);
+    S::f(&S, 3, 4);
+    Tr::f(&S, 5, 6);
+}
+"#,
+            r#"
+trait Tr {
+    fn f(&self, b: u8, a: i32);
+}
+struct S;
+impl Tr for S {
+    fn f(&self, y: u8, x: i32) {}
+}
+
+fn main() {
+    S.f(2, 1);
+    S::f(&S, 4, 3);
+    Tr::f(&S, 6, 5);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_multiline_layout() {
+        check(
+            &[Existing(0), new("c", "u32", "0")],
+            r#"
+fn foo<|>(
+    a: i32,
+    b: i32,
+) {}
+
+fn main() {
+    foo(
+        1,
+        2,
+    );
+}
+"#,
+            r#"
+fn foo(
+    a: i32,
+    c: u32,
+) {}
+
+fn main() {
+    foo(
+        1,
+        0,
+    );
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn error_on_used_param() {
+        check(
+            &[],
+            r#"
+fn foo<|>(a: i32) -> i32 { a }
+"#,
+            "error: The parameter `a: i32` of `foo` is used",
+        );
+    }
+
+    #[test]
+    fn error_on_receiver_with_side_effects() {
+        check(
+            &[],
+            r#"
+struct S;
+impl S {
+    fn f<|>(&self, _a: i32) {}
+}
+fn make() -> S { S }
+fn bar() -> i32 { 0 }
+fn main() {
+    make().f(bar());
+}
+"#,
+            "error: Can't evaluate the removed arguments of `make().f(bar())` after its receiver",
+        );
+    }
+
+    #[test]
+    fn error_on_use_as_value() {
+        check(
+            &[],
+            r#"
+fn foo<|>(_a: i32) {}
+fn main() {
+    let f = foo;
+}
+"#,
+            "error: `foo` is used as a value, its callers can't be updated",
+        );
+    }
+}
@@ -10,7 +10,7 @@ mod field_shorthand;
 use std::cell::RefCell;
 
 use hir::{
-    diagnostics::{Diagnostic as _, DiagnosticSinkBuilder},
+    diagnostics::{Diagnostic as _, DiagnosticCode, DiagnosticSinkBuilder},
     Semantics,
 };
 use ide_db::base_db::SourceDatabase;
@@ -35,15 +35,23 @@ pub struct Diagnostic {
     pub severity: Severity,
     pub fix: Option<Fix>,
     pub unused: bool,
+    pub code: Option<DiagnosticCode>,
 }
 
 impl Diagnostic {
     fn error(range: TextRange, message: String) -> Self {
-        Self { message, range, severity: Severity::Error, fix: None, unused: false }
+        Self { message, range, severity: Severity::Error, fix: None, unused: false, code: None }
     }
 
     fn hint(range: TextRange, message: String) -> Self {
-        Self { message, range, severity: Severity::WeakWarning, fix: None, unused: false }
+        Self {
+            message,
+            range,
+            severity: Severity::WeakWarning,
+            fix: None,
+            unused: false,
+            code: None,
+        }
     }
 
     fn with_fix(self, fix: Option<Fix>) -> Self {
@@ -53,6 +61,10 @@ impl Diagnostic {
     fn with_unused(self, unused: bool) -> Self {
         Self { unused, ..self }
     }
+
+    fn with_code(self, code: Option<DiagnosticCode>) -> Self {
+        Self { code, ..self }
+    }
 }
 
 #[derive(Debug)]
@@ -119,6 +131,9 @@ pub(crate) fn diagnostics(
         .on::<hir::diagnostics::NoSuchField, _>(|d| {
             res.borrow_mut().push(diagnostic_with_fix(d, &sema));
         })
+        .on::<hir::diagnostics::TypeMismatch, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+        })
         .on::<hir::diagnostics::IncorrectCase, _>(|d| {
             res.borrow_mut().push(warning_with_fix(d, &sema));
         })
@@ -126,7 +141,8 @@ pub(crate) fn diagnostics(
             // Override severity and mark as unused.
             res.borrow_mut().push(
                 Diagnostic::hint(sema.diagnostics_display_range(d).range, d.message())
-                    .with_unused(true),
+                    .with_unused(true)
+                    .with_code(Some(d.code())),
             );
         })
         // Only collect experimental diagnostics when they're enabled.
@@ -137,8 +153,10 @@ pub(crate) fn diagnostics(
     let mut sink = sink_builder
         // Diagnostics not handled above get no fix and default treatment.
         .build(|d| {
-            res.borrow_mut()
-                .push(Diagnostic::error(sema.diagnostics_display_range(d).range, d.message()));
+            res.borrow_mut().push(
+                Diagnostic::error(sema.diagnostics_display_range(d).range, d.message())
+                    .with_code(Some(d.code())),
+            );
         });
 
     if let Some(m) = sema.to_module_def(file_id) {
@@ -149,11 +167,15 @@ pub(crate) fn diagnostics(
 }
 
 fn diagnostic_with_fix<D: DiagnosticWithFix>(d: &D, sema: &Semantics<RootDatabase>) -> Diagnostic {
-    Diagnostic::error(sema.diagnostics_display_range(d).range, d.message()).with_fix(d.fix(&sema))
+    Diagnostic::error(sema.diagnostics_display_range(d).range, d.message())
+        .with_fix(d.fix(&sema))
+        .with_code(Some(d.code()))
 }
 
 fn warning_with_fix<D: DiagnosticWithFix>(d: &D, sema: &Semantics<RootDatabase>) -> Diagnostic {
-    Diagnostic::hint(sema.diagnostics_display_range(d).range, d.message()).with_fix(d.fix(&sema))
+    Diagnostic::hint(sema.diagnostics_display_range(d).range, d.message())
+        .with_fix(d.fix(&sema))
+        .with_code(Some(d.code()))
 }
 
 fn check_unnecessary_braces_in_use_statement(
@@ -305,6 +327,22 @@ mod tests {
         assert_eq!(diagnostics.len(), 0, "unexpected diagnostics:\n{:#?}", diagnostics);
     }
 
+    /// Takes a multi-file input fixture and checks that none of the diagnostics has a fix.
+    fn check_no_fix(ra_fixture: &str) {
+        let (analysis, files) = fixture::files(ra_fixture);
+        let diagnostics = files
+            .into_iter()
+            .flat_map(|file_id| {
+                analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            diagnostics.iter().all(|it| it.fix.is_none()),
+            "unexpected fix:\n{:#?}",
+            diagnostics
+        );
+    }
+
     fn check_expect(ra_fixture: &str, expect: Expect) {
         let (analysis, file_id) = fixture::file(ra_fixture);
         let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
@@ -410,7 +448,7 @@ fn div(x: i32, y: i32) -> MyResult<i32> {
 
     #[test]
     fn test_wrap_return_type_not_applicable_when_expr_type_does_not_match_ok_type() {
-        check_no_diagnostics(
+        check_no_fix(
             r#"
 //- /main.rs crate:main deps:core
 use core::result::Result::{self, Ok, Err};
@@ -427,7 +465,7 @@ pub mod result {
 
     #[test]
     fn test_wrap_return_type_not_applicable_when_return_type_is_not_result() {
-        check_no_diagnostics(
+        check_no_fix(
             r#"
 //- /main.rs crate:main deps:core
 use core::result::Result::{self, Ok, Err};
@@ -444,6 +482,142 @@ pub mod result {
         );
     }
 
+    #[test]
+    fn test_type_mismatch_add_reference() {
+        check_fix(
+            r#"
+fn takes_ref(x: &u32) {}
+fn main() {
+    let x: u32 = 92;
+    takes_ref(<|>x);
+}
+"#,
+            r#"
+fn takes_ref(x: &u32) {}
+fn main() {
+    let x: u32 = 92;
+    takes_ref(&x);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_add_mutable_reference_through_deref() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:core
+struct Wrapper(u32);
+impl core::ops::Deref for Wrapper {
+    type Target = u32;
+}
+fn takes_mut(x: &mut u32) {}
+fn main() {
+    let mut w = Wrapper(92);
+    takes_mut(<|>w);
+}
+//- /core/lib.rs crate:core
+pub mod ops {
+    #[lang = "deref"]
+    pub trait Deref {
+        type Target;
+    }
+}
+"#,
+            r#"
+struct Wrapper(u32);
+impl core::ops::Deref for Wrapper {
+    type Target = u32;
+}
+fn takes_mut(x: &mut u32) {}
+fn main() {
+    let mut w = Wrapper(92);
+    takes_mut(&mut w);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_wrap_in_some() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:core
+use core::option::Option::{self, Some, None};
+
+fn takes_opt(x: Option<u32>) {}
+fn main() {
+    let x: u32 = 92;
+    takes_opt(<|>x + 1);
+}
+//- /core/lib.rs crate:core
+pub mod option {
+    pub enum Option<T> { None, Some(T) }
+}
+"#,
+            r#"
+use core::option::Option::{self, Some, None};
+
+fn takes_opt(x: Option<u32>) {}
+fn main() {
+    let x: u32 = 92;
+    takes_opt(Some(x + 1));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_into() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:core
+struct Meters(u32);
+impl core::convert::From<u32> for Meters {
+    fn from(it: u32) -> Meters { Meters(it) }
+}
+fn takes_meters(m: Meters) {}
+fn main() {
+    let x: u32 = 92;
+    takes_meters(<|>x as u32);
+}
+//- /core/lib.rs crate:core
+pub mod convert {
+    pub trait From<T> { fn from(t: T) -> Self; }
+    pub trait Into<T> { fn into(self) -> T; }
+    impl<T, U: From<T>> Into<U> for T {
+        fn into(self) -> U { U::from(self) }
+    }
+}
+"#,
+            r#"
+struct Meters(u32);
+impl core::convert::From<u32> for Meters {
+    fn from(it: u32) -> Meters { Meters(it) }
+}
+fn takes_meters(m: Meters) {}
+fn main() {
+    let x: u32 = 92;
+    takes_meters((x as u32).into());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_no_fix() {
+        check_no_fix(
+            r#"
+struct Meters(u32);
+fn takes_meters(m: Meters) {}
+fn main() {
+    let x: u32 = 92;
+    takes_meters(x);
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_fill_struct_fields_empty() {
         check_fix(
@@ -581,6 +755,7 @@ fn test_fn() {
                                                 0,
                                             ),
                                             dst: "foo.rs",
+                                            initial_contents: "",
                                         },
                                     ],
                                     is_snippet: false,
@@ -589,6 +764,11 @@ fn test_fn() {
                             },
                         ),
                         unused: false,
+                        code: Some(
+                            DiagnosticCode(
+                                "unresolved-module",
+                            ),
+                        ),
                     },
                 ]
             "#]],
@@ -3,8 +3,8 @@
 use hir::{
     db::AstDatabase,
     diagnostics::{
-        Diagnostic, IncorrectCase, MissingFields, MissingOkInTailExpr, NoSuchField,
-        UnresolvedModule,
+        Diagnostic, IncorrectCase, MismatchAdjustment, MissingFields, MissingOkInTailExpr,
+        NoSuchField, TypeMismatch, UnresolvedModule,
     },
     HasSource, HirDisplay, Semantics, VariantDef,
 };
@@ -38,6 +38,7 @@ impl DiagnosticWithFix for UnresolvedModule {
             FileSystemEdit::CreateFile {
                 anchor: self.file.original_file(sema.db),
                 dst: self.candidate.clone(),
+                initial_contents: String::new(),
             }
             .into(),
             unresolved_module.syntax().text_range(),
@@ -102,6 +103,49 @@ impl DiagnosticWithFix for MissingOkInTailExpr {
     }
 }
 
+impl DiagnosticWithFix for TypeMismatch {
+    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+        let adjustment = self.adjustment?;
+        let root = sema.db.parse_or_expand(self.file)?;
+        let expr = self.expr.to_node(&root);
+        let expr_range = expr.syntax().text_range();
+        let (label, replacement) = match adjustment {
+            MismatchAdjustment::Ref => {
+                ("Add reference here", format!("&{}", parenthesize(&expr, false)))
+            }
+            MismatchAdjustment::RefMut => {
+                ("Add mutable reference here", format!("&mut {}", parenthesize(&expr, false)))
+            }
+            MismatchAdjustment::Into => {
+                ("Convert with `.into()`", format!("{}.into()", parenthesize(&expr, true)))
+            }
+            MismatchAdjustment::WrapInSome => ("Wrap with Some", format!("Some({})", expr)),
+        };
+        let edit = TextEdit::replace(expr_range, replacement);
+        let source_change =
+            SourceFileEdit { file_id: self.file.original_file(sema.db), edit }.into();
+        return Some(Fix::new(label, source_change, expr_range));
+
+        fn parenthesize(expr: &ast::Expr, is_receiver: bool) -> String {
+            let needs_parens = match expr {
+                ast::Expr::BinExpr(_)
+                | ast::Expr::CastExpr(_)
+                | ast::Expr::RangeExpr(_)
+                | ast::Expr::ClosureExpr(_) => true,
+                ast::Expr::RefExpr(_) | ast::Expr::PrefixExpr(_) | ast::Expr::BoxExpr(_) => {
+                    is_receiver
+                }
+                _ => false,
+            };
+            if needs_parens {
+                format!("({})", expr)
+            } else {
+                expr.to_string()
+            }
+        }
+    }
+}
+
 impl DiagnosticWithFix for IncorrectCase {
     fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
         let root = sema.db.parse_or_expand(self.file)?;
@@ -69,6 +69,15 @@ impl ShortLabel for ast::Const {
     }
 }
 
+impl ShortLabel for (ast::Const, hir::ConstValue) {
+    fn short_label(&self) -> Option<String> {
+        let (konst, value) = self;
+        let mut new_buf = short_label_from_ty(konst, konst.ty(), "const ")?;
+        format_to!(new_buf, " = {}", value);
+        Some(new_buf)
+    }
+}
+
 impl ShortLabel for ast::Static {
     fn short_label(&self) -> Option<String> {
         short_label_from_ty(self, self.ty(), "static ")
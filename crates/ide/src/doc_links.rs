@@ -1,6 +1,6 @@
 //! Resolves and rewrites links in markdown documentation.
 
-use std::{convert::TryFrom, iter::once};
+use std::{convert::TryFrom, iter::once, ops::Range};
 
 use itertools::Itertools;
 use pulldown_cmark::{BrokenLink, CowStr, Event, InlineStr, LinkType, Options, Parser, Tag};
@@ -96,6 +96,55 @@ pub(crate) fn remove_links(markdown: &str) -> String {
     out
 }
 
+/// The links in markdown documentation with their ranges in it: the targets of inline and
+/// reference links as written, and URLs appearing in the text.
+pub(crate) fn extract_links(markdown: &str) -> Vec<(Range<usize>, String)> {
+    let mut cb = |link: BrokenLink| {
+        Some((
+            /*url*/ link.reference.to_owned().into(),
+            /*title*/ link.reference.to_owned().into(),
+        ))
+    };
+    let doc = Parser::new_with_broken_link_callback(markdown, Options::empty(), Some(&mut cb));
+
+    let mut links = Vec::new();
+    let mut in_link = false;
+    for (event, range) in doc.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link(_, target, _)) => {
+                in_link = true;
+                links.push((range, target.to_string()));
+            }
+            Event::End(Tag::Link(..)) => in_link = false,
+            Event::Text(text) if !in_link => {
+                let mut offset = 0;
+                while let Some(start) = find_url(&text[offset..]) {
+                    let start = offset + start;
+                    let end = text[start..]
+                        .find(|c: char| c.is_whitespace() || "<>()[]\"'`".contains(c))
+                        .map_or(text.len(), |it| start + it);
+                    let url = text[start..end].trim_end_matches(|c| ".,;:!?".contains(c));
+                    // Text events of a line are contiguous in the source, unless the text
+                    // contains escapes.
+                    if text.len() == range.len() {
+                        links.push((
+                            range.start + start..range.start + start + url.len(),
+                            url.to_string(),
+                        ));
+                    }
+                    offset = end;
+                }
+            }
+            _ => (),
+        }
+    }
+    links
+}
+
+fn find_url(text: &str) -> Option<usize> {
+    ["https://", "http://"].iter().filter_map(|scheme| text.find(scheme)).min()
+}
+
 // FIXME:
 // BUG: For Option::Some
 // Returns https://doc.rust-lang.org/nightly/core/prelude/v1/enum.Option.html#variant.Some
@@ -175,8 +224,30 @@ fn rewrite_intra_doc_link(
     title: &str,
 ) -> Option<(String, String)> {
     let link = if target.is_empty() { title } else { target };
+    let resolved = resolve_intra_doc_link(db, def, link)?;
+    let krate = resolved.module(db)?.krate();
+    let canonical_path = resolved.canonical_path(db)?;
+    let new_target = get_doc_url(db, &krate)?
+        .join(&format!("{}/", krate.display_name(db)?))
+        .ok()?
+        .join(&canonical_path.replace("::", "/"))
+        .ok()?
+        .join(&get_symbol_filename(db, &resolved)?)
+        .ok()?
+        .into_string();
+    let new_title = strip_prefixes_suffixes(title);
+    Some((new_target, new_title.to_string()))
+}
+
+/// Resolves an intra-doc link, such as `super::Foo` or `fn@bar`, written in the documentation
+/// of `def`.
+pub(crate) fn resolve_intra_doc_link(
+    db: &RootDatabase,
+    def: Definition,
+    link: &str,
+) -> Option<ModuleDef> {
     let (link, ns) = parse_link(link);
-    let resolved = match def {
+    match def {
         Definition::ModuleDef(def) => match def {
             ModuleDef::Module(it) => it.resolve_doc_path(db, link, ns),
             ModuleDef::Function(it) => it.resolve_doc_path(db, link, ns),
@@ -190,20 +261,8 @@ fn rewrite_intra_doc_link(
         },
         Definition::Macro(it) => it.resolve_doc_path(db, link, ns),
         Definition::Field(it) => it.resolve_doc_path(db, link, ns),
-        Definition::SelfType(_) | Definition::Local(_) | Definition::TypeParam(_) => return None,
-    }?;
-    let krate = resolved.module(db)?.krate();
-    let canonical_path = resolved.canonical_path(db)?;
-    let new_target = get_doc_url(db, &krate)?
-        .join(&format!("{}/", krate.display_name(db)?))
-        .ok()?
-        .join(&canonical_path.replace("::", "/"))
-        .ok()?
-        .join(&get_symbol_filename(db, &resolved)?)
-        .ok()?
-        .into_string();
-    let new_title = strip_prefixes_suffixes(title);
-    Some((new_target, new_title.to_string()))
+        Definition::SelfType(_) | Definition::Local(_) | Definition::TypeParam(_) => None,
+    }
 }
 
 /// Try to resolve path to local documentation via path-based links (i.e. `../gateway/struct.Shard.html`).
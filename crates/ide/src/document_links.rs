@@ -0,0 +1,448 @@
+use hir::Semantics;
+use ide_db::{
+    base_db::{FileId, FileRange},
+    defs::{Definition, NameClass},
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, AstToken, AttrsOwner, HasQuotes, HasStringValue},
+    match_ast, AstNode, Direction, SyntaxElement, SyntaxKind, SyntaxToken, TextRange, TextSize,
+};
+
+use crate::{display::TryToNav, doc_links};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentLink {
+    pub range: TextRange,
+    pub target: DocumentLinkTarget,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentLinkTarget {
+    /// A file, which may not be part of the workspace, relative to the directory of `anchor`.
+    Path {
+        anchor: FileId,
+        path: String,
+    },
+    /// A file of the workspace.
+    File(FileId),
+    /// The definition an intra-doc link points to.
+    Location(FileRange),
+    Url(String),
+}
+
+// Feature: Document Links
+//
+// Makes the files of `#[path]` attributes and `include!`, `include_str!` and
+// `include_bytes!` calls, as well as the links and URLs of doc comments, clickable.
+pub(crate) fn document_links(db: &RootDatabase, file_id: FileId) -> Vec<DocumentLink> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(file_id);
+
+    let mut res = Vec::new();
+    for element in source_file.syntax().descendants_with_tokens() {
+        match element {
+            syntax::NodeOrToken::Node(node) => {
+                match_ast! {
+                    match node {
+                        ast::Module(it) => res.extend(path_attr_link(&sema, &it)),
+                        ast::MacroCall(it) => res.extend(include_link(file_id, &it)),
+                        _ => (),
+                    }
+                }
+            }
+            syntax::NodeOrToken::Token(token) => {
+                if let Some(comments) = ast::Comment::cast(token).and_then(doc_comment_lines) {
+                    doc_comment_links(&sema, file_id, &comments, &mut res);
+                }
+            }
+        }
+    }
+    res.sort_by_key(|it| it.range.start());
+    res
+}
+
+fn path_attr_link(sema: &Semantics<RootDatabase>, module: &ast::Module) -> Option<DocumentLink> {
+    if module.item_list().is_some() {
+        return None;
+    }
+    let attr = module.attrs().find(|it| it.simple_name().map_or(false, |name| name == "path"))?;
+    let (range, _) = string_literal(&attr.literal()?.syntax().first_token()?)?;
+    let file_id = sema.to_def(module)?.definition_source(sema.db).file_id.original_file(sema.db);
+    Some(DocumentLink { range, target: DocumentLinkTarget::File(file_id) })
+}
+
+fn include_link(file_id: FileId, macro_call: &ast::MacroCall) -> Option<DocumentLink> {
+    let name = macro_call.path()?.segment()?.name_ref()?;
+    if !matches!(name.text().as_str(), "include" | "include_str" | "include_bytes") {
+        return None;
+    }
+    // The first token is the opening delimiter.
+    let token = macro_call.token_tree()?.syntax().children_with_tokens().nth(1)?.into_token()?;
+    let (range, path) = string_literal(&token)?;
+    Some(DocumentLink { range, target: DocumentLinkTarget::Path { anchor: file_id, path } })
+}
+
+/// The range of the contents of a string literal and its value.
+fn string_literal(token: &SyntaxToken) -> Option<(TextRange, String)> {
+    match token.kind() {
+        SyntaxKind::STRING => {
+            let string = ast::String::cast(token.clone())?;
+            Some((string.text_range_between_quotes()?, string.value()?.to_string()))
+        }
+        SyntaxKind::RAW_STRING => {
+            let string = ast::RawString::cast(token.clone())?;
+            Some((string.text_range_between_quotes()?, string.value()?.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// The lines of the doc comment starting at `comment`, or `None` if `comment` isn't a doc
+/// comment or continues the line comments before it.
+fn doc_comment_lines(comment: ast::Comment) -> Option<Vec<ast::Comment>> {
+    let kind = comment.kind();
+    kind.doc?;
+    if kind.shape.is_block() {
+        return Some(vec![comment]);
+    }
+    let same_kind = |element: SyntaxElement| {
+        element.into_token().and_then(ast::Comment::cast).filter(|it| it.kind() == kind)
+    };
+    let mut siblings = comment
+        .syntax()
+        .siblings_with_tokens(Direction::Prev)
+        .skip(1)
+        .filter(|it| it.kind() != SyntaxKind::WHITESPACE);
+    if siblings.next().and_then(same_kind).is_some() {
+        return None;
+    }
+    let mut res = vec![comment.clone()];
+    let siblings = comment
+        .syntax()
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .filter(|it| it.kind() != SyntaxKind::WHITESPACE);
+    for sibling in siblings {
+        match same_kind(sibling) {
+            Some(it) => res.push(it),
+            None => break,
+        }
+    }
+    Some(res)
+}
+
+/// Finds the links of a doc comment. Its lines are joined first, like rustdoc does, so that
+/// reference-style links can use definitions from other lines.
+fn doc_comment_links(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    comments: &[ast::Comment],
+    acc: &mut Vec<DocumentLink>,
+) {
+    // The offset of each line in the joined text, and where it starts in the file.
+    let mut lines = Vec::new();
+    let mut text = String::new();
+    for comment in comments {
+        let line = &comment.text()[comment.prefix().len()..];
+        // Block comments are unclosed while they are being typed.
+        let line = if comment.kind().shape.is_block() {
+            line.strip_suffix("*/").unwrap_or(line)
+        } else {
+            line
+        };
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        lines.push((
+            text.len(),
+            comment.syntax().text_range().start() + TextSize::of(comment.prefix()),
+        ));
+        text.push_str(line);
+    }
+    let to_file_offset = |offset: usize| {
+        let (line_offset, start) =
+            lines.iter().rev().find(|(line_offset, _)| *line_offset <= offset)?;
+        Some(*start + TextSize::from((offset - line_offset) as u32))
+    };
+
+    let mut def = None;
+    for (range, target) in doc_links::extract_links(&text) {
+        let range = match (to_file_offset(range.start), to_file_offset(range.end)) {
+            (Some(start), Some(end)) => TextRange::new(start, end),
+            _ => continue,
+        };
+        let target = if target.contains("://") {
+            DocumentLinkTarget::Url(target)
+        } else {
+            if def.is_none() {
+                def = documented_definition(sema, file_id, &comments[0]);
+            }
+            let resolved =
+                match def.and_then(|it| doc_links::resolve_intra_doc_link(sema.db, it, &target)) {
+                    Some(it) => it,
+                    None => continue,
+                };
+            let nav = match resolved.try_to_nav(sema.db) {
+                Some(it) => it,
+                None => continue,
+            };
+            let range = nav.focus_range.unwrap_or(nav.full_range);
+            DocumentLinkTarget::Location(FileRange { file_id: nav.file_id, range })
+        };
+        acc.push(DocumentLink { range, target });
+    }
+}
+
+/// The item documented by the comment, which is the parent of outer doc comments and the
+/// module containing inner ones.
+fn documented_definition(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    comment: &ast::Comment,
+) -> Option<Definition> {
+    let mut node = comment.syntax().parent();
+    if ast::SourceFile::can_cast(node.kind()) {
+        return Some(Definition::ModuleDef(sema.to_module_def(file_id)?.into()));
+    }
+    if ast::ItemList::can_cast(node.kind()) {
+        node = node.parent()?;
+    }
+    let name = node.children().find_map(ast::Name::cast)?;
+    NameClass::classify(sema, &name)?.defined(sema.db)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let links = analysis.document_links(file_id).unwrap();
+        expect.assert_debug_eq(&links);
+    }
+
+    #[test]
+    fn include_macros() {
+        check(
+            r#"
+const SCHEMA: &str = include_str!("schema.sql");
+const DATA: &[u8] = include_bytes!(r"../data.bin");
+include!(concat!(env!("OUT_DIR"), "/gen.rs"));
+fn f() { format!("not/a/link.rs"); }
+"#,
+            expect![[r#"
+                [
+                    DocumentLink {
+                        range: 35..45,
+                        target: Path {
+                            anchor: FileId(
+                                0,
+                            ),
+                            path: "schema.sql",
+                        },
+                    },
+                    DocumentLink {
+                        range: 86..97,
+                        target: Path {
+                            anchor: FileId(
+                                0,
+                            ),
+                            path: "../data.bin",
+                        },
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn path_attribute() {
+        check(
+            r#"
+//- /main.rs
+#[path = "other/foo.rs"]
+mod foo;
+//- /other/foo.rs
+pub struct S;
+"#,
+            expect![[r#"
+                [
+                    DocumentLink {
+                        range: 10..22,
+                        target: File(
+                            FileId(
+                                1,
+                            ),
+                        ),
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn doc_comment_links() {
+        check(
+            r#"
+/// Wraps a [`Bar`], see [the docs](crate::Bar) and <https://example.com/a>.
+///
+/// Details at https://example.com/b. [Unresolved] and [link](http://example.com/c)
+pub struct Foo;
+
+pub struct Bar;
+"#,
+            expect![[r#"
+                [
+                    DocumentLink {
+                        range: 12..19,
+                        target: Location(
+                            FileRange {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                range: 193..196,
+                            },
+                        ),
+                    },
+                    DocumentLink {
+                        range: 25..47,
+                        target: Location(
+                            FileRange {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                range: 193..196,
+                            },
+                        ),
+                    },
+                    DocumentLink {
+                        range: 52..75,
+                        target: Url(
+                            "https://example.com/a",
+                        ),
+                    },
+                    DocumentLink {
+                        range: 96..117,
+                        target: Url(
+                            "https://example.com/b",
+                        ),
+                    },
+                    DocumentLink {
+                        range: 136..164,
+                        target: Url(
+                            "http://example.com/c",
+                        ),
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn reference_links() {
+        check(
+            r#"
+/// See [Foo] and [the bar][b].
+///
+/// [Foo]: crate::Bar
+/// [b]: crate::Bar
+pub struct Foo;
+
+pub struct Bar;
+"#,
+            expect![[r#"
+                [
+                    DocumentLink {
+                        range: 8..13,
+                        target: Location(
+                            FileRange {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                range: 106..109,
+                            },
+                        ),
+                    },
+                    DocumentLink {
+                        range: 18..30,
+                        target: Location(
+                            FileRange {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                range: 106..109,
+                            },
+                        ),
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unclosed_block_doc_comment() {
+        check(
+            r#"
+/** see https://example.com"#,
+            expect![[r#"
+                [
+                    DocumentLink {
+                        range: 8..27,
+                        target: Url(
+                            "https://example.com",
+                        ),
+                    },
+                ]
+            "#]],
+        );
+        check(
+            r#"
+/**"#,
+            expect![[r#"
+                []
+            "#]],
+        );
+    }
+
+    #[test]
+    fn inner_doc_comment_links() {
+        check(
+            r#"
+//! Start at [Foo].
+pub struct Foo;
+mod m {
+    //! Uses [super::Foo].
+}
+"#,
+            expect![[r#"
+                [
+                    DocumentLink {
+                        range: 13..18,
+                        target: Location(
+                            FileRange {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                range: 31..34,
+                            },
+                        ),
+                    },
+                    DocumentLink {
+                        range: 57..69,
+                        target: Location(
+                            FileRange {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                range: 31..34,
+                            },
+                        ),
+                    },
+                ]
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use hir::{AsAssocItem, AssocItemContainer, ModuleDef, ModuleSource, PathResolution, Semantics};
+use ide_db::{
+    defs::{Definition, NameClass, NameRefClass},
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, ModuleItemOwner, NameOwner},
+    match_ast, AstNode, SmolStr, SyntaxKind, TextRange,
+};
+
+use crate::{display::ToNav, goto_definition, FilePosition, NavigationTarget, RangeInfo};
+
+// Feature: Go to Declaration
+//
+// Navigates to the declaration of an identifier. This is the trait item for the items of
+// trait impls, the `use` item for imported names and the `mod foo;` item for modules, and
+// the definition otherwise.
+pub(crate) fn goto_declaration(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id).syntax().clone();
+    let original_token = goto_definition::pick_best(file.token_at_offset(position.offset))?;
+    let token = sema.descend_into_macros(original_token.clone());
+    let parent = token.parent();
+
+    let nav = match_ast! {
+        match parent {
+            ast::NameRef(name_ref) => {
+                let def = NameRefClass::classify(&sema, &name_ref)?.referenced(db);
+                import_declaration(&sema, &name_ref, def).or_else(|| declaration(db, def))
+            },
+            ast::Name(name) => {
+                let def = NameClass::classify(&sema, &name)?.referenced_or_defined(db);
+                declaration(db, def)
+            },
+            _ => None,
+        }
+    };
+    match nav {
+        Some(nav) => Some(RangeInfo::new(original_token.text_range(), vec![nav])),
+        None => goto_definition::goto_definition(db, position),
+    }
+}
+
+fn declaration(db: &RootDatabase, def: Definition) -> Option<NavigationTarget> {
+    let def = match def {
+        Definition::ModuleDef(it) => it,
+        _ => return None,
+    };
+    if let ModuleDef::Module(module) = def {
+        let src = module.declaration_source(db)?;
+        return Some(NavigationTarget::from_named(
+            db,
+            src.as_ref().map(|it| it as &dyn ast::NameOwner),
+        ));
+    }
+    let assoc_item = match def {
+        ModuleDef::Function(it) => it.as_assoc_item(db),
+        ModuleDef::Const(it) => it.as_assoc_item(db),
+        ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+        _ => None,
+    }?;
+    let trait_ = match assoc_item.container(db) {
+        AssocItemContainer::ImplDef(it) => it.trait_(db)?,
+        AssocItemContainer::Trait(_) => return None,
+    };
+    let name = assoc_item.name(db)?;
+    let trait_item = trait_.items(db).into_iter().find(|it| it.name(db).as_ref() == Some(&name))?;
+    Some(trait_item.to_nav(db))
+}
+
+/// The `use` item through which `name_ref` refers to `def`, if `def` is imported into the
+/// module the name is looked up in.
+fn import_declaration(
+    sema: &Semantics<RootDatabase>,
+    name_ref: &ast::NameRef,
+    def: Definition,
+) -> Option<NavigationTarget> {
+    let db = sema.db;
+    let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+    if path.syntax().ancestors().any(|it| ast::Use::can_cast(it.kind())) {
+        return None;
+    }
+    let module = match path.qualifier() {
+        Some(qualifier) => match sema.resolve_path(&qualifier)? {
+            PathResolution::Def(ModuleDef::Module(it)) => it,
+            _ => return None,
+        },
+        None => sema.scope(path.syntax()).module()?,
+    };
+    if def.module(db) == Some(module) && !matches!(def, Definition::ModuleDef(ModuleDef::Module(_)))
+    {
+        return None;
+    }
+
+    let src = module.definition_source(db);
+    let file_id = src.file_id.original_file(db);
+    if src.file_id != file_id.into() {
+        return None;
+    }
+    let items = match src.value {
+        ModuleSource::SourceFile(it) => it.items().collect::<Vec<_>>(),
+        ModuleSource::Module(it) => it.item_list()?.items().collect(),
+    };
+    let name = name_ref.text();
+    items
+        .into_iter()
+        .filter_map(|it| match it {
+            ast::Item::Use(it) => Some(it),
+            _ => None,
+        })
+        .find_map(|use_item| {
+            let (focus_range, imported) = use_item
+                .syntax()
+                .descendants()
+                .filter_map(ast::UseTree::cast)
+                .filter_map(|it| imported_name(&it))
+                .find(|(_, imported)| imported == name)?;
+            Some(NavigationTarget {
+                file_id,
+                full_range: use_item.syntax().text_range(),
+                focus_range: Some(focus_range),
+                name: imported,
+                kind: SyntaxKind::USE,
+                container_name: None,
+                description: None,
+                docs: None,
+            })
+        })
+}
+
+/// The name a leaf of a use tree brings into scope, with its range.
+fn imported_name(use_tree: &ast::UseTree) -> Option<(TextRange, SmolStr)> {
+    if use_tree.use_tree_list().is_some() || use_tree.star_token().is_some() {
+        return None;
+    }
+    if let Some(rename) = use_tree.rename() {
+        let name = rename.name()?;
+        return Some((name.syntax().text_range(), name.text().clone()));
+    }
+    let path = use_tree.path()?;
+    let segment = path.segment()?;
+    let name_ref = match segment.self_token() {
+        // `use foo::{self}` imports `foo`.
+        Some(_) => {
+            let qualifier = path.qualifier().or_else(|| {
+                let list = use_tree.syntax().parent().and_then(ast::UseTreeList::cast)?;
+                list.parent_use_tree().path()
+            })?;
+            qualifier.segment()?.name_ref()?
+        }
+        None => segment.name_ref()?,
+    };
+    Some((segment.syntax().text_range(), name_ref.text().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::base_db::FileRange;
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str) {
+        let (analysis, position, mut annotations) = fixture::annotations(ra_fixture);
+        let (expected, data) = annotations.pop().unwrap();
+        assert_eq!(data, "");
+
+        let mut navs =
+            analysis.goto_declaration(position).unwrap().expect("no declaration found").info;
+        assert_eq!(navs.len(), 1);
+
+        let nav = navs.pop().unwrap();
+        assert_eq!(expected, FileRange { file_id: nav.file_id, range: nav.focus_or_full_range() });
+    }
+
+    #[test]
+    fn goto_decl_of_impl_method_call() {
+        check(
+            r#"
+trait Tr {
+    fn method(&self);
+     //^^^^^^
+}
+struct S;
+impl Tr for S {
+    fn method(&self) {}
+}
+fn f(s: S) {
+    s.method<|>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_of_impl_items() {
+        check(
+            r#"
+trait Tr {
+    fn method(&self);
+     //^^^^^^
+}
+struct S;
+impl Tr for S {
+    fn method<|>(&self) {}
+}
+"#,
+        );
+        check(
+            r#"
+trait Tr {
+    type Item;
+       //^^^^
+    const C: u8;
+}
+struct S;
+impl Tr for S {
+    type Item<|> = u8;
+    const C: u8 = 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_of_inherent_method_is_definition() {
+        check(
+            r#"
+struct S;
+impl S {
+    fn method(&self) {}
+     //^^^^^^
+}
+fn f(s: S) {
+    s.method<|>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_of_imported_name() {
+        check(
+            r#"
+mod inner {
+    pub struct S;
+}
+use inner::S;
+         //^
+fn f() {
+    S<|>;
+}
+"#,
+        );
+        check(
+            r#"
+mod inner {
+    pub struct S;
+}
+use inner::{S as Renamed, self};
+               //^^^^^^^
+fn f(_: Renamed<|>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_of_re_export() {
+        check(
+            r#"
+//- /lib.rs
+mod api;
+fn f(_: api::Client<|>) {}
+//- /api.rs
+mod client {
+    pub struct Client;
+}
+pub use self::client::Client;
+                    //^^^^^^
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_of_module() {
+        check(
+            r#"
+//- /lib.rs
+mod foo;
+  //^^^
+fn f() {
+    foo<|>::g();
+}
+//- /foo.rs
+pub fn g() {}
+"#,
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use hir::{
-    Adt, AsAssocItem, AssocItemContainer, Documentation, FieldSource, HasSource, HirDisplay,
-    Module, ModuleDef, ModuleSource, Semantics,
+    Adt, AsAssocItem, AssocItemContainer, CaptureKind, Closure, Documentation, FieldSource,
+    HasAttrs, HasSource, HirDisplay, Module, ModuleDef, ModuleSource, Semantics, Type,
 };
 use ide_db::base_db::SourceDatabase;
 use ide_db::{
@@ -154,11 +154,7 @@ pub(crate) fn hover(
         }
     };
 
-    res.markup = if markdown {
-        Markup::fenced_block(&ty.display(db))
-    } else {
-        ty.display(db).to_string().into()
-    };
+    res.markup = if markdown { type_markup(db, &ty) } else { ty.display(db).to_string().into() };
     let range = sema.original_range(&node).range;
     Some(RangeInfo::new(range, res))
 }
@@ -280,6 +276,32 @@ fn hover_markup(
     }
 }
 
+fn type_markup(db: &RootDatabase, ty: &Type) -> Markup {
+    let mut buf = Markup::fenced_block(&ty.display(db)).to_string();
+    if let Some(closure) = ty.as_closure() {
+        format_to!(buf, "\n___\n\n{}", closure_captures_description(db, closure));
+    }
+    buf.into()
+}
+
+fn closure_captures_description(db: &RootDatabase, closure: Closure) -> String {
+    let captures = closure.captured_items(db);
+    let captures = if captures.is_empty() {
+        "captures nothing".to_string()
+    } else {
+        let captures = captures.iter().format_with(", ", |capture, f| {
+            let kind = match capture.kind() {
+                CaptureKind::ByRef => "by reference",
+                CaptureKind::ByMutRef => "by mutable reference",
+                CaptureKind::ByValue => "by value",
+            };
+            f(&format_args!("`{}` {}", capture.display_place(db), kind))
+        });
+        format!("captures {}", captures)
+    };
+    format!("implements `{:?}`, {}", closure.fn_trait(db), captures)
+}
+
 fn definition_owner_name(db: &RootDatabase, def: &Definition) -> Option<String> {
     match def {
         Definition::Field(f) => Some(f.parent_def(db).name(db)),
@@ -318,14 +340,14 @@ fn hover_for_definition(db: &RootDatabase, def: Definition) -> Option<Markup> {
     return match def {
         Definition::Macro(it) => {
             let src = it.source(db);
-            let docs = Documentation::from_ast(&src.value).map(Into::into);
+            let docs = it.docs(db).map(Into::into);
             hover_markup(docs, Some(macro_label(&src.value)), mod_path)
         }
-        Definition::Field(it) => {
-            let src = it.source(db);
+        Definition::Field(field) => {
+            let src = field.source(db);
             match src.value {
                 FieldSource::Named(it) => {
-                    let docs = Documentation::from_ast(&it).map(Into::into);
+                    let docs = field.docs(db).map(Into::into);
                     hover_markup(docs, it.short_label(), mod_path)
                 }
                 _ => None,
@@ -347,13 +369,20 @@ fn hover_for_definition(db: &RootDatabase, def: Definition) -> Option<Markup> {
             ModuleDef::Adt(Adt::Union(it)) => from_def_source(db, it, mod_path),
             ModuleDef::Adt(Adt::Enum(it)) => from_def_source(db, it, mod_path),
             ModuleDef::EnumVariant(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Const(it) => from_def_source(db, it, mod_path),
+            ModuleDef::Const(it) => match it.eval(db) {
+                Ok(value) => {
+                    let src = it.source(db);
+                    let docs = it.docs(db).map(Into::into);
+                    hover_markup(docs, (src.value, value).short_label(), mod_path)
+                }
+                Err(_) => from_def_source(db, it, mod_path),
+            },
             ModuleDef::Static(it) => from_def_source(db, it, mod_path),
             ModuleDef::Trait(it) => from_def_source(db, it, mod_path),
             ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path),
             ModuleDef::BuiltinType(it) => return Some(it.to_string().into()),
         },
-        Definition::Local(it) => return Some(Markup::fenced_block(&it.ty(db).display(db))),
+        Definition::Local(it) => return Some(type_markup(db, &it.ty(db))),
         Definition::TypeParam(_) | Definition::SelfType(_) => {
             // FIXME: Hover for generic param
             None
@@ -362,11 +391,11 @@ fn hover_for_definition(db: &RootDatabase, def: Definition) -> Option<Markup> {
 
     fn from_def_source<A, D>(db: &RootDatabase, def: D, mod_path: Option<String>) -> Option<Markup>
     where
-        D: HasSource<Ast = A>,
-        A: ast::DocCommentsOwner + ast::NameOwner + ShortLabel + ast::AttrsOwner,
+        D: HasSource<Ast = A> + HasAttrs + Copy,
+        A: ShortLabel,
     {
         let src = def.source(db);
-        let docs = Documentation::from_ast(&src.value).map(Into::into);
+        let docs = def.docs(db).map(Into::into);
         hover_markup(docs, src.value.short_label(), mod_path)
     }
 }
@@ -505,6 +534,71 @@ fn main() {
         );
     }
 
+    #[test]
+    fn hover_shows_lifetimes_of_local_type() {
+        check(
+            r#"
+struct Wrapper<'a, T> { inner: &'a T }
+
+fn foo<'a>(w: Wrapper<'a, u32>, s: &'static str) {
+    let pair<|> = (w.inner, s, w);
+}
+"#,
+            expect![[r#"
+                *pair*
+
+                ```rust
+                (&'a u32, &'static str, Wrapper<'a, u32>)
+                ```
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_closure_captures() {
+        check(
+            r#"
+#[lang = "copy"]
+trait Copy {}
+struct S;
+fn consume(s: S) {}
+fn foo() {
+    let mut count = 0;
+    let s = S;
+    let t = S;
+    let clo<|>sure = || { count += 1; consume(s); &t; };
+}
+"#,
+            expect![[r#"
+                *closure*
+
+                ```rust
+                || -> ()
+                ```
+
+                ---
+
+                implements `FnOnce`, captures `count` by mutable reference, `s` by value, `t` by reference
+            "#]],
+        );
+        check(
+            r#"
+fn foo() {
+    <|>|x: u32| x + 1;
+}
+"#,
+            expect![[r#"
+                *|x: u32| x + 1*
+                ```rust
+                |u32| -> u32
+                ```
+                ___
+
+                implements `Fn`, captures nothing
+            "#]],
+        );
+    }
+
     #[test]
     fn hover_shows_fn_signature() {
         // Single file with result
@@ -743,6 +837,41 @@ fn main() {
         );
     }
 
+    #[test]
+    fn hover_const_shows_evaluated_value() {
+        check(
+            r#"
+const BASE: usize = 1 << 4;
+const foo<|>: usize = BASE * 2 + 1;
+"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                const foo: usize = 33
+                ```
+            "#]],
+        );
+        check(
+            r#"const foo<|>: u8 = 255 + 1;"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                const foo: u8 = 255 + 1
+                ```
+            "#]],
+        );
+    }
+
     #[test]
     fn hover_default_generic_types() {
         check(
@@ -1501,6 +1630,38 @@ fn foo() { let bar = Ba<|>r; }
         );
     }
 
+    #[test]
+    fn test_hover_struct_doc_attr_in_cfg_attr() {
+        check(
+            r#"
+//- /lib.rs crate:test cfg:feature=docs
+/// bar docs 0
+#[cfg_attr(feature = "docs", doc = "bar docs 1")]
+#[cfg_attr(not(feature = "docs"), doc = "bar docs 2")]
+struct Bar;
+
+fn foo() { let bar = Ba<|>r; }
+"#,
+            expect![[r#"
+                *Bar*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                struct Bar
+                ```
+
+                ---
+
+                bar docs 0
+
+                bar docs 1
+            "#]],
+        );
+    }
+
     #[test]
     fn test_hover_path_link() {
         check(
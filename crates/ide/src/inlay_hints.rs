@@ -1,7 +1,8 @@
 use assists::utils::FamousDefs;
 use either::Either;
-use hir::{known, HirDisplay, Semantics};
+use hir::{known, CaptureKind, HirDisplay, Semantics};
 use ide_db::RootDatabase;
+use itertools::Itertools;
 use stdx::to_lower_snake_case;
 use syntax::{
     ast::{self, ArgListOwner, AstNode, NameOwner},
@@ -15,12 +16,19 @@ pub struct InlayHintsConfig {
     pub type_hints: bool,
     pub parameter_hints: bool,
     pub chaining_hints: bool,
+    pub closure_capture_hints: bool,
     pub max_length: Option<usize>,
 }
 
 impl Default for InlayHintsConfig {
     fn default() -> Self {
-        Self { type_hints: true, parameter_hints: true, chaining_hints: true, max_length: None }
+        Self {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            closure_capture_hints: false,
+            max_length: None,
+        }
     }
 }
 
@@ -29,6 +37,7 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    ClosureCaptureHint,
 }
 
 #[derive(Debug)]
@@ -48,6 +57,7 @@ pub struct InlayHint {
 // * types of local variables
 // * names of function arguments
 // * types of chained expressions
+// * places captured by closures, if enabled
 //
 // **Note:** VS Code does not have native support for inlay hints https://github.com/microsoft/vscode/issues/16221[yet] and the hints are implemented using decorations.
 // This approach has limitations, the caret movement and bracket highlighting near the edges of the hint may be weird:
@@ -78,6 +88,7 @@ pub(crate) fn inlay_hints(
                 ast::CallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::MethodCallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::IdentPat(it) => { get_bind_pat_hints(&mut res, &sema, config, it); },
+                ast::ClosureExpr(it) => { get_closure_capture_hints(&mut res, &sema, config, it); },
                 _ => (),
             }
         }
@@ -209,6 +220,41 @@ fn get_bind_pat_hints(
     Some(())
 }
 
+/// Shows the places a closure captures, and how, next to its parameter list.
+fn get_closure_capture_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    closure: ast::ClosureExpr,
+) -> Option<()> {
+    if !config.closure_capture_hints {
+        return None;
+    }
+
+    let param_list = closure.param_list()?;
+    let captures = sema.type_of_expr(&closure.into())?.as_closure()?.captured_items(sema.db);
+    if captures.is_empty() {
+        return None;
+    }
+    let label = captures
+        .iter()
+        .map(|capture| {
+            let prefix = match capture.kind() {
+                CaptureKind::ByRef => "&",
+                CaptureKind::ByMutRef => "&mut ",
+                CaptureKind::ByValue => "",
+            };
+            format!("{}{}", prefix, capture.display_place(sema.db))
+        })
+        .join(", ");
+    acc.push(InlayHint {
+        range: param_list.syntax().text_range(),
+        kind: InlayKind::ClosureCaptureHint,
+        label: label.into(),
+    });
+    Some(())
+}
+
 /// Checks if the type is an Iterator from std::iter and replaces its hint with an `impl Iterator<Item = Ty>`.
 fn hint_iterator(
     sema: &Semantics<RootDatabase>,
@@ -441,6 +487,7 @@ mod tests {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -463,6 +510,7 @@ fn main() {
                 type_hints: false,
                 parameter_hints: false,
                 chaining_hints: false,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -480,6 +528,7 @@ fn main() {
                 type_hints: true,
                 parameter_hints: false,
                 chaining_hints: false,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -843,6 +892,7 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -882,6 +932,7 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -904,6 +955,7 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -948,6 +1000,7 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -992,7 +1045,7 @@ fn main() {
             r#"
 fn main() {
     let data = &[1i32, 2, 3];
-      //^^^^ &[i32; _]
+      //^^^^ &[i32; 3]
     for i
 }"#,
         );
@@ -1057,6 +1110,7 @@ fn main() {
                 type_hints: true,
                 parameter_hints: false,
                 chaining_hints: false,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -1090,6 +1144,7 @@ fn main() {
                 parameter_hints: false,
                 type_hints: true,
                 chaining_hints: false,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -1127,6 +1182,7 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -1183,6 +1239,7 @@ fn main() {
                 parameter_hints: false,
                 type_hints: true,
                 chaining_hints: false,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -1220,6 +1277,7 @@ fn main() {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                closure_capture_hints: false,
                 max_length: None,
             },
             r#"
@@ -1256,4 +1314,33 @@ fn main() {
             "#,
         );
     }
+
+    #[test]
+    fn closure_capture_hints() {
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: false,
+                parameter_hints: false,
+                chaining_hints: false,
+                closure_capture_hints: true,
+                max_length: None,
+            },
+            r#"
+#[lang = "copy"]
+trait Copy {}
+struct S;
+fn consume(s: S) {}
+fn main() {
+    let mut count = 0;
+    let s = S;
+    let t = S;
+    let closure = || { count += 1; consume(s); &t; };
+                //^^ &mut count, s, &t
+    let no_captures = |x: u32| x + 1;
+    let moved = move || &t;
+                   //^^ t
+}
+"#,
+        );
+    }
 }
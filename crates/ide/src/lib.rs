@@ -23,11 +23,14 @@ mod prime_caches;
 mod display;
 
 mod call_hierarchy;
+mod change_signature;
 mod diagnostics;
+mod document_links;
 mod expand_macro;
 mod extend_selection;
 mod file_structure;
 mod folding_ranges;
+mod goto_declaration;
 mod goto_definition;
 mod goto_implementation;
 mod goto_type_definition;
@@ -42,6 +45,7 @@ mod runnables;
 mod status;
 mod syntax_highlighting;
 mod syntax_tree;
+mod type_hierarchy;
 mod typing;
 mod markdown_remove;
 mod doc_links;
@@ -63,8 +67,10 @@ use crate::display::ToNav;
 
 pub use crate::{
     call_hierarchy::CallItem,
+    change_signature::{ChangeSignatureError, SignatureParam},
     diagnostics::{Diagnostic, DiagnosticsConfig, Fix, Severity},
     display::NavigationTarget,
+    document_links::{DocumentLink, DocumentLinkTarget},
     expand_macro::ExpandedMacro,
     file_structure::StructureNode,
     folding_ranges::{Fold, FoldKind},
@@ -328,6 +334,11 @@ impl Analysis {
         self.with_db(|db| folding_ranges::folding_ranges(&db.parse(file_id).tree()))
     }
 
+    /// Returns the links to files, definitions and URLs in the file.
+    pub fn document_links(&self, file_id: FileId) -> Cancelable<Vec<DocumentLink>> {
+        self.with_db(|db| document_links::document_links(db, file_id))
+    }
+
     /// Fuzzy searches for a symbol.
     pub fn symbol_search(&self, query: Query) -> Cancelable<Vec<NavigationTarget>> {
         self.with_db(|db| {
@@ -346,6 +357,15 @@ impl Analysis {
         self.with_db(|db| goto_definition::goto_definition(db, position))
     }
 
+    /// Returns the declaration of the identifier at `position`: the trait item of an impl
+    /// item, the `use` item of an imported name or the `mod` item of a module.
+    pub fn goto_declaration(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| goto_declaration::goto_declaration(db, position))
+    }
+
     /// Returns the impls from the symbol at `position`.
     pub fn goto_implementation(
         &self,
@@ -419,6 +439,25 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Computes type hierarchy candidates for the given file position.
+    pub fn type_hierarchy(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| type_hierarchy::type_hierarchy(db, position))
+    }
+
+    /// Computes the supertraits of the trait or the traits implemented by the type at the
+    /// given file position.
+    pub fn supertypes(&self, position: FilePosition) -> Cancelable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::supertypes(db, position))
+    }
+
+    /// Computes the subtraits and implementors of the trait at the given file position.
+    pub fn subtypes(&self, position: FilePosition) -> Cancelable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::subtypes(db, position))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancelable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -508,6 +547,26 @@ impl Analysis {
         self.with_db(|db| references::rename::rename(db, position, new_name))
     }
 
+    /// Returns the edit changing the parameters of the function at the position, along with
+    /// its callers.
+    pub fn change_signature(
+        &self,
+        position: FilePosition,
+        new_params: &[SignatureParam],
+    ) -> Cancelable<Result<SourceChange, ChangeSignatureError>> {
+        self.with_db(|db| change_signature::change_signature(db, position, new_params))
+    }
+
+    /// Returns the edit renaming the module of the file, for when the file is renamed to
+    /// `new_name`.
+    pub fn will_rename_file(
+        &self,
+        file_id: FileId,
+        new_name: &str,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| references::rename::will_rename_file(db, file_id, new_name))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
@@ -1,7 +1,7 @@
 //! FIXME: write short doc here
 
 use hir::{Module, ModuleDef, ModuleSource, Semantics};
-use ide_db::base_db::SourceDatabaseExt;
+use ide_db::base_db::{FileId, SourceDatabaseExt};
 use ide_db::{
     defs::{Definition, NameClass, NameRefClass},
     RootDatabase,
@@ -14,7 +14,7 @@ use std::{
 };
 use syntax::{
     algo::find_node_at_offset,
-    ast::{self, NameOwner},
+    ast::{self, AttrsOwner, NameOwner},
     lex_single_syntax_kind, match_ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken,
 };
 use test_utils::mark;
@@ -78,6 +78,34 @@ pub(crate) fn rename_with_semantics(
     }
 }
 
+/// Renames the module defined by `file_id` along with its references, when the client is about
+/// to rename the file (or its directory for `mod.rs`) itself.
+pub(crate) fn will_rename_file(
+    db: &RootDatabase,
+    file_id: FileId,
+    new_name: &str,
+) -> Option<SourceChange> {
+    let sema = Semantics::new(db);
+    let module = sema.to_module_def(file_id)?;
+    let decl = module.declaration_source(db)?;
+    if decl
+        .value
+        .attrs()
+        .any(|attr| attr.as_simple_key_value().map_or(false, |(key, _)| key == "path"))
+    {
+        mark::hit!(will_rename_file_path_attr);
+        return None;
+    }
+    let position = FilePosition {
+        file_id: decl.file_id.original_file(db),
+        offset: decl.value.name()?.syntax().text_range().start(),
+    };
+    let mut change = rename_with_semantics(&sema, position, new_name).ok()?.info;
+    // The client moves the file.
+    change.file_system_edits.clear();
+    Some(change)
+}
+
 fn find_module_at_offset(
     sema: &Semantics<RootDatabase>,
     position: FilePosition,
@@ -1097,4 +1125,143 @@ impl Foo {
 "#,
         );
     }
+
+    fn check_will_rename_file(new_name: &str, ra_fixture: &str, expect: Expect) {
+        let (analysis, file_ids) = fixture::files(ra_fixture);
+        let source_change = analysis.will_rename_file(*file_ids.last().unwrap(), new_name).unwrap();
+        expect.assert_debug_eq(&source_change)
+    }
+
+    #[test]
+    fn test_will_rename_file() {
+        check_will_rename_file(
+            "baz",
+            r#"
+//- /main.rs
+mod foo;
+use foo::S;
+fn main() { foo::f(); }
+//- /foo.rs
+pub struct S;
+pub fn f() {}
+"#,
+            expect![[r#"
+                Some(
+                    SourceChange {
+                        source_file_edits: [
+                            SourceFileEdit {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                edit: TextEdit {
+                                    indels: [
+                                        Indel {
+                                            insert: "baz",
+                                            delete: 4..7,
+                                        },
+                                    ],
+                                },
+                            },
+                            SourceFileEdit {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                edit: TextEdit {
+                                    indels: [
+                                        Indel {
+                                            insert: "baz",
+                                            delete: 13..16,
+                                        },
+                                    ],
+                                },
+                            },
+                            SourceFileEdit {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                edit: TextEdit {
+                                    indels: [
+                                        Indel {
+                                            insert: "baz",
+                                            delete: 33..36,
+                                        },
+                                    ],
+                                },
+                            },
+                        ],
+                        file_system_edits: [],
+                        is_snippet: false,
+                    },
+                )
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_will_rename_mod_rs_directory() {
+        check_will_rename_file(
+            "baz",
+            r#"
+//- /main.rs
+mod foo;
+fn main() { foo::f(); }
+//- /foo/mod.rs
+pub fn f() {}
+"#,
+            expect![[r#"
+                Some(
+                    SourceChange {
+                        source_file_edits: [
+                            SourceFileEdit {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                edit: TextEdit {
+                                    indels: [
+                                        Indel {
+                                            insert: "baz",
+                                            delete: 4..7,
+                                        },
+                                    ],
+                                },
+                            },
+                            SourceFileEdit {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                edit: TextEdit {
+                                    indels: [
+                                        Indel {
+                                            insert: "baz",
+                                            delete: 21..24,
+                                        },
+                                    ],
+                                },
+                            },
+                        ],
+                        file_system_edits: [],
+                        is_snippet: false,
+                    },
+                )
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_will_rename_file_with_path_attr() {
+        mark::check!(will_rename_file_path_attr);
+        check_will_rename_file(
+            "baz",
+            r#"
+//- /main.rs
+#[path = "foo.rs"]
+mod bar;
+//- /foo.rs
+pub fn f() {}
+"#,
+            expect![[r#"
+                None
+            "#]],
+        );
+    }
 }
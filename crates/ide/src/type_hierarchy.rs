@@ -0,0 +1,311 @@
+//! Entry point for type-hierarchy
+
+use hir::{Adt, Crate, GenericDef, ImplDef, ModuleDef, Semantics, Trait};
+use ide_db::{
+    defs::{Definition, NameClass},
+    RootDatabase,
+};
+use rustc_hash::FxHashSet;
+use syntax::{ast, AstNode, SyntaxKind};
+
+use crate::{display::ToNav, goto_definition, FilePosition, NavigationTarget, RangeInfo};
+
+enum TypeItem {
+    Adt(Adt),
+    Trait(Trait),
+}
+
+// Feature: Type Hierarchy
+//
+// Shows the traits a type implements and the supertraits of a trait, as well as the
+// subtraits and implementors of a trait.
+pub(crate) fn type_hierarchy(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let RangeInfo { range, info: navs } = goto_definition::goto_definition(db, position)?;
+    let navs = navs
+        .into_iter()
+        .filter(|it| {
+            matches!(
+                it.kind,
+                SyntaxKind::STRUCT | SyntaxKind::ENUM | SyntaxKind::UNION | SyntaxKind::TRAIT
+            )
+        })
+        .collect::<Vec<_>>();
+    if navs.is_empty() {
+        return None;
+    }
+    Some(RangeInfo::new(range, navs))
+}
+
+pub(crate) fn supertypes(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let res = match type_item(&sema, position)? {
+        TypeItem::Trait(trait_) => {
+            trait_.direct_supertraits(db).into_iter().map(|it| it.to_nav(db)).collect()
+        }
+        TypeItem::Adt(adt) => {
+            let ty = adt.ty(db);
+            let krate = adt.module(db).krate();
+            // Blanket impls like `impl<T: Debug> Tr for T` are in the crate of the trait, which
+            // may also be a dependency of the type's crate.
+            let mut crates = dependent_crates(db, krate);
+            crates.extend(dependency_crates(db, krate).into_iter().skip(1));
+            let mut traits = Vec::new();
+            for krate in crates {
+                for impl_def in ImplDef::all_in_crate(db, krate) {
+                    let trait_ = match impl_def.trait_(db) {
+                        Some(it) if !traits.contains(&it) => it,
+                        _ => continue,
+                    };
+                    let target_ty = impl_def.target_ty(db);
+                    let implements = ty.is_equal_for_find_impls(&target_ty)
+                        || (target_ty.as_adt().is_none()
+                            && GenericDef::from(trait_).params(db).len() == 1
+                            && ty.impls_trait(db, trait_, &[]));
+                    if implements {
+                        traits.push(trait_);
+                    }
+                }
+            }
+            traits.into_iter().map(|it| it.to_nav(db)).collect()
+        }
+    };
+    Some(res)
+}
+
+pub(crate) fn subtypes(db: &RootDatabase, position: FilePosition) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let trait_ = match type_item(&sema, position)? {
+        TypeItem::Trait(it) => it,
+        TypeItem::Adt(_) => return Some(Vec::new()),
+    };
+
+    // Subtraits name the trait in their bounds, so they are among its usages.
+    let mut subtraits = Vec::new();
+    for reference in Definition::ModuleDef(trait_.into()).usages(&sema).all() {
+        let file = sema.parse(reference.file_range.file_id);
+        let token = match file.syntax().token_at_offset(reference.file_range.range.start()).next() {
+            Some(it) => it,
+            None => continue,
+        };
+        let subtrait = match token
+            .parent()
+            .ancestors()
+            .find_map(ast::Trait::cast)
+            .and_then(|it| sema.to_def(&it))
+        {
+            Some(it) => it,
+            None => continue,
+        };
+        if !subtraits.contains(&subtrait) && subtrait.direct_supertraits(db).contains(&trait_) {
+            subtraits.push(subtrait);
+        }
+    }
+
+    // Types implementing the trait are shown as themselves, blanket impls and impls for
+    // references and the like as the impl.
+    let mut implementors = Vec::new();
+    let mut seen = FxHashSet::default();
+    for krate in dependent_crates(db, trait_.module(db).krate()) {
+        for impl_def in ImplDef::for_trait(db, krate, trait_) {
+            match impl_def.target_ty(db).as_adt() {
+                Some(adt) => {
+                    if seen.insert(adt) {
+                        implementors.push(adt.to_nav(db));
+                    }
+                }
+                None => implementors.push(impl_def.to_nav(db)),
+            }
+        }
+    }
+
+    Some(subtraits.into_iter().map(|it| it.to_nav(db)).chain(implementors).collect())
+}
+
+fn type_item(sema: &Semantics<RootDatabase>, position: FilePosition) -> Option<TypeItem> {
+    let file = sema.parse(position.file_id);
+    let name =
+        sema.find_node_at_offset_with_descend::<ast::Name>(file.syntax(), position.offset)?;
+    match NameClass::classify(sema, &name)?.defined(sema.db)? {
+        Definition::ModuleDef(ModuleDef::Adt(it)) => Some(TypeItem::Adt(it)),
+        Definition::ModuleDef(ModuleDef::Trait(it)) => Some(TypeItem::Trait(it)),
+        _ => None,
+    }
+}
+
+/// The crates which can contain impls involving an item of `krate`: the crate itself and
+/// the ones depending on it, directly or not.
+fn dependent_crates(db: &RootDatabase, krate: Crate) -> Vec<Crate> {
+    reachable_crates(krate, |it| it.reverse_dependencies(db))
+}
+
+/// `krate` and the crates it depends on, directly or not.
+fn dependency_crates(db: &RootDatabase, krate: Crate) -> Vec<Crate> {
+    reachable_crates(krate, |it| it.dependencies(db).into_iter().map(|dep| dep.krate).collect())
+}
+
+fn reachable_crates(krate: Crate, edges: impl Fn(Crate) -> Vec<Crate>) -> Vec<Crate> {
+    let mut res = vec![krate];
+    let mut i = 0;
+    while i < res.len() {
+        for next in edges(res[i]) {
+            if !res.contains(&next) {
+                res.push(next);
+            }
+        }
+        i += 1;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::base_db::FilePosition;
+
+    use crate::fixture;
+
+    fn check_hierarchy(
+        ra_fixture: &str,
+        expected: &str,
+        expected_supertypes: &[&str],
+        expected_subtypes: &[&str],
+    ) {
+        let (analysis, pos) = fixture::position(ra_fixture);
+
+        let mut navs = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        assert_eq!(navs.len(), 1);
+        let nav = navs.pop().unwrap();
+        nav.assert_match(expected);
+
+        let item_pos =
+            FilePosition { file_id: nav.file_id, offset: nav.focus_or_full_range().start() };
+        let supertypes = analysis.supertypes(item_pos).unwrap().unwrap();
+        let supertypes = supertypes.iter().map(|it| it.debug_render()).collect::<Vec<_>>();
+        assert_eq!(supertypes, expected_supertypes);
+
+        let subtypes = analysis.subtypes(item_pos).unwrap().unwrap();
+        let subtypes = subtypes.iter().map(|it| it.debug_render()).collect::<Vec<_>>();
+        assert_eq!(subtypes, expected_subtypes);
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_trait() {
+        check_hierarchy(
+            r#"
+trait Base {}
+trait Tr<|>: Base {}
+trait Sub: Tr {}
+struct S;
+impl Base for S {}
+impl Tr for S {}
+"#,
+            "Tr TRAIT FileId(0) 14..31 20..22",
+            &["Base TRAIT FileId(0) 0..13 6..10"],
+            &["Sub TRAIT FileId(0) 32..48 38..41", "S STRUCT FileId(0) 49..58 56..57"],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_trait_implementors() {
+        check_hierarchy(
+            r#"
+trait Tr {}
+struct S;
+enum E {}
+impl Tr for S {}
+impl Tr for E {}
+impl Tr for &S {}
+fn f(_: impl Tr<|>) {}
+"#,
+            "Tr TRAIT FileId(0) 0..11 6..8",
+            &[],
+            &[
+                "S STRUCT FileId(0) 12..21 19..20",
+                "E ENUM FileId(0) 22..31 27..28",
+                "impl IMPL FileId(0) 66..83 78..80",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_struct() {
+        check_hierarchy(
+            r#"
+trait A {}
+trait B {}
+struct S<|>;
+impl S {}
+impl A for S {}
+impl B for S {}
+"#,
+            "S STRUCT FileId(0) 22..31 29..30",
+            &["A TRAIT FileId(0) 0..10 6..7", "B TRAIT FileId(0) 11..21 17..18"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_struct_with_blanket_impls() {
+        check_hierarchy(
+            r#"
+//- /main.rs crate:main deps:lib
+struct S<|>;
+impl lib::Marker for S {}
+//- /lib.rs crate:lib
+pub trait Marker {}
+pub trait Blanket {}
+impl<T: Marker> Blanket for T {}
+pub trait Unimplemented {}
+impl<T: Unimplemented> Blanket for &T {}
+"#,
+            "S STRUCT FileId(0) 0..9 7..8",
+            &["Marker TRAIT FileId(1) 0..19 10..16", "Blanket TRAIT FileId(1) 20..40 30..37"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_with_where_clause_supertrait() {
+        check_hierarchy(
+            r#"
+trait Base<|> {}
+trait Sub where Self: Base {}
+"#,
+            "Base TRAIT FileId(0) 0..13 6..10",
+            &[],
+            &["Sub TRAIT FileId(0) 14..43 20..23"],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_across_crates() {
+        check_hierarchy(
+            r#"
+//- /main.rs crate:main deps:lib
+struct S;
+impl lib::Tr for S {}
+trait Sub: lib::Tr {}
+//- /lib.rs crate:lib
+pub trait Tr<|> {}
+"#,
+            "Tr TRAIT FileId(1) 0..15 10..12",
+            &[],
+            &["Sub TRAIT FileId(0) 32..53 38..41", "S STRUCT FileId(0) 0..9 7..8"],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_not_on_function() {
+        let (analysis, pos) = fixture::position(
+            r#"
+fn f<|>() {}
+"#,
+        );
+        assert!(analysis.type_hierarchy(pos).unwrap().is_none());
+    }
+}
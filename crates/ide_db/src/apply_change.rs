@@ -145,6 +145,7 @@ impl RootDatabase {
             hir::db::MacroDefQuery
             hir::db::ParseMacroQuery
             hir::db::MacroExpandQuery
+            hir::db::MacroExpandErrorQuery
 
             // DefDatabase
             hir::db::ItemTreeQuery
@@ -44,8 +44,20 @@ impl From<Vec<SourceFileEdit>> for SourceChange {
 
 #[derive(Debug, Clone)]
 pub enum FileSystemEdit {
-    CreateFile { anchor: FileId, dst: String },
-    MoveFile { src: FileId, anchor: FileId, dst: String },
+    /// Creates the file at `dst`, relative to the directory of `anchor`.
+    CreateFile {
+        anchor: FileId,
+        dst: String,
+        initial_contents: String,
+    },
+    MoveFile {
+        src: FileId,
+        anchor: FileId,
+        dst: String,
+    },
+    DeleteFile {
+        file: FileId,
+    },
 }
 
 impl From<FileSystemEdit> for SourceChange {
@@ -12,6 +12,8 @@ mod subtree_source;
 #[cfg(test)]
 mod tests;
 
+use std::fmt;
+
 pub use tt::{Delimiter, Punct};
 
 use crate::{
@@ -33,6 +35,7 @@ pub enum ExpandError {
     ConversionError,
     InvalidRepeat,
     ProcMacroError(tt::ExpansionError),
+    Other(String),
 }
 
 impl From<tt::ExpansionError> for ExpandError {
@@ -41,6 +44,26 @@ impl From<tt::ExpansionError> for ExpandError {
     }
 }
 
+impl fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpandError::NoMatchingRule => f.write_str("no rule matches input tokens"),
+            ExpandError::UnexpectedToken => f.write_str("unexpected token in input"),
+            ExpandError::BindingError(e) => f.write_str(e),
+            ExpandError::ConversionError => f.write_str("could not convert tokens"),
+            ExpandError::InvalidRepeat => f.write_str("invalid repetition"),
+            ExpandError::ProcMacroError(e) => match e {
+                tt::ExpansionError::IOError(e) => write!(f, "I/O error: {}", e),
+                tt::ExpansionError::JsonError(e) => write!(f, "JSON encoding error: {}", e),
+                tt::ExpansionError::Unknown(e) | tt::ExpansionError::ExpansionError(e) => {
+                    f.write_str(e)
+                }
+            },
+            ExpandError::Other(e) => f.write_str(e),
+        }
+    }
+}
+
 pub use crate::syntax_bridge::{
     ast_to_token_tree, parse_to_token_tree, syntax_node_to_token_tree, token_tree_to_syntax_node,
     TokenMap,
@@ -80,8 +80,21 @@ pub struct PackageData {
     pub dependencies: Vec<PackageDependency>,
     pub edition: Edition,
     pub features: Vec<String>,
+    /// Outputs of the build script and of the compilation of proc-macro crates, only populated
+    /// when `load_out_dirs_from_check` is enabled.
+    pub build_data: BuildData,
+}
+
+/// What `cargo check` told us about a package.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BuildData {
+    /// `cargo:rustc-cfg` instructions of the build script
     pub cfgs: Vec<CfgFlag>,
+    /// `cargo:rustc-env` instructions of the build script
+    pub envs: Vec<(String, String)>,
+    /// The `OUT_DIR` of the build script
     pub out_dir: Option<AbsPathBuf>,
+    /// The compiled dylib, for proc-macro crates
     pub proc_macro_dylib_path: Option<AbsPathBuf>,
 }
 
@@ -164,14 +177,14 @@ impl CargoWorkspace {
             format!("Failed to run `cargo metadata --manifest-path {}`", cargo_toml.display())
         })?;
 
-        let mut out_dir_by_id = FxHashMap::default();
-        let mut cfgs = FxHashMap::default();
-        let mut proc_macro_dylib_paths = FxHashMap::default();
+        let mut build_data_map = BuildDataMap::default();
         if cargo_features.load_out_dirs_from_check {
-            let resources = load_extern_resources(cargo_toml, cargo_features)?;
-            out_dir_by_id = resources.out_dirs;
-            cfgs = resources.cfgs;
-            proc_macro_dylib_paths = resources.proc_dylib_paths;
+            // A failing build shouldn't prevent us from loading the workspace, we just won't know
+            // about generated code.
+            match load_build_data(cargo_toml, cargo_features) {
+                Ok(it) => build_data_map = it,
+                Err(err) => log::error!("failed to run build scripts: {:#}", err),
+            }
         }
 
         let mut pkg_by_id = FxHashMap::default();
@@ -197,9 +210,7 @@ impl CargoWorkspace {
                 edition,
                 dependencies: Vec::new(),
                 features: Vec::new(),
-                cfgs: cfgs.get(&id).cloned().unwrap_or_default(),
-                out_dir: out_dir_by_id.get(&id).cloned(),
-                proc_macro_dylib_path: proc_macro_dylib_paths.get(&id).cloned(),
+                build_data: build_data_map.remove(&id).unwrap_or_default(),
             });
             let pkg_data = &mut packages[pkg];
             pkg_by_id.insert(id, pkg);
@@ -277,19 +288,19 @@ impl CargoWorkspace {
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub(crate) struct ExternResources {
-    out_dirs: FxHashMap<PackageId, AbsPathBuf>,
-    proc_dylib_paths: FxHashMap<PackageId, AbsPathBuf>,
-    cfgs: FxHashMap<PackageId, Vec<CfgFlag>>,
-}
+pub(crate) type BuildDataMap = FxHashMap<PackageId, BuildData>;
 
-pub(crate) fn load_extern_resources(
+/// Runs `cargo check` and collects the outputs of the build scripts and the paths of the compiled
+/// proc-macro dylibs, per package.
+pub(crate) fn load_build_data(
     cargo_toml: &Path,
     cargo_features: &CargoConfig,
-) -> Result<ExternResources> {
+) -> Result<BuildDataMap> {
     let mut cmd = Command::new(toolchain::cargo());
-    cmd.args(&["check", "--message-format=json", "--manifest-path"]).arg(cargo_toml);
+    cmd.args(&["check", "--workspace", "--message-format=json", "--manifest-path"]).arg(cargo_toml);
+    if let Some(target) = &cargo_features.target {
+        cmd.args(&["--target", target]);
+    }
     if cargo_features.all_features {
         cmd.arg("--all-features");
     } else {
@@ -304,32 +315,40 @@ pub(crate) fn load_extern_resources(
         }
     }
 
-    let output = cmd.output()?;
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run `cargo check` for {}", cargo_toml.display()))?;
 
-    let mut res = ExternResources::default();
+    let mut res = BuildDataMap::default();
 
+    // Compilation errors are fine here: the build scripts and proc-macros are built before the
+    // crates that fail to compile.
     for message in cargo_metadata::Message::parse_stream(output.stdout.as_slice()) {
         if let Ok(message) = message {
             match message {
-                Message::BuildScriptExecuted(BuildScript { package_id, out_dir, cfgs, .. }) => {
-                    let cfgs = {
-                        let mut acc = Vec::new();
-                        for cfg in cfgs {
-                            match cfg.parse::<CfgFlag>() {
-                                Ok(it) => acc.push(it),
-                                Err(err) => {
-                                    anyhow::bail!("invalid cfg from cargo-metadata: {}", err)
-                                }
-                            };
-                        }
-                        acc
-                    };
+                Message::BuildScriptExecuted(BuildScript {
+                    package_id,
+                    out_dir,
+                    cfgs,
+                    env,
+                    ..
+                }) => {
+                    let build_data = res.entry(package_id).or_default();
+                    build_data.cfgs = cfgs
+                        .iter()
+                        .filter_map(|cfg| match cfg.parse::<CfgFlag>() {
+                            Ok(it) => Some(it),
+                            Err(err) => {
+                                log::error!("invalid cfg from build script: {}", err);
+                                None
+                            }
+                        })
+                        .collect();
+                    build_data.envs = env;
                     // cargo_metadata crate returns default (empty) path for
                     // older cargos, which is not absolute, so work around that.
                     if out_dir != PathBuf::default() {
-                        let out_dir = AbsPathBuf::assert(out_dir);
-                        res.out_dirs.insert(package_id.clone(), out_dir);
-                        res.cfgs.insert(package_id, cfgs);
+                        build_data.out_dir = Some(AbsPathBuf::assert(out_dir));
                     }
                 }
                 Message::CompilerArtifact(message) => {
@@ -339,7 +358,8 @@ pub(crate) fn load_extern_resources(
                         if let Some(filename) = message.filenames.iter().find(|name| is_dylib(name))
                         {
                             let filename = AbsPathBuf::assert(filename.clone());
-                            res.proc_dylib_paths.insert(package_id, filename);
+                            res.entry(package_id).or_default().proc_macro_dylib_path =
+                                Some(filename);
                         }
                     }
                 }
@@ -21,7 +21,7 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use crate::cfg_flag::CfgFlag;
 
 pub use crate::{
-    cargo_workspace::{CargoConfig, CargoWorkspace, Package, Target, TargetKind},
+    cargo_workspace::{BuildData, CargoConfig, CargoWorkspace, Package, Target, TargetKind},
     project_json::{ProjectJson, ProjectJsonData},
     sysroot::Sysroot,
 };
@@ -245,7 +245,7 @@ impl ProjectWorkspace {
                     let pkg_root = cargo[pkg].root().to_path_buf();
 
                     let mut include = vec![pkg_root.clone()];
-                    include.extend(cargo[pkg].out_dir.clone());
+                    include.extend(cargo[pkg].build_data.out_dir.clone());
 
                     let mut exclude = vec![pkg_root.join(".git")];
                     if is_member {
@@ -275,7 +275,7 @@ impl ProjectWorkspace {
                 .collect(),
             ProjectWorkspace::Cargo { cargo, sysroot: _sysroot } => cargo
                 .packages()
-                .filter_map(|pkg| cargo[pkg].proc_macro_dylib_path.as_ref())
+                .filter_map(|pkg| cargo[pkg].build_data.proc_macro_dylib_path.as_ref())
                 .cloned()
                 .collect(),
         }
@@ -391,17 +391,21 @@ impl ProjectWorkspace {
                                 for feature in cargo[pkg].features.iter() {
                                     opts.insert_key_value("feature".into(), feature.into());
                                 }
-                                opts.extend(cargo[pkg].cfgs.iter().cloned());
+                                opts.extend(cargo[pkg].build_data.cfgs.iter().cloned());
                                 opts
                             };
                             let mut env = Env::default();
-                            if let Some(out_dir) = &cargo[pkg].out_dir {
+                            for (key, value) in cargo[pkg].build_data.envs.iter() {
+                                env.set(key, value.clone());
+                            }
+                            if let Some(out_dir) = &cargo[pkg].build_data.out_dir {
                                 // NOTE: cargo and rustc seem to hide non-UTF-8 strings from env! and option_env!()
                                 if let Some(out_dir) = out_dir.to_str().map(|s| s.to_owned()) {
                                     env.set("OUT_DIR", out_dir);
                                 }
                             }
                             let proc_macro = cargo[pkg]
+                                .build_data
                                 .proc_macro_dylib_path
                                 .as_ref()
                                 .map(|it| proc_macro_client.by_dylib_path(&it))
@@ -7,7 +7,10 @@ use std::{env, path::PathBuf};
 
 use anyhow::{bail, format_err, Result};
 use pico_args::Arguments;
-use rust_analyzer::cli::{AnalysisStatsCmd, BenchCmd, BenchWhat, Position, Verbosity};
+use rust_analyzer::cli::{
+    AnalysisStatsCmd, BenchCmd, BenchWhat, DiagnosticsCmd, DiagnosticsFormat, FailOn, LsifCmd,
+    Position, Verbosity,
+};
 use ssr::{SsrPattern, SsrRule};
 use vfs::AbsPathBuf;
 
@@ -23,7 +26,8 @@ pub(crate) enum Command {
     Highlight { rainbow: bool },
     AnalysisStats(AnalysisStatsCmd),
     Bench(BenchCmd),
-    Diagnostics { path: PathBuf, load_output_dirs: bool, with_proc_macro: bool },
+    Diagnostics(DiagnosticsCmd),
+    Lsif(LsifCmd),
     Ssr { rules: Vec<SsrRule> },
     StructuredSearch { debug_snippet: Option<String>, patterns: Vec<SsrPattern> },
     ProcMacro,
@@ -88,7 +92,17 @@ analysis-bench <PATH> Benchmark specific analysis operation
                       Load OUT_DIR values by running `cargo check` before analysis
     --with-proc-macro Use proc-macro-srv for proc-macro expanding
 
-diagnostics <PATH>
+diagnostics <PATH>    Print diagnostics for all the modules of the workspace
+    <PATH>            Directory with Cargo.toml
+    --format <FORMAT> Output format: `human` (default), `json` or `sarif`
+    --fail-on <SEVERITY>
+                      Exit with a non-zero status if a diagnostic of at least this
+                      severity is found: `error` (default), `warning` or `never`
+    --load-output-dirs
+                      Load OUT_DIR values by running `cargo check` before analysis
+    --with-proc-macro Use proc-macro-srv for proc-macro expanding
+
+lsif <PATH>           Dump an LSIF index of the workspace to stdout, one JSON object per line
     <PATH>            Directory with Cargo.toml
     --load-output-dirs
                       Load OUT_DIR values by running `cargo check` before analysis
@@ -185,13 +199,22 @@ impl Args {
                     .free_from_str()?
                     .ok_or_else(|| format_err!("expected positional argument"))?,
             }),
-            "diagnostics" => Command::Diagnostics {
+            "diagnostics" => Command::Diagnostics(DiagnosticsCmd {
+                format: matches.opt_value_from_str("--format")?.unwrap_or(DiagnosticsFormat::Human),
+                fail_on: matches.opt_value_from_str("--fail-on")?.unwrap_or(FailOn::Error),
                 load_output_dirs: matches.contains("--load-output-dirs"),
                 with_proc_macro: matches.contains("--with-proc-macro"),
                 path: matches
                     .free_from_str()?
                     .ok_or_else(|| format_err!("expected positional argument"))?,
-            },
+            }),
+            "lsif" => Command::Lsif(LsifCmd {
+                load_output_dirs: matches.contains("--load-output-dirs"),
+                with_proc_macro: matches.contains("--with-proc-macro"),
+                path: matches
+                    .free_from_str()?
+                    .ok_or_else(|| format_err!("expected positional argument"))?,
+            }),
             "proc-macro" => Command::ProcMacro,
             "ssr" => Command::Ssr {
                 rules: {
@@ -38,9 +38,8 @@ fn try_main() -> Result<()> {
         args::Command::Highlight { rainbow } => cli::highlight(rainbow)?,
         args::Command::AnalysisStats(cmd) => cmd.run(args.verbosity)?,
         args::Command::Bench(cmd) => cmd.run(args.verbosity)?,
-        args::Command::Diagnostics { path, load_output_dirs, with_proc_macro } => {
-            cli::diagnostics(path.as_ref(), load_output_dirs, with_proc_macro)?
-        }
+        args::Command::Diagnostics(cmd) => cmd.run()?,
+        args::Command::Lsif(cmd) => cmd.run()?,
         args::Command::Ssr { rules } => {
             cli::apply_ssr_rules(rules)?;
         }
@@ -114,7 +113,8 @@ fn run_server() -> Result<()> {
         }),
     };
 
-    let initialize_result = serde_json::to_value(initialize_result).unwrap();
+    let mut initialize_result = serde_json::to_value(initialize_result).unwrap();
+    rust_analyzer::add_proposed_capabilities(&mut initialize_result["capabilities"]);
 
     connection.initialize_finish(initialize_id, initialize_result)?;
 
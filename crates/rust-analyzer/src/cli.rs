@@ -4,20 +4,25 @@ mod load_cargo;
 mod analysis_stats;
 mod analysis_bench;
 mod diagnostics;
+mod lsif;
 mod progress_report;
 mod ssr;
 
 use std::io::Read;
 
 use anyhow::Result;
-use ide::Analysis;
+use hir::{Crate, Module};
+use ide::{Analysis, FileId};
+use ide_db::{base_db::SourceDatabaseExt, RootDatabase};
+use rustc_hash::FxHashSet;
 use syntax::{AstNode, SourceFile};
 
 pub use self::{
     analysis_bench::{BenchCmd, BenchWhat, Position},
     analysis_stats::AnalysisStatsCmd,
-    diagnostics::diagnostics,
+    diagnostics::{DiagnosticsCmd, DiagnosticsFormat, FailOn},
     load_cargo::load_cargo,
+    lsif::LsifCmd,
     ssr::{apply_ssr_rules, search_for_patterns},
 };
 
@@ -82,3 +87,31 @@ fn report_metric(metric: &str, value: u64, unit: &str) {
     }
     println!("METRIC:{}:{}:{}", metric, value, unit)
 }
+
+/// Returns the files of all the modules of the workspace crates, skipping library crates.
+fn workspace_files(db: &RootDatabase) -> Vec<FileId> {
+    let mut visited_files = FxHashSet::default();
+    let mut res = Vec::new();
+
+    let mut work = Vec::new();
+    for krate in Crate::all(db) {
+        let module = krate.root_module(db);
+        let file_id = module.definition_source(db).file_id.original_file(db);
+        let source_root = db.file_source_root(file_id);
+        let source_root = db.source_root(source_root);
+        if !source_root.is_library {
+            work.push(module);
+        }
+    }
+
+    while let Some(module) = work.pop() {
+        let file_id = module.definition_source(db).file_id.original_file(db);
+        if visited_files.insert(file_id) {
+            res.push(file_id);
+        }
+        work.extend(module.children(db).collect::<Vec<Module>>());
+    }
+
+    res.sort();
+    res
+}
@@ -1,64 +1,322 @@
 //! Analyze all modules in a project for diagnostics. Exits with a non-zero status
-//! code if any errors are found.
+//! code if any diagnostics at or above the configured severity are found.
 
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use anyhow::anyhow;
-use rustc_hash::FxHashSet;
+use anyhow::{anyhow, bail};
+use serde_json::{json, Value};
 
-use hir::Crate;
-use ide::{DiagnosticsConfig, Severity};
-use ide_db::base_db::SourceDatabaseExt;
+use ide::{Analysis, Diagnostic, DiagnosticsConfig, FileId, LineIndex, Severity};
+use lsp_types::Url;
+use syntax::TextRange;
+use vfs::{AbsPathBuf, Vfs};
 
-use crate::cli::{load_cargo::load_cargo, Result};
+use crate::cli::{load_cargo::load_cargo, workspace_files, Result};
 
-pub fn diagnostics(path: &Path, load_output_dirs: bool, with_proc_macro: bool) -> Result<()> {
-    let (host, _vfs) = load_cargo(path, load_output_dirs, with_proc_macro)?;
-    let db = host.raw_database();
-    let analysis = host.analysis();
+pub struct DiagnosticsCmd {
+    pub path: PathBuf,
+    pub load_output_dirs: bool,
+    pub with_proc_macro: bool,
+    pub format: DiagnosticsFormat,
+    pub fail_on: FailOn,
+}
+
+/// How the diagnostics are printed to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    /// One `path:line:column: severity[code]: message` line per diagnostic.
+    Human,
+    /// A JSON array with one object per diagnostic, including the edits of its fix.
+    Json,
+    /// A SARIF 2.1.0 log, as consumed by code scanning and code review tools.
+    Sarif,
+}
+
+impl FromStr for DiagnosticsFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "human" => Ok(DiagnosticsFormat::Human),
+            "json" => Ok(DiagnosticsFormat::Json),
+            "sarif" => Ok(DiagnosticsFormat::Sarif),
+            _ => bail!("unknown diagnostics format `{}`, expected `human`, `json` or `sarif`", s),
+        }
+    }
+}
+
+/// The least severe diagnostic that makes the command fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailOn {
+    Error,
+    Warning,
+    Never,
+}
+
+impl FailOn {
+    fn fails(self, severity: Severity) -> bool {
+        match (self, severity) {
+            (FailOn::Never, _) => false,
+            (FailOn::Warning, _) => true,
+            (FailOn::Error, Severity::Error) => true,
+            (FailOn::Error, Severity::WeakWarning) => false,
+        }
+    }
+}
 
-    let mut found_error = false;
-    let mut visited_files = FxHashSet::default();
+impl FromStr for FailOn {
+    type Err = anyhow::Error;
 
-    let mut work = Vec::new();
-    let krates = Crate::all(db);
-    for krate in krates {
-        let module = krate.root_module(db);
-        let file_id = module.definition_source(db).file_id;
-        let file_id = file_id.original_file(db);
-        let source_root = db.file_source_root(file_id);
-        let source_root = db.source_root(source_root);
-        if !source_root.is_library {
-            work.push(module);
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "error" => Ok(FailOn::Error),
+            "warning" => Ok(FailOn::Warning),
+            "never" => Ok(FailOn::Never),
+            _ => bail!("unknown severity `{}`, expected `error`, `warning` or `never`", s),
         }
     }
+}
+
+impl DiagnosticsCmd {
+    pub fn run(self) -> Result<()> {
+        let (host, vfs) = load_cargo(&self.path, self.load_output_dirs, self.with_proc_macro)?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+        let root = AbsPathBuf::assert(std::env::current_dir()?.join(&self.path));
+
+        let mut diagnostics = Vec::new();
+        for file_id in workspace_files(db) {
+            if self.format == DiagnosticsFormat::Human {
+                eprintln!("processing file: {}", vfs.file_path(file_id));
+            }
+            for diagnostic in analysis.diagnostics(&DiagnosticsConfig::default(), file_id)? {
+                diagnostics.push((file_id, diagnostic));
+            }
+        }
 
-    for module in work {
-        let file_id = module.definition_source(db).file_id.original_file(db);
-        if !visited_files.contains(&file_id) {
-            let crate_name =
-                module.krate().display_name(db).as_deref().unwrap_or("unknown").to_string();
-            println!("processing crate: {}, module: {}", crate_name, _vfs.file_path(file_id));
-            for diagnostic in analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap()
-            {
-                if matches!(diagnostic.severity, Severity::Error) {
-                    found_error = true;
+        let printer = Printer { analysis: &analysis, vfs: &vfs, root: &root };
+        match self.format {
+            DiagnosticsFormat::Human => {
+                for (file_id, diagnostic) in &diagnostics {
+                    println!("{}", printer.human(*file_id, diagnostic)?);
                 }
+                eprintln!();
+                eprintln!("diagnostic scan complete");
+            }
+            DiagnosticsFormat::Json => {
+                let diagnostics = diagnostics
+                    .iter()
+                    .map(|(file_id, diagnostic)| printer.json(*file_id, diagnostic))
+                    .collect::<Result<Vec<_>>>()?;
+                println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+            }
+            DiagnosticsFormat::Sarif => {
+                println!("{}", serde_json::to_string_pretty(&printer.sarif(&diagnostics)?)?);
+            }
+        }
 
-                println!("{:?}", diagnostic);
+        let failed = diagnostics.iter().any(|(_, it)| self.fail_on.fails(it.severity));
+        if failed {
+            Err(anyhow!("diagnostic error detected"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct Printer<'a> {
+    analysis: &'a Analysis,
+    vfs: &'a Vfs,
+    root: &'a AbsPathBuf,
+}
+
+impl Printer<'_> {
+    /// The path of the file relative to the workspace root, or its absolute path if it is
+    /// outside of it.
+    fn path(&self, file_id: FileId) -> String {
+        let path = self.vfs.file_path(file_id);
+        match path.as_path().and_then(|it| it.strip_prefix(self.root)) {
+            Some(relative) => relative.as_ref().display().to_string(),
+            None => path.to_string(),
+        }
+    }
+
+    fn human(&self, file_id: FileId, diagnostic: &Diagnostic) -> Result<String> {
+        let line_index = self.analysis.file_line_index(file_id)?;
+        let start = line_index.line_col(diagnostic.range.start());
+        let code = diagnostic.code.map(|it| format!("[{}]", it.as_str())).unwrap_or_default();
+        Ok(format!(
+            "{}:{}:{}: {}{}: {}",
+            self.path(file_id),
+            start.line + 1,
+            start.col_utf16 + 1,
+            severity(diagnostic.severity),
+            code,
+            diagnostic.message
+        ))
+    }
+
+    fn json(&self, file_id: FileId, diagnostic: &Diagnostic) -> Result<Value> {
+        let line_index = self.analysis.file_line_index(file_id)?;
+        let fixes = match &diagnostic.fix {
+            Some(fix) => {
+                let edits = fix
+                    .source_change
+                    .source_file_edits
+                    .iter()
+                    .map(|file_edit| {
+                        let line_index = self.analysis.file_line_index(file_edit.file_id)?;
+                        let edits = file_edit
+                            .edit
+                            .iter()
+                            .map(|indel| {
+                                json!({
+                                    "file": self.path(file_edit.file_id),
+                                    "range": json_range(&line_index, indel.delete),
+                                    "newText": indel.insert,
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        Ok(edits)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                vec![json!({
+                    "label": fix.label.to_string(),
+                    "edits": edits.into_iter().flatten().collect::<Vec<_>>(),
+                })]
             }
+            None => Vec::new(),
+        };
+        Ok(json!({
+            "code": diagnostic.code.map(|it| it.as_str().to_string()),
+            "severity": severity(diagnostic.severity),
+            "message": diagnostic.message,
+            "file": self.path(file_id),
+            "range": json_range(&line_index, diagnostic.range),
+            "fixes": fixes,
+        }))
+    }
 
-            visited_files.insert(file_id);
+    fn sarif(&self, diagnostics: &[(FileId, Diagnostic)]) -> Result<Value> {
+        let mut rules = diagnostics
+            .iter()
+            .filter_map(|(_, it)| it.code.map(|it| it.as_str().to_string()))
+            .collect::<Vec<_>>();
+        rules.sort();
+        rules.dedup();
+
+        let mut results = Vec::new();
+        for (file_id, diagnostic) in diagnostics {
+            let line_index = self.analysis.file_line_index(*file_id)?;
+            let mut result = json!({
+                "level": severity(diagnostic.severity),
+                "message": { "text": diagnostic.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": self.sarif_artifact(*file_id),
+                        "region": sarif_region(&line_index, diagnostic.range),
+                    },
+                }],
+            });
+            if let Some(code) = diagnostic.code {
+                result["ruleId"] = json!(code.as_str());
+            }
+            if let Some(fix) = &diagnostic.fix {
+                let changes = fix
+                    .source_change
+                    .source_file_edits
+                    .iter()
+                    .map(|file_edit| {
+                        let line_index = self.analysis.file_line_index(file_edit.file_id)?;
+                        let replacements = file_edit
+                            .edit
+                            .iter()
+                            .map(|indel| {
+                                json!({
+                                    "deletedRegion": sarif_region(&line_index, indel.delete),
+                                    "insertedContent": { "text": indel.insert },
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        Ok(json!({
+                            "artifactLocation": self.sarif_artifact(file_edit.file_id),
+                            "replacements": replacements,
+                        }))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                result["fixes"] = json!([{
+                    "description": { "text": fix.label.to_string() },
+                    "artifactChanges": changes,
+                }]);
+            }
+            results.push(result);
         }
+
+        let root: &Path = self.root.as_ref();
+        let root = Url::from_directory_path(root)
+            .map_err(|()| anyhow!("invalid workspace root: {}", root.display()))?;
+        Ok(json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "rust-analyzer",
+                        "informationUri": "https://rust-analyzer.github.io/",
+                        "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                    },
+                },
+                "originalUriBaseIds": { "%SRCROOT%": { "uri": root.as_str() } },
+                "results": results,
+            }],
+        }))
     }
 
-    println!();
-    println!("diagnostic scan complete");
+    fn sarif_artifact(&self, file_id: FileId) -> Value {
+        let path = self.vfs.file_path(file_id);
+        match path.as_path() {
+            Some(abs) => match abs.strip_prefix(self.root) {
+                Some(relative) => json!({
+                    "uri": relative.as_ref().display().to_string().replace('\\', "/"),
+                    "uriBaseId": "%SRCROOT%",
+                }),
+                None => match Url::from_file_path(abs.as_ref()) {
+                    Ok(url) => json!({ "uri": url.as_str() }),
+                    Err(()) => json!({ "uri": path.to_string() }),
+                },
+            },
+            None => json!({ "uri": path.to_string() }),
+        }
+    }
+}
 
-    if found_error {
-        println!();
-        Err(anyhow!("diagnostic error detected"))
-    } else {
-        Ok(())
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::WeakWarning => "warning",
     }
 }
+
+/// One-based lines and columns, columns are counted in UTF-16 code units.
+fn json_range(line_index: &LineIndex, range: TextRange) -> Value {
+    let start = line_index.line_col(range.start());
+    let end = line_index.line_col(range.end());
+    json!({
+        "start": { "line": start.line + 1, "column": start.col_utf16 + 1 },
+        "end": { "line": end.line + 1, "column": end.col_utf16 + 1 },
+    })
+}
+
+fn sarif_region(line_index: &LineIndex, range: TextRange) -> Value {
+    let start = line_index.line_col(range.start());
+    let end = line_index.line_col(range.end());
+    json!({
+        "startLine": start.line + 1,
+        "startColumn": start.col_utf16 + 1,
+        "endLine": end.line + 1,
+        "endColumn": end.col_utf16 + 1,
+    })
+}
@@ -0,0 +1,341 @@
+//! Dumps an LSIF index of the workspace: a graph of documents, ranges, hover texts and
+//! definition and reference results which code browsers use to navigate the code without
+//! running a language server.
+//!
+//! See https://microsoft.github.io/language-server-protocol/specifications/lsif/0.5.0/specification/
+
+use std::{
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use hir::{AsAssocItem, AssocItemContainer, HirDisplay, ModuleDef, Semantics};
+use ide::{FileId, FilePosition, LineIndex};
+use ide_db::{
+    defs::{Definition, NameClass},
+    RootDatabase,
+};
+use lsp_types::Url;
+use syntax::{algo::find_node_at_offset, ast, AstNode, SyntaxKind, TextRange};
+use vfs::AbsPathBuf;
+
+use crate::cli::{load_cargo::load_cargo, workspace_files, Result};
+
+pub struct LsifCmd {
+    pub path: PathBuf,
+    pub load_output_dirs: bool,
+    pub with_proc_macro: bool,
+}
+
+/// A range of a file; definitions are identified by the range of their name.
+type Span = (FileId, TextRange);
+
+impl LsifCmd {
+    pub fn run(self) -> Result<()> {
+        let (host, vfs) = load_cargo(&self.path, self.load_output_dirs, self.with_proc_macro)?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+        let root = AbsPathBuf::assert(std::env::current_dir()?.join(&self.path));
+
+        let files = workspace_files(db);
+        let workspace = files.iter().copied().collect::<FxHashSet<_>>();
+
+        // Resolve every identifier of the workspace to the definition it points to.
+        let mut definitions: Vec<Span> = Vec::new();
+        let mut definition_ids: FxHashMap<Span, usize> = FxHashMap::default();
+        let mut usages: FxHashMap<Span, usize> = FxHashMap::default();
+        for &file_id in &files {
+            eprintln!("processing file: {}", vfs.file_path(file_id));
+            let source_file = analysis.parse(file_id)?;
+            let tokens =
+                source_file.syntax().descendants_with_tokens().filter_map(|it| it.into_token());
+            for token in tokens {
+                if !matches!(
+                    token.kind(),
+                    SyntaxKind::IDENT
+                        | SyntaxKind::SELF_KW
+                        | SyntaxKind::SUPER_KW
+                        | SyntaxKind::CRATE_KW
+                ) {
+                    continue;
+                }
+                let position = FilePosition { file_id, offset: token.text_range().start() };
+                let nav = match analysis
+                    .goto_definition(position)?
+                    .and_then(|it| it.info.into_iter().next())
+                {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let definition = (nav.file_id, nav.focus_range.unwrap_or(nav.full_range));
+                let idx = *definition_ids.entry(definition).or_insert_with(|| {
+                    definitions.push(definition);
+                    definitions.len() - 1
+                });
+                usages.insert((file_id, token.text_range()), idx);
+            }
+        }
+
+        // References found by the search but not by goto definition, such as the ones inside
+        // macro calls, are attached to the definition as well.
+        let mut references: Vec<Vec<Span>> = Vec::with_capacity(definitions.len());
+        for (idx, &(file_id, range)) in definitions.iter().enumerate() {
+            if !workspace.contains(&file_id) {
+                references.push(Vec::new());
+                continue;
+            }
+            usages.entry((file_id, range)).or_insert(idx);
+            let position = FilePosition { file_id, offset: range.start() };
+            let spans = match analysis.find_all_refs(position, None)? {
+                Some(refs) => refs
+                    .references()
+                    .iter()
+                    .map(|it| (it.file_range.file_id, it.file_range.range))
+                    .filter(|(file_id, _)| workspace.contains(file_id))
+                    .collect::<Vec<_>>(),
+                None => Vec::new(),
+            };
+            for &span in &spans {
+                usages.entry(span).or_insert(idx);
+            }
+            references.push(spans);
+        }
+
+        let mut emitter = Emitter::new();
+        let project_root = url(root.as_ref())?;
+        emitter.vertex(
+            "metaData",
+            json!({
+                "version": "0.5.0",
+                "projectRoot": project_root.as_str(),
+                "positionEncoding": "utf-16",
+                "toolInfo": { "name": "rust-analyzer", "version": env!("REV") },
+            }),
+        )?;
+        let project = emitter.vertex("project", json!({ "kind": "rust" }))?;
+
+        let mut spans_by_file: FxHashMap<FileId, Vec<TextRange>> = FxHashMap::default();
+        for &(file_id, range) in usages.keys() {
+            spans_by_file.entry(file_id).or_default().push(range);
+        }
+
+        let mut documents = FxHashMap::default();
+        let mut ranges = FxHashMap::default();
+        for &file_id in &files {
+            let path = vfs.file_path(file_id);
+            let path = path.as_path().ok_or_else(|| anyhow!("not an absolute path: {}", path))?;
+            let document = emitter.vertex(
+                "document",
+                json!({ "uri": url(path.as_ref())?.as_str(), "languageId": "rust" }),
+            )?;
+            documents.insert(file_id, document);
+
+            let line_index = analysis.file_line_index(file_id)?;
+            let mut spans = spans_by_file.remove(&file_id).unwrap_or_default();
+            spans.sort_by_key(|it| (it.start(), it.end()));
+            let mut ids = Vec::with_capacity(spans.len());
+            for range in spans {
+                let id = emitter.vertex("range", lsif_range(&line_index, range))?;
+                ranges.insert((file_id, range), id);
+                ids.push(id);
+            }
+            if !ids.is_empty() {
+                emitter.edge("contains", json!({ "outV": document, "inVs": ids }))?;
+            }
+        }
+        let document_ids = files.iter().map(|it| documents[it]).collect::<Vec<_>>();
+        emitter.edge("contains", json!({ "outV": project, "inVs": document_ids }))?;
+
+        let mut ranges_by_definition: Vec<Vec<Span>> = vec![Vec::new(); definitions.len()];
+        for (&span, &idx) in usages.iter() {
+            ranges_by_definition[idx].push(span);
+        }
+
+        let sema = Semantics::new(db);
+        for (idx, &(file_id, range)) in definitions.iter().enumerate() {
+            let mut spans = std::mem::take(&mut ranges_by_definition[idx]);
+            spans.sort_by_key(|&(file_id, range)| (file_id, range.start(), range.end()));
+
+            let result_set = emitter.vertex("resultSet", json!({}))?;
+            for span in spans.iter() {
+                emitter.edge("next", json!({ "outV": ranges[span], "inV": result_set }))?;
+            }
+
+            let position = FilePosition { file_id, offset: range.start() };
+            if let Some(hover) = analysis.hover(position, true, true)? {
+                let hover_result = emitter.vertex(
+                    "hoverResult",
+                    json!({
+                        "result": {
+                            "contents": { "kind": "markdown", "value": hover.info.markup.as_str() },
+                        },
+                    }),
+                )?;
+                emitter.edge(
+                    "textDocument/hover",
+                    json!({ "outV": result_set, "inV": hover_result }),
+                )?;
+            }
+
+            if let Some(&definition_range) = ranges.get(&(file_id, range)) {
+                let definition_result = emitter.vertex("definitionResult", json!({}))?;
+                emitter.edge(
+                    "textDocument/definition",
+                    json!({ "outV": result_set, "inV": definition_result }),
+                )?;
+                emitter.edge(
+                    "item",
+                    json!({
+                        "outV": definition_result,
+                        "inVs": [definition_range],
+                        "document": documents[&file_id],
+                    }),
+                )?;
+
+                let reference_result = emitter.vertex("referenceResult", json!({}))?;
+                emitter.edge(
+                    "textDocument/references",
+                    json!({ "outV": result_set, "inV": reference_result }),
+                )?;
+                emitter.edge(
+                    "item",
+                    json!({
+                        "outV": reference_result,
+                        "inVs": [definition_range],
+                        "document": documents[&file_id],
+                        "property": "definitions",
+                    }),
+                )?;
+                let mut by_document: FxHashMap<FileId, Vec<u64>> = FxHashMap::default();
+                for span in spans.iter().chain(references[idx].iter()) {
+                    if *span != (file_id, range) {
+                        by_document.entry(span.0).or_default().push(ranges[span]);
+                    }
+                }
+                let mut by_document = by_document.into_iter().collect::<Vec<_>>();
+                by_document.sort_by_key(|(file_id, _)| *file_id);
+                for (document, mut ids) in by_document {
+                    ids.sort();
+                    ids.dedup();
+                    emitter.edge(
+                        "item",
+                        json!({
+                            "outV": reference_result,
+                            "inVs": ids,
+                            "document": documents[&document],
+                            "property": "references",
+                        }),
+                    )?;
+                }
+            }
+
+            if let Some(identifier) = moniker(&sema, file_id, range) {
+                let kind = if workspace.contains(&file_id) { "export" } else { "import" };
+                let moniker = emitter.vertex(
+                    "moniker",
+                    json!({
+                        "scheme": "rust-analyzer",
+                        "identifier": identifier,
+                        "unique": "scheme",
+                        "kind": kind,
+                    }),
+                )?;
+                emitter.edge("moniker", json!({ "outV": result_set, "inV": moniker }))?;
+            }
+        }
+
+        emitter.finish()
+    }
+}
+
+/// Writes the vertices and edges of the graph to stdout, one JSON object per line.
+struct Emitter {
+    out: BufWriter<io::Stdout>,
+    next_id: u64,
+}
+
+impl Emitter {
+    fn new() -> Emitter {
+        Emitter { out: BufWriter::new(io::stdout()), next_id: 0 }
+    }
+
+    fn vertex(&mut self, label: &str, data: Value) -> Result<u64> {
+        self.emit("vertex", label, data)
+    }
+
+    fn edge(&mut self, label: &str, data: Value) -> Result<u64> {
+        self.emit("edge", label, data)
+    }
+
+    fn emit(&mut self, type_: &str, label: &str, mut data: Value) -> Result<u64> {
+        self.next_id += 1;
+        data["id"] = json!(self.next_id);
+        data["type"] = json!(type_);
+        data["label"] = json!(label);
+        writeln!(self.out, "{}", data)?;
+        Ok(self.next_id)
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// A cross-crate identifier of the item defined at `range`, such as `std::vec::Vec::push`.
+fn moniker(sema: &Semantics<RootDatabase>, file_id: FileId, range: TextRange) -> Option<String> {
+    let db = sema.db;
+    let source_file = sema.parse(file_id);
+    let name = find_node_at_offset::<ast::Name>(source_file.syntax(), range.start())?;
+    let def = match NameClass::classify(sema, &name)?.defined(db)? {
+        Definition::ModuleDef(it) => it,
+        _ => return None,
+    };
+    let krate = match def {
+        ModuleDef::Module(it) => it.krate(),
+        _ => def.module(db)?.krate(),
+    };
+    let assoc_item = match def {
+        ModuleDef::Function(it) => it.as_assoc_item(db),
+        ModuleDef::Const(it) => it.as_assoc_item(db),
+        ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+        _ => None,
+    };
+    let path = match assoc_item.map(|it| it.container(db)) {
+        None => def.canonical_path(db)?,
+        Some(AssocItemContainer::Trait(trait_)) => {
+            format!("{}::{}", ModuleDef::Trait(trait_).canonical_path(db)?, def.name(db)?)
+        }
+        // Items of trait impls are found through the trait instead.
+        Some(AssocItemContainer::ImplDef(impl_def)) if impl_def.target_trait(db).is_some() => {
+            return None
+        }
+        Some(AssocItemContainer::ImplDef(impl_def)) => {
+            let self_ty = impl_def.target_ty(db);
+            let self_ty = match self_ty.as_adt() {
+                Some(adt) => ModuleDef::Adt(adt).canonical_path(db)?,
+                None => self_ty.display(db).to_string(),
+            };
+            format!("{}::{}", self_ty, def.name(db)?)
+        }
+    };
+    Some(format!("{}::{}", krate.display_name(db)?, path))
+}
+
+fn url(path: &Path) -> Result<Url> {
+    Url::from_file_path(path).map_err(|()| anyhow!("invalid path: {}", path.display()))
+}
+
+/// Zero-based lines and characters, characters are counted in UTF-16 code units.
+fn lsif_range(line_index: &LineIndex, range: TextRange) -> Value {
+    let start = line_index.line_col(range.start());
+    let end = line_index.line_col(range.end());
+    json!({
+        "start": { "line": start.line, "character": start.col_utf16 },
+        "end": { "line": end.line, "character": end.col_utf16 },
+    })
+}
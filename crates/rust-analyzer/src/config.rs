@@ -123,7 +123,7 @@ pub struct NotificationsConfig {
 
 #[derive(Debug, Clone)]
 pub enum RustfmtConfig {
-    Rustfmt { extra_args: Vec<String> },
+    Rustfmt { extra_args: Vec<String>, enable_range_formatting: bool },
     CustomCommand { command: String, args: Vec<String> },
 }
 
@@ -166,7 +166,10 @@ impl Config {
 
             cargo_autoreload: true,
             cargo: CargoConfig::default(),
-            rustfmt: RustfmtConfig::Rustfmt { extra_args: Vec::new() },
+            rustfmt: RustfmtConfig::Rustfmt {
+                extra_args: Vec::new(),
+                enable_range_formatting: false,
+            },
             flycheck: Some(FlycheckConfig::CargoCommand {
                 command: "check".to_string(),
                 target_triple: None,
@@ -182,6 +185,7 @@ impl Config {
                 type_hints: true,
                 parameter_hints: true,
                 chaining_hints: true,
+                closure_capture_hints: false,
                 max_length: None,
             },
             completion: CompletionConfig {
@@ -250,7 +254,10 @@ impl Config {
                 let command = args.remove(0);
                 RustfmtConfig::CustomCommand { command, args }
             }
-            Some(_) | None => RustfmtConfig::Rustfmt { extra_args: data.rustfmt_extraArgs },
+            Some(_) | None => RustfmtConfig::Rustfmt {
+                extra_args: data.rustfmt_extraArgs,
+                enable_range_formatting: data.rustfmt_enableRangeFormatting,
+            },
         };
 
         self.flycheck = if data.checkOnSave_enable {
@@ -280,6 +287,7 @@ impl Config {
             type_hints: data.inlayHints_typeHints,
             parameter_hints: data.inlayHints_parameterHints,
             chaining_hints: data.inlayHints_chainingHints,
+            closure_capture_hints: data.inlayHints_closureCaptureHints,
             max_length: data.inlayHints_maxLength,
         };
 
@@ -502,10 +510,11 @@ config_data! {
         hoverActions_run: bool             = true,
         hoverActions_linksInHover: bool    = true,
 
-        inlayHints_chainingHints: bool      = true,
-        inlayHints_maxLength: Option<usize> = None,
-        inlayHints_parameterHints: bool     = true,
-        inlayHints_typeHints: bool          = true,
+        inlayHints_chainingHints: bool       = true,
+        inlayHints_closureCaptureHints: bool = false,
+        inlayHints_maxLength: Option<usize>  = None,
+        inlayHints_parameterHints: bool      = true,
+        inlayHints_typeHints: bool           = true,
 
         lens_debug: bool            = true,
         lens_enable: bool           = true,
@@ -523,6 +532,7 @@ config_data! {
 
         rustfmt_extraArgs: Vec<String>               = Vec::new(),
         rustfmt_overrideCommand: Option<Vec<String>> = None,
+        rustfmt_enableRangeFormatting: bool          = false,
 
         withSysroot: bool = true,
     }
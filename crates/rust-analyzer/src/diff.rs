@@ -0,0 +1,195 @@
+//! Computes a minimal, line-based `TextEdit` between two versions of a document, so that
+//! formatting only touches the lines it actually changes.
+use ide::TextEdit;
+use syntax::{TextRange, TextSize};
+
+/// Returns the edit turning `left` into `right`, as a list of replaced line ranges.
+pub(crate) fn diff(left: &str, right: &str) -> TextEdit {
+    let mut builder = TextEdit::builder();
+    for hunk in line_diff(left, right) {
+        builder.replace(hunk.delete, right[hunk.insert].to_string());
+    }
+    builder.finish()
+}
+
+/// A contiguous block of lines in the left text that is replaced by a block of the right text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Hunk {
+    /// Range of the replaced lines in the left text.
+    pub(crate) delete: TextRange,
+    /// Range of the inserted lines in the right text.
+    pub(crate) insert: TextRange,
+}
+
+pub(crate) fn line_diff(left: &str, right: &str) -> Vec<Hunk> {
+    let left_lines = Lines::new(left);
+    let right_lines = Lines::new(right);
+
+    let mut res = Vec::new();
+    let mut pending: Option<(usize, usize, usize, usize)> = None;
+    let mut flush = |pending: &mut Option<(usize, usize, usize, usize)>| {
+        if let Some((l_start, l_end, r_start, r_end)) = pending.take() {
+            res.push(Hunk {
+                delete: left_lines.range(l_start, l_end),
+                insert: right_lines.range(r_start, r_end),
+            });
+        }
+    };
+
+    for op in myers(&left_lines.lines, &right_lines.lines) {
+        match op {
+            DiffOp::Equal { .. } => flush(&mut pending),
+            DiffOp::Delete { left, right } => match &mut pending {
+                Some((_, l_end, _, _)) => *l_end = left + 1,
+                None => pending = Some((left, left + 1, right, right)),
+            },
+            DiffOp::Insert { left, right } => match &mut pending {
+                Some((_, _, _, r_end)) => *r_end = right + 1,
+                None => pending = Some((left, left, right, right + 1)),
+            },
+        }
+    }
+    flush(&mut pending);
+    res
+}
+
+struct Lines<'a> {
+    lines: Vec<&'a str>,
+    /// Start offset of every line, plus the length of the text.
+    offsets: Vec<TextSize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Lines<'a> {
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        let mut offsets = Vec::with_capacity(lines.len() + 1);
+        let mut offset = TextSize::from(0);
+        for line in &lines {
+            offsets.push(offset);
+            offset += TextSize::of(*line);
+        }
+        offsets.push(offset);
+        Lines { lines, offsets }
+    }
+
+    fn range(&self, start: usize, end: usize) -> TextRange {
+        TextRange::new(self.offsets[start], self.offsets[end])
+    }
+}
+
+/// A single step of the edit script; `left` and `right` are the line indices in the respective
+/// texts at which the step happens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal { left: usize, right: usize },
+    Delete { left: usize, right: usize },
+    Insert { left: usize, right: usize },
+}
+
+/// Myers' O((N + M)D) diff algorithm.
+fn myers(left: &[&str], right: &[&str]) -> Vec<DiffOp> {
+    let n = left.len() as isize;
+    let m = right.len() as isize;
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    // Backtracking from step `d` only reads the diagonals `-d - 1..=d + 1` of the previous
+    // step, so only those are kept.
+    let mut trace = Vec::new();
+
+    'outer: for d in 0..=max as isize {
+        trace.push(v[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && left[x as usize] == right[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                break 'outer;
+            }
+            k += 2;
+        }
+    }
+
+    // Walk the trace backwards to recover the edit script.
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let idx = (k + d + 1) as usize;
+        let prev_k = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) { k + 1 } else { k - 1 };
+        let prev_x = v[(prev_k + d + 1) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(DiffOp::Equal { left: x as usize, right: y as usize });
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(DiffOp::Insert { left: x as usize, right: prev_y as usize });
+            } else {
+                ops.push(DiffOp::Delete { left: prev_x as usize, right: y as usize });
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(left: &str, right: &str) {
+        let edit = diff(left, right);
+        let mut actual = left.to_string();
+        edit.apply(&mut actual);
+        assert_eq!(actual, right);
+    }
+
+    #[test]
+    fn diff_applies() {
+        check("", "");
+        check("", "a\n");
+        check("a\n", "");
+        check("a\nb\nc\n", "a\nb\nc\n");
+        check("a\nb\nc\n", "a\nx\nc\n");
+        check("a\nb\nc", "a\nb\nc\n");
+        check("a\nb\nc\n", "x\na\nb\ny\nc\nz");
+        check("a\nb\nc\nd\ne\nf\n", "b\nc\nX\ne\nf\nY\n");
+        check("fn main() {\n    foo( );\n}\n", "fn main() {\n    foo();\n}\n");
+    }
+
+    #[test]
+    fn diff_is_minimal() {
+        let left = "a\nb\nc\nd\ne\n";
+        let right = "a\nB\nc\nd\nE\n";
+        let hunks = line_diff(left, right);
+        assert_eq!(
+            hunks,
+            vec![
+                Hunk {
+                    delete: TextRange::new(2.into(), 4.into()),
+                    insert: TextRange::new(2.into(), 4.into()),
+                },
+                Hunk {
+                    delete: TextRange::new(8.into(), 10.into()),
+                    insert: TextRange::new(8.into(), 10.into()),
+                },
+            ]
+        );
+    }
+}
@@ -8,8 +8,9 @@ use std::{
 };
 
 use ide::{
-    FileId, FilePosition, FileRange, HoverAction, HoverGotoTypeData, NavigationTarget, Query,
-    RangeInfo, Runnable, RunnableKind, SearchScope, TextEdit,
+    FileId, FilePosition, FileRange, HoverAction, HoverGotoTypeData, Indel, LineIndex,
+    NavigationTarget, Query, RangeInfo, Runnable, RunnableKind, SearchScope, SignatureParam,
+    SourceChange, TextEdit,
 };
 use itertools::Itertools;
 use lsp_server::ErrorCode;
@@ -18,20 +19,21 @@ use lsp_types::{
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeActionKind, CodeLens, Command, CompletionItem, Diagnostic, DiagnosticTag,
     DocumentFormattingParams, DocumentHighlight, DocumentSymbol, FoldingRange, FoldingRangeParams,
-    HoverContents, Location, Position, PrepareRenameResponse, Range, RenameParams,
+    HoverContents, Location, NumberOrString, Position, PrepareRenameResponse, Range, RenameParams,
     SemanticTokensDeltaParams, SemanticTokensFullDeltaResult, SemanticTokensParams,
     SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
     SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
 };
 use project_model::TargetKind;
 use serde::{Deserialize, Serialize};
-use serde_json::to_value;
+use serde_json::{json, to_value};
 use stdx::{format_to, split_once};
 use syntax::{algo, ast, AstNode, SyntaxKind, TextRange, TextSize};
 
 use crate::{
     cargo_target_spec::CargoTargetSpec,
     config::RustfmtConfig,
+    diff::diff,
     from_json, from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
     lsp_ext::{self, InlayHint, InlayHintsParams},
@@ -411,6 +413,21 @@ pub(crate) fn handle_goto_definition(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_goto_declaration(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::request::GotoDeclarationParams,
+) -> Result<Option<lsp_types::request::GotoDeclarationResponse>> {
+    let _p = profile::span("handle_goto_declaration");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    let nav_info = match snap.analysis.goto_declaration(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let src = FileRange { file_id: position.file_id, range: nav_info.range };
+    let res = to_proto::goto_definition_response(&snap, Some(src), nav_info.info)?;
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_goto_implementation(
     snap: GlobalStateSnapshot,
     params: lsp_types::request::GotoImplementationParams,
@@ -576,6 +593,21 @@ pub(crate) fn handle_completion(
     Ok(Some(items.into()))
 }
 
+pub(crate) fn handle_document_link(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::DocumentLinkParams,
+) -> Result<Option<Vec<lsp_types::DocumentLink>>> {
+    let _p = profile::span("handle_document_link");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let links = snap.analysis.document_links(file_id)?;
+    let line_index = snap.analysis.file_line_index(file_id)?;
+    let res = links
+        .into_iter()
+        .filter_map(|it| to_proto::document_link(&snap, &line_index, it))
+        .collect();
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_folding_range(
     snap: GlobalStateSnapshot,
     params: FoldingRangeParams,
@@ -704,15 +736,34 @@ pub(crate) fn handle_formatting(
     params: DocumentFormattingParams,
 ) -> Result<Option<Vec<lsp_types::TextEdit>>> {
     let _p = profile::span("handle_formatting");
-    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    run_rustfmt(&snap, params.text_document, None)
+}
+
+pub(crate) fn handle_range_formatting(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::DocumentRangeFormattingParams,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    let _p = profile::span("handle_range_formatting");
+    run_rustfmt(&snap, params.text_document, Some(params.range))
+}
+
+fn run_rustfmt(
+    snap: &GlobalStateSnapshot,
+    text_document: TextDocumentIdentifier,
+    range: Option<Range>,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    let file_id = from_proto::file_id(&snap, &text_document.uri)?;
     let file = snap.analysis.file_text(file_id)?;
     let crate_ids = snap.analysis.crate_for(file_id)?;
 
-    let file_line_index = snap.analysis.file_line_index(file_id)?;
-    let end_position = to_proto::position(&file_line_index, TextSize::of(file.as_str()));
+    let line_index = snap.analysis.file_line_index(file_id)?;
+    let range = range.map(|range| from_proto::text_range(&line_index, range));
 
+    // Whether rustfmt itself restricts the formatting to `range`; otherwise, the whole file is
+    // formatted and only the changes touching `range` are kept.
+    let mut formats_range = false;
     let mut rustfmt = match &snap.config.rustfmt {
-        RustfmtConfig::Rustfmt { extra_args } => {
+        RustfmtConfig::Rustfmt { extra_args, enable_range_formatting } => {
             let mut cmd = process::Command::new(toolchain::rustfmt());
             cmd.args(extra_args);
             if let Some(&crate_id) = crate_ids.first() {
@@ -721,6 +772,21 @@ pub(crate) fn handle_formatting(
                 cmd.arg("--edition");
                 cmd.arg(edition.to_string());
             }
+            if let (Some(range), true) = (range, *enable_range_formatting) {
+                // `--file-lines` is 1-based and inclusive.
+                let (start_line, end_line) = selected_lines(&line_index, range);
+                let (start_line, end_line) = (start_line + 1, end_line + 1);
+                cmd.arg("--unstable-features");
+                cmd.arg("--file-lines");
+                cmd.arg(
+                    json!([{
+                        "file": "stdin",
+                        "range": [start_line, end_line]
+                    }])
+                    .to_string(),
+                );
+                formats_range = true;
+            }
             cmd
         }
         RustfmtConfig::CustomCommand { command, args } => {
@@ -766,12 +832,38 @@ pub(crate) fn handle_formatting(
 
     if *file == captured_stdout {
         // The document is already formatted correctly -- no edits needed.
-        Ok(None)
+        return Ok(None);
+    }
+
+    let mut edit = diff(&file, &captured_stdout);
+    if let (Some(range), false) = (range, formats_range) {
+        let mut builder = TextEdit::builder();
+        let (start_line, end_line) = selected_lines(&line_index, range);
+        let touches_range = |indel: &Indel| {
+            let (indel_start, indel_end) = selected_lines(&line_index, indel.delete);
+            indel_start <= end_line && start_line <= indel_end
+        };
+        for indel in edit.into_iter().filter(touches_range) {
+            builder.replace(indel.delete, indel.insert);
+        }
+        edit = builder.finish();
+    }
+    if edit.is_empty() {
+        return Ok(None);
+    }
+    let line_endings = snap.file_line_endings(file_id);
+    Ok(Some(to_proto::text_edit_vec(&line_index, line_endings, edit)))
+}
+
+/// The first and last line, 0-based, of `range`. A range ending at the start of a line does
+/// not select that line.
+fn selected_lines(line_index: &LineIndex, range: TextRange) -> (u32, u32) {
+    let start = line_index.line_col(range.start());
+    let end = line_index.line_col(range.end());
+    if end.col_utf16 == 0 && end.line > start.line {
+        (start.line, end.line - 1)
     } else {
-        Ok(Some(vec![lsp_types::TextEdit {
-            range: Range::new(Position::new(0, 0), end_position),
-            new_text: captured_stdout,
-        }]))
+        (start.line, end.line)
     }
 }
 
@@ -1106,6 +1198,76 @@ pub(crate) fn handle_ssr(
     to_proto::workspace_edit(&snap, source_change)
 }
 
+pub(crate) fn handle_change_signature(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ChangeSignatureParams,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let _p = profile::span("handle_change_signature");
+    let position = from_proto::file_position(&snap, params.position)?;
+    let new_params = params
+        .parameters
+        .into_iter()
+        .map(|param| match param {
+            lsp_ext::SignatureParameter::Existing { index } => SignatureParam::Existing(index),
+            lsp_ext::SignatureParameter::New { pattern, ty, default_value } => {
+                SignatureParam::New { pattern, ty, default_value }
+            }
+        })
+        .collect::<Vec<_>>();
+    let source_change = snap.analysis.change_signature(position, &new_params)??;
+    to_proto::workspace_edit(&snap, source_change)
+}
+
+pub(crate) fn handle_will_rename_files(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::RenameFilesParams,
+) -> Result<Option<lsp_types::WorkspaceEdit>> {
+    let _p = profile::span("handle_will_rename_files");
+    let mut source_change = SourceChange::default();
+    for file_rename in params.files {
+        let (old_path, new_path) =
+            match (Url::parse(&file_rename.old_uri), Url::parse(&file_rename.new_uri)) {
+                (Ok(old), Ok(new)) => match (old.to_file_path(), new.to_file_path()) {
+                    (Ok(old), Ok(new)) => (old, new),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+        // Only renames, moves to another directory would need the module tree to change.
+        if old_path.parent() != new_path.parent() {
+            continue;
+        }
+        // The directory of a `mod.rs` module, or a module file.
+        let (file_path, new_name) = if new_path.extension().map_or(false, |it| it == "rs") {
+            let old_stem = old_path.file_stem().and_then(|it| it.to_str());
+            if matches!(old_stem, Some("mod") | Some("lib") | Some("main")) {
+                continue;
+            }
+            (old_path.clone(), new_path.file_stem())
+        } else {
+            (old_path.join("mod.rs"), new_path.file_name())
+        };
+        let new_name = match new_name.and_then(|it| it.to_str()) {
+            Some(it) => it,
+            None => continue,
+        };
+        let file_id = match Url::from_file_path(&file_path)
+            .ok()
+            .and_then(|url| from_proto::file_id(&snap, &url).ok())
+        {
+            Some(it) => it,
+            None => continue,
+        };
+        if let Some(change) = snap.analysis.will_rename_file(file_id, new_name)? {
+            source_change.source_file_edits.extend(change.source_file_edits);
+        }
+    }
+    if source_change.source_file_edits.is_empty() {
+        return Ok(None);
+    }
+    to_proto::workspace_edit(&snap, source_change).map(Some)
+}
+
 pub(crate) fn publish_diagnostics(
     snap: &GlobalStateSnapshot,
     file_id: FileId,
@@ -1120,7 +1282,7 @@ pub(crate) fn publish_diagnostics(
         .map(|d| Diagnostic {
             range: to_proto::range(&line_index, d.range),
             severity: Some(to_proto::diagnostic_severity(d.severity)),
-            code: None,
+            code: d.code.map(|d| d.as_str().to_owned()).map(NumberOrString::String),
             code_description: None,
             source: Some("rust-analyzer".to_string()),
             message: d.message,
@@ -1239,6 +1401,75 @@ pub(crate) fn handle_call_hierarchy_outgoing(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_type_hierarchy_prepare(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_prepare");
+    let position = from_proto::file_position(&snap, params)?;
+
+    let nav_info = match snap.analysis.type_hierarchy(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let res = nav_info
+        .info
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_supertypes(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyItemParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_supertypes");
+    let item = params.item;
+
+    let doc = TextDocumentIdentifier::new(item.uri);
+    let frange = from_proto::file_range(&snap, doc, item.selection_range)?;
+    let fpos = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
+
+    let navs = match snap.analysis.supertypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_subtypes(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeHierarchyItemParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = profile::span("handle_type_hierarchy_subtypes");
+    let item = params.item;
+
+    let doc = TextDocumentIdentifier::new(item.uri);
+    let frange = from_proto::file_range(&snap, doc, item.selection_range)?;
+    let fpos = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
+
+    let navs = match snap.analysis.subtypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let res = navs
+        .into_iter()
+        .map(|it| to_proto::type_hierarchy_item(&snap, it))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_semantic_tokens_full(
     snap: GlobalStateSnapshot,
     params: SemanticTokensParams,
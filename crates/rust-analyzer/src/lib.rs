@@ -29,6 +29,7 @@ mod from_proto;
 mod semantic_tokens;
 mod markdown;
 mod diagnostics;
+mod diff;
 mod line_endings;
 mod request_metrics;
 mod lsp_utils;
@@ -42,7 +43,10 @@ use serde::de::DeserializeOwned;
 use std::fmt;
 use vfs::Vfs;
 
-pub use crate::{caps::server_capabilities, main_loop::main_loop};
+pub use crate::{
+    caps::{add_proposed_capabilities, server_capabilities},
+    main_loop::main_loop,
+};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -29,7 +29,11 @@ impl GlobalState {
         }
     }
     pub(crate) fn maybe_refresh(&mut self, changes: &[(AbsPathBuf, ChangeKind)]) {
-        if !changes.iter().any(|(path, kind)| is_interesting(path, *kind)) {
+        // Build scripts have to be re-run to pick up new `OUT_DIR` contents, cfgs and env vars.
+        let build_script_changed = self.config.cargo.load_out_dirs_from_check
+            && changes.iter().any(|(path, _)| path.ends_with("build.rs"));
+        if !build_script_changed && !changes.iter().any(|(path, kind)| is_interesting(path, *kind))
+        {
             return;
         }
         match self.status {
@@ -5,10 +5,11 @@ use std::{
 };
 
 use ide::{
-    Assist, AssistKind, CallInfo, CompletionItem, CompletionItemKind, Documentation,
-    FileSystemEdit, Fold, FoldKind, Highlight, HighlightModifier, HighlightTag, HighlightedRange,
-    Indel, InlayHint, InlayKind, InsertTextFormat, LineIndex, Markup, NavigationTarget,
-    ReferenceAccess, ResolvedAssist, Runnable, Severity, SourceChange, SourceFileEdit, TextEdit,
+    Assist, AssistKind, CallInfo, CompletionItem, CompletionItemKind, DocumentLink,
+    DocumentLinkTarget, Documentation, FileSystemEdit, Fold, FoldKind, Highlight,
+    HighlightModifier, HighlightTag, HighlightedRange, Indel, InlayHint, InlayKind,
+    InsertTextFormat, LineIndex, Markup, NavigationTarget, ReferenceAccess, ResolvedAssist,
+    Runnable, Severity, SourceChange, SourceFileEdit, TextEdit,
 };
 use ide_db::base_db::{FileId, FileRange};
 use itertools::Itertools;
@@ -325,6 +326,7 @@ pub(crate) fn inlay_hint(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_e
             InlayKind::ParameterHint => lsp_ext::InlayKind::ParameterHint,
             InlayKind::TypeHint => lsp_ext::InlayKind::TypeHint,
             InlayKind::ChainingHint => lsp_ext::InlayKind::ChainingHint,
+            InlayKind::ClosureCaptureHint => lsp_ext::InlayKind::ClosureCaptureHint,
         },
     }
 }
@@ -481,6 +483,39 @@ pub(crate) fn folding_range(
     }
 }
 
+pub(crate) fn document_link(
+    snap: &GlobalStateSnapshot,
+    line_index: &LineIndex,
+    link: DocumentLink,
+) -> Option<lsp_types::DocumentLink> {
+    let target = match link.target {
+        DocumentLinkTarget::Path { anchor, path } => {
+            if Path::new(&path).is_absolute() {
+                url_from_abs_path(Path::new(&path))
+            } else {
+                snap.anchored_path(anchor, &path)
+            }
+        }
+        DocumentLinkTarget::File(file_id) => url(snap, file_id),
+        DocumentLinkTarget::Location(frange) => {
+            let mut target = url(snap, frange.file_id);
+            let line_index = snap.analysis.file_line_index(frange.file_id).ok()?;
+            let position = position(&line_index, frange.range.start());
+            // Editors select the position given as `#L<line>,<column>`, both one-based.
+            let fragment = format!("L{},{}", position.line + 1, position.character + 1);
+            target.set_fragment(Some(&fragment));
+            target
+        }
+        DocumentLinkTarget::Url(it) => lsp_types::Url::parse(&it).ok()?,
+    };
+    Some(lsp_types::DocumentLink {
+        range: range(line_index, link.range),
+        target: Some(target),
+        tooltip: None,
+        data: None,
+    })
+}
+
 pub(crate) fn url(snap: &GlobalStateSnapshot, file_id: FileId) -> lsp_types::Url {
     snap.file_id_to_url(file_id)
 }
@@ -628,7 +663,7 @@ pub(crate) fn resource_op(
     file_system_edit: FileSystemEdit,
 ) -> lsp_types::ResourceOp {
     match file_system_edit {
-        FileSystemEdit::CreateFile { anchor, dst } => {
+        FileSystemEdit::CreateFile { anchor, dst, .. } => {
             let uri = snap.anchored_path(anchor, &dst);
             lsp_types::ResourceOp::Create(lsp_types::CreateFile { uri, options: None })
         }
@@ -637,6 +672,10 @@ pub(crate) fn resource_op(
             let new_uri = snap.anchored_path(anchor, &dst);
             lsp_types::ResourceOp::Rename(lsp_types::RenameFile { old_uri, new_uri, options: None })
         }
+        FileSystemEdit::DeleteFile { file } => {
+            let uri = snap.file_id_to_url(file);
+            lsp_types::ResourceOp::Delete(lsp_types::DeleteFile { uri, options: None })
+        }
     }
 }
 
@@ -646,8 +685,30 @@ pub(crate) fn snippet_workspace_edit(
 ) -> Result<lsp_ext::SnippetWorkspaceEdit> {
     let mut document_changes: Vec<lsp_ext::SnippetDocumentChangeOperation> = Vec::new();
     for op in source_change.file_system_edits {
+        // A created file doesn't have a `FileId` yet, so its contents are addressed by URI.
+        let initial_contents = match &op {
+            FileSystemEdit::CreateFile { anchor, dst, initial_contents }
+                if !initial_contents.is_empty() =>
+            {
+                let uri = snap.anchored_path(*anchor, dst);
+                Some((uri, initial_contents.clone()))
+            }
+            _ => None,
+        };
         let op = resource_op(&snap, op);
         document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Op(op));
+        if let Some((uri, new_text)) = initial_contents {
+            let text_document = lsp_types::VersionedTextDocumentIdentifier { uri, version: None };
+            let range = lsp_types::Range::default();
+            let insert_text_format = if source_change.is_snippet {
+                Some(lsp_types::InsertTextFormat::Snippet)
+            } else {
+                None
+            };
+            let edits = vec![lsp_ext::SnippetTextEdit { range, new_text, insert_text_format }];
+            let edit = lsp_ext::SnippetTextDocumentEdit { text_document, edits };
+            document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Edit(edit));
+        }
     }
     for edit in source_change.source_file_edits {
         let edit = snippet_text_document_edit(&snap, source_change.is_snippet, edit)?;
@@ -721,6 +782,26 @@ pub(crate) fn call_hierarchy_item(
     })
 }
 
+pub(crate) fn type_hierarchy_item(
+    snap: &GlobalStateSnapshot,
+    target: NavigationTarget,
+) -> Result<lsp_ext::TypeHierarchyItem> {
+    let name = target.name.to_string();
+    let detail = target.description.clone();
+    let kind = symbol_kind(target.kind);
+    let (uri, range, selection_range) = location_info(snap, target)?;
+    Ok(lsp_ext::TypeHierarchyItem {
+        name,
+        kind,
+        tags: None,
+        detail,
+        uri,
+        range,
+        selection_range,
+        data: None,
+    })
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
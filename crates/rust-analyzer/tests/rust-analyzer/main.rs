@@ -15,11 +15,14 @@ use std::{collections::HashMap, path::PathBuf, time::Instant};
 
 use lsp_types::{
     notification::DidOpenTextDocument,
-    request::{CodeActionRequest, Completion, Formatting, GotoTypeDefinition, HoverRequest},
+    request::{
+        CodeActionRequest, Completion, Formatting, GotoTypeDefinition, HoverRequest,
+        RangeFormatting,
+    },
     CodeActionContext, CodeActionParams, CompletionParams, DidOpenTextDocumentParams,
-    DocumentFormattingParams, FormattingOptions, GotoDefinitionParams, HoverParams,
-    PartialResultParams, Position, Range, TextDocumentItem, TextDocumentPositionParams,
-    WorkDoneProgressParams,
+    DocumentFormattingParams, DocumentRangeFormattingParams, FormattingOptions,
+    GotoDefinitionParams, HoverParams, PartialResultParams, Position, Range, TextDocumentItem,
+    TextDocumentPositionParams, WorkDoneProgressParams,
 };
 use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
 use serde_json::json;
@@ -190,14 +193,107 @@ pub use std::collections::HashMap;
         },
         json!([
             {
-                "newText": r#"mod bar;
+                "newText": "fn main() {}\n",
+                "range": {
+                    "end": { "character": 0, "line": 4 },
+                    "start": { "character": 0, "line": 2 }
+                }
+            }
+        ]),
+    );
+}
 
-fn main() {}
+#[test]
+fn test_format_document_range() {
+    if skip_slow_tests() {
+        return;
+    }
 
-pub use std::collections::HashMap;
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+fn foo() {
+}
+
+fn main() {
+}
 "#,
+    )
+    .wait_until_workspace_is_loaded();
+
+    server.request::<RangeFormatting>(
+        DocumentRangeFormattingParams {
+            text_document: server.doc_id("src/lib.rs"),
+            range: Range::new(Position::new(3, 0), Position::new(4, 1)),
+            options: FormattingOptions {
+                tab_size: 4,
+                insert_spaces: false,
+                insert_final_newline: None,
+                trim_final_newlines: None,
+                trim_trailing_whitespace: None,
+                properties: HashMap::new(),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        },
+        json!([
+            {
+                "newText": "fn main() {}\n",
                 "range": {
-                    "end": { "character": 0, "line": 6 },
+                    "end": { "character": 0, "line": 5 },
+                    "start": { "character": 0, "line": 3 }
+                }
+            }
+        ]),
+    );
+}
+
+#[test]
+fn test_format_document_range_ending_at_line_start() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+fn foo() {
+}
+
+fn main() {
+}
+"#,
+    )
+    .wait_until_workspace_is_loaded();
+
+    server.request::<RangeFormatting>(
+        DocumentRangeFormattingParams {
+            text_document: server.doc_id("src/lib.rs"),
+            range: Range::new(Position::new(0, 0), Position::new(3, 0)),
+            options: FormattingOptions {
+                tab_size: 4,
+                insert_spaces: false,
+                insert_final_newline: None,
+                trim_final_newlines: None,
+                trim_trailing_whitespace: None,
+                properties: HashMap::new(),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        },
+        json!([
+            {
+                "newText": "fn foo() {}\n",
+                "range": {
+                    "end": { "character": 0, "line": 2 },
                     "start": { "character": 0, "line": 0 }
                 }
             }
@@ -248,17 +344,17 @@ pub use std::collections::HashMap;
         },
         json!([
             {
-                "newText": r#"mod bar;
-
-async fn test() {}
-
-fn main() {}
-
-pub use std::collections::HashMap;
-"#,
+                "newText": "async fn test() {}\n",
                 "range": {
-                    "end": { "character": 0, "line": 9 },
-                    "start": { "character": 0, "line": 0 }
+                    "end": { "character": 0, "line": 4 },
+                    "start": { "character": 0, "line": 2 }
+                }
+            },
+            {
+                "newText": "fn main() {}\n",
+                "range": {
+                    "end": { "character": 0, "line": 7 },
+                    "start": { "character": 0, "line": 5 }
                 }
             }
         ]),
@@ -587,7 +683,7 @@ fn main() {
         ),
         work_done_progress_params: Default::default(),
     });
-    assert!(res.to_string().contains("&str"));
+    assert!(res.to_string().contains("&'static str"));
 
     let res = server.send_request::<HoverRequest>(HoverParams {
         text_document_position_params: TextDocumentPositionParams::new(
@@ -651,6 +747,57 @@ fn main() {
     );
 }
 
+#[test]
+fn build_script_env_check() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = Project::with_fixture(
+        r###"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /build.rs
+use std::{env, fs, path::Path};
+
+fn main() {
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let dest_path = Path::new(&out_dir).join("generated.rs");
+    fs::write(&dest_path, r#"pub fn message() -> &'static str { "Hello, World!" }"#).unwrap();
+    println!("cargo:rustc-env=GENERATED_FILE=generated.rs");
+    println!("cargo:rerun-if-changed=build.rs");
+}
+//- /src/main.rs
+#[rustc_builtin_macro] macro_rules! include {}
+#[rustc_builtin_macro] macro_rules! concat {}
+#[rustc_builtin_macro] macro_rules! env {}
+
+include!(concat!(env!("OUT_DIR"), "/", env!("GENERATED_FILE")));
+
+fn main() {
+    let should_be_str = message();
+}
+"###,
+    )
+    .with_config(|config| {
+        config.cargo.load_out_dirs_from_check = true;
+    })
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let res = server.send_request::<HoverRequest>(HoverParams {
+        text_document_position_params: TextDocumentPositionParams::new(
+            server.doc_id("src/main.rs"),
+            Position::new(7, 10),
+        ),
+        work_done_progress_params: Default::default(),
+    });
+    assert!(res.to_string().contains("&'static str"));
+}
+
 #[test]
 fn resolve_proc_macro() {
     if skip_slow_tests() {
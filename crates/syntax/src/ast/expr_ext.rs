@@ -358,6 +358,26 @@ impl ast::Literal {
             _ => unreachable!(),
         }
     }
+
+    /// Returns the value of an integer literal, or `None` if this isn't one or
+    /// it doesn't fit into `u128`.
+    pub fn int_value(&self) -> Option<u128> {
+        let suffix = match self.kind() {
+            LiteralKind::IntNumber { suffix } => suffix,
+            _ => return None,
+        };
+        let token = self.token();
+        let text = token.text().as_str();
+        let text = &text[..text.len() - suffix.map_or(0, |it| it.len())];
+        let text = text.replace('_', "");
+        let (digits, radix) = match text.get(..2) {
+            Some("0x") => (&text[2..], 16),
+            Some("0o") => (&text[2..], 8),
+            Some("0b") => (&text[2..], 2),
+            _ => (&text[..], 10),
+        };
+        u128::from_str_radix(digits, radix).ok()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,6 +26,7 @@ macro_rules! eprintln {
 
 mod syntax_node;
 mod syntax_error;
+mod edition;
 mod parsing;
 mod validation;
 mod ptr;
@@ -46,7 +47,11 @@ use text_edit::Indel;
 pub use crate::{
     algo::InsertPosition,
     ast::{AstNode, AstToken},
-    parsing::lexer::{lex_single_syntax_kind, lex_single_valid_syntax_kind, tokenize, Token},
+    edition::{Edition, ParseEditionError},
+    parsing::lexer::{
+        lex_single_syntax_kind, lex_single_valid_syntax_kind, tokenize, tokenize_with_edition,
+        Token,
+    },
     ptr::{AstPtr, SyntaxNodePtr},
     syntax_error::SyntaxError,
     syntax_node::{
@@ -156,7 +161,11 @@ pub use crate::ast::SourceFile;
 
 impl SourceFile {
     pub fn parse(text: &str) -> Parse<SourceFile> {
-        let (green, mut errors) = parsing::parse_text(text);
+        SourceFile::parse_with_edition(text, Edition::Edition2018)
+    }
+
+    pub fn parse_with_edition(text: &str, edition: Edition) -> Parse<SourceFile> {
+        let (green, mut errors) = parsing::parse_text(text, edition);
         let root = SyntaxNode::new_root(green.clone());
 
         if cfg!(debug_assertions) {
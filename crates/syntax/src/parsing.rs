@@ -6,7 +6,7 @@ mod text_token_source;
 mod text_tree_sink;
 mod reparsing;
 
-use crate::{syntax_node::GreenNode, AstNode, SyntaxError, SyntaxNode};
+use crate::{syntax_node::GreenNode, AstNode, Edition, SyntaxError, SyntaxNode};
 use text_token_source::TextTokenSource;
 use text_tree_sink::TextTreeSink;
 
@@ -15,8 +15,8 @@ pub(crate) use lexer::*;
 pub(crate) use self::reparsing::incremental_reparse;
 use parser::SyntaxKind;
 
-pub(crate) fn parse_text(text: &str) -> (GreenNode, Vec<SyntaxError>) {
-    let (tokens, lexer_errors) = tokenize(&text);
+pub(crate) fn parse_text(text: &str, edition: Edition) -> (GreenNode, Vec<SyntaxError>) {
+    let (tokens, lexer_errors) = tokenize_with_edition(&text, edition);
 
     let mut token_source = TextTokenSource::new(text, &tokens);
     let mut tree_sink = TextTreeSink::new(text, &tokens);
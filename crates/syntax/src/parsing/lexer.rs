@@ -6,7 +6,7 @@ use std::convert::TryInto;
 use rustc_lexer::{LiteralKind as LK, RawStrError};
 
 use crate::{
-    SyntaxError,
+    Edition, SyntaxError,
     SyntaxKind::{self, *},
     TextRange, TextSize, T,
 };
@@ -24,6 +24,15 @@ pub struct Token {
 /// Beware that it checks for shebang first and its length contributes to resulting
 /// tokens offsets.
 pub fn tokenize(text: &str) -> (Vec<Token>, Vec<SyntaxError>) {
+    tokenize_with_edition(text, Edition::Edition2018)
+}
+
+/// The same as `tokenize()`, but with the lexing rules of the given edition.
+///
+/// Since the 2021 edition, an identifier directly followed by `#`, `"` or `'`
+/// is a reserved prefix. Such prefixes are still tokenized as identifiers, but
+/// reported as errors.
+pub fn tokenize_with_edition(text: &str, edition: Edition) -> (Vec<Token>, Vec<SyntaxError>) {
     // non-empty string is a precondtion of `rustc_lexer::strip_shebang()`.
     if text.is_empty() {
         return Default::default();
@@ -42,6 +51,7 @@ pub fn tokenize(text: &str) -> (Vec<Token>, Vec<SyntaxError>) {
 
     let text_without_shebang = &text[offset..];
 
+    let mut prev_ident_range = None;
     for rustc_token in rustc_lexer::tokenize(text_without_shebang) {
         let token_len: TextSize = rustc_token.len.try_into().unwrap();
         let token_range = TextRange::at(offset.try_into().unwrap(), token_len);
@@ -49,6 +59,21 @@ pub fn tokenize(text: &str) -> (Vec<Token>, Vec<SyntaxError>) {
         let (syntax_kind, err_message) =
             rustc_token_kind_to_syntax_kind(&rustc_token.kind, &text[token_range]);
 
+        if let Some(ident_range) = prev_ident_range {
+            if edition == Edition::Edition2021
+                && text[token_range].starts_with(|c| matches!(c, '#' | '"' | '\''))
+            {
+                errors.push(SyntaxError::new(
+                    format!(
+                        "Unknown prefix `{}`, prefixes are reserved since Rust 2021",
+                        &text[ident_range]
+                    ),
+                    ident_range,
+                ));
+            }
+        }
+        prev_ident_range = if syntax_kind == IDENT { Some(token_range) } else { None };
+
         tokens.push(Token { kind: syntax_kind, len: token_len });
 
         if let Some(err_message) = err_message {
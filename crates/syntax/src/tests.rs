@@ -4,11 +4,14 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use expect_test::expect_file;
+use expect_test::{expect, expect_file};
 use rayon::prelude::*;
 use test_utils::project_dir;
 
-use crate::{fuzz, tokenize, SourceFile, SyntaxError, TextRange, TextSize, Token};
+use crate::{
+    fuzz, tokenize, tokenize_with_edition, Edition, SourceFile, SyntaxError, TextRange, TextSize,
+    Token,
+};
 
 #[test]
 fn lexer_tests() {
@@ -28,6 +31,24 @@ fn lexer_tests() {
     });
 }
 
+#[test]
+fn lexer_reserved_prefixes() {
+    let text = r#"k#ident f"string" c'x' b"bytes" r#raw"#;
+
+    let (_tokens, errors) = tokenize_with_edition(text, Edition::Edition2018);
+    assert!(errors.is_empty());
+
+    let (tokens, errors) = tokenize_with_edition(text, Edition::Edition2021);
+    assert_eq!(tokens, tokenize(text).0);
+    let actual = dump_tokens_and_errors(&[], &errors, text);
+    expect![[r#"
+        > error0..1 token("k") msg(Unknown prefix `k`, prefixes are reserved since Rust 2021)
+        > error8..9 token("f") msg(Unknown prefix `f`, prefixes are reserved since Rust 2021)
+        > error18..19 token("c") msg(Unknown prefix `c`, prefixes are reserved since Rust 2021)
+    "#]]
+    .assert_eq(&actual);
+}
+
 #[test]
 fn parse_smoke_test() {
     let code = r##"
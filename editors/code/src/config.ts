@@ -102,6 +102,7 @@ export class Config {
             typeHints: this.get<boolean>("inlayHints.typeHints"),
             parameterHints: this.get<boolean>("inlayHints.parameterHints"),
             chainingHints: this.get<boolean>("inlayHints.chainingHints"),
+            closureCaptureHints: this.get<boolean>("inlayHints.closureCaptureHints"),
             maxLength: this.get<null | number>("inlayHints.maxLength"),
         };
     }
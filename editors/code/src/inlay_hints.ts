@@ -12,7 +12,8 @@ export function activateInlayHints(ctx: Ctx) {
         async onConfigChange() {
             const anyEnabled = ctx.config.inlayHints.typeHints
                 || ctx.config.inlayHints.parameterHints
-                || ctx.config.inlayHints.chainingHints;
+                || ctx.config.inlayHints.chainingHints
+                || ctx.config.inlayHints.closureCaptureHints;
             const enabled = ctx.config.inlayHints.enable && anyEnabled;
 
             if (!enabled) return this.dispose();
@@ -42,14 +43,16 @@ export function activateInlayHints(ctx: Ctx) {
 const typeHints = createHintStyle("type");
 const paramHints = createHintStyle("parameter");
 const chainingHints = createHintStyle("chaining");
+const closureCaptureHints = createHintStyle("closureCapture");
 
-function createHintStyle(hintKind: "type" | "parameter" | "chaining") {
+function createHintStyle(hintKind: "type" | "parameter" | "chaining" | "closureCapture") {
     // U+200C is a zero-width non-joiner to prevent the editor from forming a ligature
     // between code and type hints
     const [pos, render] = ({
         type: ["after", (label: string) => `\u{200c}: ${label}`],
         parameter: ["before", (label: string) => `${label}: `],
         chaining: ["after", (label: string) => `\u{200c}: ${label}`],
+        closureCapture: ["before", (label: string) => `[${label}] `],
     } as const)[hintKind];
 
     const fg = new vscode.ThemeColor(`rust_analyzer.inlayHints.foreground.${hintKind}Hints`);
@@ -105,7 +108,7 @@ class HintsUpdater implements Disposable {
 
     dispose() {
         this.sourceFiles.forEach(file => file.inlaysRequest?.cancel());
-        this.ctx.visibleRustEditors.forEach(editor => this.renderDecorations(editor, { param: [], type: [], chaining: [] }));
+        this.ctx.visibleRustEditors.forEach(editor => this.renderDecorations(editor, { param: [], type: [], chaining: [], closureCapture: [] }));
         this.disposables.forEach(d => d.dispose());
     }
 
@@ -164,10 +167,11 @@ class HintsUpdater implements Disposable {
         editor.setDecorations(typeHints.decorationType, decorations.type);
         editor.setDecorations(paramHints.decorationType, decorations.param);
         editor.setDecorations(chainingHints.decorationType, decorations.chaining);
+        editor.setDecorations(closureCaptureHints.decorationType, decorations.closureCapture);
     }
 
     private hintsToDecorations(hints: ra.InlayHint[]): InlaysDecorations {
-        const decorations: InlaysDecorations = { type: [], param: [], chaining: [] };
+        const decorations: InlaysDecorations = { type: [], param: [], chaining: [], closureCapture: [] };
         const conv = this.ctx.client.protocol2CodeConverter;
 
         for (const hint of hints) {
@@ -184,6 +188,10 @@ class HintsUpdater implements Disposable {
                     decorations.chaining.push(chainingHints.toDecoration(hint, conv));
                     continue;
                 }
+                case ra.InlayHint.Kind.ClosureCaptureHint: {
+                    decorations.closureCapture.push(closureCaptureHints.toDecoration(hint, conv));
+                    continue;
+                }
             }
         }
         return decorations;
@@ -211,6 +219,7 @@ interface InlaysDecorations {
     type: vscode.DecorationOptions[];
     param: vscode.DecorationOptions[];
     chaining: vscode.DecorationOptions[];
+    closureCapture: vscode.DecorationOptions[];
 }
 
 interface RustSourceFile {
@@ -77,13 +77,14 @@ export interface Runnable {
 }
 export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>("experimental/runnables");
 
-export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint;
+export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint | InlayHint.ClosureCaptureHint;
 
 export namespace InlayHint {
     export const enum Kind {
         TypeHint = "TypeHint",
         ParamHint = "ParameterHint",
         ChainingHint = "ChainingHint",
+        ClosureCaptureHint = "ClosureCaptureHint",
     }
     interface Common {
         range: lc.Range;
@@ -92,6 +93,7 @@ export namespace InlayHint {
     export type TypeHint = Common & { kind: Kind.TypeHint };
     export type ParamHint = Common & { kind: Kind.ParamHint };
     export type ChainingHint = Common & { kind: Kind.ChainingHint };
+    export type ClosureCaptureHint = Common & { kind: Kind.ClosureCaptureHint };
 }
 export interface InlayHintsParams {
     textDocument: lc.TextDocumentIdentifier;